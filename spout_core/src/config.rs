@@ -1,14 +1,27 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use iroh::SecretKey;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tokio::fs;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
+
+use crate::service::{
+    breaker::BreakerConfig, feed::TopicFeedConfig, view_buffer::ViewBufferConfig,
+};
+use crate::ids::IdFormat;
+use crate::text::Normalization;
 
 static DATA_DIR_NAME: &str = "spout_next";
 static SPOUT_DB_NAME: &str = "spout_db.sqlite";
 static CONFIG_FILE_NAME: &str = "config.json";
 
+/// Extra places to look for data from an older install, as a path list
+/// (`:`-separated, `;` on Windows)
+static LEGACY_DATA_DIRS_ENV: &str = "SPOUT_LEGACY_DATA_DIRS";
+
 // For now this directory structure should be like
 // data_dir_path
 // |- spout_next
@@ -21,6 +34,72 @@ fn default_secret_key() -> SecretKey {
     SecretKey::generate(&mut rand::rng())
 }
 
+/// A loaded `config.json` that can't be used as is
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("{field} is not a usable secret key (all bytes identical); generate a new one")]
+    InvalidKey { field: &'static str },
+}
+
+/// Keys a hand edit would produce, like all zeros; serde already enforces 32 bytes
+fn is_weak_key(key: &SecretKey) -> bool {
+    let bytes = key.to_bytes();
+    bytes.iter().all(|&byte| byte == bytes[0])
+}
+
+fn default_db_breaker_failure_threshold() -> u32 {
+    BreakerConfig::default().failure_threshold
+}
+
+fn default_db_breaker_cool_down_secs() -> u64 {
+    BreakerConfig::default().cool_down.as_secs()
+}
+
+fn default_soft_delete_retention_days() -> u32 {
+    30
+}
+
+fn default_online_timeout_secs() -> u64 {
+    10
+}
+
+fn default_max_page_size() -> u64 {
+    100
+}
+
+fn default_page_size() -> u64 {
+    20
+}
+
+fn default_max_profiles_per_identity() -> u64 {
+    32
+}
+
+fn default_profile_creations_per_hour() -> u32 {
+    10
+}
+
+fn default_edit_window_minutes() -> u32 {
+    24 * 60
+}
+
+fn default_view_flush_interval_secs() -> u64 {
+    ViewBufferConfig::default().flush_interval.as_secs()
+}
+
+fn default_view_buffer_max_pending() -> usize {
+    ViewBufferConfig::default().max_pending
+}
+
+fn default_topic_feed_buffer() -> usize {
+    TopicFeedConfig::default().buffer
+}
+
+fn default_max_topic_subscribers() -> usize {
+    TopicFeedConfig::default().max_subscribers
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SpoutConfig {
     /// Secret key for the local node/instance.
@@ -34,11 +113,153 @@ pub struct SpoutConfig {
     pub(crate) client_secret_key: SecretKey,
 
     pub(crate) database_path: PathBuf,
+
+    /// Consecutive DB failures before the services start short-circuiting.
+    #[serde(default = "default_db_breaker_failure_threshold")]
+    pub(crate) db_breaker_failure_threshold: u32,
+
+    /// Seconds the DB circuit breaker stays open before probing again.
+    #[serde(default = "default_db_breaker_cool_down_secs")]
+    pub(crate) db_breaker_cool_down_secs: u64,
+
+    /// Days a soft-deleted post is kept before `purge_expired_deletes` removes it.
+    #[serde(default = "default_soft_delete_retention_days")]
+    pub(crate) soft_delete_retention_days: u32,
+
+    /// Keep starting in local-only mode if relays can't be reached in time.
+    #[serde(default)]
+    pub(crate) offline_tolerant_startup: bool,
+
+    /// Seconds to wait for the endpoints to come online in offline-tolerant mode.
+    #[serde(default = "default_online_timeout_secs")]
+    pub(crate) online_timeout_secs: u64,
+
+    /// Largest page paged list RPCs return; bigger `limit`s are clamped.
+    #[serde(default = "default_max_page_size")]
+    pub(crate) max_page_size: u64,
+
+    /// Page size list RPCs use when the caller leaves `limit` out.
+    #[serde(default = "default_page_size")]
+    pub(crate) default_page_size: u64,
+
+    /// Most profiles a single node may create.
+    #[serde(default = "default_max_profiles_per_identity")]
+    pub(crate) max_profiles_per_identity: u64,
+
+    /// Profiles a single node may create per hour, with bursts up to the same
+    /// number. Keeps one node from squatting on names.
+    #[serde(default = "default_profile_creations_per_hour")]
+    pub(crate) profile_creations_per_hour: u32,
+
+    /// Minutes after posting during which the author may still edit a post.
+    #[serde(default = "default_edit_window_minutes")]
+    pub(crate) edit_window_minutes: u32,
+
+    /// Batch post-view writes in memory instead of writing each one. A crash
+    /// loses up to `view_flush_interval_secs` of views.
+    #[serde(default)]
+    pub(crate) buffer_views: bool,
+
+    /// Seconds between flushes of the view buffer.
+    #[serde(default = "default_view_flush_interval_secs")]
+    pub(crate) view_flush_interval_secs: u64,
+
+    /// Buffered views that force a flush before the interval is up.
+    #[serde(default = "default_view_buffer_max_pending")]
+    pub(crate) view_buffer_max_pending: usize,
+
+    /// New posts held per topic for live subscribers; one that falls further
+    /// behind is told to resync.
+    #[serde(default = "default_topic_feed_buffer")]
+    pub(crate) topic_feed_buffer: usize,
+
+    /// Live subscriptions allowed per topic.
+    #[serde(default = "default_max_topic_subscribers")]
+    pub(crate) max_topic_subscribers: usize,
+
+    /// Strip leading/trailing whitespace from post titles and bodies before storing them.
+    #[serde(default)]
+    pub(crate) trim_post_text: bool,
+
+    /// Squeeze runs of blank lines in post titles and bodies down to one.
+    #[serde(default)]
+    pub(crate) collapse_post_blank_lines: bool,
+
+    /// How new ids are minted. Existing ids keep parsing either way.
+    #[serde(default)]
+    pub(crate) id_format: IdFormat,
+
+    /// Connect the UI client to the in-process server over loopback only,
+    /// with no relay or discovery, instead of as a regular iroh peer. It still
+    /// binds a (localhost) endpoint and speaks QUIC; zel and iroh offer no
+    /// in-memory channel, hence the name.
+    #[serde(default)]
+    pub(crate) loopback_client: bool,
+}
+
+/// The part of [`SpoutConfig`] that can change on a running core.
+///
+/// Services read it through a [`SharedRuntimeConfig`] on every call, so
+/// `SpoutCore::reload_config` takes effect without a restart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    pub max_page_size: u64,
+    pub default_page_size: u64,
+    pub soft_delete_retention_days: u32,
+    pub max_profiles_per_identity: u64,
+    pub profile_creations_per_hour: u32,
+    pub edit_window_minutes: u32,
+    /// Applied to post titles and bodies on every write
+    pub post_text: Normalization,
+    /// Incident kill switch: reject every post/topic write. Toggled over the
+    /// maintenance RPC, never read from `config.json`.
+    pub posting_frozen: bool,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            max_page_size: default_max_page_size(),
+            default_page_size: default_page_size(),
+            soft_delete_retention_days: default_soft_delete_retention_days(),
+            max_profiles_per_identity: default_max_profiles_per_identity(),
+            profile_creations_per_hour: default_profile_creations_per_hour(),
+            edit_window_minutes: default_edit_window_minutes(),
+            post_text: Normalization::default(),
+            posting_frozen: false,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Clamp a requested page size to `max_page_size`
+    pub fn page_size(&self, limit: u64) -> u64 {
+        limit.min(self.max_page_size)
+    }
+
+    /// The caller's `limit`, or `default_page_size` when it was left out
+    pub fn limit_or_default(&self, limit: Option<u64>) -> u64 {
+        limit.unwrap_or(self.default_page_size)
+    }
+}
+
+/// [`RuntimeConfig`] shared between `SpoutCore` and its services
+pub type SharedRuntimeConfig = Arc<RwLock<RuntimeConfig>>;
+
+/// Clamp an offset to what sqlite can bind; binding anything larger panics,
+/// and no table gets anywhere near that long
+pub fn page_offset(offset: u64) -> u64 {
+    offset.min(i64::MAX as u64)
+}
+
+/// Snapshot of the current runtime settings
+pub fn runtime(shared: &SharedRuntimeConfig) -> RuntimeConfig {
+    *shared.read().unwrap_or_else(|e| e.into_inner())
 }
 
 impl SpoutConfig {
     /// Creates a new SpoutConfig with generated secret keys and the specified data directory
-    fn new(data_dir: PathBuf) -> Self {
+    pub(crate) fn new(data_dir: PathBuf) -> Self {
         let secret_key = default_secret_key();
         let client_secret_key = default_secret_key();
         let database_path = data_dir.join(SPOUT_DB_NAME);
@@ -47,28 +268,270 @@ impl SpoutConfig {
             secret_key,
             client_secret_key,
             database_path,
+            db_breaker_failure_threshold: default_db_breaker_failure_threshold(),
+            db_breaker_cool_down_secs: default_db_breaker_cool_down_secs(),
+            soft_delete_retention_days: default_soft_delete_retention_days(),
+            offline_tolerant_startup: false,
+            online_timeout_secs: default_online_timeout_secs(),
+            max_page_size: default_max_page_size(),
+            default_page_size: default_page_size(),
+            max_profiles_per_identity: default_max_profiles_per_identity(),
+            profile_creations_per_hour: default_profile_creations_per_hour(),
+            edit_window_minutes: default_edit_window_minutes(),
+            buffer_views: false,
+            view_flush_interval_secs: default_view_flush_interval_secs(),
+            view_buffer_max_pending: default_view_buffer_max_pending(),
+            topic_feed_buffer: default_topic_feed_buffer(),
+            max_topic_subscribers: default_max_topic_subscribers(),
+            trim_post_text: false,
+            collapse_post_blank_lines: false,
+            id_format: IdFormat::default(),
+            loopback_client: false,
+        }
+    }
+
+    /// Reject settings that would start a broken or insecure node
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (field, key) in [
+            ("secret_key", &self.secret_key),
+            ("client_secret_key", &self.client_secret_key),
+        ] {
+            if is_weak_key(key) {
+                return Err(ConfigError::InvalidKey { field });
+            }
+        }
+        Ok(())
+    }
+
+    /// The hot-reloadable settings
+    pub(crate) fn runtime_config(&self) -> RuntimeConfig {
+        RuntimeConfig {
+            max_page_size: self.max_page_size,
+            default_page_size: self.default_page_size,
+            soft_delete_retention_days: self.soft_delete_retention_days,
+            max_profiles_per_identity: self.max_profiles_per_identity,
+            profile_creations_per_hour: self.profile_creations_per_hour,
+            edit_window_minutes: self.edit_window_minutes,
+            post_text: Normalization {
+                trim_ends: self.trim_post_text,
+                collapse_blank_lines: self.collapse_post_blank_lines,
+            },
+            posting_frozen: false,
+        }
+    }
+
+    /// Fields that only take effect on restart and differ in `reloaded`
+    pub(crate) fn restart_only_changes(&self, reloaded: &SpoutConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.secret_key.to_bytes() != reloaded.secret_key.to_bytes() {
+            changed.push("secret_key");
+        }
+        if self.client_secret_key.to_bytes() != reloaded.client_secret_key.to_bytes() {
+            changed.push("client_secret_key");
+        }
+        if self.database_path != reloaded.database_path {
+            changed.push("database_path");
+        }
+        if self.breaker_config() != reloaded.breaker_config() {
+            changed.push("db_breaker_*");
+        }
+        if self.online_timeout() != reloaded.online_timeout() {
+            changed.push("offline_tolerant_startup/online_timeout_secs");
+        }
+        if self.view_buffer_config() != reloaded.view_buffer_config() {
+            changed.push("buffer_views/view_*");
+        }
+        if self.topic_feed_config() != reloaded.topic_feed_config() {
+            changed.push("topic_feed_buffer/max_topic_subscribers");
+        }
+        if self.id_format != reloaded.id_format {
+            changed.push("id_format");
+        }
+        if self.loopback_client != reloaded.loopback_client {
+            changed.push("loopback_client");
+        }
+        changed
+    }
+
+    /// How long `start` waits for an endpoint to come online; `None` waits indefinitely
+    pub(crate) fn online_timeout(&self) -> Option<Duration> {
+        self.offline_tolerant_startup
+            .then(|| Duration::from_secs(self.online_timeout_secs))
+    }
+
+    /// Write-behind settings for post views; `None` writes every view directly
+    pub(crate) fn view_buffer_config(&self) -> Option<ViewBufferConfig> {
+        self.buffer_views.then(|| ViewBufferConfig {
+            flush_interval: Duration::from_secs(self.view_flush_interval_secs),
+            max_pending: self.view_buffer_max_pending,
+        })
+    }
+
+    /// Limits for the live topic feeds
+    pub(crate) fn topic_feed_config(&self) -> TopicFeedConfig {
+        TopicFeedConfig {
+            buffer: self.topic_feed_buffer,
+            max_subscribers: self.max_topic_subscribers,
+        }
+    }
+
+    /// Circuit breaker thresholds for the DB-backed services
+    pub(crate) fn breaker_config(&self) -> BreakerConfig {
+        BreakerConfig {
+            failure_threshold: self.db_breaker_failure_threshold,
+            cool_down: Duration::from_secs(self.db_breaker_cool_down_secs),
         }
     }
 }
 
-/// Gets the existing config or initializes a new one if it doesn't exist
-pub async fn get_or_init() -> Result<SpoutConfig, Box<dyn std::error::Error>> {
+/// Where `config.json` lives on this platform
+pub fn config_path() -> PathBuf {
     let data_dir = dirs::data_dir().expect("failed to find a data directory on this platform");
+    data_dir.join(DATA_DIR_NAME).join(CONFIG_FILE_NAME)
+}
 
-    let spout_dir = data_dir.join(DATA_DIR_NAME);
-    let config_path = spout_dir.join(CONFIG_FILE_NAME);
+/// Where an older install may have left its `spout_next` directory.
+///
+/// `data_dir` has moved between OS releases, so this checks the other
+/// platform data/config dirs plus anything listed in `SPOUT_LEGACY_DATA_DIRS`.
+pub fn legacy_data_dirs() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = std::env::var_os(LEGACY_DATA_DIRS_ENV)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    for base in [dirs::data_local_dir(), dirs::config_dir()] {
+        candidates.extend(base.map(|base| base.join(DATA_DIR_NAME)));
+    }
+    candidates
+}
+
+/// Copy a legacy data directory into `current` if `current` holds nothing yet.
+///
+/// The first candidate with a `config.json` or database wins; one that can't
+/// be inspected is skipped. Its files are copied, not moved, into a sibling
+/// staging directory that only replaces `current` once complete, so a failed
+/// copy leaves `current` empty rather than half-filled. A `database_path`
+/// inside it is rebased onto `current`. Returns the adopted directory, if any.
+pub async fn adopt_legacy_data(
+    current: &Path,
+    candidates: &[PathBuf],
+) -> std::io::Result<Option<PathBuf>> {
+    // Never overwrite what's already here
+    if let Ok(mut entries) = fs::read_dir(current).await {
+        if entries.next_entry().await?.is_some() {
+            return Ok(None);
+        }
+    }
+
+    for legacy in candidates {
+        if legacy == current {
+            continue;
+        }
+        let has_data = fs::try_exists(legacy.join(CONFIG_FILE_NAME)).await.unwrap_or(false)
+            || fs::try_exists(legacy.join(SPOUT_DB_NAME)).await.unwrap_or(false);
+        if !has_data {
+            continue;
+        }
+
+        let staging = staging_dir(current);
+        // Left over from a copy that was interrupted
+        if fs::try_exists(&staging).await? {
+            fs::remove_dir_all(&staging).await?;
+        }
+        let staged = async {
+            copy_dir(legacy, &staging).await?;
+            rebase_database_path(&staging.join(CONFIG_FILE_NAME), legacy, current).await
+        };
+        if let Err(error) = staged.await {
+            let _ = fs::remove_dir_all(&staging).await;
+            return Err(error);
+        }
+
+        // `current` is known to be empty; swap the finished copy in for it
+        match fs::remove_dir(current).await {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+        if let Err(error) = fs::rename(&staging, current).await {
+            let _ = fs::remove_dir_all(&staging).await;
+            fs::create_dir_all(current).await?;
+            return Err(error);
+        }
+        println!("adopted spout data from {legacy:?} into {current:?}");
+        return Ok(Some(legacy.clone()));
+    }
+    Ok(None)
+}
+
+/// Where `adopt_legacy_data` assembles a copy before moving it into `current`
+fn staging_dir(current: &Path) -> PathBuf {
+    let mut name = current.file_name().unwrap_or_default().to_os_string();
+    name.push(".adopting");
+    current.with_file_name(name)
+}
+
+async fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut pending = vec![(from.to_path_buf(), to.to_path_buf())];
+    while let Some((from, to)) = pending.pop() {
+        fs::create_dir_all(&to).await?;
+        let mut entries = fs::read_dir(&from).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let target = to.join(entry.file_name());
+            if entry.file_type().await?.is_dir() {
+                pending.push((entry.path(), target));
+            } else {
+                fs::copy(entry.path(), target).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Point an adopted config's `database_path` at the copy instead of the original
+async fn rebase_database_path(config_path: &Path, from: &Path, to: &Path) -> std::io::Result<()> {
+    let Ok(contents) = fs::read_to_string(config_path).await else {
+        return Ok(());
+    };
+    let Ok(mut config) = serde_json::from_str::<serde_json::Value>(&contents) else {
+        return Ok(());
+    };
+    let rebased = config["database_path"]
+        .as_str()
+        .and_then(|path| Path::new(path).strip_prefix(from).ok())
+        .map(|relative| to.join(relative));
+    if let Some(rebased) = rebased {
+        config["database_path"] = rebased.to_string_lossy().into_owned().into();
+        let json = serde_json::to_string_pretty(&config).map_err(std::io::Error::other)?;
+        fs::write(config_path, json).await?;
+    }
+    Ok(())
+}
+
+/// Reads and parses an existing config file
+pub async fn load(path: &Path) -> Result<SpoutConfig, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path).await?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Gets the existing config or initializes a new one if it doesn't exist
+pub async fn get_or_init() -> Result<SpoutConfig, Box<dyn std::error::Error>> {
+    let config_path = config_path();
+    let spout_dir = config_path
+        .parent()
+        .expect("config path has a parent")
+        .to_path_buf();
 
     // Create the spout directory if it doesn't exist
     fs::create_dir_all(&spout_dir).await?;
+    // Best effort: without the old data this is simply a fresh install
+    if let Err(error) = adopt_legacy_data(&spout_dir, &legacy_data_dirs()).await {
+        eprintln!("couldn't adopt legacy spout data, starting fresh: {error}");
+    }
 
     // Check if config file exists
     if config_path.exists() {
         // Read and deserialize existing config
-        let mut file = fs::File::open(&config_path).await?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).await?;
-
-        let config: SpoutConfig = serde_json::from_str(&contents)?;
+        let config = load(&config_path).await?;
+        config.validate()?;
         Ok(config)
     } else {
         // Create new config
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What kind of row a change describes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ChangeEntity {
+    #[sea_orm(string_value = "group")]
+    Group,
+    #[sea_orm(string_value = "topic")]
+    Topic,
+    #[sea_orm(string_value = "post")]
+    Post,
+}
+
+/// What happened to the row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ChangeOp {
+    #[sea_orm(string_value = "create")]
+    Create,
+    #[sea_orm(string_value = "update")]
+    Update,
+    #[sea_orm(string_value = "delete")]
+    Delete,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "change_log")]
+pub struct Model {
+    /// Increases with every appended change, never reused
+    #[sea_orm(primary_key)]
+    pub seq: i64,
+    pub entity: ChangeEntity,
+    pub op: ChangeOp,
+    /// Id of the group, topic or post, depending on `entity`
+    pub entity_id: Uuid,
+    /// JSON of the row as of this change; for deletes, as it was just before
+    pub payload: String,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,33 @@
+use crate::ids::{PostId, ProfileId, TopicId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "direct_message")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: PostId,
+    pub topic_id: TopicId,
+    /// The sender; always one of the topic's two participants
+    pub profile_id: ProfileId,
+    pub body: String,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::direct_topic::Entity",
+        from = "Column::TopicId",
+        to = "super::direct_topic::Column::Id"
+    )]
+    DirectTopic,
+}
+
+impl Related<super::direct_topic::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DirectTopic.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
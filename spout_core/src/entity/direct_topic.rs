@@ -0,0 +1,46 @@
+use crate::ids::{ProfileId, TopicId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A private conversation between exactly two profiles
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "direct_topic")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: TopicId,
+    /// The participant with the smaller id; see [`participants`]
+    pub low_profile_id: ProfileId,
+    pub high_profile_id: ProfileId,
+    pub created_at: String,
+    /// Bumped on every message, for newest-first listings
+    pub last_message_at: String,
+}
+
+impl Model {
+    pub fn is_participant(&self, profile_id: ProfileId) -> bool {
+        self.low_profile_id == profile_id || self.high_profile_id == profile_id
+    }
+}
+
+/// A pair of profiles in the `(low, high)` order the table stores them in
+pub fn participants(a: ProfileId, b: ProfileId) -> (ProfileId, ProfileId) {
+    if a.as_uuid() <= b.as_uuid() {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::direct_message::Entity")]
+    DirectMessage,
+}
+
+impl Related<super::direct_message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DirectMessage.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -8,10 +8,21 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: GroupId,
     pub profile_id: ProfileId,
+    /// Read-only: no new topics or posts while set
+    pub archived: bool,
+    pub archived_at: Option<String>,
+    /// `None` for groups created before creation times were recorded
+    pub created_at: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::ProfileId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
     #[sea_orm(has_many = "super::group_admin::Entity")]
     GroupAdmin,
     #[sea_orm(has_many = "super::group_banned::Entity")]
@@ -22,6 +33,12 @@ pub enum Relation {
     GroupTopic,
 }
 
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
 impl Related<super::group_admin::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GroupAdmin.def()
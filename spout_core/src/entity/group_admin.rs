@@ -19,6 +19,12 @@ pub enum Relation {
         to = "super::group::Column::Id"
     )]
     Group,
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::IdentityId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
 }
 
 impl Related<super::group::Entity> for Entity {
@@ -27,4 +33,10 @@ impl Related<super::group::Entity> for Entity {
     }
 }
 
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,6 +2,20 @@ use crate::ids::{PostId, TopicId, UserId};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// How a post body should be rendered
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum PostFormat {
+    #[sea_orm(string_value = "plain")]
+    #[serde(rename = "Plain")]
+    Plain,
+    #[sea_orm(string_value = "markdown")]
+    #[serde(rename = "Markdown")]
+    Markdown,
+}
+
+/// Sent over RPC as-is, so field names are part of the wire contract; see
+/// `test_group_post_json_shape`
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "group_post")]
 pub struct Model {
@@ -13,6 +27,15 @@ pub struct Model {
     pub title: String,
     pub body: String,
     pub created_at: String,
+    /// Set when the post is soft-deleted; purged after the retention window
+    pub deleted_at: Option<String>,
+    /// Last time the title or body changed, `None` if never edited
+    pub edited_at: Option<String>,
+    /// Starts at 1, bumped on every edit
+    pub version: i32,
+    pub format: PostFormat,
+    /// 1-based position within the topic, assigned at creation and never reused
+    pub seq: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
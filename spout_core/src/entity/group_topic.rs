@@ -10,6 +10,11 @@ pub struct Model {
     pub group_id: GroupId,
     pub profile_id: ProfileId,
     pub created_at: String,
+    pub title: String,
+    /// Set on rename; `None` until the topic is first renamed
+    pub updated_at: Option<String>,
+    /// Position among the group's pinned topics; `None` when not pinned
+    pub pin_order: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,6 +25,12 @@ pub enum Relation {
         to = "super::group::Column::Id"
     )]
     Group,
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::ProfileId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
     #[sea_orm(has_many = "super::group_post::Entity")]
     GroupPost,
 }
@@ -30,6 +41,12 @@ impl Related<super::group::Entity> for Entity {
     }
 }
 
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
 impl Related<super::group_post::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GroupPost.def()
@@ -1,5 +1,6 @@
 use crate::ids::{GroupId, ProfileId, UserId};
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Func, SimpleExpr};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
@@ -9,6 +10,10 @@ pub struct Model {
     pub id: UserId,
     pub group_id: GroupId,
     pub profile_id: ProfileId,
+    /// Display name used inside this group instead of the profile's own
+    pub nickname: Option<String>,
+    /// When the profile joined; `None` for memberships older than the column
+    pub created_at: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -19,6 +24,12 @@ pub enum Relation {
         to = "super::group::Column::Id"
     )]
     Group,
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::ProfileId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
 }
 
 impl Related<super::group::Entity> for Entity {
@@ -27,4 +38,20 @@ impl Related<super::group::Entity> for Entity {
     }
 }
 
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
+/// The name a member goes by inside their group: the nickname if set, else
+/// the profile name. Only valid in queries that join `profile`.
+pub fn display_name() -> SimpleExpr {
+    Func::coalesce([
+        Expr::col((Entity, Column::Nickname)).into(),
+        Expr::col((super::profile::Entity, super::profile::Column::Name)).into(),
+    ])
+    .into()
+}
+
 impl ActiveModelBehavior for ActiveModel {}
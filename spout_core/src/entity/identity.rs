@@ -1,6 +1,8 @@
 use crate::ids::ProfileId;
+use iroh::PublicKey;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 // Note: iroh::PublicKey is represented as Vec<u8> in the database
 // The binary column stores the 32-byte public key
@@ -11,6 +13,21 @@ pub struct Model {
     pub node_id: Vec<u8>,
     #[sea_orm(primary_key, auto_increment = false)]
     pub profile_id: ProfileId,
+    /// The node's default/primary profile; at most one per node_id
+    pub is_default: bool,
+}
+
+/// A stored `node_id` that doesn't decode to a public key
+#[derive(Debug, Error)]
+#[error("corrupt identity: node_id is not a valid 32-byte public key")]
+pub struct CorruptIdentity;
+
+impl Model {
+    /// Decode `node_id` back into the node's public key
+    pub fn public_key(&self) -> Result<PublicKey, CorruptIdentity> {
+        let bytes: &[u8; 32] = self.node_id.as_slice().try_into().map_err(|_| CorruptIdentity)?;
+        PublicKey::from_bytes(bytes).map_err(|_| CorruptIdentity)
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
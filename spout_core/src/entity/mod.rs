@@ -2,6 +2,9 @@
 // This module contains SeaORM-based entity definitions
 // that mirror the sqlx models in the `models` module
 
+pub mod change_log;
+pub mod direct_message;
+pub mod direct_topic;
 pub mod group;
 pub mod group_admin;
 pub mod group_banned;
@@ -9,13 +12,32 @@ pub mod group_post;
 pub mod group_topic;
 pub mod group_user;
 pub mod identity;
+pub mod notification;
+pub mod post_report;
+pub mod post_view_history;
 pub mod profile;
+pub mod profile_state;
+pub mod rate_state;
+pub mod topic_subscription;
+pub mod topic_tag;
 
 #[cfg(test)]
 mod tests;
 
 pub mod prelude {
     // Re-export all entities for convenience
+    pub use super::change_log::{
+        ActiveModel as ChangeLogActiveModel, ChangeEntity, ChangeOp, Column as ChangeLogColumn,
+        Entity as ChangeLog, Model as ChangeLogModel,
+    };
+    pub use super::direct_message::{
+        ActiveModel as DirectMessageActiveModel, Column as DirectMessageColumn,
+        Entity as DirectMessage, Model as DirectMessageModel,
+    };
+    pub use super::direct_topic::{
+        ActiveModel as DirectTopicActiveModel, Column as DirectTopicColumn,
+        Entity as DirectTopic, Model as DirectTopicModel,
+    };
     pub use super::group::{
         ActiveModel as GroupActiveModel, Column as GroupColumn, Entity as Group,
         Model as GroupModel,
@@ -30,7 +52,7 @@ pub mod prelude {
     };
     pub use super::group_post::{
         ActiveModel as GroupPostActiveModel, Column as GroupPostColumn, Entity as GroupPost,
-        Model as GroupPostModel,
+        Model as GroupPostModel, PostFormat,
     };
     pub use super::group_topic::{
         ActiveModel as GroupTopicActiveModel, Column as GroupTopicColumn, Entity as GroupTopic,
@@ -44,10 +66,38 @@ pub mod prelude {
         ActiveModel as IdentityActiveModel, Column as IdentityColumn, Entity as Identity,
         Model as IdentityModel,
     };
+    pub use super::notification::{
+        ActiveModel as NotificationActiveModel, Column as NotificationColumn,
+        Entity as Notification, Model as NotificationModel, NotificationKind,
+    };
+    pub use super::post_report::{
+        ActiveModel as PostReportActiveModel, Column as PostReportColumn, Entity as PostReport,
+        Model as PostReportModel, ReportStatus,
+    };
+    pub use super::post_view_history::{
+        ActiveModel as PostViewHistoryActiveModel, Column as PostViewHistoryColumn,
+        Entity as PostViewHistory, Model as PostViewHistoryModel,
+    };
     pub use super::profile::{
         ActiveModel as ProfileActiveModel, Column as ProfileColumn, Entity as Profile,
         Model as ProfileModel,
     };
+    pub use super::profile_state::{
+        ActiveModel as ProfileStateActiveModel, Column as ProfileStateColumn,
+        Entity as ProfileState, Model as ProfileStateModel,
+    };
+    pub use super::rate_state::{
+        ActiveModel as RateStateActiveModel, Column as RateStateColumn, Entity as RateState,
+        Model as RateStateModel,
+    };
+    pub use super::topic_subscription::{
+        ActiveModel as TopicSubscriptionActiveModel, Column as TopicSubscriptionColumn,
+        Entity as TopicSubscription, Model as TopicSubscriptionModel, SubscriptionLevel,
+    };
+    pub use super::topic_tag::{
+        ActiveModel as TopicTagActiveModel, Column as TopicTagColumn, Entity as TopicTag,
+        Model as TopicTagModel,
+    };
 
     // Re-export commonly used SeaORM types and traits
     pub use sea_orm::{
@@ -0,0 +1,57 @@
+use crate::ids::{NotificationId, PostId, ProfileId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Why a notification was queued
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum NotificationKind {
+    /// Someone replied to the recipient's post
+    #[sea_orm(string_value = "reply")]
+    Reply,
+    /// The recipient was `@mentioned` in a post
+    #[sea_orm(string_value = "mention")]
+    Mention,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "notification")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: NotificationId,
+    pub recipient_profile_id: ProfileId,
+    pub kind: NotificationKind,
+    pub source_post_id: PostId,
+    pub read: bool,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::RecipientProfileId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
+    #[sea_orm(
+        belongs_to = "super::group_post::Entity",
+        from = "Column::SourcePostId",
+        to = "super::group_post::Column::Id"
+    )]
+    GroupPost,
+}
+
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
+impl Related<super::group_post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupPost.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,48 @@
+use crate::ids::{PostId, ProfileId, ReportId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where a report is in moderation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ReportStatus {
+    /// Waiting for an admin
+    #[sea_orm(string_value = "open")]
+    Open,
+    /// An admin acted on the report
+    #[sea_orm(string_value = "resolved")]
+    Resolved,
+    /// An admin reviewed the report and took no action
+    #[sea_orm(string_value = "dismissed")]
+    Dismissed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "post_report")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: ReportId,
+    pub post_id: PostId,
+    pub reporter_profile_id: ProfileId,
+    pub reason: String,
+    pub status: ReportStatus,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group_post::Entity",
+        from = "Column::PostId",
+        to = "super::group_post::Column::Id"
+    )]
+    GroupPost,
+}
+
+impl Related<super::group_post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupPost.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,44 @@
+use crate::ids::{PostId, UserId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A post a user opened, kept for a bounded recently-viewed list
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "post_view_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: UserId,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub post_id: PostId,
+    pub viewed_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group_user::Entity",
+        from = "Column::UserId",
+        to = "super::group_user::Column::Id"
+    )]
+    GroupUser,
+    #[sea_orm(
+        belongs_to = "super::group_post::Entity",
+        from = "Column::PostId",
+        to = "super::group_post::Column::Id"
+    )]
+    GroupPost,
+}
+
+impl Related<super::group_user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupUser.def()
+    }
+}
+
+impl Related<super::group_post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupPost.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
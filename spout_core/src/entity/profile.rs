@@ -2,6 +2,8 @@ use crate::ids::ProfileId;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Sent over RPC as-is, so field names are part of the wire contract; see
+/// `test_profile_json_shape`
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "profile")]
 pub struct Model {
@@ -9,11 +11,43 @@ pub struct Model {
     pub id: ProfileId,
     pub name: String,
     #[sea_orm(column_name = "desc")]
+    #[serde(rename = "desc")]
     pub desc: String,
     pub picture: Option<Vec<u8>>,
+    /// `None` for profiles created before creation times were recorded
+    pub created_at: Option<String>,
+    /// The owning node's id, duplicated from `identity` so it can be rebuilt.
+    /// Never sent over RPC: it would link a node's profiles together.
+    #[serde(skip)]
+    pub owner_node_id: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::group::Entity")]
+    Group,
+    #[sea_orm(has_many = "super::group_topic::Entity")]
+    GroupTopic,
+    #[sea_orm(has_many = "super::identity::Entity")]
+    Identity,
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl Related<super::group_topic::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupTopic.def()
+    }
+}
+
+impl Related<super::identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Identity.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A node's profile-creation token bucket, as of `updated_at`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "rate_state")]
+pub struct Model {
+    /// The node's 32-byte public key
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub node_id: Vec<u8>,
+    /// Creations left; refills continuously up to the hourly limit
+    pub tokens: f64,
+    pub updated_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
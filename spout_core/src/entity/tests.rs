@@ -29,10 +29,12 @@ mod entity_tests {
             name: Set("Test User".to_string()),
             desc: Set("Test Description".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
 
         // Insert profile
-        let insert_result = Profile::insert(profile)
+        let _insert_result = Profile::insert(profile)
             .exec(&db)
             .await
             .expect("Failed to insert profile");
@@ -62,6 +64,8 @@ mod entity_tests {
             name: Set("User with Picture".to_string()),
             desc: Set("Has a picture".to_string()),
             picture: Set(Some(picture_data.clone())),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
 
         Profile::insert(profile).exec(&db).await.unwrap();
@@ -86,6 +90,8 @@ mod entity_tests {
                 name: Set(format!("User {}", i)),
                 desc: Set(format!("Description {}", i)),
                 picture: Set(None),
+                created_at: Set(None),
+                owner_node_id: Set(None),
             };
             Profile::insert(profile).exec(&db).await.unwrap();
         }
@@ -112,6 +118,8 @@ mod entity_tests {
             name: Set("Group Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -120,6 +128,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -141,6 +152,8 @@ mod entity_tests {
             name: Set("Admin User".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -148,6 +161,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -180,6 +196,8 @@ mod entity_tests {
             name: Set("User".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -187,6 +205,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -195,6 +216,8 @@ mod entity_tests {
             id: Set(UserId::new()),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
         GroupUser::insert(user1).exec(&db).await.unwrap();
 
@@ -203,6 +226,8 @@ mod entity_tests {
             id: Set(UserId::new()),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
 
         // This should fail due to unique constraint on (group_id, profile_id)
@@ -221,6 +246,8 @@ mod entity_tests {
             name: Set("Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -228,6 +255,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -242,6 +272,8 @@ mod entity_tests {
             id: Set(UserId::new()),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
         GroupUser::insert(user).exec(&db).await.unwrap();
 
@@ -264,6 +296,125 @@ mod entity_tests {
         assert_eq!(users.len(), 0, "Users should be cascade deleted");
     }
 
+    async fn insert_profile(db: &DatabaseConnection, name: &str) -> ProfileId {
+        let profile_id = ProfileId::new();
+        let profile = ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set(name.to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        };
+        Profile::insert(profile).exec(db).await.unwrap();
+        profile_id
+    }
+
+    async fn insert_group(db: &DatabaseConnection, owner: ProfileId) -> GroupId {
+        let group_id = GroupId::new();
+        let group = GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(owner),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        };
+        Group::insert(group).exec(db).await.unwrap();
+        group_id
+    }
+
+    #[tokio::test]
+    async fn test_cascade_delete_group_removes_bans() {
+        let db = setup_test_db().await;
+        let owner = insert_profile(&db, "Owner").await;
+        let banned = insert_profile(&db, "Banned").await;
+        let group_id = insert_group(&db, owner).await;
+        let other_group = insert_group(&db, owner).await;
+
+        for group_id in [group_id, other_group] {
+            let ban = GroupBannedActiveModel {
+                group_id: Set(group_id),
+                identity_id: Set(banned),
+            };
+            GroupBanned::insert(ban).exec(&db).await.unwrap();
+        }
+
+        Group::delete_by_id(group_id).exec(&db).await.unwrap();
+
+        let bans = GroupBanned::find().all(&db).await.unwrap();
+        assert_eq!(bans.len(), 1, "Only the deleted group's bans should go");
+        assert_eq!(bans[0].group_id, other_group);
+    }
+
+    #[tokio::test]
+    async fn test_cascade_delete_group_removes_admins() {
+        let db = setup_test_db().await;
+        let owner = insert_profile(&db, "Owner").await;
+        let moderator = insert_profile(&db, "Moderator").await;
+        let group_id = insert_group(&db, owner).await;
+        let other_group = insert_group(&db, owner).await;
+
+        let admins = [(group_id, owner), (group_id, moderator), (other_group, owner)];
+        for (group_id, identity_id) in admins {
+            let admin = GroupAdminActiveModel {
+                group_id: Set(group_id),
+                identity_id: Set(identity_id),
+            };
+            GroupAdmin::insert(admin).exec(&db).await.unwrap();
+        }
+
+        Group::delete_by_id(group_id).exec(&db).await.unwrap();
+
+        let admins = GroupAdmin::find().all(&db).await.unwrap();
+        assert_eq!(admins.len(), 1, "Only the deleted group's admins should go");
+        assert_eq!((admins[0].group_id, admins[0].identity_id), (other_group, owner));
+    }
+
+    #[tokio::test]
+    async fn test_cascade_delete_profile_removes_identities_and_memberships() {
+        let db = setup_test_db().await;
+        let owner = insert_profile(&db, "Owner").await;
+        let member = insert_profile(&db, "Member").await;
+        let group_id = insert_group(&db, owner).await;
+
+        let node_id = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        for profile_id in [owner, member] {
+            let identity = IdentityActiveModel {
+                node_id: Set(node_id.clone()),
+                profile_id: Set(profile_id),
+                is_default: Set(profile_id == owner),
+            };
+            Identity::insert(identity).exec(&db).await.unwrap();
+
+            let user = GroupUserActiveModel {
+                id: Set(UserId::new()),
+                group_id: Set(group_id),
+                profile_id: Set(profile_id),
+                nickname: Set(None),
+                created_at: Set(None),
+            };
+            GroupUser::insert(user).exec(&db).await.unwrap();
+        }
+
+        Profile::delete_by_id(member).exec(&db).await.unwrap();
+
+        let identities = Identity::find().all(&db).await.unwrap();
+        assert_eq!(identities.len(), 1, "The member's identity should be cascade deleted");
+        assert_eq!(identities[0].profile_id, owner);
+
+        let users = GroupUser::find().all(&db).await.unwrap();
+        assert_eq!(users.len(), 1, "The member's memberships should be cascade deleted");
+        assert_eq!(users[0].profile_id, owner);
+
+        // An identity can't point at a profile that doesn't exist
+        let identity = IdentityActiveModel {
+            node_id: Set(node_id),
+            profile_id: Set(member),
+            is_default: Set(false),
+        };
+        assert!(Identity::insert(identity).exec(&db).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_topic_and_posts() {
         let db = setup_test_db().await;
@@ -275,6 +426,8 @@ mod entity_tests {
             name: Set("Poster".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -282,6 +435,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -290,6 +446,8 @@ mod entity_tests {
             id: Set(user_id),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
         GroupUser::insert(user).exec(&db).await.unwrap();
 
@@ -300,6 +458,9 @@ mod entity_tests {
             group_id: Set(group_id),
             profile_id: Set(profile_id),
             created_at: Set("2024-01-01T00:00:00Z".to_string()),
+            title: Set("Test Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
         };
         GroupTopic::insert(topic).exec(&db).await.unwrap();
 
@@ -313,6 +474,11 @@ mod entity_tests {
             title: Set("First Post".to_string()),
             body: Set("Hello, World!".to_string()),
             created_at: Set("2024-01-01T00:01:00Z".to_string()),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(1),
         };
         GroupPost::insert(post).exec(&db).await.unwrap();
 
@@ -339,6 +505,8 @@ mod entity_tests {
             name: Set("User".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -346,6 +514,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -354,6 +525,8 @@ mod entity_tests {
             id: Set(user_id),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
         GroupUser::insert(user).exec(&db).await.unwrap();
 
@@ -363,6 +536,9 @@ mod entity_tests {
             group_id: Set(group_id),
             profile_id: Set(profile_id),
             created_at: Set("2024-01-01".to_string()),
+            title: Set("Test Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
         };
         GroupTopic::insert(topic).exec(&db).await.unwrap();
 
@@ -376,6 +552,11 @@ mod entity_tests {
                 title: Set(format!("Post {}", i)),
                 body: Set(format!("Body {}", i)),
                 created_at: Set("2024-01-01".to_string()),
+                deleted_at: Set(None),
+                edited_at: Set(None),
+                version: Set(1),
+                format: Set(PostFormat::Plain),
+                seq: Set(i + 1),
             };
             GroupPost::insert(post).exec(&db).await.unwrap();
         }
@@ -415,6 +596,8 @@ mod entity_tests {
             name: Set("User".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -423,6 +606,7 @@ mod entity_tests {
         let identity = IdentityActiveModel {
             node_id: Set(node_id.clone()),
             profile_id: Set(profile_id),
+            is_default: Set(false),
         };
         Identity::insert(identity).exec(&db).await.unwrap();
 
@@ -453,6 +637,8 @@ mod entity_tests {
                 name: Set(format!("Profile {}", i)),
                 desc: Set("Persona".to_string()),
                 picture: Set(None),
+                created_at: Set(None),
+                owner_node_id: Set(None),
             };
             Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -460,6 +646,7 @@ mod entity_tests {
             let identity = IdentityActiveModel {
                 node_id: Set(node_id.clone()),
                 profile_id: Set(profile_id),
+                is_default: Set(false),
             };
             Identity::insert(identity).exec(&db).await.unwrap();
         }
@@ -485,6 +672,8 @@ mod entity_tests {
             name: Set("Exclusive Profile".to_string()),
             desc: Set("Belongs to one identity only".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -493,6 +682,7 @@ mod entity_tests {
         let identity1 = IdentityActiveModel {
             node_id: Set(node_id_1),
             profile_id: Set(profile_id),
+            is_default: Set(false),
         };
         Identity::insert(identity1).exec(&db).await.unwrap();
 
@@ -501,6 +691,7 @@ mod entity_tests {
         let identity2 = IdentityActiveModel {
             node_id: Set(node_id_2),
             profile_id: Set(profile_id), // Same profile!
+            is_default: Set(false),
         };
 
         let result = Identity::insert(identity2).exec(&db).await;
@@ -521,6 +712,8 @@ mod entity_tests {
             name: Set("Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -528,6 +721,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -539,6 +735,8 @@ mod entity_tests {
                 name: Set(format!("Admin {}", i)),
                 desc: Set("Admin".to_string()),
                 picture: Set(None),
+                created_at: Set(None),
+                owner_node_id: Set(None),
             };
             Profile::insert(admin_profile).exec(&db).await.unwrap();
 
@@ -575,6 +773,8 @@ mod entity_tests {
             name: Set("Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -582,6 +782,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -593,6 +796,8 @@ mod entity_tests {
                 name: Set(format!("User {}", i)),
                 desc: Set("User".to_string()),
                 picture: Set(None),
+                created_at: Set(None),
+                owner_node_id: Set(None),
             };
             Profile::insert(user_profile).exec(&db).await.unwrap();
 
@@ -600,6 +805,8 @@ mod entity_tests {
                 id: Set(UserId::new()),
                 group_id: Set(group_id),
                 profile_id: Set(user_profile_id),
+                nickname: Set(None),
+                created_at: Set(None),
             };
             GroupUser::insert(user).exec(&db).await.unwrap();
         }
@@ -629,6 +836,8 @@ mod entity_tests {
             name: Set("Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -636,6 +845,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -646,6 +858,9 @@ mod entity_tests {
                 group_id: Set(group_id),
                 profile_id: Set(profile_id),
                 created_at: Set(format!("2024-01-{:02}", i + 1)),
+                title: Set("Test Topic".to_string()),
+                updated_at: Set(None),
+                pin_order: Set(None),
             };
             GroupTopic::insert(topic).exec(&db).await.unwrap();
         }
@@ -664,6 +879,69 @@ mod entity_tests {
         assert_eq!(topics.len(), 4);
     }
 
+    #[tokio::test]
+    async fn test_find_profile_with_related_topics_and_identities() {
+        let db = setup_test_db().await;
+
+        let profile_id = ProfileId::new();
+        let profile = ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set("Author".to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        };
+        Profile::insert(profile).exec(&db).await.unwrap();
+
+        let node_id = iroh::SecretKey::generate(&mut rand::rng()).public();
+        let identity = IdentityActiveModel {
+            node_id: Set(node_id.as_bytes().to_vec()),
+            profile_id: Set(profile_id),
+            is_default: Set(true),
+        };
+        Identity::insert(identity).exec(&db).await.unwrap();
+
+        let group_id = GroupId::new();
+        let group = GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        };
+        Group::insert(group).exec(&db).await.unwrap();
+
+        for i in 0..2 {
+            let topic = GroupTopicActiveModel {
+                id: Set(TopicId::new()),
+                group_id: Set(group_id),
+                profile_id: Set(profile_id),
+                created_at: Set(format!("2024-01-{:02}", i + 1)),
+                title: Set("Test Topic".to_string()),
+                updated_at: Set(None),
+                pin_order: Set(None),
+            };
+            GroupTopic::insert(topic).exec(&db).await.unwrap();
+        }
+
+        let with_topics = Profile::find_by_id(profile_id)
+            .find_with_related(GroupTopic)
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(with_topics.len(), 1);
+        assert_eq!(with_topics[0].1.len(), 2);
+
+        let with_identities = Profile::find_by_id(profile_id)
+            .find_with_related(Identity)
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(with_identities[0].1.len(), 1);
+        assert_eq!(with_identities[0].1[0].public_key().unwrap(), node_id);
+    }
+
     #[tokio::test]
     async fn test_find_topic_with_related_posts() {
         let db = setup_test_db().await;
@@ -675,6 +953,8 @@ mod entity_tests {
             name: Set("User".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -682,6 +962,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -690,6 +973,8 @@ mod entity_tests {
             id: Set(user_id),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
         };
         GroupUser::insert(user).exec(&db).await.unwrap();
 
@@ -699,6 +984,9 @@ mod entity_tests {
             group_id: Set(group_id),
             profile_id: Set(profile_id),
             created_at: Set("2024-01-01".to_string()),
+            title: Set("Test Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
         };
         GroupTopic::insert(topic).exec(&db).await.unwrap();
 
@@ -712,6 +1000,11 @@ mod entity_tests {
                 title: Set(format!("Post {}", i)),
                 body: Set(format!("Body {}", i)),
                 created_at: Set("2024-01-01".to_string()),
+                deleted_at: Set(None),
+                edited_at: Set(None),
+                version: Set(1),
+                format: Set(PostFormat::Plain),
+                seq: Set(i + 1),
             };
             GroupPost::insert(post).exec(&db).await.unwrap();
         }
@@ -741,6 +1034,8 @@ mod entity_tests {
             name: Set("Owner".to_string()),
             desc: Set("Desc".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&db).await.unwrap();
 
@@ -748,6 +1043,9 @@ mod entity_tests {
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
         };
         Group::insert(group).exec(&db).await.unwrap();
 
@@ -764,4 +1062,109 @@ mod entity_tests {
         assert_eq!(group.id, group_id);
         assert_eq!(admins.len(), 0, "Group should have no admins");
     }
+
+    /// zel always speaks JSON on the wire, so this only guards that the models
+    /// stay usable with a compact codec and records the size win for list
+    /// responses. Uuids go out as 16 raw bytes under postcard vs 38 JSON chars.
+    #[test]
+    fn test_postcard_list_sizes() {
+        let posts: Vec<GroupPostModel> = (0..100)
+            .map(|i| GroupPostModel {
+                id: PostId::new(),
+                user_id: UserId::new(),
+                topic_id: TopicId::new(),
+                parent_post_id: (i % 2 == 0).then(PostId::new),
+                title: format!("Post {i}"),
+                body: "Lorem ipsum dolor sit amet".to_string(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+                deleted_at: None,
+                edited_at: None,
+                version: 1,
+                format: PostFormat::Plain,
+                seq: i + 1,
+            })
+            .collect();
+        let profiles: Vec<ProfileModel> = (0..100)
+            .map(|i| ProfileModel {
+                id: ProfileId::new(),
+                name: format!("User {i}"),
+                desc: "Description".to_string(),
+                picture: Some(vec![0u8; 64]),
+                created_at: None,
+                owner_node_id: None,
+            })
+            .collect();
+
+        let json = serde_json::to_vec(&posts).unwrap();
+        let compact = postcard::to_allocvec(&posts).unwrap();
+        assert!(compact.len() * 2 < json.len(), "{} vs {}", compact.len(), json.len());
+        assert_eq!(postcard::from_bytes::<Vec<GroupPostModel>>(&compact).unwrap(), posts);
+
+        let json = serde_json::to_vec(&profiles).unwrap();
+        let compact = postcard::to_allocvec(&profiles).unwrap();
+        assert!(compact.len() * 2 < json.len(), "{} vs {}", compact.len(), json.len());
+        assert_eq!(postcard::from_bytes::<Vec<ProfileModel>>(&compact).unwrap(), profiles);
+    }
+
+    fn fixed_uuid(n: u128) -> uuid::Uuid {
+        uuid::Uuid::from_u128(n)
+    }
+
+    /// Clients decode these field names; a failure here is a breaking RPC change
+    #[test]
+    fn test_profile_json_shape() {
+        let profile = ProfileModel {
+            id: ProfileId::from_uuid(fixed_uuid(1)),
+            name: "Test User".to_string(),
+            desc: "Description".to_string(),
+            picture: Some(vec![1, 2]),
+            created_at: Some("2024-01-01T00:00:00+00:00".to_string()),
+            owner_node_id: None,
+        };
+
+        let expected = serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000001",
+            "name": "Test User",
+            "desc": "Description",
+            "picture": [1, 2],
+            "created_at": "2024-01-01T00:00:00+00:00",
+        });
+        assert_eq!(serde_json::to_value(&profile).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<ProfileModel>(expected).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_group_post_json_shape() {
+        let post = GroupPostModel {
+            id: PostId::from_uuid(fixed_uuid(1)),
+            user_id: UserId::from_uuid(fixed_uuid(2)),
+            topic_id: TopicId::from_uuid(fixed_uuid(3)),
+            parent_post_id: Some(PostId::from_uuid(fixed_uuid(4))),
+            title: "Title".to_string(),
+            body: "Body".to_string(),
+            created_at: "2024-01-01T00:00:00+00:00".to_string(),
+            deleted_at: None,
+            edited_at: Some("2024-01-02T00:00:00+00:00".to_string()),
+            version: 2,
+            format: PostFormat::Markdown,
+            seq: 7,
+        };
+
+        let expected = serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000001",
+            "user_id": "00000000-0000-0000-0000-000000000002",
+            "topic_id": "00000000-0000-0000-0000-000000000003",
+            "parent_post_id": "00000000-0000-0000-0000-000000000004",
+            "title": "Title",
+            "body": "Body",
+            "created_at": "2024-01-01T00:00:00+00:00",
+            "deleted_at": null,
+            "edited_at": "2024-01-02T00:00:00+00:00",
+            "version": 2,
+            "format": "Markdown",
+            "seq": 7,
+        });
+        assert_eq!(serde_json::to_value(&post).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<GroupPostModel>(expected).unwrap(), post);
+    }
 }
@@ -0,0 +1,61 @@
+use crate::ids::{ProfileId, TopicId};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Which notifications a profile wants from a topic
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum SubscriptionLevel {
+    /// Replies and mentions (the default)
+    #[default]
+    #[sea_orm(string_value = "all")]
+    All,
+    /// Only `@mentions`
+    #[sea_orm(string_value = "mentions")]
+    Mentions,
+    /// Nothing
+    #[sea_orm(string_value = "muted")]
+    Muted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "topic_subscription")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub profile_id: ProfileId,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub topic_id: TopicId,
+    pub level: SubscriptionLevel,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::profile::Entity",
+        from = "Column::ProfileId",
+        to = "super::profile::Column::Id"
+    )]
+    Profile,
+    #[sea_orm(
+        belongs_to = "super::group_topic::Entity",
+        from = "Column::TopicId",
+        to = "super::group_topic::Column::Id"
+    )]
+    GroupTopic,
+}
+
+impl Related<super::profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profile.def()
+    }
+}
+
+impl Related<super::group_topic::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupTopic.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
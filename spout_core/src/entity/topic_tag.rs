@@ -0,0 +1,30 @@
+use crate::ids::TopicId;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "topic_tag")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub topic_id: TopicId,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tag: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group_topic::Entity",
+        from = "Column::TopicId",
+        to = "super::group_topic::Column::Id"
+    )]
+    GroupTopic,
+}
+
+impl Related<super::group_topic::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupTopic.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -2,8 +2,226 @@ use sea_orm::{ColIdx, DbErr, QueryResult, TryFromU64, TryGetError, TryGetable, V
 use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
 use uuid::Uuid;
 
+/// Strategy used by the id types to mint fresh UUIDs.
+///
+/// Production uses [`UuidV7`] (time-ordered). Deployments that don't want to
+/// leak creation time can switch to [`UuidV4`] with [`set_id_source`].
+pub trait IdSource: Send + Sync {
+    fn generate(&self) -> Uuid;
+}
+
+/// Time-ordered UUIDv7 ids (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7;
+
+impl IdSource for UuidV7 {
+    fn generate(&self) -> Uuid {
+        Uuid::now_v7()
+    }
+}
+
+/// Random UUIDv4 ids
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV4;
+
+impl IdSource for UuidV4 {
+    fn generate(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// ULIDs in the same 16 bytes: a 48-bit millisecond timestamp, then 80 random bits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ulid;
+
+impl IdSource for Ulid {
+    fn generate(&self) -> Uuid {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let mut bytes = [0u8; 16];
+        bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+        rand::Rng::fill(&mut rand::rng(), &mut bytes[6..]);
+        Uuid::from_bytes(bytes)
+    }
+}
+
+/// How new ids are minted, and how [`IdFormat::render`] spells them.
+///
+/// Both kinds start with a millisecond timestamp, so ids sort by creation
+/// time either way, as bytes and in their own string form. `Display`, serde
+/// and the database always use the hyphenated UUID form, so switching
+/// formats never changes how existing rows read or travel; only
+/// `to_formatted_string` follows the setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdFormat {
+    #[default]
+    UuidV7,
+    /// 26 Crockford base32 characters
+    Ulid,
+}
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+impl IdFormat {
+    /// Spell `uuid` the way this format writes ids
+    pub fn render(self, uuid: &Uuid) -> String {
+        match self {
+            IdFormat::UuidV7 => uuid.to_string(),
+            IdFormat::Ulid => {
+                let mut value = uuid.as_u128();
+                let mut encoded = [0u8; 26];
+                for digit in encoded.iter_mut().rev() {
+                    *digit = CROCKFORD[(value & 0x1f) as usize];
+                    value >>= 5;
+                }
+                encoded.iter().map(|&digit| digit as char).collect()
+            }
+        }
+    }
+}
+
+/// Decode a ULID string, accepting lowercase and Crockford's `I`/`L`/`O` aliases
+fn decode_ulid(s: &str) -> Option<Uuid> {
+    if s.len() != 26 {
+        return None;
+    }
+    let mut value: u128 = 0;
+    for (i, c) in s.bytes().enumerate() {
+        let c = match c.to_ascii_uppercase() {
+            b'I' | b'L' => b'1',
+            b'O' => b'0',
+            c => c,
+        };
+        let digit = CROCKFORD.iter().position(|&d| d == c)? as u128;
+        // 26 digits carry 130 bits; the first may only use the low 3
+        if i == 0 && digit > 7 {
+            return None;
+        }
+        value = (value << 5) | digit;
+    }
+    Some(Uuid::from_u128(value))
+}
+
+/// Parse an id written as a UUID or a ULID
+fn parse_uuid(s: &str) -> Result<Uuid, uuid::Error> {
+    match decode_ulid(s) {
+        Some(uuid) => Ok(uuid),
+        None => Uuid::parse_str(s),
+    }
+}
+
+static ID_SOURCE: RwLock<Option<Arc<dyn IdSource>>> = RwLock::new(None);
+
+static ID_FORMAT: RwLock<IdFormat> = RwLock::new(IdFormat::UuidV7);
+
+/// Replace the process-wide id source used by every `new()`
+pub fn set_id_source(source: Arc<dyn IdSource>) {
+    *ID_SOURCE.write().unwrap_or_else(|e| e.into_inner()) = Some(source);
+}
+
+/// Restore the default [`UuidV7`] id source
+pub fn reset_id_source() {
+    *ID_SOURCE.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Mint new ids in `format` from now on, and render them that way.
+///
+/// Like [`set_id_source`] this is process-wide, shared by every `SpoutCore`.
+pub fn set_id_format(format: IdFormat) {
+    *ID_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format;
+    match source_for(format) {
+        Some(source) => set_id_source(source),
+        None => reset_id_source(),
+    }
+}
+
+/// The id source `format` mints with, `None` for the default
+fn source_for(format: IdFormat) -> Option<Arc<dyn IdSource>> {
+    match format {
+        IdFormat::UuidV7 => None,
+        IdFormat::Ulid => Some(Arc::new(Ulid)),
+    }
+}
+
+/// The format set by [`set_id_format`]
+pub fn id_format() -> IdFormat {
+    *ID_FORMAT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Mint a UUID from the currently configured id source
+pub fn generate_uuid() -> Uuid {
+    match ID_SOURCE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(source) => source.generate(),
+        None => UuidV7.generate(),
+    }
+}
+
+/// A UUID-backed id that can be parsed from its string form
+pub trait Id: FromStr<Err = uuid::Error> {
+    /// Type name used in parse errors
+    const NAME: &'static str;
+}
+
+impl Id for Uuid {
+    const NAME: &'static str = "Uuid";
+}
+
+/// A string that isn't a valid id of the expected type
+#[derive(Debug, Error)]
+#[error("invalid {kind} {value:?}")]
+pub struct IdParseError {
+    pub kind: &'static str,
+    pub value: String,
+    #[source]
+    pub source: uuid::Error,
+}
+
+/// Parse any id type, keeping the offending string and type in the error
+pub fn parse<T: Id>(s: &str) -> Result<T, IdParseError> {
+    s.parse().map_err(|source| IdParseError {
+        kind: T::NAME,
+        value: s.to_string(),
+        source,
+    })
+}
+
+/// Id types that can travel as their raw 16 UUID bytes.
+///
+/// Implemented by every id type; used by [`compact`].
+pub trait CompactId: Sized {
+    fn to_bytes(&self) -> [u8; 16];
+    fn from_bytes(bytes: [u8; 16]) -> Self;
+}
+
+/// Serde adapter encoding an id as 16 raw bytes instead of a 36-char string.
+///
+/// Opt in per field with `#[serde(with = "crate::ids::compact")]`; the id
+/// types themselves keep the string encoding.
+pub mod compact {
+    use super::CompactId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T: CompactId, S: Serializer>(
+        id: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        id.to_bytes().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: CompactId, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        <[u8; 16]>::deserialize(deserializer).map(T::from_bytes)
+    }
+}
+
 macro_rules! define_id {
     ($name:ident) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -12,7 +230,11 @@ macro_rules! define_id {
 
         impl $name {
             pub fn new() -> Self {
-                Self(Uuid::now_v7())
+                Self(generate_uuid())
+            }
+
+            pub fn new_with(source: &dyn IdSource) -> Self {
+                Self(source.generate())
             }
 
             pub fn from_uuid(uuid: Uuid) -> Self {
@@ -27,8 +249,37 @@ macro_rules! define_id {
                 self.0
             }
 
+            /// Parse a UUID or ULID string
             pub fn parse_str(s: &str) -> Result<Self, uuid::Error> {
-                Ok(Self(Uuid::parse_str(s)?))
+                Ok(Self(parse_uuid(s)?))
+            }
+
+            /// The id in the configured [`IdFormat`], for showing to people
+            pub fn to_formatted_string(&self) -> String {
+                id_format().render(&self.0)
+            }
+
+            /// The raw 16 UUID bytes
+            pub fn to_bytes(&self) -> [u8; 16] {
+                *self.0.as_bytes()
+            }
+
+            pub fn from_bytes(bytes: [u8; 16]) -> Self {
+                Self(Uuid::from_bytes(bytes))
+            }
+        }
+
+        impl Id for $name {
+            const NAME: &'static str = stringify!($name);
+        }
+
+        impl CompactId for $name {
+            fn to_bytes(&self) -> [u8; 16] {
+                $name::to_bytes(self)
+            }
+
+            fn from_bytes(bytes: [u8; 16]) -> Self {
+                $name::from_bytes(bytes)
             }
         }
 
@@ -66,7 +317,7 @@ macro_rules! define_id {
             type Err = uuid::Error;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                Ok(Self(Uuid::parse_str(s)?))
+                Ok(Self(parse_uuid(s)?))
             }
         }
 
@@ -74,7 +325,7 @@ macro_rules! define_id {
             type Error = uuid::Error;
 
             fn try_from(s: String) -> Result<Self, Self::Error> {
-                Ok(Self(Uuid::parse_str(&s)?))
+                Ok(Self(parse_uuid(&s)?))
             }
         }
 
@@ -82,7 +333,7 @@ macro_rules! define_id {
             type Error = uuid::Error;
 
             fn try_from(s: &'a str) -> Result<Self, Self::Error> {
-                Ok(Self(Uuid::parse_str(s)?))
+                Ok(Self(parse_uuid(s)?))
             }
         }
 
@@ -147,10 +398,14 @@ define_id!(GroupId);
 define_id!(UserId);
 define_id!(TopicId);
 define_id!(PostId);
+define_id!(NotificationId);
+define_id!(UploadId);
+define_id!(ReportId);
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_profile_id_creation() {
@@ -159,6 +414,98 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    /// Mints v7 ids like the default source, counting how many it handed out
+    #[derive(Default)]
+    struct CountingV7(AtomicUsize);
+
+    impl IdSource for CountingV7 {
+        fn generate(&self) -> Uuid {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Uuid::now_v7()
+        }
+    }
+
+    #[test]
+    fn test_id_source_default_and_swap() {
+        // Default source is time-ordered v7
+        assert_eq!(ProfileId::new().as_uuid().get_version_num(), 7);
+
+        // The source is process-wide, so the swap keeps minting v7 ids for
+        // tests running alongside
+        let source = Arc::new(CountingV7::default());
+        set_id_source(source.clone());
+        PostId::new();
+        assert!(source.0.load(Ordering::SeqCst) >= 1);
+
+        reset_id_source();
+        let minted = source.0.load(Ordering::SeqCst);
+        assert_eq!(PostId::new().as_uuid().get_version_num(), 7);
+        assert_eq!(source.0.load(Ordering::SeqCst), minted);
+    }
+
+    #[test]
+    fn test_id_format_picks_source() {
+        assert!(source_for(IdFormat::UuidV7).is_none());
+
+        let source = source_for(IdFormat::Ulid).unwrap();
+        let id = PostId::new_with(source.as_ref());
+        let rendered = IdFormat::Ulid.render(id.as_uuid());
+        assert_eq!(rendered.len(), 26);
+        assert_eq!(PostId::parse_str(&rendered).unwrap(), id);
+    }
+
+    #[test]
+    fn test_both_formats_round_trip() {
+        for source in [&UuidV7 as &dyn IdSource, &Ulid] {
+            let id = TopicId::new_with(source);
+            for format in [IdFormat::UuidV7, IdFormat::Ulid] {
+                let rendered = format.render(id.as_uuid());
+                assert_eq!(TopicId::parse_str(&rendered).unwrap(), id);
+                assert_eq!(rendered.parse::<TopicId>().unwrap(), id);
+            }
+            assert_eq!(IdFormat::Ulid.render(id.as_uuid()).len(), 26);
+        }
+
+        // Case and Crockford's look-alike letters don't matter
+        let id = UserId::new_with(&Ulid);
+        let rendered = IdFormat::Ulid.render(id.as_uuid());
+        assert_eq!(UserId::parse_str(&rendered.to_lowercase()).unwrap(), id);
+        let zero = UserId::from_uuid(Uuid::nil());
+        assert_eq!(UserId::parse_str(&"O".repeat(26)).unwrap(), zero);
+
+        // A leading digit above 7 would overflow 128 bits
+        assert!(UserId::parse_str(&"8".repeat(26)).is_err());
+        assert!(UserId::parse_str("not-a-ulid-or-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_ulids_sort_by_creation_time() {
+        let first = Ulid.generate();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = Ulid.generate();
+        assert!(first.as_bytes() < second.as_bytes());
+        assert!(IdFormat::Ulid.render(&first) < IdFormat::Ulid.render(&second));
+    }
+
+    #[test]
+    fn test_existing_uuid_rows_parse_as_before() {
+        // A row written before the switch, in UUID form
+        let stored = "01936b9e-8b2a-7c3d-9e4f-5a6b7c8d9e0f";
+        let id = ProfileId::parse_str(stored).unwrap();
+        assert_eq!(id.to_string(), stored);
+        assert_eq!(ProfileId::parse_str(&IdFormat::Ulid.render(id.as_uuid())).unwrap(), id);
+        assert_eq!(serde_json::to_value(id).unwrap(), stored);
+    }
+
+    #[test]
+    fn test_new_with_explicit_source() {
+        let id = GroupId::new_with(&UuidV4);
+        assert_eq!(id.as_uuid().get_version_num(), 4);
+
+        let id = GroupId::new_with(&UuidV7);
+        assert_eq!(id.as_uuid().get_version_num(), 7);
+    }
+
     #[test]
     fn test_id_conversion() {
         let uuid = Uuid::now_v7();
@@ -175,6 +522,109 @@ mod tests {
         assert_eq!(id, parsed);
     }
 
+    #[test]
+    fn test_parse_helper() {
+        let id = TopicId::new();
+        let parsed: TopicId = parse(&id.to_string()).unwrap();
+        assert_eq!(parsed, id);
+
+        let uuid = Uuid::now_v7();
+        assert_eq!(parse::<Uuid>(&uuid.to_string()).unwrap(), uuid);
+    }
+
+    #[test]
+    fn test_parse_helper_rejects_invalid() {
+        let error = parse::<UserId>("not-a-uuid").unwrap_err();
+        assert_eq!(error.kind, "UserId");
+        assert_eq!(error.value, "not-a-uuid");
+        assert_eq!(error.to_string(), "invalid UserId \"not-a-uuid\"");
+        assert!(std::error::Error::source(&error).is_some());
+
+        assert!(parse::<PostId>("").is_err());
+    }
+
+    /// Randomized inputs per test; enough to hit odd bytes, cheap enough for CI
+    const FUZZ_CASES: usize = 500;
+
+    /// Parse `s` as a `PostId` through every string conversion, one result each
+    fn parse_every_way(s: &str) -> Vec<Option<PostId>> {
+        vec![
+            PostId::parse_str(s).ok(),
+            s.parse::<PostId>().ok(),
+            <PostId as TryFrom<&str>>::try_from(s).ok(),
+            <PostId as TryFrom<String>>::try_from(s.to_string()).ok(),
+            parse::<PostId>(s).ok(),
+        ]
+    }
+
+    /// Deserialize `s` as a JSON string holding a `PostId`
+    fn deserialize(s: &str) -> Option<PostId> {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).ok()
+    }
+
+    #[test]
+    fn test_random_ids_round_trip_every_path() {
+        for _ in 0..FUZZ_CASES {
+            // Any 16 bytes, not just well-formed versions
+            let uuid = Uuid::from_bytes(rand::random());
+            let id = PostId::from_uuid(uuid);
+
+            let displayed = format!("{id}");
+            assert_eq!(displayed, id.to_string());
+            assert_eq!(displayed, uuid.hyphenated().to_string());
+            let ulid = IdFormat::Ulid.render(&uuid);
+            for rendered in [&displayed, &ulid] {
+                assert!(parse_every_way(rendered).iter().all(|parsed| *parsed == Some(id)));
+            }
+
+            // Serde stays in UUID form; ULIDs are for people, not the wire
+            assert_eq!(serde_json::to_value(id).unwrap(), displayed.as_str());
+            assert_eq!(deserialize(&displayed), Some(id));
+            assert_eq!(deserialize(&ulid), None);
+            assert_eq!(PostId::from_bytes(id.to_bytes()), id);
+        }
+    }
+
+    #[test]
+    fn test_malformed_ids_fail_every_path() {
+        use rand::Rng;
+
+        let fixed = [
+            "",
+            " ",
+            "not-a-uuid",
+            "01936b9e-8b2a-7c3d-9e4f-5a6b7c8d9e0",
+            "01936b9e-8b2a-7c3d-9e4f-5a6b7c8d9e0f0",
+            "01936b9e-8b2a-7c3d-9e4f-5a6b7c8d9e0g",
+            " 01936b9e-8b2a-7c3d-9e4f-5a6b7c8d9e0f",
+            "01ARZ3NDEKTSV4RRFFQ69G5FA",
+            "01ARZ3NDEKTSV4RRFFQ69G5FAVV",
+            "01ARZ3NDEKTSV4RRFFQ69G5FA!",
+            "U1ARZ3NDEKTSV4RRFFQ69G5FAV",
+        ];
+        for s in fixed {
+            assert!(parse_every_way(s).iter().all(Option::is_none), "{s:?} parsed");
+            assert_eq!(deserialize(s), None);
+        }
+
+        let mut rng = rand::rng();
+        for _ in 0..FUZZ_CASES {
+            let valid = PostId::from_uuid(Uuid::from_bytes(rng.random())).to_string();
+
+            // Cut short, grown by a character, or with one character made non-hex
+            let truncated = &valid[..rng.random_range(0..valid.len())];
+            let extended = format!("{valid}{}", rng.random_range(0..10));
+            let mut corrupted = valid.clone().into_bytes();
+            corrupted[rng.random_range(0..valid.len())] = b'x';
+            let corrupted = String::from_utf8(corrupted).unwrap();
+
+            for s in [truncated, &extended, &corrupted] {
+                assert!(parse_every_way(s).iter().all(Option::is_none), "{s:?} parsed");
+                assert_eq!(deserialize(s), None);
+            }
+        }
+    }
+
     #[test]
     fn test_id_serialization() {
         let id = TopicId::new();
@@ -183,10 +633,42 @@ mod tests {
         assert_eq!(id, deserialized);
     }
 
+    #[test]
+    fn test_id_bytes_roundtrip() {
+        let id = PostId::new();
+        let bytes = id.to_bytes();
+        assert_eq!(&bytes, id.as_uuid().as_bytes());
+        assert_eq!(PostId::from_bytes(bytes), id);
+    }
+
+    #[test]
+    fn test_compact_serialization() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wire {
+            #[serde(with = "compact")]
+            compact: GroupId,
+            string: GroupId,
+        }
+
+        let wire = Wire {
+            compact: GroupId::new(),
+            string: GroupId::new(),
+        };
+        let json = serde_json::to_value(&wire).unwrap();
+
+        // Compact field is the 16 raw bytes, the default stays a UUID string
+        let bytes: Vec<u8> = serde_json::from_value(json["compact"].clone()).unwrap();
+        assert_eq!(bytes, wire.compact.to_bytes());
+        assert_eq!(json["string"], wire.string.to_string());
+
+        let roundtrip: Wire = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, wire);
+    }
+
     #[test]
     fn test_type_safety() {
         let profile_id = ProfileId::new();
-        let group_id = GroupId::new();
+        let _group_id = GroupId::new();
         
         // This should compile - same type
         let _same: ProfileId = profile_id;
@@ -3,12 +3,29 @@ pub mod ids;
 pub mod models;
 use tokio::sync::OnceCell;
 
-use std::{sync::Arc, time::Duration};
-
-use iroh::Endpoint;
+use std::{
+    future::Future,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use iroh::{endpoint::Connection, Endpoint, EndpointAddr, RelayMode, SecretKey, TransportAddr};
+use sea_orm::{DatabaseConnection, DbErr};
 use zel_core::{prelude::RpcServerBuilder, protocol::RpcClient, IrohBundle};
 
+use crate::config::{RuntimeConfig, SharedRuntimeConfig};
+use crate::service::breaker::DbBreaker;
+use crate::service::clock::{SharedClock, SystemClock};
+use crate::service::groups::{GroupsClient, GroupsServer, GroupsService};
+use crate::service::maintenance::{MaintenanceClient, MaintenanceServer, MaintenanceService};
+use crate::service::posts::{PostsClient, PostsServer, PostsService, PostsServiceError};
 use crate::service::profiles::{ProfilesClient, ProfilesServer, ProfilesService};
+use crate::service::feed::{SharedTopicFeed, TopicFeed};
+use crate::service::sync::{ChangesClient, ChangesServer, SyncService};
+use crate::service::system::{SystemClient, SystemServer, SystemService};
+use crate::service::topics::{TopicsClient, TopicsServer, TopicsService};
+use crate::service::view_buffer::{SharedViewBuffer, ViewBuffer};
 
 pub mod service;
 
@@ -16,8 +33,11 @@ pub mod error;
 
 pub mod config;
 
+pub mod text;
+
 static SPOUT_CORE: OnceCell<Arc<SpoutCore>> = OnceCell::const_new();
 static ALPN: &[u8] = b"spout::0.1.0";
+static PURGE_BATCH_SIZE: u64 = 500;
 
 pub async fn core() -> Arc<SpoutCore> {
     SPOUT_CORE
@@ -26,6 +46,22 @@ pub async fn core() -> Arc<SpoutCore> {
         .clone()
 }
 
+/// Connectivity status of a running [`SpoutCore`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Health {
+    /// The server endpoint reached a relay/home network during startup.
+    pub server_online: bool,
+    /// The UI client endpoint reached a relay/home network during startup.
+    pub client_online: bool,
+}
+
+impl Health {
+    /// Running local-only because an endpoint never came online.
+    pub fn is_degraded(&self) -> bool {
+        !(self.server_online && self.client_online)
+    }
+}
+
 /// Main runtime handle for Spout.
 pub struct SpoutCore {
     pub config: config::SpoutConfig,
@@ -38,11 +74,41 @@ pub struct SpoutCore {
 
     /// Typed clients for the local server.
     pub profiles: ProfilesClient,
+    pub groups: GroupsClient,
+    pub topics: TopicsClient,
+    pub posts: PostsClient,
+    pub maintenance: MaintenanceClient,
+    pub system: SystemClient,
+    pub sync: ChangesClient,
+
+    /// Database shared by the services, for maintenance tasks.
+    db: DatabaseConnection,
+
+    /// Hot-reloadable settings the services read on every call.
+    runtime: SharedRuntimeConfig,
+
+    /// Time source shared with the services.
+    clock: SharedClock,
+
+    /// Buffered post views and the task flushing them, when `buffer_views` is on.
+    view_buffer: Option<(SharedViewBuffer, tokio::task::JoinHandle<()>)>,
+
+    /// Live per-topic post streams every posts service publishes to.
+    topic_feed: SharedTopicFeed,
+
+    health: Health,
 }
 
 impl SpoutCore {
     pub async fn start() -> Result<Self, Box<dyn std::error::Error>> {
         let config = config::get_or_init().await?;
+        Self::start_with_config(config).await
+    }
+
+    /// Start against an already loaded config instead of the platform one
+    pub async fn start_with_config(
+        config: config::SpoutConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         println!("{config:?}");
         // ----------------
         // Server endpoint
@@ -50,41 +116,115 @@ impl SpoutCore {
         let mut server_builder = IrohBundle::builder(Some(config.secret_key.clone())).await?;
         let server_endpoint = server_builder.endpoint().clone();
 
+        // The id format is process-wide; only touch it when this config asks
+        // for a different one, so a second core doesn't reset the first's
+        if config.id_format != ids::id_format() {
+            ids::set_id_format(config.id_format);
+        }
+
         // DB + migrations
         let db = models::open_or_create_db(&config).await;
-        models::migrate_up(db.clone()).await;
+        models::migrate_up(db.clone()).await?;
+
+        // One breaker for every service sharing this DB
+        let db_breaker = DbBreaker::new(config.breaker_config());
+
+        let runtime: SharedRuntimeConfig = Arc::new(RwLock::new(config.runtime_config()));
+        let clock: SharedClock = Arc::new(SystemClock);
+
+        let view_buffer = config.view_buffer_config().map(|buffer_config| {
+            let buffer = Arc::new(ViewBuffer::new(db.clone(), buffer_config));
+            let flusher = buffer.spawn_flusher();
+            (buffer, flusher)
+        });
+        let topic_feed = Arc::new(TopicFeed::new(config.topic_feed_config()));
+
+        let profiles_service = ProfilesService::new(db.clone())
+            .with_breaker(db_breaker.clone())
+            .with_runtime_config(runtime.clone())
+            .with_clock(clock.clone());
+
+        let groups_service = GroupsService::new(db.clone())
+            .with_breaker(db_breaker.clone())
+            .with_runtime_config(runtime.clone())
+            .with_clock(clock.clone());
+
+        let topics_service = TopicsService::new(db.clone())
+            .with_breaker(db_breaker.clone())
+            .with_runtime_config(runtime.clone())
+            .with_clock(clock.clone());
+
+        let mut posts_service = PostsService::new(db.clone())
+            .with_breaker(db_breaker.clone())
+            .with_runtime_config(runtime.clone())
+            .with_clock(clock.clone())
+            .with_feed(topic_feed.clone());
+        if let Some((buffer, _)) = &view_buffer {
+            posts_service = posts_service.with_view_buffer(buffer.clone());
+        }
+
+        // Only the UI client endpoint below may use the maintenance controls
+        let maintenance_service =
+            MaintenanceService::new(db.clone(), runtime.clone(), config.client_secret_key.public());
+
+        let system_service = SystemService::new(server_endpoint.clone());
 
-        let profiles_service = ProfilesService::new(db.clone());
+        let sync_service = SyncService::new(db.clone())
+            .with_breaker(db_breaker.clone())
+            .with_runtime_config(runtime.clone());
 
         // Register RPC servers
         let rpc_server_builder = RpcServerBuilder::new(ALPN, server_endpoint.clone());
 
         let rpc_server_builder = profiles_service.register_service(rpc_server_builder);
+        let rpc_server_builder = groups_service.register_service(rpc_server_builder);
+        let rpc_server_builder = topics_service.register_service(rpc_server_builder);
+        let rpc_server_builder = posts_service.register_service(rpc_server_builder);
+        let rpc_server_builder = maintenance_service.register_service(rpc_server_builder);
+        let rpc_server_builder = system_service.register_service(rpc_server_builder);
+        let rpc_server_builder = sync_service.register_service(rpc_server_builder);
 
         let rpc_server = rpc_server_builder.build();
 
         let server = server_builder.accept(ALPN, rpc_server).finish().await;
 
-        server.wait_online().await;
+        let server_online = wait_online(server.wait_online(), config.online_timeout()).await;
 
         // ----------------
         // Client endpoint (for UI)
         // ----------------
-        let client_endpoint = Endpoint::builder()
-            .secret_key(config.client_secret_key.clone())
-            .alpns(vec![ALPN.to_vec()])
-            .bind()
-            .await?;
+        let (client_endpoint, conn, client_online) = if config.loopback_client {
+            let (endpoint, conn) =
+                connect_loopback(&server, config.client_secret_key.clone()).await?;
+            // Nothing to come online for without a relay
+            (endpoint, conn, true)
+        } else {
+            let client_endpoint = Endpoint::builder()
+                .secret_key(config.client_secret_key.clone())
+                .alpns(vec![ALPN.to_vec()])
+                .bind()
+                .await?;
+
+            let client_online =
+                wait_online(client_endpoint.online(), config.online_timeout()).await;
 
-        client_endpoint.online().await;
+            // Loopback/direct addresses still work without relays
 
-        // Connect client endpoint -> server endpoint
-        let conn = client_endpoint
-            .connect(server.endpoint.addr(), ALPN)
-            .await?;
+            // Connect client endpoint -> server endpoint
+            let conn = client_endpoint
+                .connect(server.endpoint.addr(), ALPN)
+                .await?;
+            (client_endpoint, conn, client_online)
+        };
 
         let rpc = RpcClient::new(conn).await?;
-        let profiles = ProfilesClient::new(rpc);
+        let profiles = ProfilesClient::new(rpc.clone());
+        let groups = GroupsClient::new(rpc.clone());
+        let topics = TopicsClient::new(rpc.clone());
+        let posts = PostsClient::new(rpc.clone());
+        let maintenance = MaintenanceClient::new(rpc.clone());
+        let system = SystemClient::new(rpc.clone());
+        let sync = ChangesClient::new(rpc);
 
         if profiles.list_profiles().await?.is_empty() {
             profiles
@@ -97,10 +237,100 @@ impl SpoutCore {
             server,
             client_endpoint,
             profiles,
+            groups,
+            topics,
+            posts,
+            maintenance,
+            system,
+            sync,
+            db,
+            runtime,
+            clock,
+            view_buffer,
+            topic_feed,
+            health: Health {
+                server_online,
+                client_online,
+            },
         })
     }
 
+    /// Connectivity status recorded at startup
+    pub fn health(&self) -> Health {
+        self.health
+    }
+
+    /// The live topic feed, for posts services built outside `start`
+    pub fn topic_feed(&self) -> SharedTopicFeed {
+        self.topic_feed.clone()
+    }
+
+    /// The server endpoint's current address; encode it with
+    /// [`service::system::encode_node_addr`] to share it with peers.
+    pub fn node_addr(&self) -> EndpointAddr {
+        self.server.endpoint.addr()
+    }
+
+    /// Hard-delete posts that were soft-deleted longer ago than the retention window.
+    ///
+    /// Returns how many soft-deleted posts were purged.
+    pub async fn purge_expired_deletes(&self) -> Result<u64, PostsServiceError> {
+        let retention_days = config::runtime(&self.runtime).soft_delete_retention_days;
+        let retention = chrono::Duration::days(retention_days.into());
+        let cutoff = (self.clock.now() - retention).to_rfc3339();
+
+        PostsService::new(self.db.clone())
+            .with_runtime_config(self.runtime.clone())
+            .with_clock(self.clock.clone())
+            ._purge_deleted_before(cutoff, PURGE_BATCH_SIZE)
+            .await
+    }
+
+    /// Rows pointing at a parent that no longer exists, per relation. Read-only;
+    /// see [`SpoutCore::prune_orphans`] to remove them.
+    pub async fn find_orphans(&self) -> Result<Vec<models::orphans::Orphans>, DbErr> {
+        models::orphans::find_orphans(&self.db).await
+    }
+
+    /// Delete every orphaned row, returning how many were removed
+    pub async fn prune_orphans(&self) -> Result<u64, DbErr> {
+        models::orphans::prune_orphans(&self.db).await
+    }
+
+    /// Re-read `config.json` and apply its hot-reloadable settings (page sizes,
+    /// soft-delete retention, profiles per identity) to the running services.
+    ///
+    /// Keys, the database path and the other startup-only settings keep their
+    /// running values until restart; a warning lists any that changed.
+    pub async fn reload_config(&self) -> Result<RuntimeConfig, Box<dyn std::error::Error>> {
+        let reloaded = config::load(&config::config_path()).await?;
+        Ok(apply_reload(&self.runtime, &self.config, &reloaded))
+    }
+
+    /// Delete all data and start over with an empty database. `config.json`,
+    /// and with it the node and client keys, is kept.
+    ///
+    /// Must not run while any core has the database open, so call it before
+    /// `start` or after `shutdown`. Fails with [`models::ResetError::NotConfirmed`]
+    /// without touching anything unless `confirm` is set.
+    pub async fn factory_reset(confirm: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if !confirm {
+            return Err(models::ResetError::NotConfirmed.into());
+        }
+
+        let config = config::get_or_init().await?;
+        let db = models::open_or_create_db(&config).await;
+        models::reset_db(&db, confirm).await?;
+        db.close().await?;
+        Ok(())
+    }
+
     pub async fn shutdown(self) -> Result<(), Box<dyn std::error::Error>> {
+        // Buffered views must reach the DB before it goes away
+        if let Some((buffer, flusher)) = self.view_buffer {
+            buffer.shutdown(flusher).await?;
+        }
+
         // Close client endpoint
         self.client_endpoint.close().await;
 
@@ -110,6 +340,74 @@ impl SpoutCore {
     }
 }
 
+/// Swap `reloaded`'s hot-reloadable settings into `runtime`, warning about the rest
+fn apply_reload(
+    runtime: &SharedRuntimeConfig,
+    running: &config::SpoutConfig,
+    reloaded: &config::SpoutConfig,
+) -> RuntimeConfig {
+    let ignored = running.restart_only_changes(reloaded);
+    if !ignored.is_empty() {
+        eprintln!("config reload: restart required to apply {}", ignored.join(", "));
+    }
+
+    let mut current = runtime.write().unwrap_or_else(|e| e.into_inner());
+    *current = RuntimeConfig {
+        // A freeze outlives reloads; only the maintenance RPC lifts it
+        posting_frozen: current.posting_frozen,
+        ..reloaded.runtime_config()
+    };
+    *current
+}
+
+/// Connect a client endpoint to the in-process `server` over loopback.
+///
+/// This is not an in-process transport: it still binds a second endpoint and
+/// speaks QUIC to the server. zel's `RpcClient` and `RequestContext` only work
+/// over an iroh `Connection`, and iroh has no in-memory transport to build one
+/// on, so the most this can do is bind to localhost only, never talk to a
+/// relay or discovery service, and dial the server's own sockets directly.
+async fn connect_loopback(
+    server: &IrohBundle,
+    secret_key: SecretKey,
+) -> Result<(Endpoint, Connection), Box<dyn std::error::Error>> {
+    let endpoint = Endpoint::builder()
+        .secret_key(secret_key)
+        .relay_mode(RelayMode::Disabled)
+        .clear_discovery()
+        .bind_addr_v4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+        .bind_addr_v6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 0, 0, 0))
+        .alpns(vec![ALPN.to_vec()])
+        .bind()
+        .await?;
+
+    // The server binds the unspecified address; reach those ports via loopback
+    let loopback = server.endpoint.bound_sockets().into_iter().map(|socket| {
+        let ip = match socket {
+            SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+            SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+        };
+        TransportAddr::Ip(SocketAddr::new(ip, socket.port()))
+    });
+    let server_addr = EndpointAddr::from_parts(server.endpoint.id(), loopback);
+
+    let conn = endpoint.connect(server_addr, ALPN).await?;
+    Ok((endpoint, conn))
+}
+
+/// Wait for an endpoint to come online, giving up after `timeout` if one is set.
+///
+/// Returns whether it came online.
+async fn wait_online(online: impl Future<Output = ()>, timeout: Option<Duration>) -> bool {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, online).await.is_ok(),
+        None => {
+            online.await;
+            true
+        }
+    }
+}
+
 pub mod prelude {
     pub use super::ids;
     pub use super::entity;
@@ -123,3 +421,471 @@ pub mod prelude {
 
     pub use zel_core;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh client endpoint (relays off) connected to `server`
+    async fn connect(server: &IrohBundle) -> (Endpoint, RpcClient) {
+        let endpoint = Endpoint::builder()
+            .secret_key(iroh::SecretKey::generate(&mut rand::rng()))
+            .relay_mode(iroh::RelayMode::Disabled)
+            .alpns(vec![ALPN.to_vec()])
+            .bind()
+            .await
+            .unwrap();
+        let conn = endpoint.connect(server.endpoint.addr(), ALPN).await.unwrap();
+        (endpoint, RpcClient::new(conn).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_wait_online_times_out_into_degraded_mode() {
+        // An endpoint that never reaches a relay
+        let online = wait_online(std::future::pending(), Some(Duration::from_millis(20))).await;
+        assert!(!online);
+
+        let health = Health {
+            server_online: online,
+            client_online: true,
+        };
+        assert!(health.is_degraded());
+    }
+
+    #[tokio::test]
+    async fn test_reload_config_applies_new_page_size() {
+        use crate::{
+            models::migrator::Migrator,
+            service::{groups::GroupsService, topics::TopicsService},
+        };
+        use sea_orm::Database;
+        use sea_orm_migration::MigratorTrait;
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("spout_reload_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.json");
+        let running = config::SpoutConfig::new(dir.clone());
+        tokio::fs::write(&path, serde_json::to_vec(&running).unwrap()).await.unwrap();
+
+        let runtime: SharedRuntimeConfig = Arc::new(RwLock::new(running.runtime_config()));
+        let posts = PostsService::new(db.clone()).with_runtime_config(runtime.clone());
+
+        // A topic with 4 posts
+        let node_id = iroh::SecretKey::generate(&mut rand::rng()).public();
+        let profile = ProfilesService::new(db.clone())
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+        let groups = GroupsService::new(db.clone());
+        let group = groups._create_group(profile.id).await.unwrap();
+        let user = groups._add_user(group.id, profile.id).await.unwrap();
+        let topic = TopicsService::new(db.clone())
+            ._create_topic_with_post(group.id, user.id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap()
+            .topic;
+        for _ in 0..3 {
+            posts
+                ._create_post(user.id, topic.id, "Title".to_string(), "Body".to_string())
+                .await
+                .unwrap();
+        }
+        assert_eq!(posts._list_posts_for_topic(topic.id, 10, 0).await.unwrap().len(), 4);
+
+        // Lower the limit on disk, as an operator would
+        let mut edited: serde_json::Value = serde_json::to_value(&running).unwrap();
+        edited["max_page_size"] = 2.into();
+        edited["database_path"] = "/elsewhere.sqlite".into();
+        tokio::fs::write(&path, serde_json::to_vec(&edited).unwrap()).await.unwrap();
+
+        // An operator freeze survives the reload
+        runtime.write().unwrap().posting_frozen = true;
+
+        let reloaded = config::load(&path).await.unwrap();
+        let applied = apply_reload(&runtime, &running, &reloaded);
+        assert_eq!(applied.max_page_size, 2);
+        assert!(applied.posting_frozen);
+        assert_eq!(posts._list_posts_for_topic(topic.id, 10, 0).await.unwrap().len(), 2);
+        assert_eq!(running.restart_only_changes(&reloaded), vec!["database_path"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_adopt_legacy_data_copies_forward_once() {
+        let root = std::env::temp_dir().join(format!("spout_legacy_{}", ids::ProfileId::new()));
+        let (legacy, current) = (root.join("old"), root.join("new"));
+        tokio::fs::create_dir_all(legacy.join("pictures")).await.unwrap();
+        tokio::fs::create_dir_all(&current).await.unwrap();
+
+        let old_config = config::SpoutConfig::new(legacy.clone());
+        let old_json = serde_json::to_vec(&old_config).unwrap();
+        tokio::fs::write(legacy.join("config.json"), &old_json).await.unwrap();
+        tokio::fs::write(legacy.join("spout_db.sqlite"), b"db").await.unwrap();
+        tokio::fs::write(legacy.join("pictures/a.png"), b"png").await.unwrap();
+
+        // Candidates without data are skipped
+        let candidates = vec![root.join("missing"), legacy.clone()];
+        let adopted = config::adopt_legacy_data(&current, &candidates).await.unwrap();
+        assert_eq!(adopted, Some(legacy.clone()));
+
+        let adopted_config = config::load(&current.join("config.json")).await.unwrap();
+        assert_eq!(adopted_config.database_path, current.join("spout_db.sqlite"));
+        assert_eq!(adopted_config.secret_key.to_bytes(), old_config.secret_key.to_bytes());
+        let db = tokio::fs::read(current.join("spout_db.sqlite")).await.unwrap();
+        assert_eq!(db, b"db");
+        let picture = tokio::fs::read(current.join("pictures/a.png")).await.unwrap();
+        assert_eq!(picture, b"png");
+        // The original stays where it was
+        assert_eq!(tokio::fs::read(legacy.join("config.json")).await.unwrap(), old_json);
+
+        // Once there's data here, nothing is copied over it again
+        tokio::fs::write(legacy.join("spout_db.sqlite"), b"newer").await.unwrap();
+        let adopted = config::adopt_legacy_data(&current, &candidates).await.unwrap();
+        assert_eq!(adopted, None);
+        let db = tokio::fs::read(current.join("spout_db.sqlite")).await.unwrap();
+        assert_eq!(db, b"db");
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_adopt_legacy_data_failing_copy_leaves_current_empty() {
+        let root = std::env::temp_dir().join(format!("spout_legacy_{}", ids::ProfileId::new()));
+        let (legacy, current) = (root.join("old"), root.join("new"));
+        tokio::fs::create_dir_all(&legacy).await.unwrap();
+        tokio::fs::create_dir_all(&current).await.unwrap();
+
+        let old_config = config::SpoutConfig::new(legacy.clone());
+        let old_json = serde_json::to_vec(&old_config).unwrap();
+        tokio::fs::write(legacy.join("config.json"), &old_json).await.unwrap();
+        tokio::fs::write(legacy.join("spout_db.sqlite"), b"db").await.unwrap();
+        // Copying through a dangling link fails somewhere in the middle
+        std::os::unix::fs::symlink(root.join("gone"), legacy.join("broken")).unwrap();
+
+        let candidates = vec![legacy.clone()];
+        assert!(config::adopt_legacy_data(&current, &candidates).await.is_err());
+
+        // Nothing half-copied is left for the next start to mistake for real data
+        let mut entries = tokio::fs::read_dir(&current).await.unwrap();
+        assert!(entries.next_entry().await.unwrap().is_none());
+        assert!(!tokio::fs::try_exists(root.join("new.adopting")).await.unwrap());
+
+        // Once the source is readable again, the next start adopts it in full
+        tokio::fs::remove_file(legacy.join("broken")).await.unwrap();
+        let adopted = config::adopt_legacy_data(&current, &candidates).await.unwrap();
+        assert_eq!(adopted, Some(legacy.clone()));
+        let db = tokio::fs::read(current.join("spout_db.sqlite")).await.unwrap();
+        assert_eq!(db, b"db");
+        let adopted_config = config::load(&current.join("config.json")).await.unwrap();
+        assert_eq!(adopted_config.database_path, current.join("spout_db.sqlite"));
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_zeroed_keys() {
+        let dir = std::env::temp_dir().join(format!("spout_keys_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.json");
+
+        let valid = config::SpoutConfig::new(dir.clone());
+        assert!(valid.validate().is_ok());
+
+        // A hand-edited key, as it would come back from disk
+        for field in ["secret_key", "client_secret_key"] {
+            let mut edited = serde_json::to_value(&valid).unwrap();
+            edited[field] = serde_json::to_value([0u8; 32]).unwrap();
+            tokio::fs::write(&path, serde_json::to_vec(&edited).unwrap()).await.unwrap();
+
+            let loaded = config::load(&path).await.unwrap();
+            let error = loaded.validate().unwrap_err();
+            assert!(matches!(error, config::ConfigError::InvalidKey { field: f } if f == field));
+        }
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_runtime_change_leaves_persisted_config_alone() {
+        let dir = std::env::temp_dir().join(format!("spout_runtime_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("config.json");
+        let persisted = config::SpoutConfig::new(dir.clone());
+        let on_disk = serde_json::to_vec(&persisted).unwrap();
+        tokio::fs::write(&path, &on_disk).await.unwrap();
+
+        let runtime: SharedRuntimeConfig = Arc::new(RwLock::new(persisted.runtime_config()));
+        assert_eq!(config::runtime(&runtime).page_size(1000), persisted.max_page_size);
+
+        runtime.write().unwrap().max_page_size = 5;
+        assert_eq!(config::runtime(&runtime).page_size(1000), 5);
+
+        // Neither the loaded config nor the file saw the change
+        assert_eq!(persisted.runtime_config().page_size(1000), persisted.max_page_size);
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), on_disk);
+        let reloaded = config::load(&path).await.unwrap();
+        assert_eq!(reloaded.max_page_size, persisted.max_page_size);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_profiles_isolated_per_connection() {
+        use crate::models::migrator::Migrator;
+        use sea_orm::Database;
+        use sea_orm_migration::MigratorTrait;
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        // The real server path: zel router + ProfilesService over iroh
+        let mut server_builder = IrohBundle::builder(None).await.unwrap();
+        let rpc_server = ProfilesService::new(db)
+            .register_service(RpcServerBuilder::new(ALPN, server_builder.endpoint().clone()))
+            .build();
+        let server = server_builder.accept(ALPN, rpc_server).finish().await;
+
+        let (alice_endpoint, alice) = connect(&server).await;
+        let (bob_endpoint, bob) = connect(&server).await;
+        let (alice, bob) = (ProfilesClient::new(alice), ProfilesClient::new(bob));
+
+        let (alice_profile, bob_profile) = tokio::join!(
+            alice.create_profile("Alice".to_string(), "Desc".to_string(), None),
+            bob.create_profile("Bob".to_string(), "Desc".to_string(), None),
+        );
+        let (alice_profile, bob_profile) = (alice_profile.unwrap(), bob_profile.unwrap());
+
+        let alice_sees: Vec<_> = alice.list_profiles().await.unwrap();
+        let bob_sees: Vec<_> = bob.list_profiles().await.unwrap();
+        assert_eq!(alice_sees, vec![alice_profile.clone()]);
+        assert_eq!(bob_sees, vec![bob_profile.clone()]);
+
+        // Owner checks hold across connections too
+        assert!(alice.set_default_profile(bob_profile.id).await.is_err());
+
+        alice_endpoint.close().await;
+        bob_endpoint.close().await;
+        server.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_system_node_addr_matches_server_endpoint() {
+        use crate::service::system::decode_node_addr;
+
+        let mut server_builder = IrohBundle::builder(None).await.unwrap();
+        let rpc_server = SystemService::new(server_builder.endpoint().clone())
+            .register_service(RpcServerBuilder::new(ALPN, server_builder.endpoint().clone()))
+            .build();
+        let server = server_builder.accept(ALPN, rpc_server).finish().await;
+
+        let (endpoint, rpc) = connect(&server).await;
+        let encoded = SystemClient::new(rpc).node_addr().await.unwrap();
+        let addr = decode_node_addr(&encoded).unwrap();
+
+        // Same node, reachable on the ports the server actually bound
+        assert_eq!(addr.id, server.endpoint.id());
+        let bound_ports: Vec<u16> =
+            server.endpoint.bound_sockets().iter().map(|socket| socket.port()).collect();
+        assert!(addr.ip_addrs().next().is_some());
+        assert!(addr.ip_addrs().all(|socket| bound_ports.contains(&socket.port())));
+
+        // And the shared form is enough to dial it
+        endpoint.connect(addr, ALPN).await.unwrap();
+
+        endpoint.close().await;
+        server.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_loopback_client_binds_only_localhost() {
+        use crate::models::migrator::Migrator;
+        use sea_orm::Database;
+        use sea_orm_migration::MigratorTrait;
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let mut server_builder = IrohBundle::builder(None).await.unwrap();
+        let rpc_server = ProfilesService::new(db)
+            .register_service(RpcServerBuilder::new(ALPN, server_builder.endpoint().clone()))
+            .build();
+        let server = server_builder.accept(ALPN, rpc_server).finish().await;
+
+        let secret_key = SecretKey::generate(&mut rand::rng());
+        let (endpoint, conn) = connect_loopback(&server, secret_key).await.unwrap();
+
+        // Bound to localhost only and never given a relay
+        assert!(endpoint.bound_sockets().iter().all(|socket| socket.ip().is_loopback()));
+        assert_eq!(endpoint.addr().relay_urls().count(), 0);
+
+        let profiles = ProfilesClient::new(RpcClient::new(conn).await.unwrap());
+        let created = profiles
+            .create_profile("Local".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(profiles.list_profiles().await.unwrap(), vec![created]);
+
+        endpoint.close().await;
+        server.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_rpcs_default_missing_limit_and_offset() {
+        use crate::{
+            models::migrator::Migrator,
+            service::{
+                groups::GroupsService,
+                posts::{PostsClient, PostsServer},
+                topics::{TopicsClient, TopicsServer, TopicsService},
+            },
+        };
+        use sea_orm::Database;
+        use sea_orm_migration::MigratorTrait;
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let runtime = SharedRuntimeConfig::default();
+        runtime.write().unwrap().default_page_size = 3;
+
+        // A topic with 5 posts
+        let node_id = iroh::SecretKey::generate(&mut rand::rng()).public();
+        let profile = ProfilesService::new(db.clone())
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+        let groups = GroupsService::new(db.clone());
+        let group = groups._create_group(profile.id).await.unwrap();
+        let user = groups._add_user(group.id, profile.id).await.unwrap();
+        let topics = TopicsService::new(db.clone()).with_runtime_config(runtime.clone());
+        let topic = topics
+            ._create_topic_with_post(group.id, user.id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap()
+            .topic;
+        let posts = PostsService::new(db.clone()).with_runtime_config(runtime.clone());
+        for _ in 0..4 {
+            posts
+                ._create_post(user.id, topic.id, "Title".to_string(), "Body".to_string())
+                .await
+                .unwrap();
+        }
+
+        let mut server_builder = IrohBundle::builder(None).await.unwrap();
+        let rpc_server = RpcServerBuilder::new(ALPN, server_builder.endpoint().clone());
+        let rpc_server = posts.clone().register_service(rpc_server);
+        let rpc_server = topics.register_service(rpc_server).build();
+        let server = server_builder.accept(ALPN, rpc_server).finish().await;
+
+        let (endpoint, rpc) = connect(&server).await;
+        let posts_client = PostsClient::new(rpc.clone());
+        let topics_client = TopicsClient::new(rpc);
+
+        let all = posts._list_posts_for_topic(topic.id, 10, 0).await.unwrap();
+        assert_eq!(all.len(), 5);
+
+        // No limit/offset: the first default-sized page
+        let page = posts_client.list_posts_for_topic(topic.id, None, None).await.unwrap();
+        assert_eq!(page, all[..3]);
+
+        // Either one alone still works
+        let page = posts_client.list_posts_for_topic(topic.id, None, Some(3)).await.unwrap();
+        assert_eq!(page, all[3..]);
+        let page = posts_client.list_posts_for_topic(topic.id, Some(1), None).await.unwrap();
+        assert_eq!(page, all[..1]);
+
+        let started = topics_client.topics_started_by(profile.id, None, None).await.unwrap();
+        assert_eq!(started.len(), 1);
+
+        endpoint.close().await;
+        server.shutdown(Duration::from_secs(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_started_core_serves_groups_topics_and_posts() {
+        let dir = std::env::temp_dir().join(format!("spout_start_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let mut config = config::SpoutConfig::new(dir.clone());
+        config.loopback_client = true;
+        config.offline_tolerant_startup = true;
+        config.online_timeout_secs = 1;
+
+        let core = SpoutCore::start_with_config(config).await.unwrap();
+        let profile = core.profiles.list_profiles().await.unwrap().remove(0);
+        let group = core.groups.create_group(profile.id).await.unwrap();
+        let user = core.groups.add_user(group.id, profile.id).await.unwrap();
+
+        let topic = core.topics.create_topic(group.id, profile.id, "Title".to_string()).await;
+        let topic = topic.unwrap();
+        assert_eq!(core.topics.get_topic(topic.id).await.unwrap(), topic);
+
+        let created = core
+            .posts
+            .create_post(user.id, topic.id, "Hello".to_string(), "World".to_string())
+            .await
+            .unwrap();
+        assert_eq!(core.posts.get_post(created.id).await.unwrap(), created);
+
+        core.shutdown().await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_started_core_serves_changes_since() {
+        use crate::entity::prelude::{ChangeEntity, ChangeOp};
+
+        let dir = std::env::temp_dir().join(format!("spout_sync_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let mut config = config::SpoutConfig::new(dir.clone());
+        config.loopback_client = true;
+        config.offline_tolerant_startup = true;
+        config.online_timeout_secs = 0;
+
+        let core = SpoutCore::start_with_config(config).await.unwrap();
+        let profile = core.profiles.list_profiles().await.unwrap().remove(0);
+        let group = core.groups.create_group(profile.id).await.unwrap();
+
+        let changes = core.sync.changes_since(0, None).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].entity, changes[0].op), (ChangeEntity::Group, ChangeOp::Create));
+        assert_eq!(changes[0].entity_id, uuid::Uuid::from(group.id));
+        assert!(core.sync.changes_since(changes[0].seq, None).await.unwrap().is_empty());
+
+        core.shutdown().await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offline_tolerant_start_reports_server_offline() {
+        let dir = std::env::temp_dir().join(format!("spout_offline_{}", ids::ProfileId::new()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let mut config = config::SpoutConfig::new(dir.clone());
+        config.loopback_client = true;
+        config.offline_tolerant_startup = true;
+        // No time at all to reach a relay, so startup can't wait for one
+        config.online_timeout_secs = 0;
+
+        let core = SpoutCore::start_with_config(config).await.unwrap();
+        let health = core.health();
+        assert!(!health.server_online);
+        assert!(health.client_online, "The local client has no relay to wait for");
+        assert!(health.is_degraded());
+
+        // Degraded, but still serving the local client
+        assert_eq!(core.profiles.list_profiles().await.unwrap().len(), 1);
+
+        core.shutdown().await.unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_online_without_timeout() {
+        assert!(wait_online(async {}, None).await);
+        assert!(wait_online(async {}, Some(Duration::from_secs(1))).await);
+    }
+}
@@ -2,14 +2,15 @@ pub mod post;
 pub mod topic;
 pub mod user;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{pool::PoolConnection, prelude::*, Any, AnyPool};
+use sqlx::{any::AnyRow, pool::PoolConnection, prelude::*, Any, AnyPool};
 use thiserror::Error;
 use uuid::Uuid;
 
 use crate::{
     error::MigrationError,
-    ids::{GroupId, ProfileId},
+    ids::{parse, GroupId, Id, IdParseError, ProfileId},
 };
 
 #[derive(Debug, Error)]
@@ -18,6 +19,22 @@ pub enum GroupError {
     DatabaseError(#[from] sqlx::Error),
     #[error("invalid uuid")]
     InvalidUuid(#[from] uuid::Error),
+    #[error(transparent)]
+    InvalidId(#[from] IdParseError),
+}
+
+/// Read a string column holding an id, failing with `sqlx::Error::Decode` if it isn't a UUID
+pub(crate) fn try_get_id<T: Id>(row: &AnyRow, column: &str) -> Result<T, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    Ok(parse(&value)?)
+}
+
+/// Read an rfc3339 string column as a UTC timestamp
+pub(crate) fn try_get_timestamp(row: &AnyRow, column: &str) -> Result<DateTime<Utc>, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    let timestamp =
+        DateTime::parse_from_rfc3339(&value).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+    Ok(timestamp.with_timezone(&Utc))
 }
 
 #[derive(Serialize, Deserialize, FromRow)]
@@ -79,11 +96,9 @@ impl Group {
         let group = match row {
             Some(row) => {
                 let id_str: String = row.try_get("id")?;
-                let id =
-                    GroupId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let id: GroupId = parse(&id_str)?;
                 let profile_id_str: String = row.try_get("profile_id")?;
-                let profile_id = ProfileId::parse_str(&profile_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let profile_id: ProfileId = parse(&profile_id_str)?;
 
                 // Load admin identities
                 let admin_rows = sqlx::query(
@@ -100,8 +115,7 @@ impl Group {
                 let mut admin_identities = Vec::new();
                 for row in admin_rows {
                     let identity_id_str: String = row.try_get("identity_id")?;
-                    let identity_id = Uuid::parse_str(&identity_id_str)
-                        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                    let identity_id: Uuid = parse(&identity_id_str)?;
                     admin_identities.push(identity_id);
                 }
 
@@ -120,8 +134,7 @@ impl Group {
                 let mut banned_identities = Vec::new();
                 for row in banned_rows {
                     let identity_id_str: String = row.try_get("identity_id")?;
-                    let identity_id = Uuid::parse_str(&identity_id_str)
-                        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                    let identity_id: Uuid = parse(&identity_id_str)?;
                     banned_identities.push(identity_id);
                 }
 
@@ -255,10 +268,9 @@ impl Group {
         let mut groups = Vec::new();
         for row in rows {
             let id_str: String = row.try_get("id")?;
-            let id = GroupId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let id: GroupId = parse(&id_str)?;
             let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let profile_id: ProfileId = parse(&profile_id_str)?;
 
             // Load admin identities
             let admin_rows = sqlx::query(
@@ -275,8 +287,7 @@ impl Group {
             let mut admin_identities = Vec::new();
             for admin_row in admin_rows {
                 let admin_id_str: String = admin_row.try_get("identity_id")?;
-                let admin_id =
-                    Uuid::parse_str(&admin_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let admin_id: Uuid = parse(&admin_id_str)?;
                 admin_identities.push(admin_id);
             }
 
@@ -295,8 +306,7 @@ impl Group {
             let mut banned_identities = Vec::new();
             for banned_row in banned_rows {
                 let banned_id_str: String = banned_row.try_get("identity_id")?;
-                let banned_id = Uuid::parse_str(&banned_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let banned_id: Uuid = parse(&banned_id_str)?;
                 banned_identities.push(banned_id);
             }
 
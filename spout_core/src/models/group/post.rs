@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{pool::PoolConnection, prelude::*, Any};
+use sqlx::{any::AnyRow, pool::PoolConnection, prelude::*, Any};
 use thiserror::Error;
 
+use super::{try_get_id, try_get_timestamp};
 use crate::ids::{PostId, TopicId, UserId};
 
 #[derive(Debug, Error)]
@@ -39,6 +40,23 @@ pub struct PostView {
     pub created_at: DateTime<Utc>,
 }
 
+impl PostView {
+    /// Decode a row selected with the `PostView` column list
+    pub fn from_row(row: &AnyRow) -> Result<Self, sqlx::Error> {
+        Ok(PostView {
+            id: try_get_id(row, "id")?,
+            user_id: try_get_id(row, "user_id")?,
+            user_profile_name: row.try_get("user_profile_name")?,
+            topic_id: try_get_id(row, "topic_id")?,
+            topic_profile_name: row.try_get("topic_profile_name")?,
+            topic_profile_desc: row.try_get("topic_profile_desc")?,
+            title: row.try_get("title")?,
+            body: row.try_get("body")?,
+            created_at: try_get_timestamp(row, "created_at")?,
+        })
+    }
+}
+
 impl Post {
     pub async fn create<'a, E>(
         user_id: UserId,
@@ -106,49 +124,7 @@ impl Post {
         .fetch_optional(&mut **conn)
         .await?;
 
-        let post = match row {
-            Some(row) => {
-                let id_str: String = row.try_get("id")?;
-                let id =
-                    PostId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let user_id_str: String = row.try_get("user_id")?;
-                let user_id = UserId::parse_str(&user_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let user_profile_name: String = row.try_get("user_profile_name")?;
-
-                let topic_id_str: String = row.try_get("topic_id")?;
-                let topic_id = TopicId::parse_str(&topic_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let topic_profile_name: String = row.try_get("topic_profile_name")?;
-                let topic_profile_desc: String = row.try_get("topic_profile_desc")?;
-
-                let title: String = row.try_get("title")?;
-                let body: String = row.try_get("body")?;
-
-                let created_at_str: String = row.try_get("created_at")?;
-                let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                    .with_timezone(&Utc);
-
-                Some(PostView {
-                    id,
-                    user_id,
-                    user_profile_name,
-                    topic_id,
-                    topic_profile_name,
-                    topic_profile_desc,
-                    title,
-                    body,
-                    created_at,
-                })
-            }
-            None => None,
-        };
-
-        Ok(post)
+        Ok(row.as_ref().map(PostView::from_row).transpose()?)
     }
 
     pub async fn list_for_topic(
@@ -185,46 +161,7 @@ impl Post {
         .fetch_all(&mut **conn)
         .await?;
 
-        let mut posts = Vec::new();
-        for row in rows {
-            let id_str: String = row.try_get("id")?;
-            let id = PostId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let user_id_str: String = row.try_get("user_id")?;
-            let user_id =
-                UserId::parse_str(&user_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let user_profile_name: String = row.try_get("user_profile_name")?;
-
-            let topic_id_str: String = row.try_get("topic_id")?;
-            let topic_id =
-                TopicId::parse_str(&topic_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let topic_profile_name: String = row.try_get("topic_profile_name")?;
-            let topic_profile_desc: String = row.try_get("topic_profile_desc")?;
-
-            let title: String = row.try_get("title")?;
-            let body: String = row.try_get("body")?;
-
-            let created_at_str: String = row.try_get("created_at")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            posts.push(PostView {
-                id,
-                user_id,
-                user_profile_name,
-                topic_id,
-                topic_profile_name,
-                topic_profile_desc,
-                title,
-                body,
-                created_at,
-            });
-        }
-
-        Ok(posts)
+        Ok(rows.iter().map(PostView::from_row).collect::<Result<_, _>>()?)
     }
 
     pub async fn list_for_user(
@@ -261,46 +198,7 @@ impl Post {
         .fetch_all(&mut **conn)
         .await?;
 
-        let mut posts = Vec::new();
-        for row in rows {
-            let id_str: String = row.try_get("id")?;
-            let id = PostId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let user_id_str: String = row.try_get("user_id")?;
-            let user_id =
-                UserId::parse_str(&user_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let user_profile_name: String = row.try_get("user_profile_name")?;
-
-            let topic_id_str: String = row.try_get("topic_id")?;
-            let topic_id =
-                TopicId::parse_str(&topic_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let topic_profile_name: String = row.try_get("topic_profile_name")?;
-            let topic_profile_desc: String = row.try_get("topic_profile_desc")?;
-
-            let title: String = row.try_get("title")?;
-            let body: String = row.try_get("body")?;
-
-            let created_at_str: String = row.try_get("created_at")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            posts.push(PostView {
-                id,
-                user_id,
-                user_profile_name,
-                topic_id,
-                topic_profile_name,
-                topic_profile_desc,
-                title,
-                body,
-                created_at,
-            });
-        }
-
-        Ok(posts)
+        Ok(rows.iter().map(PostView::from_row).collect::<Result<_, _>>()?)
     }
 
     pub async fn delete<'a, E>(id: &PostId, conn: E) -> Result<(), PostError>
@@ -554,6 +452,50 @@ mod test {
         assert!(posts.iter().any(|p| p.topic_id == topic2.id));
     }
 
+    #[tokio::test]
+    async fn maps_post_view_row() {
+        test_utils::init_test_drivers();
+        let pool = test_utils::create_test_db().await;
+
+        let id = PostId::new();
+        let user_id = UserId::new();
+        let topic_id = TopicId::new();
+        let created_at = Utc::now();
+
+        let row = sqlx::query(
+            r#"
+      SELECT
+        ? as id,
+        ? as user_id,
+        'Author' as user_profile_name,
+        ? as topic_id,
+        'Topic' as topic_profile_name,
+        'About' as topic_profile_desc,
+        'Title' as title,
+        'Body' as body,
+        ? as created_at
+      "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(topic_id.to_string())
+        .bind(created_at.to_rfc3339())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let view = PostView::from_row(&row).unwrap();
+        assert_eq!(view.id, id);
+        assert_eq!(view.user_id, user_id);
+        assert_eq!(view.user_profile_name, "Author");
+        assert_eq!(view.topic_id, topic_id);
+        assert_eq!(view.topic_profile_name, "Topic");
+        assert_eq!(view.topic_profile_desc, "About");
+        assert_eq!(view.title, "Title");
+        assert_eq!(view.body, "Body");
+        assert_eq!(view.created_at, created_at);
+    }
+
     #[tokio::test]
     async fn deletes_post() {
         test_utils::init_test_drivers();
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{pool::PoolConnection, prelude::*, Any};
+use sqlx::{any::AnyRow, pool::PoolConnection, prelude::*, Any};
 use thiserror::Error;
 
+use super::{try_get_id, try_get_timestamp};
 use crate::ids::{GroupId, ProfileId, TopicId};
 
 #[derive(Debug, Error)]
@@ -34,6 +35,20 @@ pub struct TopicView {
     pub created_at: DateTime<Utc>,
 }
 
+impl TopicView {
+    /// Decode a row selected with the `TopicView` column list
+    pub fn from_row(row: &AnyRow) -> Result<Self, sqlx::Error> {
+        Ok(TopicView {
+            id: try_get_id(row, "id")?,
+            group_id: try_get_id(row, "group_id")?,
+            profile_id: try_get_id(row, "profile_id")?,
+            profile_name: row.try_get("profile_name")?,
+            profile_desc: row.try_get("profile_desc")?,
+            created_at: try_get_timestamp(row, "created_at")?,
+        })
+    }
+}
+
 impl Topic {
     pub async fn create<'a, E>(
         group_id: GroupId,
@@ -89,41 +104,7 @@ impl Topic {
         .fetch_optional(&mut **conn)
         .await?;
 
-        let topic = match row {
-            Some(row) => {
-                let id_str: String = row.try_get("id")?;
-                let id =
-                    TopicId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let group_id_str: String = row.try_get("group_id")?;
-                let group_id = GroupId::parse_str(&group_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let profile_id_str: String = row.try_get("profile_id")?;
-                let profile_id = ProfileId::parse_str(&profile_id_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-                let profile_name: String = row.try_get("profile_name")?;
-                let profile_desc: String = row.try_get("profile_desc")?;
-
-                let created_at_str: String = row.try_get("created_at")?;
-                let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                    .with_timezone(&Utc);
-
-                Some(TopicView {
-                    id,
-                    group_id,
-                    profile_id,
-                    profile_name,
-                    profile_desc,
-                    created_at,
-                })
-            }
-            None => None,
-        };
-
-        Ok(topic)
+        Ok(row.as_ref().map(TopicView::from_row).transpose()?)
     }
 
     pub async fn list_for_group(
@@ -154,38 +135,7 @@ impl Topic {
         .fetch_all(&mut **conn)
         .await?;
 
-        let mut topics = Vec::new();
-        for row in rows {
-            let id_str: String = row.try_get("id")?;
-            let id = TopicId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let group_id_str: String = row.try_get("group_id")?;
-            let group_id =
-                GroupId::parse_str(&group_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let profile_name: String = row.try_get("profile_name")?;
-            let profile_desc: String = row.try_get("profile_desc")?;
-
-            let created_at_str: String = row.try_get("created_at")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            topics.push(TopicView {
-                id,
-                group_id,
-                profile_id,
-                profile_name,
-                profile_desc,
-                created_at,
-            });
-        }
-
-        Ok(topics)
+        Ok(rows.iter().map(TopicView::from_row).collect::<Result<_, _>>()?)
     }
 
     pub async fn latest_for_group(
@@ -224,38 +174,7 @@ impl Topic {
         .fetch_all(&mut **conn)
         .await?;
 
-        let mut topics = Vec::new();
-        for row in rows {
-            let id_str: String = row.try_get("id")?;
-            let id = TopicId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let group_id_str: String = row.try_get("group_id")?;
-            let group_id =
-                GroupId::parse_str(&group_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-
-            let profile_name: String = row.try_get("profile_name")?;
-            let profile_desc: String = row.try_get("profile_desc")?;
-
-            let created_at_str: String = row.try_get("created_at")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            topics.push(TopicView {
-                id,
-                group_id,
-                profile_id,
-                profile_name,
-                profile_desc,
-                created_at,
-            });
-        }
-
-        Ok(topics)
+        Ok(rows.iter().map(TopicView::from_row).collect::<Result<_, _>>()?)
     }
 
     pub async fn delete<'a, E>(id: &TopicId, conn: E) -> Result<(), TopicError>
@@ -488,4 +407,53 @@ mod test {
         let fetched = Topic::by_id(&topic.id, &mut conn).await.unwrap();
         assert!(fetched.is_none());
     }
+
+    #[tokio::test]
+    async fn maps_topic_view_row() {
+        test_utils::init_test_drivers();
+        let pool = test_utils::create_test_db().await;
+
+        let id = TopicId::new();
+        let group_id = GroupId::new();
+        let profile_id = ProfileId::new();
+        let created_at = Utc::now();
+
+        let select = r#"
+      SELECT
+        ? as id,
+        ? as group_id,
+        ? as profile_id,
+        'Topic' as profile_name,
+        'About' as profile_desc,
+        ? as created_at
+      "#;
+
+        let row = sqlx::query(select)
+            .bind(id.to_string())
+            .bind(group_id.to_string())
+            .bind(profile_id.to_string())
+            .bind(created_at.to_rfc3339())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let view = TopicView::from_row(&row).unwrap();
+        assert_eq!(view.id, id);
+        assert_eq!(view.group_id, group_id);
+        assert_eq!(view.profile_id, profile_id);
+        assert_eq!(view.profile_name, "Topic");
+        assert_eq!(view.profile_desc, "About");
+        assert_eq!(view.created_at, created_at);
+
+        // A malformed id is a decode error, not a panic
+        let row = sqlx::query(select)
+            .bind("not-a-uuid")
+            .bind(group_id.to_string())
+            .bind(profile_id.to_string())
+            .bind(created_at.to_rfc3339())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(matches!(TopicView::from_row(&row), Err(sqlx::Error::Decode(_))));
+    }
 }
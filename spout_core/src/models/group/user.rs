@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{pool::PoolConnection, prelude::*, Any};
 use thiserror::Error;
 
-use crate::ids::{GroupId, ProfileId, UserId};
+use crate::ids::{parse, GroupId, IdParseError, ProfileId, UserId};
 
 #[derive(Debug, Error)]
 pub enum UserError {
@@ -10,6 +10,8 @@ pub enum UserError {
     DatabaseError(#[from] sqlx::Error),
     #[error("invalid uuid")]
     InvalidUuid(#[from] uuid::Error),
+    #[error(transparent)]
+    InvalidId(#[from] IdParseError),
 }
 
 #[derive(Serialize, Deserialize, FromRow)]
@@ -92,13 +94,11 @@ impl User {
         let mut users = Vec::new();
         for row in rows {
             let id_str: String = row.try_get("id")?;
-            let id = UserId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let id: UserId = parse(&id_str)?;
             let group_id_str: String = row.try_get("group_id")?;
-            let group_id =
-                GroupId::parse_str(&group_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let group_id: GroupId = parse(&group_id_str)?;
             let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let profile_id: ProfileId = parse(&profile_id_str)?;
 
             users.push(User {
                 id,
@@ -128,13 +128,11 @@ impl User {
         let mut users = Vec::new();
         for row in rows {
             let id_str: String = row.try_get("id")?;
-            let id = UserId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let id: UserId = parse(&id_str)?;
             let group_id_str: String = row.try_get("group_id")?;
-            let group_id =
-                GroupId::parse_str(&group_id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let group_id: GroupId = parse(&group_id_str)?;
             let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let profile_id: ProfileId = parse(&profile_id_str)?;
 
             users.push(User {
                 id,
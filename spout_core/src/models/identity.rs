@@ -3,7 +3,11 @@ use serde::{Deserialize, Serialize};
 use sqlx::{pool::PoolConnection, prelude::*, Any, AnyPool};
 use thiserror::Error;
 
-use crate::{error::MigrationError, identity::migrations::create_identities_table, ids::ProfileId};
+use crate::{
+    error::MigrationError,
+    identity::migrations::create_identities_table,
+    ids::{parse, IdParseError, ProfileId},
+};
 
 #[derive(Debug, Error)]
 pub enum IdentityError {
@@ -13,6 +17,8 @@ pub enum IdentityError {
     InvalidPublicKey(#[from] iroh::KeyParsingError),
     #[error("invalid uuid")]
     InvalidUuid(#[from] uuid::Error),
+    #[error(transparent)]
+    InvalidId(#[from] IdParseError),
 }
 
 #[derive(Serialize, Deserialize, FromRow)]
@@ -82,7 +88,7 @@ impl Identity {
             let node_id = PublicKey::from_bytes(&node_id_arr)?;
 
             let profile_id_str: String = row.try_get("profile_id")?;
-            let profile_id = ProfileId::parse_str(&profile_id_str)?;
+            let profile_id: ProfileId = parse(&profile_id_str)?;
 
             identities.push(Identity {
                 node_id,
@@ -1,3 +1,4 @@
+use sea_orm::{ConnectionTrait, Statement};
 use sea_orm_migration::prelude::*;
 
 mod m20251212_000001_create_identity_table;
@@ -8,9 +9,62 @@ mod m20251212_000005_create_group_banned_table;
 mod m20251212_000006_create_group_users_table;
 mod m20251212_000007_create_group_topics_table;
 mod m20251212_000008_create_group_posts_table;
+mod m20251212_000009_add_identity_is_default;
+mod m20251212_000010_add_group_post_deleted_at;
+mod m20251212_000011_create_notifications_table;
+mod m20251212_000012_add_group_archived;
+mod m20251212_000013_create_profile_state_table;
+mod m20251212_000014_create_post_view_history_table;
+mod m20251212_000015_extend_group_posts;
+mod m20251212_000016_add_group_post_seq;
+mod m20251212_000017_create_topic_subscriptions_table;
+mod m20251212_000018_add_group_topic_title;
+mod m20251212_000019_add_group_user_nickname;
+mod m20251212_000020_create_topic_tags_table;
+mod m20251212_000021_add_group_topic_pin_order;
+mod m20251212_000022_create_direct_messages_table;
+mod m20251212_000023_add_group_post_listing_indexes;
+mod m20251212_000024_create_post_reports_table;
+mod m20251212_000025_add_created_at_columns;
+mod m20251212_000026_create_change_log_table;
+mod m20251212_000027_add_identity_profile_fk;
+mod m20251212_000028_add_profile_owner_node_id;
+mod m20251212_000029_create_rate_state_table;
 
 pub struct Migrator;
 
+/// Where migration 5 moves a `group_banned` table left by the sqlx models
+const LEGACY_GROUP_BANNED: &str = "group_banned_sqlx";
+
+/// Free the name `index` for `table` when the sqlx models, whose tables are
+/// named in the plural but whose index names match ours, already used it.
+///
+/// The sqlx setup creates its indexes `IF NOT EXISTS`, so on a shared database
+/// it goes without the ones reclaimed here.
+async fn reclaim_index_name(
+    manager: &SchemaManager<'_>,
+    index: &str,
+    table: &str,
+) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let owner = db
+        .query_one(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "SELECT tbl_name FROM sqlite_master WHERE type = 'index' AND name = ?",
+            [index.into()],
+        ))
+        .await?
+        .map(|row| row.try_get::<String>("", "tbl_name"))
+        .transpose()?;
+
+    if owner.is_some_and(|owner| owner != table) {
+        manager
+            .drop_index(Index::drop().name(index).to_owned())
+            .await?;
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
@@ -23,6 +77,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20251212_000006_create_group_users_table::Migration),
             Box::new(m20251212_000007_create_group_topics_table::Migration),
             Box::new(m20251212_000008_create_group_posts_table::Migration),
+            Box::new(m20251212_000009_add_identity_is_default::Migration),
+            Box::new(m20251212_000010_add_group_post_deleted_at::Migration),
+            Box::new(m20251212_000011_create_notifications_table::Migration),
+            Box::new(m20251212_000012_add_group_archived::Migration),
+            Box::new(m20251212_000013_create_profile_state_table::Migration),
+            Box::new(m20251212_000014_create_post_view_history_table::Migration),
+            Box::new(m20251212_000015_extend_group_posts::Migration),
+            Box::new(m20251212_000016_add_group_post_seq::Migration),
+            Box::new(m20251212_000017_create_topic_subscriptions_table::Migration),
+            Box::new(m20251212_000018_add_group_topic_title::Migration),
+            Box::new(m20251212_000019_add_group_user_nickname::Migration),
+            Box::new(m20251212_000020_create_topic_tags_table::Migration),
+            Box::new(m20251212_000021_add_group_topic_pin_order::Migration),
+            Box::new(m20251212_000022_create_direct_messages_table::Migration),
+            Box::new(m20251212_000023_add_group_post_listing_indexes::Migration),
+            Box::new(m20251212_000024_create_post_reports_table::Migration),
+            Box::new(m20251212_000025_add_created_at_columns::Migration),
+            Box::new(m20251212_000026_create_change_log_table::Migration),
+            Box::new(m20251212_000027_add_identity_profile_fk::Migration),
+            Box::new(m20251212_000028_add_profile_owner_node_id::Migration),
+            Box::new(m20251212_000029_create_rate_state_table::Migration),
         ]
     }
 }
@@ -45,6 +120,217 @@ async fn test_migrations_okay() -> Result<(), DbErr> {
     assert!(schema_manager.has_table("group_user").await?);
     assert!(schema_manager.has_table("group_topic").await?);
     assert!(schema_manager.has_table("group_post").await?);
+    assert!(schema_manager.has_table("notification").await?);
+    assert!(schema_manager.has_table("profile_state").await?);
+    assert!(schema_manager.has_table("post_view_history").await?);
+    assert!(schema_manager.has_table("topic_subscription").await?);
+    assert!(schema_manager.has_table("topic_tag").await?);
+    assert!(schema_manager.has_table("direct_topic").await?);
+    assert!(schema_manager.has_table("direct_message").await?);
+    assert!(schema_manager.has_table("post_report").await?);
+    assert!(schema_manager.has_table("rate_state").await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extend_group_posts_backfills_existing_rows() -> Result<(), DbErr> {
+    use sea_orm::{ConnectionTrait, Statement};
+
+    let db = Database::connect("sqlite::memory:").await?;
+    let before = Migrator::migrations()
+        .iter()
+        .position(|m| m.name() == "m20251212_000015_extend_group_posts")
+        .expect("migration registered") as u32;
+
+    // Stop just before the extension and insert a post the old way
+    Migrator::up(&db, Some(before)).await?;
+    db.execute_unprepared("PRAGMA foreign_keys = OFF").await?;
+    db.execute_unprepared(
+        "INSERT INTO group_post (id, user_id, topic_id, title, body, created_at) \
+         VALUES (X'01', X'02', X'03', 'Old', 'Body', '2025-12-12T00:00:00Z')",
+    )
+    .await?;
+
+    Migrator::up(&db, None).await?;
+
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT edited_at, version, format FROM group_post",
+        ))
+        .await?
+        .expect("post should survive the migration");
+    assert_eq!(row.try_get::<Option<String>>("", "edited_at")?, None);
+    assert_eq!(row.try_get::<i32>("", "version")?, 1);
+    assert_eq!(row.try_get::<String>("", "format")?, "plain");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_identity_profile_fk_drops_orphaned_identities() -> Result<(), DbErr> {
+    use sea_orm::{ConnectionTrait, Statement};
+
+    let db = Database::connect("sqlite::memory:").await?;
+    let before = Migrator::migrations()
+        .iter()
+        .position(|m| m.name() == "m20251212_000027_add_identity_profile_fk")
+        .expect("migration registered") as u32;
+
+    // One identity with its profile, one whose profile was deleted without it
+    Migrator::up(&db, Some(before)).await?;
+    db.execute_unprepared("INSERT INTO profile (id, name, desc) VALUES (X'01', 'Kept', 'Desc')")
+        .await?;
+    db.execute_unprepared(
+        "INSERT INTO identity (node_id, profile_id, is_default) \
+         VALUES (X'AA', X'01', TRUE), (X'AA', X'02', FALSE)",
+    )
+    .await?;
+
+    Migrator::up(&db, None).await?;
+
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT profile_id, is_default FROM identity",
+        ))
+        .await?;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].try_get::<Vec<u8>>("", "profile_id")?, vec![0x01]);
+    assert!(rows[0].try_get::<bool>("", "is_default")?);
+
+    // Deleting the profile now takes its identity with it
+    db.execute_unprepared("DELETE FROM profile WHERE id = X'01'").await?;
+    let remaining = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT COUNT(*) AS n FROM identity",
+        ))
+        .await?
+        .expect("count row");
+    assert_eq!(remaining.try_get::<i64>("", "n")?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_profile_owner_node_id_backfilled_from_identity() -> Result<(), DbErr> {
+    use sea_orm::{ConnectionTrait, Statement};
+
+    let db = Database::connect("sqlite::memory:").await?;
+    let before = Migrator::migrations()
+        .iter()
+        .position(|m| m.name() == "m20251212_000028_add_profile_owner_node_id")
+        .expect("migration registered") as u32;
+
+    Migrator::up(&db, Some(before)).await?;
+    db.execute_unprepared(
+        "INSERT INTO profile (id, name, desc) VALUES (X'01', 'Linked', 'Desc'), \
+         (X'02', 'Unlinked', 'Desc')",
+    )
+    .await?;
+    db.execute_unprepared(
+        "INSERT INTO identity (node_id, profile_id, is_default) VALUES (X'AA', X'01', TRUE)",
+    )
+    .await?;
+
+    Migrator::up(&db, None).await?;
+
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT owner_node_id FROM profile ORDER BY id",
+        ))
+        .await?;
+    let owners: Vec<Option<Vec<u8>>> = rows
+        .iter()
+        .map(|row| row.try_get("", "owner_node_id"))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(owners, vec![Some(vec![0xAA]), None]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_over_sqlx_schema() -> Result<(), DbErr> {
+    async fn count(db: &sea_orm::DatabaseConnection, table: &str) -> Result<i64, DbErr> {
+        let sql = format!("SELECT COUNT(*) AS n FROM {table}");
+        let row = db
+            .query_one(Statement::from_string(db.get_database_backend(), sql))
+            .await?
+            .expect("count row");
+        row.try_get("", "n")
+    }
+
+    let db = Database::connect("sqlite::memory:").await?;
+
+    // What the sqlx models' migrations leave behind
+    for ddl in [
+        "CREATE TABLE profiles (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL, \
+         desc TEXT NOT NULL, picture BLOB)",
+        "CREATE TABLE identities (node_id BLOB NOT NULL, profile_id TEXT NOT NULL, \
+         PRIMARY KEY (node_id, profile_id))",
+        "CREATE TABLE groups (id TEXT PRIMARY KEY NOT NULL, profile_id TEXT NOT NULL)",
+        "CREATE INDEX idx_groups_profile_id ON groups(profile_id)",
+        "CREATE TABLE group_admins (group_id TEXT NOT NULL, identity_id TEXT NOT NULL, \
+         PRIMARY KEY (group_id, identity_id))",
+        "CREATE INDEX idx_group_admins_identity_id ON group_admins(identity_id)",
+        "CREATE TABLE group_banned (group_id TEXT NOT NULL, identity_id TEXT NOT NULL, \
+         PRIMARY KEY (group_id, identity_id))",
+        "CREATE INDEX idx_group_banned_identity_id ON group_banned(identity_id)",
+        "CREATE TABLE group_users (id TEXT PRIMARY KEY NOT NULL, group_id TEXT NOT NULL, \
+         profile_id TEXT NOT NULL, UNIQUE(group_id, profile_id))",
+        "CREATE INDEX idx_group_users_group_id ON group_users(group_id)",
+        "CREATE INDEX idx_group_users_profile_id ON group_users(profile_id)",
+        "CREATE TABLE group_topics (id TEXT PRIMARY KEY NOT NULL, group_id TEXT NOT NULL, \
+         profile_id TEXT NOT NULL, created_at TEXT NOT NULL)",
+        "CREATE INDEX idx_group_topics_group_id ON group_topics(group_id)",
+        "CREATE INDEX idx_group_topics_profile_id ON group_topics(profile_id)",
+        "CREATE INDEX idx_group_topics_created_at ON group_topics(created_at)",
+        "CREATE TABLE group_posts (id TEXT PRIMARY KEY NOT NULL, user_id TEXT NOT NULL, \
+         topic_id TEXT NOT NULL, title TEXT NOT NULL, body TEXT NOT NULL, \
+         created_at TEXT NOT NULL)",
+        "CREATE INDEX idx_group_posts_topic_id ON group_posts(topic_id)",
+        "CREATE INDEX idx_group_posts_user_id ON group_posts(user_id)",
+        "CREATE INDEX idx_group_posts_created_at ON group_posts(created_at)",
+        "INSERT INTO group_banned VALUES ('legacy-group', 'legacy-identity')",
+    ] {
+        db.execute_unprepared(ddl).await?;
+    }
+
+    Migrator::up(&db, None).await?;
+    assert!(Migrator::get_pending_migrations(&db).await?.is_empty());
+
+    // Every shared index name now belongs to the SeaORM table
+    let owners = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name, tbl_name FROM sqlite_master WHERE type = 'index' \
+             AND name LIKE 'idx_group%' AND tbl_name IN \
+             ('groups', 'group_admins', 'group_banned_sqlx', 'group_users', \
+              'group_topics', 'group_posts')",
+        ))
+        .await?;
+    assert!(owners.is_empty(), "sqlx tables still hold {} index names", owners.len());
+    let reclaimed = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT tbl_name FROM sqlite_master WHERE name = 'idx_groups_profile_id'",
+        ))
+        .await?
+        .expect("index recreated");
+    assert_eq!(reclaimed.try_get::<String>("", "tbl_name")?, "group");
+
+    // The old bans are kept aside; the new table starts empty
+    assert_eq!(count(&db, LEGACY_GROUP_BANNED).await?, 1);
+    assert_eq!(count(&db, "group_banned").await?, 0);
+
+    // The sqlx tables themselves are left alone
+    let schema_manager = SchemaManager::new(&db);
+    for table in ["profiles", "identities", "groups", "group_posts"] {
+        assert!(schema_manager.has_table(table).await?, "{table}");
+    }
 
     Ok(())
 }
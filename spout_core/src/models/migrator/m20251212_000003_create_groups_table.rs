@@ -17,6 +17,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on profile_id
+        super::reclaim_index_name(manager, "idx_groups_profile_id", "group").await?;
         manager
             .create_index(
                 Index::create()
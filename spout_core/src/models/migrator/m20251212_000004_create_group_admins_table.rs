@@ -32,6 +32,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on identity_id
+        super::reclaim_index_name(manager, "idx_group_admins_identity_id", "group_admin").await?;
         manager
             .create_index(
                 Index::create()
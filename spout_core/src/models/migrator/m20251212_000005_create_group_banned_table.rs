@@ -8,6 +8,18 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The old sqlx models used this name too, with text ids and no foreign
+        // key. Move such a table aside instead of adopting it.
+        if manager.has_table("group_banned").await? {
+            manager
+                .rename_table(
+                    Table::rename()
+                        .table(GroupBanned::Table, Alias::new(super::LEGACY_GROUP_BANNED))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
         manager
             .create_table(
                 Table::create()
@@ -32,6 +44,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on identity_id
+        super::reclaim_index_name(manager, "idx_group_banned_identity_id", "group_banned").await?;
         manager
             .create_index(
                 Index::create()
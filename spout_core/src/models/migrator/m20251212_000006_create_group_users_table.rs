@@ -50,6 +50,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on group_id
+        super::reclaim_index_name(manager, "idx_group_users_group_id", "group_user").await?;
         manager
             .create_index(
                 Index::create()
@@ -61,6 +62,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on profile_id
+        super::reclaim_index_name(manager, "idx_group_users_profile_id", "group_user").await?;
         manager
             .create_index(
                 Index::create()
@@ -38,6 +38,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on group_id
+        super::reclaim_index_name(manager, "idx_group_topics_group_id", "group_topic").await?;
         manager
             .create_index(
                 Index::create()
@@ -49,6 +50,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on profile_id
+        super::reclaim_index_name(manager, "idx_group_topics_profile_id", "group_topic").await?;
         manager
             .create_index(
                 Index::create()
@@ -60,6 +62,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on created_at
+        super::reclaim_index_name(manager, "idx_group_topics_created_at", "group_topic").await?;
         manager
             .create_index(
                 Index::create()
@@ -49,6 +49,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on topic_id
+        super::reclaim_index_name(manager, "idx_group_posts_topic_id", "group_post").await?;
         manager
             .create_index(
                 Index::create()
@@ -60,6 +61,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on user_id
+        super::reclaim_index_name(manager, "idx_group_posts_user_id", "group_post").await?;
         manager
             .create_index(
                 Index::create()
@@ -71,6 +73,7 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Create index on created_at
+        super::reclaim_index_name(manager, "idx_group_posts_created_at", "group_post").await?;
         manager
             .create_index(
                 Index::create()
@@ -0,0 +1,48 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000001_create_identity_table::Identity;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Flag which of a node's profiles is its default/primary one.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Identity::Table)
+                    .add_column(boolean(IdentityDefault::IsDefault).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing nodes: their first linked profile (the seeded "Default") becomes the default
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE identity SET is_default = TRUE \
+                 WHERE rowid IN (SELECT MIN(rowid) FROM identity GROUP BY node_id)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Identity::Table)
+                    .drop_column(IdentityDefault::IsDefault)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdentityDefault {
+    IsDefault,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Soft-delete marker for posts; NULL means live.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .add_column(timestamp_null(GroupPostDeletedAt::DeletedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Retention purges scan by deleted_at
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_group_posts_deleted_at")
+                    .table(GroupPost::Table)
+                    .col(GroupPostDeletedAt::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_group_posts_deleted_at")
+                    .table(GroupPost::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .drop_column(GroupPostDeletedAt::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupPostDeletedAt {
+    DeletedAt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .col(pk_uuid(Notification::Id))
+                    .col(uuid(Notification::RecipientProfileId))
+                    .col(string(Notification::Kind))
+                    .col(uuid(Notification::SourcePostId))
+                    .col(boolean(Notification::Read).default(false))
+                    .col(timestamp(Notification::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-recipient_profile_id")
+                            .from(Notification::Table, Notification::RecipientProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-source_post_id")
+                            .from(Notification::Table, Notification::SourcePostId)
+                            .to(GroupPost::Table, GroupPost::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Inbox queries are per recipient, newest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notifications_recipient_created_at")
+                    .table(Notification::Table)
+                    .col(Notification::RecipientProfileId)
+                    .col(Notification::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notification::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Notification {
+    Table,
+    Id,
+    RecipientProfileId,
+    Kind,
+    SourcePostId,
+    Read,
+    CreatedAt,
+}
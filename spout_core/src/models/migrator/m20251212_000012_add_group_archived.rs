@@ -0,0 +1,57 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000003_create_groups_table::Group;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Archived groups stay readable but accept no new topics or posts.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only takes one column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Group::Table)
+                    .add_column(boolean(GroupArchive::Archived).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Group::Table)
+                    .add_column(timestamp_null(GroupArchive::ArchivedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Group::Table)
+                    .drop_column(GroupArchive::ArchivedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Group::Table)
+                    .drop_column(GroupArchive::Archived)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupArchive {
+    Archived,
+    ArchivedAt,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+use super::m20251212_000003_create_groups_table::Group;
+use super::m20251212_000007_create_group_topics_table::GroupTopic;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProfileState::Table)
+                    .col(pk_uuid(ProfileState::ProfileId))
+                    .col(uuid_null(ProfileState::LastGroupId))
+                    .col(uuid_null(ProfileState::LastTopicId))
+                    .col(timestamp(ProfileState::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-profile-state-profile_id")
+                            .from(ProfileState::Table, ProfileState::ProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    // A deleted group/topic just clears the resume point
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-profile-state-last_group_id")
+                            .from(ProfileState::Table, ProfileState::LastGroupId)
+                            .to(Group::Table, Group::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-profile-state-last_topic_id")
+                            .from(ProfileState::Table, ProfileState::LastTopicId)
+                            .to(GroupTopic::Table, GroupTopic::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProfileState::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum ProfileState {
+    Table,
+    ProfileId,
+    LastGroupId,
+    LastTopicId,
+    UpdatedAt,
+}
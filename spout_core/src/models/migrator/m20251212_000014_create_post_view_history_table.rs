@@ -0,0 +1,72 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000006_create_group_users_table::GroupUser;
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostViewHistory::Table)
+                    .col(uuid(PostViewHistory::UserId))
+                    .col(uuid(PostViewHistory::PostId))
+                    .col(timestamp(PostViewHistory::ViewedAt))
+                    // Re-viewing a post bumps its row instead of adding one
+                    .index(
+                        Index::create()
+                            .primary()
+                            .col(PostViewHistory::UserId)
+                            .col(PostViewHistory::PostId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post-view-history-user_id")
+                            .from(PostViewHistory::Table, PostViewHistory::UserId)
+                            .to(GroupUser::Table, GroupUser::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post-view-history-post_id")
+                            .from(PostViewHistory::Table, PostViewHistory::PostId)
+                            .to(GroupPost::Table, GroupPost::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // History is read and trimmed per user, newest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_post_view_history_user_viewed_at")
+                    .table(PostViewHistory::Table)
+                    .col(PostViewHistory::UserId)
+                    .col(PostViewHistory::ViewedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostViewHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum PostViewHistory {
+    Table,
+    UserId,
+    PostId,
+    ViewedAt,
+}
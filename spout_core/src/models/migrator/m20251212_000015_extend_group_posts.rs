@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Edit tracking and body format for posts. `deleted_at` already landed in
+    // m20251212_000010. The defaults backfill existing rows: never edited,
+    // first version, plain text.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .add_column(timestamp_null(GroupPostEdits::EditedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .add_column(integer(GroupPostEdits::Version).default(1))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .add_column(string(GroupPostEdits::Format).default("plain"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            GroupPostEdits::Format,
+            GroupPostEdits::Version,
+            GroupPostEdits::EditedAt,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(GroupPost::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupPostEdits {
+    EditedAt,
+    Version,
+    Format,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Per-topic post number (#1, #2, ...) for stable references.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .add_column(big_integer(GroupPostSeq::Seq).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Number existing posts in creation order within their topic
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE group_post SET seq = (
+                    SELECT COUNT(*) FROM group_post AS earlier
+                    WHERE earlier.topic_id = group_post.topic_id
+                      AND (earlier.created_at < group_post.created_at
+                        OR (earlier.created_at = group_post.created_at
+                          AND earlier.rowid <= group_post.rowid))
+                )",
+            )
+            .await?;
+
+        // Also what makes two racing writers fail instead of sharing a number
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_group_posts_topic_seq")
+                    .table(GroupPost::Table)
+                    .col(GroupPost::TopicId)
+                    .col(GroupPostSeq::Seq)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_group_posts_topic_seq")
+                    .table(GroupPost::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupPost::Table)
+                    .drop_column(GroupPostSeq::Seq)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupPostSeq {
+    Seq,
+}
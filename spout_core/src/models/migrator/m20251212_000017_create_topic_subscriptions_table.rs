@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+use super::m20251212_000007_create_group_topics_table::GroupTopic;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Per-topic notification preference; no row means `all`.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TopicSubscription::Table)
+                    .col(uuid(TopicSubscription::ProfileId))
+                    .col(uuid(TopicSubscription::TopicId))
+                    .col(string(TopicSubscription::Level))
+                    .index(
+                        Index::create()
+                            .primary()
+                            .col(TopicSubscription::ProfileId)
+                            .col(TopicSubscription::TopicId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-topic-subscription-profile_id")
+                            .from(TopicSubscription::Table, TopicSubscription::ProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-topic-subscription-topic_id")
+                            .from(TopicSubscription::Table, TopicSubscription::TopicId)
+                            .to(GroupTopic::Table, GroupTopic::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TopicSubscription::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum TopicSubscription {
+    Table,
+    ProfileId,
+    TopicId,
+    Level,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000007_create_group_topics_table::GroupTopic;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Topics get their own renamable title instead of borrowing the opening
+    // post's. `updated_at` stays NULL until the first rename.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupTopic::Table)
+                    .add_column(string(GroupTopicTitle::Title).default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GroupTopic::Table)
+                    .add_column(timestamp_null(GroupTopicTitle::UpdatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing topics keep the title of their opening post
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE group_topic SET title = COALESCE((
+                    SELECT title FROM group_post
+                    WHERE group_post.topic_id = group_topic.id
+                      AND group_post.parent_post_id IS NULL
+                    ORDER BY group_post.seq, group_post.created_at
+                    LIMIT 1
+                ), '')",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [GroupTopicTitle::UpdatedAt, GroupTopicTitle::Title] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(GroupTopic::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupTopicTitle {
+    Title,
+    UpdatedAt,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000007_create_group_topics_table::GroupTopic;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Free-form topic tags, stored normalized (trimmed, lowercase).
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TopicTag::Table)
+                    .col(uuid(TopicTag::TopicId))
+                    .col(string(TopicTag::Tag))
+                    .index(
+                        Index::create()
+                            .primary()
+                            .col(TopicTag::TopicId)
+                            .col(TopicTag::Tag),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-topic-tag-topic_id")
+                            .from(TopicTag::Table, TopicTag::TopicId)
+                            .to(GroupTopic::Table, GroupTopic::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TopicTag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum TopicTag {
+    Table,
+    TopicId,
+    Tag,
+}
@@ -0,0 +1,136 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Private two-profile conversations, kept apart from group topics and posts
+    // so they never show up in group listings.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DirectTopic::Table)
+                    .col(pk_uuid(DirectTopic::Id))
+                    // The pair is stored ordered (low < high) so it is unique
+                    .col(uuid(DirectTopic::LowProfileId))
+                    .col(uuid(DirectTopic::HighProfileId))
+                    .col(timestamp(DirectTopic::CreatedAt))
+                    .col(timestamp(DirectTopic::LastMessageAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-direct-topic-low_profile_id")
+                            .from(DirectTopic::Table, DirectTopic::LowProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-direct-topic-high_profile_id")
+                            .from(DirectTopic::Table, DirectTopic::HighProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_direct_topic_pair_unique")
+                    .table(DirectTopic::Table)
+                    .col(DirectTopic::LowProfileId)
+                    .col(DirectTopic::HighProfileId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // The unique index covers lookups by the low side; this one the high side
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_direct_topic_high_profile_id")
+                    .table(DirectTopic::Table)
+                    .col(DirectTopic::HighProfileId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DirectMessage::Table)
+                    .col(pk_uuid(DirectMessage::Id))
+                    .col(uuid(DirectMessage::TopicId))
+                    .col(uuid(DirectMessage::ProfileId))
+                    .col(string(DirectMessage::Body))
+                    .col(timestamp(DirectMessage::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-direct-message-topic_id")
+                            .from(DirectMessage::Table, DirectMessage::TopicId)
+                            .to(DirectTopic::Table, DirectTopic::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-direct-message-profile_id")
+                            .from(DirectMessage::Table, DirectMessage::ProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Messages are read per topic in conversation order
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_direct_message_topic_created_at")
+                    .table(DirectMessage::Table)
+                    .col(DirectMessage::TopicId)
+                    .col(DirectMessage::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DirectMessage::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DirectTopic::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum DirectTopic {
+    Table,
+    Id,
+    LowProfileId,
+    HighProfileId,
+    CreatedAt,
+    LastMessageAt,
+}
+
+#[derive(DeriveIden)]
+pub enum DirectMessage {
+    Table,
+    Id,
+    TopicId,
+    ProfileId,
+    Body,
+    CreatedAt,
+}
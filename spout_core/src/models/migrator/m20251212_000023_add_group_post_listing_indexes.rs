@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+const TOPIC_CREATED_AT: &str = "idx_group_posts_topic_created_at";
+const USER_CREATED_AT: &str = "idx_group_posts_user_created_at";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Topic and user post listings filter on one column and sort by created_at;
+    // the single-column indexes left sqlite sorting every match in a temp b-tree.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name(TOPIC_CREATED_AT)
+                    .table(GroupPost::Table)
+                    .col(GroupPost::TopicId)
+                    .col(GroupPost::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_CREATED_AT)
+                    .table(GroupPost::Table)
+                    .col(GroupPost::UserId)
+                    .col(GroupPost::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [TOPIC_CREATED_AT, USER_CREATED_AT] {
+            manager
+                .drop_index(Index::drop().name(name).table(GroupPost::Table).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+use super::m20251212_000008_create_group_posts_table::GroupPost;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Posts flagged for the group's admins to review.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostReport::Table)
+                    .col(pk_uuid(PostReport::Id))
+                    .col(uuid(PostReport::PostId))
+                    .col(uuid(PostReport::ReporterProfileId))
+                    .col(string(PostReport::Reason))
+                    .col(string(PostReport::Status).default("open"))
+                    .col(timestamp(PostReport::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post-report-post_id")
+                            .from(PostReport::Table, PostReport::PostId)
+                            .to(GroupPost::Table, GroupPost::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post-report-reporter_profile_id")
+                            .from(PostReport::Table, PostReport::ReporterProfileId)
+                            .to(Profile::Table, Profile::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One open report per reporter and post; closed ones don't count.
+        // Partial index, which sea-query's builder can't express
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE UNIQUE INDEX idx_post_report_open_unique \
+                 ON post_report (post_id, reporter_profile_id) WHERE status = 'open'",
+            )
+            .await?;
+
+        // Moderation queues list a group's reports by status
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_post_report_status_created_at")
+                    .table(PostReport::Table)
+                    .col(PostReport::Status)
+                    .col(PostReport::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostReport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum PostReport {
+    Table,
+    Id,
+    PostId,
+    ReporterProfileId,
+    Reason,
+    Status,
+    CreatedAt,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+use super::m20251212_000003_create_groups_table::Group;
+use super::m20251212_000006_create_group_users_table::GroupUser;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Creation times for profiles, groups and memberships. Rows from before
+    // this migration have no known time and stay NULL.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(timestamp_null(CreatedAt::CreatedAt))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(CreatedAt::CreatedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+fn tables() -> [DynIden; 3] {
+    [Profile::Table.into_iden(), Group::Table.into_iden(), GroupUser::Table.into_iden()]
+}
+
+#[derive(DeriveIden)]
+enum CreatedAt {
+    CreatedAt,
+}
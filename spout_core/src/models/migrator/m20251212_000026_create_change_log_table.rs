@@ -0,0 +1,42 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Append-only record of group, topic and post writes for incremental sync.
+    // No foreign keys: entries outlive the rows they describe.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChangeLog::Table)
+                    .col(pk_auto(ChangeLog::Seq))
+                    .col(string(ChangeLog::Entity))
+                    .col(string(ChangeLog::Op))
+                    .col(uuid(ChangeLog::EntityId))
+                    .col(text(ChangeLog::Payload))
+                    .col(timestamp(ChangeLog::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChangeLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum ChangeLog {
+    Table,
+    Seq,
+    Entity,
+    Op,
+    EntityId,
+    Payload,
+    CreatedAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{
+    m20251212_000001_create_identity_table::Identity,
+    m20251212_000002_create_profiles_table::Profile,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Where the rebuilt table is filled before it replaces `identity`
+const REBUILT_IDENTITY: &str = "identity_rebuilt";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // identity predates profile, so it never got a foreign key and deleting a
+    // profile left its identity behind. sqlite can't add one in place: rebuild
+    // the table with it, dropping links to profiles that are already gone.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_identity(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_identity(manager, false).await
+    }
+}
+
+async fn rebuild_identity(manager: &SchemaManager<'_>, with_profile_fk: bool) -> Result<(), DbErr> {
+    let mut table = Table::create()
+        .table(Alias::new(REBUILT_IDENTITY))
+        .col(binary(Identity::NodeId))
+        .col(uuid_uniq(Identity::ProfileId))
+        .col(boolean(IdentityDefault::IsDefault).default(false))
+        .primary_key(Index::create().col(Identity::NodeId).col(Identity::ProfileId))
+        .to_owned();
+    if with_profile_fk {
+        table.foreign_key(
+            ForeignKey::create()
+                .name("fk-identity-profile_id")
+                .from(Alias::new(REBUILT_IDENTITY), Identity::ProfileId)
+                .to(Profile::Table, Profile::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .on_update(ForeignKeyAction::Cascade),
+        );
+    }
+    manager.create_table(table).await?;
+
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "INSERT INTO {REBUILT_IDENTITY} (node_id, profile_id, is_default) \
+             SELECT node_id, profile_id, is_default FROM identity \
+             WHERE profile_id IN (SELECT id FROM profile)"
+        ))
+        .await?;
+
+    manager.drop_table(Table::drop().table(Identity::Table).to_owned()).await?;
+    manager
+        .rename_table(
+            Table::rename()
+                .table(Alias::new(REBUILT_IDENTITY), Identity::Table)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum IdentityDefault {
+    IsDefault,
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20251212_000002_create_profiles_table::Profile;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // A copy of each profile's owning node id, so a lost identity table can be
+    // rebuilt. Existing profiles take it from their current identity row.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Profile::Table)
+                    .add_column(binary_null(ProfileOwner::OwnerNodeId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE profile SET owner_node_id = \
+                 (SELECT node_id FROM identity WHERE identity.profile_id = profile.id)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Profile::Table)
+                    .drop_column(ProfileOwner::OwnerNodeId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProfileOwner {
+    OwnerNodeId,
+}
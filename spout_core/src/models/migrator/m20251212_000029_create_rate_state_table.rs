@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Per-node token buckets for rate-limited actions, kept in the database so
+    // a restart doesn't refill them. No foreign key: nodes have no table.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RateState::Table)
+                    .col(binary(RateState::NodeId).primary_key())
+                    .col(double(RateState::Tokens))
+                    .col(timestamp(RateState::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RateState::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum RateState {
+    Table,
+    NodeId,
+    Tokens,
+    UpdatedAt,
+}
@@ -1,21 +1,314 @@
-use sea_orm::{Database, DatabaseConnection};
+use std::time::Duration;
+
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DatabaseTransaction, DbErr,
+    Statement, TransactionTrait,
+};
+use sqlx::sqlite::SqliteJournalMode;
 use sea_orm_migration::MigratorTrait;
+use thiserror::Error;
+use tokio::time::Instant;
 
-use crate::config::SpoutConfig;
+use crate::{config::SpoutConfig, ids::IdParseError};
 
 pub mod migrator;
+pub mod orphans;
+#[cfg(test)]
+pub(crate) mod query_plan;
+
+/// How long `migrate_up` waits for another process to finish migrating
+pub const MIGRATION_LOCK_WAIT: Duration = Duration::from_secs(30);
+
+/// A lock held this long belongs to a process that died mid-migration.
+///
+/// Shorter than [`MIGRATION_LOCK_WAIT`] so a waiter can take over a dead
+/// process's lock instead of timing out behind it.
+const MIGRATION_LOCK_STALE_SECS: i64 = 20;
+const _: () = assert!(MIGRATION_LOCK_STALE_SECS < MIGRATION_LOCK_WAIT.as_secs() as i64);
+
+const MIGRATION_LOCK_POLL: Duration = Duration::from_millis(50);
+
+/// How long a connection waits on another writer before "database is locked"
+pub const DB_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pooled connections per core; WAL lets them read while one of them writes
+const DB_MAX_CONNECTIONS: u32 = 4;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum MigrateError {
+    #[error("fatal database error")]
+    DbError(#[from] DbErr),
+
+    #[error("another process is still migrating this database (gave up after {0:?})")]
+    LockTimeout(Duration),
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ResetError {
+    #[error("factory reset needs explicit confirmation")]
+    NotConfirmed,
+
+    #[error("fatal database error")]
+    DbError(#[from] DbErr),
+
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+}
+
+/// Row decoding reports a bad id like any other undecodable column
+impl From<IdParseError> for sqlx::Error {
+    fn from(error: IdParseError) -> Self {
+        sqlx::Error::Decode(Box::new(error))
+    }
+}
 
 pub async fn open_or_create_db(config: &SpoutConfig) -> DatabaseConnection {
     // Use display() to convert PathBuf to string representation
     let connection_string = format!("sqlite://{}?mode=rwc", config.database_path.display());
 
-    Database::connect(&connection_string)
+    open_db(&connection_string)
         .await
         .expect("Failed to connect to database")
 }
 
-pub async fn migrate_up(db: DatabaseConnection) {
-    migrator::Migrator::up(&db, None)
+/// Connect to an on-disk sqlite database in WAL mode.
+///
+/// The UI and the server may both hold pools on the same file, so writers
+/// wait up to [`DB_BUSY_TIMEOUT`] for each other instead of failing at once.
+pub async fn open_db(url: &str) -> Result<DatabaseConnection, DbErr> {
+    let mut options = ConnectOptions::new(url);
+    options
+        .max_connections(DB_MAX_CONNECTIONS)
+        .sqlx_logging(false)
+        .map_sqlx_sqlite_opts(|opts| {
+            opts.journal_mode(SqliteJournalMode::Wal).busy_timeout(DB_BUSY_TIMEOUT)
+        });
+    Database::connect(options).await
+}
+
+/// Begin a transaction that holds sqlite's write lock from its first statement.
+///
+/// A plain `BEGIN` only takes the lock at the first write, and a transaction
+/// that has already read can't wait for it: once another connection commits
+/// in between, it fails with "database is locked" regardless of
+/// [`DB_BUSY_TIMEOUT`]. Writing first (what `BEGIN IMMEDIATE` would do, which
+/// SeaORM doesn't issue) makes concurrent writers queue instead.
+pub async fn begin_write(db: &DatabaseConnection) -> Result<DatabaseTransaction, DbErr> {
+    let txn = db.begin().await?;
+    txn.execute_unprepared("UPDATE seaql_migrations SET version = version WHERE 0")
+        .await?;
+    Ok(txn)
+}
+
+/// Run pending migrations, waiting up to [`MIGRATION_LOCK_WAIT`] for another process
+pub async fn migrate_up(db: DatabaseConnection) -> Result<(), MigrateError> {
+    migrate_up_locked(&db, MIGRATION_LOCK_WAIT).await?;
+    Ok(())
+}
+
+/// Run pending migrations while holding the `migration_lock` row.
+///
+/// Two cores sharing a sqlite file (CLI + GUI) would otherwise race each
+/// other through the same migrations. Waits up to `wait` for the other
+/// process, then fails with [`MigrateError::LockTimeout`]. Returns how many
+/// migrations this call applied.
+pub async fn migrate_up_locked(
+    db: &DatabaseConnection,
+    wait: Duration,
+) -> Result<usize, MigrateError> {
+    // Lives outside the migrator: it has to exist before any migration runs
+    db.execute_unprepared(
+        "CREATE TABLE IF NOT EXISTS migration_lock (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            locked INTEGER NOT NULL DEFAULT 0,
+            locked_at INTEGER
+        )",
+    )
+    .await?;
+    db.execute_unprepared("INSERT OR IGNORE INTO migration_lock (id, locked) VALUES (1, 0)")
+        .await?;
+
+    let deadline = Instant::now() + wait;
+    while !try_lock_migrations(db).await? {
+        if Instant::now() >= deadline {
+            return Err(MigrateError::LockTimeout(wait));
+        }
+        tokio::time::sleep(MIGRATION_LOCK_POLL).await;
+    }
+
+    let applied = apply_pending_migrations(db).await;
+
+    // Released even when a migration failed so the next start can retry
+    db.execute_unprepared("UPDATE migration_lock SET locked = 0, locked_at = NULL WHERE id = 1")
+        .await?;
+
+    Ok(applied?)
+}
+
+/// Drop every table in `db` and migrate it back up to an empty current schema.
+///
+/// Does nothing and fails with [`ResetError::NotConfirmed`] unless `confirm`
+/// is set. Nothing else may be using `db` meanwhile.
+pub async fn reset_db(db: &DatabaseConnection, confirm: bool) -> Result<(), ResetError> {
+    if !confirm {
+        return Err(ResetError::NotConfirmed);
+    }
+
+    // Drops the sqlx-era and lock tables too, not only the migrator's own
+    migrator::Migrator::fresh(db).await?;
+    // Brings back the lock table; nothing is pending after `fresh`
+    migrate_up_locked(db, MIGRATION_LOCK_WAIT).await?;
+    Ok(())
+}
+
+/// Take the lock if it's free or stale; `false` means someone else holds it
+async fn try_lock_migrations(db: &DatabaseConnection) -> Result<bool, DbErr> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "UPDATE migration_lock
+             SET locked = 1, locked_at = CAST(strftime('%s', 'now') AS INTEGER)
+             WHERE id = 1
+               AND (locked = 0 OR locked_at < CAST(strftime('%s', 'now') AS INTEGER) - ?)",
+            [MIGRATION_LOCK_STALE_SECS.into()],
+        ))
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+async fn apply_pending_migrations(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let pending = migrator::Migrator::get_pending_migrations(db).await?.len();
+    migrator::Migrator::up(db, None).await?;
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh sqlite file under the temp dir and its connection url
+    fn temp_db(name: &str) -> (std::path::PathBuf, String) {
+        let file = format!("{name}_{}.sqlite", crate::ids::ProfileId::new());
+        let path = std::env::temp_dir().join(file);
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        (path, url)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_migrate_applies_once() {
+        let (path, url) = temp_db("spout_migrate_race");
+        let first = Database::connect(&url).await.unwrap();
+        let second = Database::connect(&url).await.unwrap();
+
+        let (a, b) = tokio::join!(
+            migrate_up_locked(&first, MIGRATION_LOCK_WAIT),
+            migrate_up_locked(&second, MIGRATION_LOCK_WAIT),
+        );
+        let mut applied = [a.unwrap(), b.unwrap()];
+        applied.sort();
+
+        // One caller did all the work, the other found nothing pending
+        let total = migrator::Migrator::migrations().len();
+        assert_eq!(applied, [0, total]);
+
+        let row = first
+            .query_one(Statement::from_string(
+                first.get_database_backend(),
+                "SELECT COUNT(*) AS applied, COUNT(DISTINCT version) AS distinct_versions
+                 FROM seaql_migrations",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.try_get::<i64>("", "applied").unwrap(), total as i64);
+        assert_eq!(row.try_get::<i64>("", "distinct_versions").unwrap(), total as i64);
+        assert!(migrator::Migrator::get_pending_migrations(&second).await.unwrap().is_empty());
+
+        first.close().await.unwrap();
+        second.close().await.unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_reset_db_empties_tables_and_keeps_schema() {
+        use sea_orm::{ActiveModelTrait, EntityTrait, PaginatorTrait, Set};
+        use sea_orm_migration::SchemaManager;
+
+        use crate::entity::prelude::{Profile, ProfileActiveModel};
+
+        let (path, url) = temp_db("spout_reset");
+        let db = Database::connect(&url).await.unwrap();
+        migrate_up_locked(&db, MIGRATION_LOCK_WAIT).await.unwrap();
+
+        ProfileActiveModel {
+            id: Set(crate::ids::ProfileId::new()),
+            name: Set("Seed".to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let result = reset_db(&db, false).await;
+        assert!(matches!(result, Err(ResetError::NotConfirmed)));
+        assert_eq!(Profile::find().count(&db).await.unwrap(), 1);
+
+        reset_db(&db, true).await.unwrap();
+        assert_eq!(Profile::find().count(&db).await.unwrap(), 0);
+
+        let schema_manager = SchemaManager::new(&db);
+        for table in ["profile", "group", "group_post", "post_report", "migration_lock"] {
+            assert!(schema_manager.has_table(table).await.unwrap(), "{table}");
+        }
+        assert!(migrator::Migrator::get_pending_migrations(&db).await.unwrap().is_empty());
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_fails_fast_while_locked() {
+        let (path, url) = temp_db("spout_migrate_locked");
+        let db = Database::connect(&url).await.unwrap();
+
+        // Create the lock table, then hold the lock like another process would
+        migrate_up_locked(&db, MIGRATION_LOCK_WAIT).await.unwrap();
+        assert!(try_lock_migrations(&db).await.unwrap());
+
+        let result = migrate_up_locked(&db, Duration::from_millis(200)).await;
+        assert!(matches!(result, Err(MigrateError::LockTimeout(_))));
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_takes_over_stale_lock() {
+        let (path, url) = temp_db("spout_migrate_stale");
+        let db = Database::connect(&url).await.unwrap();
+
+        // Left behind by a process that died mid-migration
+        migrate_up_locked(&db, MIGRATION_LOCK_WAIT).await.unwrap();
+        db.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "UPDATE migration_lock
+             SET locked = 1, locked_at = CAST(strftime('%s', 'now') AS INTEGER) - ?
+             WHERE id = 1",
+            [(MIGRATION_LOCK_STALE_SECS + 1).into()],
+        ))
         .await
-        .expect("Failed to run migrations");
+        .unwrap();
+
+        assert_eq!(migrate_up_locked(&db, Duration::from_millis(200)).await.unwrap(), 0);
+        assert!(try_lock_migrations(&db).await.unwrap(), "The lock is released again");
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(path);
+    }
 }
@@ -0,0 +1,313 @@
+//! Rows whose parent is gone.
+//!
+//! Foreign keys are enforced on every connection we open, but databases
+//! edited by hand or created before enforcement can still hold rows pointing
+//! at deleted groups, topics or profiles.
+
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use serde::{Deserialize, Serialize};
+
+use super::begin_write;
+
+/// A reference from `table.column` to `parent.id` that every row must satisfy
+struct ParentLink {
+    table: &'static str,
+    column: &'static str,
+    parent: &'static str,
+}
+
+const fn link(table: &'static str, column: &'static str, parent: &'static str) -> ParentLink {
+    ParentLink {
+        table,
+        column,
+        parent,
+    }
+}
+
+/// The relations checked, parents before children so one pass of
+/// `prune_orphans` catches most cascades
+const PARENT_LINKS: &[ParentLink] = &[
+    link("group", "profile_id", "profile"),
+    link("identity", "profile_id", "profile"),
+    link("group_admin", "group_id", "group"),
+    link("group_admin", "identity_id", "profile"),
+    link("group_banned", "group_id", "group"),
+    link("group_banned", "identity_id", "profile"),
+    link("group_user", "group_id", "group"),
+    link("group_user", "profile_id", "profile"),
+    link("group_topic", "group_id", "group"),
+    link("group_topic", "profile_id", "profile"),
+    link("group_post", "topic_id", "group_topic"),
+    link("group_post", "user_id", "group_user"),
+    link("group_post", "parent_post_id", "group_post"),
+    link("notification", "recipient_profile_id", "profile"),
+    link("notification", "source_post_id", "group_post"),
+    link("post_report", "post_id", "group_post"),
+    link("post_report", "reporter_profile_id", "profile"),
+    link("post_view_history", "user_id", "group_user"),
+    link("post_view_history", "post_id", "group_post"),
+    link("topic_subscription", "profile_id", "profile"),
+    link("topic_subscription", "topic_id", "group_topic"),
+    link("topic_tag", "topic_id", "group_topic"),
+    link("profile_state", "profile_id", "profile"),
+    link("direct_topic", "low_profile_id", "profile"),
+    link("direct_topic", "high_profile_id", "profile"),
+    link("direct_message", "topic_id", "direct_topic"),
+    link("direct_message", "profile_id", "profile"),
+];
+
+/// Orphaned rows of one relation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Orphans {
+    pub table: String,
+    pub column: String,
+    pub parent: String,
+    /// sqlite rowids, since not every table has an `id` column
+    pub rowids: Vec<i64>,
+}
+
+impl ParentLink {
+    /// Anti-join selecting the rowids of rows whose parent is missing
+    fn orphan_rowids_sql(&self) -> String {
+        let ParentLink {
+            table,
+            column,
+            parent,
+        } = self;
+        format!(
+            r#"SELECT child.rowid AS orphan FROM "{table}" AS child
+               LEFT JOIN "{parent}" AS parent ON parent.id = child."{column}"
+               WHERE child."{column}" IS NOT NULL AND parent.id IS NULL
+               ORDER BY child.rowid"#
+        )
+    }
+
+    async fn orphans<C: ConnectionTrait>(&self, conn: &C) -> Result<Vec<i64>, DbErr> {
+        let backend = conn.get_database_backend();
+        let statement = Statement::from_string(backend, self.orphan_rowids_sql());
+        conn.query_all(statement)
+            .await?
+            .iter()
+            .map(|row| row.try_get::<i64>("", "orphan"))
+            .collect()
+    }
+}
+
+/// Every relation with orphaned rows; relations without any are left out
+pub async fn find_orphans<C: ConnectionTrait>(conn: &C) -> Result<Vec<Orphans>, DbErr> {
+    let mut found = Vec::new();
+    for link in PARENT_LINKS {
+        let rowids = link.orphans(conn).await?;
+        if !rowids.is_empty() {
+            found.push(Orphans {
+                table: link.table.to_string(),
+                column: link.column.to_string(),
+                parent: link.parent.to_string(),
+                rowids,
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// Delete every orphaned row in one transaction, returning how many went
+/// (rows removed by `ON DELETE CASCADE` aren't counted).
+///
+/// Deleting an orphan can orphan its own children (when foreign keys didn't
+/// cascade it), so this repeats until a pass finds nothing.
+pub async fn prune_orphans(db: &sea_orm::DatabaseConnection) -> Result<u64, DbErr> {
+    let txn = begin_write(db).await?;
+    let mut pruned = 0;
+    loop {
+        let mut pass = 0;
+        for link in PARENT_LINKS {
+            let delete = format!(
+                r#"DELETE FROM "{}" WHERE rowid IN ({})"#,
+                link.table,
+                link.orphan_rowids_sql()
+            );
+            pass += txn.execute_unprepared(&delete).await?.rows_affected();
+        }
+        if pass == 0 {
+            break;
+        }
+        pruned += pass;
+    }
+    txn.commit().await?;
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entity::prelude::*,
+        ids::{GroupId, PostId, ProfileId, TopicId, UserId},
+        models::migrator::Migrator,
+    };
+    use sea_orm_migration::MigratorTrait;
+
+    async fn setup_test_db() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db
+    }
+
+    async fn insert_profile(db: &DatabaseConnection, name: &str) -> ProfileId {
+        let profile_id = ProfileId::new();
+        ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set(name.to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        profile_id
+    }
+
+    /// A member and a topic with one post in a group that isn't there
+    async fn insert_orphaned_tree(db: &DatabaseConnection, profile_id: ProfileId) -> UserId {
+        let group_id = GroupId::new();
+        let user_id = UserId::new();
+        GroupUserActiveModel {
+            id: Set(user_id),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        let topic_id = TopicId::new();
+        GroupTopicActiveModel {
+            id: Set(topic_id),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            created_at: Set("2024-01-01T00:00:00Z".to_string()),
+            title: Set("Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        GroupPostActiveModel {
+            id: Set(PostId::new()),
+            user_id: Set(user_id),
+            topic_id: Set(topic_id),
+            parent_post_id: Set(None),
+            title: Set("Title".to_string()),
+            body: Set("Body".to_string()),
+            created_at: Set("2024-01-01T00:00:00Z".to_string()),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(1),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        user_id
+    }
+
+    #[tokio::test]
+    async fn test_find_and_prune_orphans() {
+        let db = setup_test_db().await;
+        let profile_id = insert_profile(&db, "Member").await;
+
+        // A healthy group the checks must leave alone
+        let group_id = GroupId::new();
+        GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        assert!(find_orphans(&db).await.unwrap().is_empty());
+
+        db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        let orphaned_user = insert_orphaned_tree(&db, profile_id).await;
+        db.execute_unprepared("PRAGMA foreign_keys = ON").await.unwrap();
+
+        let found = find_orphans(&db).await.unwrap();
+        let relations: Vec<(&str, &str)> = found
+            .iter()
+            .map(|orphans| (orphans.table.as_str(), orphans.column.as_str()))
+            .collect();
+        assert_eq!(relations, vec![("group_user", "group_id"), ("group_topic", "group_id")]);
+        assert!(found.iter().all(|orphans| orphans.rowids.len() == 1));
+
+        // The post only hangs off the orphans and cascades with them, uncounted
+        let pruned = prune_orphans(&db).await.unwrap();
+        assert_eq!(pruned, 2);
+        assert!(find_orphans(&db).await.unwrap().is_empty());
+        assert!(GroupUser::find_by_id(orphaned_user).one(&db).await.unwrap().is_none());
+        assert_eq!(GroupPost::find().count(&db).await.unwrap(), 0);
+        assert!(Group::find_by_id(group_id).one(&db).await.unwrap().is_some());
+        assert_eq!(prune_orphans(&db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_orphans_without_cascades() {
+        let db = setup_test_db().await;
+        let profile_id = insert_profile(&db, "Member").await;
+
+        // With foreign keys off nothing cascades; later passes catch the children
+        db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        insert_orphaned_tree(&db, profile_id).await;
+        let pruned = prune_orphans(&db).await.unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = ON").await.unwrap();
+
+        assert_eq!(pruned, 3);
+        assert_eq!(GroupPost::find().count(&db).await.unwrap(), 0);
+        assert!(find_orphans(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ban_of_missing_profile_is_orphaned() {
+        let db = setup_test_db().await;
+        let owner = insert_profile(&db, "Owner").await;
+        let group_id = GroupId::new();
+        GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(owner),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        GroupBannedActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(ProfileId::new()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = ON").await.unwrap();
+
+        let found = find_orphans(&db).await.unwrap();
+        let relations: Vec<(&str, &str)> = found
+            .iter()
+            .map(|orphans| (orphans.table.as_str(), orphans.column.as_str()))
+            .collect();
+        assert_eq!(relations, vec![("group_banned", "identity_id")]);
+        assert_eq!(prune_orphans(&db).await.unwrap(), 1);
+        assert_eq!(GroupBanned::find().count(&db).await.unwrap(), 0);
+    }
+}
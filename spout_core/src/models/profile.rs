@@ -2,12 +2,18 @@ use serde::{Deserialize, Serialize};
 use sqlx::{pool::PoolConnection, prelude::*, Any, AnyPool};
 use thiserror::Error;
 
-use crate::{error::MigrationError, ids::ProfileId, profile::migrations::create_profiles_table};
+use crate::{
+    error::MigrationError,
+    ids::{parse, IdParseError, ProfileId},
+    profile::migrations::create_profiles_table,
+};
 
 #[derive(Debug, Error)]
 pub enum ProfileError {
     #[error("database error")]
     DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    InvalidId(#[from] IdParseError),
 }
 
 #[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq, Eq)]
@@ -70,8 +76,7 @@ impl Profile {
         let profile = match row {
             Some(row) => {
                 let id_str: String = row.try_get("id")?;
-                let id =
-                    ProfileId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let id: ProfileId = parse(&id_str)?;
                 let name: String = row.try_get("name")?;
                 let desc: String = row.try_get("desc")?;
                 let picture: Option<Vec<u8>> = row.try_get("picture")?;
@@ -107,8 +112,7 @@ impl Profile {
         let profile = match row {
             Some(row) => {
                 let id_str: String = row.try_get("id")?;
-                let id =
-                    ProfileId::parse_str(&id_str).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                let id: ProfileId = parse(&id_str)?;
                 let name: String = row.try_get("name")?;
                 let desc: String = row.try_get("desc")?;
                 let picture: Option<Vec<u8>> = row.try_get("picture")?;
@@ -0,0 +1,90 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+
+/// Panic unless sqlite's `EXPLAIN QUERY PLAN` for `sql` names `index_name`
+pub(crate) async fn assert_uses_index(db: &DatabaseConnection, sql: &str, index_name: &str) {
+    let explain = Statement::from_string(
+        db.get_database_backend(),
+        format!("EXPLAIN QUERY PLAN {sql}"),
+    );
+    let plan: Vec<String> = db
+        .query_all(explain)
+        .await
+        .expect("EXPLAIN QUERY PLAN should run")
+        .iter()
+        .map(|row| row.try_get("", "detail").expect("plan rows have a detail column"))
+        .collect();
+
+    // Whole words only, so `idx_a` doesn't match `idx_a_b`
+    let uses_index = plan
+        .iter()
+        .any(|step| step.split_whitespace().any(|word| word == index_name));
+    assert!(
+        uses_index,
+        "expected {index_name} in the plan for\n  {sql}\nbut got\n  {}",
+        plan.join("\n  ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{Database, DbBackend, QueryTrait};
+    use sea_orm_migration::MigratorTrait;
+
+    use super::*;
+    use crate::{
+        entity::prelude::*,
+        ids::{GroupId, ProfileId, TopicId, UserId},
+        models::migrator::Migrator,
+    };
+
+    async fn migrated_db() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db
+    }
+
+    fn sql(query: impl QueryTrait) -> String {
+        query.build(DbBackend::Sqlite).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_topic_post_listing_uses_index() {
+        let db = migrated_db().await;
+        // PostsService::_list_posts_for_topic
+        let query = GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(TopicId::new()))
+            .order_by_asc(GroupPostColumn::CreatedAt)
+            .limit(20)
+            .offset(0);
+        assert_uses_index(&db, &sql(query), "idx_group_posts_topic_created_at").await;
+    }
+
+    #[tokio::test]
+    async fn test_user_post_listing_uses_index() {
+        let db = migrated_db().await;
+        // PostsService::_list_posts_by_user
+        let query = GroupPost::find()
+            .filter(GroupPostColumn::UserId.eq(UserId::new()))
+            .order_by_desc(GroupPostColumn::CreatedAt)
+            .limit(20)
+            .offset(0);
+        assert_uses_index(&db, &sql(query), "idx_group_posts_user_created_at").await;
+    }
+
+    #[tokio::test]
+    async fn test_member_lookup_uses_index() {
+        let db = migrated_db().await;
+        // The membership check behind posting, nicknames and bans
+        let query = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(GroupId::new()))
+            .filter(GroupUserColumn::ProfileId.eq(ProfileId::new()));
+        assert_uses_index(&db, &sql(query), "idx_group_users_group_profile_unique").await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected idx_missing in the plan")]
+    async fn test_missing_index_fails() {
+        let db = migrated_db().await;
+        assert_uses_index(&db, "SELECT * FROM group_post", "idx_missing").await;
+    }
+}
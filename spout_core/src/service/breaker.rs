@@ -0,0 +1,239 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use zel_core::prelude::*;
+
+/// Thresholds for [`DbBreaker`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive infra failures before the breaker opens
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before letting a probe through
+    pub cool_down: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen,
+}
+
+/// Circuit breaker shared by the services talking to the same database.
+///
+/// Wraps each RPC handler call. Infra/system errors count as failures; app
+/// errors (not found, unauthorized, ...) mean the DB answered, so they count as
+/// successes. Once open, calls are rejected with `ResourceError::infra` without
+/// touching the DB until the cool-down passes, then a single probe decides
+/// whether to close again.
+#[derive(Clone, Debug)]
+pub struct DbBreaker {
+    config: BreakerConfig,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for DbBreaker {
+    fn default() -> Self {
+        Self::new(BreakerConfig::default())
+    }
+}
+
+impl DbBreaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    /// Whether calls are currently being short-circuited
+    pub fn is_open(&self) -> bool {
+        matches!(*self.lock(), State::Open { until } if Instant::now() < until)
+    }
+
+    /// Run `fut` through the breaker, converting its error into a `ResourceError`
+    pub async fn call<T, E, F>(&self, fut: F) -> Result<T, ResourceError>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Into<ResourceError>,
+    {
+        let mut pending = Pending {
+            breaker: self,
+            probe: self.acquire()?,
+        };
+
+        let result = fut.await.map_err(Into::into);
+        pending.probe = false;
+        match &result {
+            Err(error) if error.severity() != ErrorSeverity::Application => self.on_failure(),
+            _ => self.on_success(),
+        }
+
+        result
+    }
+
+    /// Admit a call, returning whether it is the half-open probe
+    fn acquire(&self) -> Result<bool, ResourceError> {
+        let mut state = self.lock();
+        match *state {
+            State::Closed { .. } => Ok(false),
+            State::Open { until } if Instant::now() >= until => {
+                // Cool-down elapsed, let this call probe the DB
+                *state = State::HalfOpen;
+                Ok(true)
+            }
+            State::Open { .. } | State::HalfOpen => Err(ResourceError::infra(
+                "database circuit breaker open, try again later",
+            )),
+        }
+    }
+
+    fn on_success(&self) {
+        *self.lock() = State::Closed { failures: 0 };
+    }
+
+    fn on_failure(&self) {
+        let mut state = self.lock();
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            // A failed probe re-opens immediately
+            State::HalfOpen | State::Open { .. } => self.config.failure_threshold,
+        };
+
+        *state = if failures >= self.config.failure_threshold {
+            State::Open {
+                until: Instant::now() + self.config.cool_down,
+            }
+        } else {
+            State::Closed { failures }
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Settles a probe that never finished (dropped mid-await or panicked) as a
+/// failure, so the breaker re-opens for another cool-down instead of staying
+/// half-open and rejecting every call
+struct Pending<'a> {
+    breaker: &'a DbBreaker,
+    probe: bool,
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            self.breaker.on_failure();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::DbErr;
+
+    fn test_breaker() -> DbBreaker {
+        DbBreaker::new(BreakerConfig {
+            failure_threshold: 3,
+            cool_down: Duration::from_millis(50),
+        })
+    }
+
+    async fn failing() -> Result<(), ResourceError> {
+        Err(ResourceError::infra(DbErr::Custom("disk full".to_string())))
+    }
+
+    #[tokio::test]
+    async fn test_breaker_trips_and_recovers() {
+        let breaker = test_breaker();
+
+        for _ in 0..3 {
+            assert!(breaker.call(failing()).await.is_err());
+        }
+        assert!(breaker.is_open(), "Breaker should open after 3 failures");
+
+        // While open, the wrapped future is never polled
+        let mut ran = false;
+        let result = breaker
+            .call(async {
+                ran = true;
+                Ok::<_, ResourceError>(())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().severity(), ErrorSeverity::Infrastructure);
+        assert!(!ran, "Open breaker should short-circuit");
+
+        // After the cool-down a successful probe closes the breaker
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        breaker
+            .call(async { Ok::<_, ResourceError>(()) })
+            .await
+            .expect("Probe should be let through");
+        assert!(!breaker.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_reopens() {
+        let breaker = test_breaker();
+
+        for _ in 0..3 {
+            let _ = breaker.call(failing()).await;
+        }
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        assert!(breaker.call(failing()).await.is_err());
+        assert!(breaker.is_open(), "A failed probe should re-open the breaker");
+    }
+
+    #[tokio::test]
+    async fn test_dropped_probe_does_not_wedge_half_open() {
+        let breaker = test_breaker();
+
+        for _ in 0..3 {
+            let _ = breaker.call(failing()).await;
+        }
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // The probe is cancelled before it completes
+        let probe = breaker.call(std::future::pending::<Result<(), ResourceError>>());
+        let timed_out = tokio::time::timeout(Duration::from_millis(10), probe).await;
+        assert!(timed_out.is_err());
+        assert!(breaker.is_open(), "An unfinished probe should count as failed");
+
+        // After another cool-down the next probe gets through and closes it
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        breaker
+            .call(async { Ok::<_, ResourceError>(()) })
+            .await
+            .expect("Probe should be let through");
+        assert!(!breaker.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_app_errors_do_not_trip() {
+        let breaker = test_breaker();
+
+        for _ in 0..10 {
+            let _ = breaker
+                .call(async { Err::<(), _>(ResourceError::app("post not found")) })
+                .await;
+        }
+        assert!(!breaker.is_open());
+    }
+}
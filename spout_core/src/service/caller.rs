@@ -0,0 +1,29 @@
+use iroh::PublicKey;
+use zel_core::prelude::*;
+
+/// The identity of whoever issued an RPC.
+///
+/// `RequestContext` can only be built from a live iroh connection, so RPC
+/// bodies that need the caller's identity live in `*_as(&impl Caller, ..)`
+/// methods; the server impl passes its context, tests pass a [`TestCaller`].
+pub trait Caller {
+    fn remote_id(&self) -> PublicKey;
+}
+
+impl Caller for RequestContext {
+    fn remote_id(&self) -> PublicKey {
+        RequestContext::remote_id(self)
+    }
+}
+
+/// Synthetic caller for unit tests
+#[cfg(test)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TestCaller(pub PublicKey);
+
+#[cfg(test)]
+impl Caller for TestCaller {
+    fn remote_id(&self) -> PublicKey {
+        self.0
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+/// Source of "now" for every timestamp the services write or compare against.
+///
+/// Production uses [`SystemClock`]; tests inject a [`MockClock`] through the
+/// services' `with_clock` to make time-dependent behavior deterministic.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// `now()` in the rfc3339 form the timestamp columns store
+    fn now_rfc3339(&self) -> String {
+        self.now().to_rfc3339()
+    }
+}
+
+/// The real wall clock (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// [`Clock`] shared between `SpoutCore` and its services
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Manually advanced clock for unit tests
+#[cfg(test)]
+#[derive(Clone, Debug)]
+pub(crate) struct MockClock(Arc<std::sync::Mutex<DateTime<Utc>>>);
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self(Arc::new(std::sync::Mutex::new(start)))
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{entity::prelude::GroupPostModel, ids::TopicId};
+
+/// How much a [`TopicFeed`] holds for its subscribers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TopicFeedConfig {
+    /// Posts kept per topic for subscribers that haven't read them yet
+    pub buffer: usize,
+    /// Live subscriptions allowed per topic
+    pub max_subscribers: usize,
+}
+
+impl Default for TopicFeedConfig {
+    fn default() -> Self {
+        Self {
+            buffer: 64,
+            max_subscribers: 32,
+        }
+    }
+}
+
+/// What a topic subscriber receives
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeedEvent {
+    Post(GroupPostModel),
+    /// The subscriber fell more than `buffer` posts behind and missed some;
+    /// re-fetch the topic, then keep reading
+    Resync,
+}
+
+/// A topic already has `max` live subscriptions
+#[derive(Debug, Error)]
+#[error("too many subscribers for this topic (max {max})")]
+pub struct TooManySubscribers {
+    pub max: usize,
+}
+
+/// New posts, fanned out to everyone subscribed to their topic.
+///
+/// Each topic gets a bounded channel, so a slow subscriber costs at most
+/// `buffer` posts of memory. Instead of blocking the writer or growing,
+/// it loses the oldest posts and is told to resync.
+pub struct TopicFeed {
+    config: TopicFeedConfig,
+    channels: Mutex<HashMap<TopicId, broadcast::Sender<GroupPostModel>>>,
+}
+
+/// [`TopicFeed`] shared between `SpoutCore` and the posts service
+pub type SharedTopicFeed = Arc<TopicFeed>;
+
+impl Default for TopicFeed {
+    fn default() -> Self {
+        Self::new(TopicFeedConfig::default())
+    }
+}
+
+impl TopicFeed {
+    pub fn new(config: TopicFeedConfig) -> Self {
+        Self {
+            config,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_channels(&self) -> MutexGuard<'_, HashMap<TopicId, broadcast::Sender<GroupPostModel>>> {
+        self.channels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start receiving the posts made in `topic_id` from now on
+    pub fn subscribe(&self, topic_id: TopicId) -> Result<FeedSubscription, TooManySubscribers> {
+        let mut channels = self.lock_channels();
+        let sender = channels
+            .entry(topic_id)
+            .or_insert_with(|| broadcast::channel(self.config.buffer.max(1)).0);
+        if sender.receiver_count() >= self.config.max_subscribers {
+            return Err(TooManySubscribers {
+                max: self.config.max_subscribers,
+            });
+        }
+        Ok(FeedSubscription {
+            receiver: sender.subscribe(),
+        })
+    }
+
+    /// Hand a committed post to its topic's subscribers
+    pub(crate) fn publish(&self, post: &GroupPostModel) {
+        let mut channels = self.lock_channels();
+        if let Some(sender) = channels.get(&post.topic_id) {
+            // Only fails once every subscriber is gone
+            if sender.send(post.clone()).is_err() {
+                channels.remove(&post.topic_id);
+            }
+        }
+    }
+
+    /// Live subscriptions to `topic_id`
+    pub fn subscriber_count(&self, topic_id: TopicId) -> usize {
+        self.lock_channels()
+            .get(&topic_id)
+            .map_or(0, |sender| sender.receiver_count())
+    }
+}
+
+/// One subscriber's view of a [`TopicFeed`] topic
+pub struct FeedSubscription {
+    receiver: broadcast::Receiver<GroupPostModel>,
+}
+
+impl FeedSubscription {
+    /// The next post, or [`FeedEvent::Resync`] in place of the ones missed by lagging.
+    ///
+    /// `None` once the feed is gone.
+    pub async fn next(&mut self) -> Option<FeedEvent> {
+        match self.receiver.recv().await {
+            Ok(post) => Some(FeedEvent::Post(post)),
+            Err(RecvError::Lagged(_)) => Some(FeedEvent::Resync),
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::{
+        entity::group_post::PostFormat,
+        ids::{PostId, UserId},
+    };
+
+    fn post(topic_id: TopicId, seq: i64) -> GroupPostModel {
+        GroupPostModel {
+            id: PostId::new(),
+            user_id: UserId::new(),
+            topic_id,
+            parent_post_id: None,
+            title: format!("Post {seq}"),
+            body: "Body".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            deleted_at: None,
+            edited_at: None,
+            version: 1,
+            format: PostFormat::Plain,
+            seq,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_gets_resync() {
+        let feed = TopicFeed::new(TopicFeedConfig {
+            buffer: 2,
+            max_subscribers: 4,
+        });
+        let topic_id = TopicId::new();
+        let mut keeping_up = feed.subscribe(topic_id).unwrap();
+        let mut lagging = feed.subscribe(topic_id).unwrap();
+
+        // One subscriber reads each post as it comes, the other falls behind
+        let posts: Vec<GroupPostModel> = (1..=5).map(|seq| post(topic_id, seq)).collect();
+        for post in &posts {
+            feed.publish(post);
+            assert_eq!(keeping_up.next().await, Some(FeedEvent::Post(post.clone())));
+        }
+
+        // Told it missed some, then the newest `buffer` posts
+        assert_eq!(lagging.next().await, Some(FeedEvent::Resync));
+        assert_eq!(lagging.next().await, Some(FeedEvent::Post(posts[3].clone())));
+        assert_eq!(lagging.next().await, Some(FeedEvent::Post(posts[4].clone())));
+
+        // Other topics' subscribers see none of it
+        let mut elsewhere = feed.subscribe(TopicId::new()).unwrap();
+        feed.publish(&post(topic_id, 6));
+        let waited = tokio::time::timeout(Duration::from_millis(10), elsewhere.next()).await;
+        assert!(waited.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_are_capped_per_topic() {
+        let feed = TopicFeed::new(TopicFeedConfig {
+            buffer: 8,
+            max_subscribers: 2,
+        });
+        let topic_id = TopicId::new();
+        let first = feed.subscribe(topic_id).unwrap();
+        let _second = feed.subscribe(topic_id).unwrap();
+
+        let error = feed.subscribe(topic_id).err().unwrap();
+        assert_eq!(error.max, 2);
+        assert!(feed.subscribe(TopicId::new()).is_ok());
+
+        // Dropping a subscription frees its slot
+        drop(first);
+        assert_eq!(feed.subscriber_count(topic_id), 1);
+        assert!(feed.subscribe(topic_id).is_ok());
+    }
+}
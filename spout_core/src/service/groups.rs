@@ -1,13 +1,30 @@
-use sea_orm::{DatabaseConnection, TransactionTrait};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use sea_orm::{
+    sea_query::{Expr, OnConflict, Query, SimpleExpr},
+    DatabaseConnection, FromQueryResult, JoinType,
+};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zel_core::prelude::*;
 
 use crate::{
-    entity::prelude::*,
-    ids::{GroupId, ProfileId},
+    config::{self, SharedRuntimeConfig},
+    entity::{group, group_admin, group_user, prelude::*},
+    ids::{GroupId, ProfileId, UserId},
+    models::begin_write,
+    service::{
+        breaker::DbBreaker,
+        clock::{SharedClock, SystemClock},
+        sync::record_change,
+    },
 };
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum GroupsServiceError {
     #[error("fatal database error")]
     DbError(#[from] DbErr),
@@ -20,8 +37,42 @@ pub enum GroupsServiceError {
 
     #[error("unauthorized: not a group admin")]
     Unauthorized,
+
+    #[error("too many profiles in one request (max {MAX_BULK_ADD_USERS})")]
+    TooManyProfiles,
+
+    #[error("too many groups in one request (max {MAX_GROUPS_BY_IDS})")]
+    TooManyGroups,
+
+    #[error("profile is not a member of the group")]
+    NotAMember,
+
+    #[error("nickname must be at most {MAX_NICKNAME_CHARS} characters with no control characters")]
+    InvalidNickname,
+
+    #[error("profile is banned from the group")]
+    Banned,
+
+    #[error("group is archived")]
+    GroupArchived,
+
+    #[error("group admins can't be banned")]
+    CannotBanAdmin,
+
+    #[error("can't merge a group into itself")]
+    MergeIntoSelf,
+
+    #[error("can't remove a group's last admin")]
+    CannotRemoveLastAdmin,
+
+    #[error("the group owner must transfer_ownership to another member before leaving")]
+    OwnerMustTransferFirst,
+
+    #[error("the group owner can't be demoted; transfer_ownership first")]
+    CannotDemoteOwner,
 }
 
+/// DB failures map to `ResourceError::infra`, every domain variant to `ResourceError::app`
 impl From<GroupsServiceError> for ResourceError {
     fn from(error: GroupsServiceError) -> Self {
         match error {
@@ -29,18 +80,176 @@ impl From<GroupsServiceError> for ResourceError {
             GroupsServiceError::GroupNotFound => ResourceError::app(error),
             GroupsServiceError::ProfileNotFound => ResourceError::app(error),
             GroupsServiceError::Unauthorized => ResourceError::app(error),
+            GroupsServiceError::TooManyProfiles => ResourceError::app(error),
+            GroupsServiceError::TooManyGroups => ResourceError::app(error),
+            GroupsServiceError::NotAMember => ResourceError::app(error),
+            GroupsServiceError::InvalidNickname => ResourceError::app(error),
+            GroupsServiceError::Banned => ResourceError::app(error),
+            GroupsServiceError::GroupArchived => ResourceError::app(error),
+            GroupsServiceError::CannotBanAdmin => ResourceError::app(error),
+            GroupsServiceError::MergeIntoSelf => ResourceError::app(error),
+            GroupsServiceError::CannotRemoveLastAdmin => ResourceError::app(error),
+            GroupsServiceError::OwnerMustTransferFirst => ResourceError::app(error),
+            GroupsServiceError::CannotDemoteOwner => ResourceError::app(error),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
         }
     }
 }
 
+/// Upper bound on profiles accepted by a single `add_users_bulk` call
+pub const MAX_BULK_ADD_USERS: usize = 500;
+
+/// Upper bound on ids accepted by a single `get_groups_by_ids` call
+pub const MAX_GROUPS_BY_IDS: usize = 200;
+
+/// Whether `profile_id` is on `group_id`'s ban list
+async fn is_banned<C: ConnectionTrait>(
+    conn: &C,
+    group_id: GroupId,
+    profile_id: ProfileId,
+) -> Result<bool, DbErr> {
+    Ok(GroupBanned::find_by_id((group_id, profile_id)).one(conn).await?.is_some())
+}
+
+/// Keeps the `group_user` rows of profiles not banned from `group_id`; a ban
+/// leaves the row in place so the member's posts survive it, so every lookup of
+/// active members (here and in the posts service's notifications) needs this
+pub(crate) fn not_banned(group_id: GroupId) -> SimpleExpr {
+    GroupUserColumn::ProfileId.not_in_subquery(
+        Query::select()
+            .column(GroupBannedColumn::IdentityId)
+            .from(GroupBanned)
+            .and_where(GroupBannedColumn::GroupId.eq(group_id))
+            .to_owned(),
+    )
+}
+
+/// Longest group nickname accepted by `_set_nickname`, in characters
+pub const MAX_NICKNAME_CHARS: usize = 64;
+
+/// What happened to one profile in an `add_users_bulk` call
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BulkAddStatus {
+    Added,
+    AlreadyMember,
+    ProfileNotFound,
+    Banned,
+}
+
+/// Per-input result of `add_users_bulk`, in request order
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkAddResult {
+    pub profile_id: ProfileId,
+    pub status: BulkAddStatus,
+}
+
+/// A member as shown inside their group
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub user_id: UserId,
+    pub profile_id: ProfileId,
+    /// The group nickname if the member set one, otherwise their profile name
+    pub display_name: String,
+}
+
+/// What `merge_groups` moved into the destination group
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMerge {
+    /// The destination, as it is after the merge
+    pub group: GroupModel,
+    pub topics_moved: u64,
+    /// Source members that weren't in the destination yet
+    pub members_moved: u64,
+}
+
+/// What `remove_user` did
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemoveUserOutcome {
+    Removed,
+    /// The owner left as the group's last member, so the group went with them
+    GroupDeleted,
+}
+
+/// A membership together with the member's full profile
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberProfile {
+    pub user_id: UserId,
+    pub nickname: Option<String>,
+    pub profile: ProfileModel,
+}
+
+/// A profile's standing in a group. There's no separate moderator tier;
+/// admins do the moderating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupRole {
+    Admin,
+    Member,
+}
+
+/// One entry of `my_roles`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMembership {
+    pub group: GroupModel,
+    pub role: GroupRole,
+    pub is_owner: bool,
+}
+
+/// How a profile relates to a group in `list_my_groups`, strongest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupRelationship {
+    Owner,
+    Admin,
+    Member,
+}
+
+/// One entry of `list_my_groups`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MyGroup {
+    pub group: GroupModel,
+    pub relationship: GroupRelationship,
+}
+
 #[derive(Clone)]
 pub struct GroupsService {
     db: DatabaseConnection,
+    breaker: DbBreaker,
+    clock: SharedClock,
+    runtime: SharedRuntimeConfig,
 }
 
 impl GroupsService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            breaker: DbBreaker::default(),
+            clock: Arc::new(SystemClock),
+            runtime: SharedRuntimeConfig::default(),
+        }
+    }
+
+    /// Share a circuit breaker with the other services using the same DB
+    pub fn with_breaker(mut self, breaker: DbBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read hot-reloadable limits from `SpoutCore`'s shared runtime config
+    pub fn with_runtime_config(mut self, runtime: SharedRuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// The underlying database connection, for queries the service doesn't cover
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.db
     }
 
     /// Create a new group owned by the specified profile
@@ -58,16 +267,22 @@ impl GroupsService {
             return Err(GroupsServiceError::ProfileNotFound);
         }
 
-        let txn = self.db.begin().await?;
+        let txn = begin_write(&self.db).await?;
 
         // Create group
         let group_id = GroupId::new();
         let group = GroupActiveModel {
             id: Set(group_id),
             profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(Some(self.clock.now_rfc3339())),
         };
 
         let group_result = Group::insert(group).exec_with_returning(&txn).await?;
+        let created_at = group_result.created_at.clone().unwrap_or_default();
+        let (entity, op) = (ChangeEntity::Group, ChangeOp::Create);
+        record_change(&txn, entity, op, group_id, &group_result, created_at).await?;
 
         // Make the creator an admin
         let admin = GroupAdminActiveModel {
@@ -80,27 +295,104 @@ impl GroupsService {
         Ok(group_result)
     }
 
-    /// List all groups owned by a profile
+    /// List all non-archived groups owned by a profile
     pub async fn _list_groups(
         &self,
         profile_id: ProfileId,
     ) -> Result<Vec<GroupModel>, GroupsServiceError> {
         let groups = Group::find()
             .filter(GroupColumn::ProfileId.eq(profile_id))
+            .filter(GroupColumn::Archived.eq(false))
+            .all(&self.db)
+            .await?;
+
+        Ok(groups)
+    }
+
+    /// List the archived groups owned by a profile
+    pub async fn _list_archived_groups(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<Vec<GroupModel>, GroupsServiceError> {
+        let groups = Group::find()
+            .filter(GroupColumn::ProfileId.eq(profile_id))
+            .filter(GroupColumn::Archived.eq(true))
             .all(&self.db)
             .await?;
 
         Ok(groups)
     }
 
+    /// Archive or unarchive a group (admin only)
+    pub async fn _set_archived(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        archived: bool,
+    ) -> Result<GroupModel, GroupsServiceError> {
+        let group = self._get_group(group_id).await?;
+
+        if !self._is_admin(group_id, actor_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+
+        if group.archived == archived {
+            return Ok(group);
+        }
+
+        let now = self.clock.now_rfc3339();
+        let mut group: GroupActiveModel = group.into();
+        group.archived = Set(archived);
+        group.archived_at = Set(archived.then(|| now.clone()));
+
+        let txn = begin_write(&self.db).await?;
+        let group = group.update(&txn).await?;
+        let (entity, op) = (ChangeEntity::Group, ChangeOp::Update);
+        record_change(&txn, entity, op, group_id, &group, now).await?;
+        txn.commit().await?;
+        Ok(group)
+    }
+
     /// Get a specific group by ID
     pub async fn _get_group(&self, group_id: GroupId) -> Result<GroupModel, GroupsServiceError> {
-        Group::find_by_id(group_id)
-            .one(&self.db)
+        self._try_get_group(group_id)
             .await?
             .ok_or(GroupsServiceError::GroupNotFound)
     }
 
+    /// Get a specific group by ID, `None` if it doesn't exist
+    pub async fn _try_get_group(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Option<GroupModel>, GroupsServiceError> {
+        Ok(Group::find_by_id(group_id).one(&self.db).await?)
+    }
+
+    /// Get several groups at once, in input order; unknown ids are skipped
+    pub async fn _get_groups_by_ids(
+        &self,
+        ids: Vec<GroupId>,
+    ) -> Result<Vec<GroupModel>, GroupsServiceError> {
+        if ids.len() > MAX_GROUPS_BY_IDS {
+            return Err(GroupsServiceError::TooManyGroups);
+        }
+        // Don't send an empty `IN ()` to the database
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut found: HashMap<GroupId, GroupModel> = Group::find()
+            .filter(GroupColumn::Id.is_in(ids.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|group| (group.id, group))
+            .collect();
+
+        // `remove` also drops repeated ids after their first occurrence
+        Ok(ids.into_iter().filter_map(|id| found.remove(&id)).collect())
+    }
+
     /// Delete a group (only by owner or admin)
     pub async fn _delete_group(
         &self,
@@ -113,8 +405,15 @@ impl GroupsService {
             return Err(GroupsServiceError::Unauthorized);
         }
 
-        // Delete will cascade to all related records due to FK constraints
-        Group::delete_by_id(group_id).exec(&self.db).await?;
+        let group = self._get_group(group_id).await?;
+
+        // Delete will cascade to all related records due to FK constraints;
+        // only the group itself gets a change log entry
+        let txn = begin_write(&self.db).await?;
+        Group::delete_by_id(group_id).exec(&txn).await?;
+        let (entity, op, now) = (ChangeEntity::Group, ChangeOp::Delete, self.clock.now_rfc3339());
+        record_change(&txn, entity, op, group_id, &group, now).await?;
+        txn.commit().await?;
 
         Ok(())
     }
@@ -134,6 +433,142 @@ impl GroupsService {
         Ok(admin.is_some())
     }
 
+    /// Check if a profile is a member of a group.
+    ///
+    /// Banned profiles are never members, even though their `group_user` row
+    /// is kept around.
+    pub async fn _is_member(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, GroupsServiceError> {
+        let member = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .filter(not_banned(group_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(member.is_some())
+    }
+
+    /// Every group a profile owns, administers, or is an unbanned member of,
+    /// ordered by group id
+    pub async fn _my_roles(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<Vec<GroupMembership>, GroupsServiceError> {
+        let administered = Group::find()
+            .join(JoinType::InnerJoin, group::Relation::GroupAdmin.def())
+            .filter(GroupAdminColumn::IdentityId.eq(profile_id))
+            .all(&self.db)
+            .await?;
+
+        let joined = Group::find()
+            .join(JoinType::InnerJoin, group::Relation::GroupUser.def())
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .filter(
+                GroupColumn::Id.not_in_subquery(
+                    Query::select()
+                        .column(GroupBannedColumn::GroupId)
+                        .from(GroupBanned)
+                        .and_where(GroupBannedColumn::IdentityId.eq(profile_id))
+                        .to_owned(),
+                ),
+            )
+            .all(&self.db)
+            .await?;
+
+        let owned = Group::find()
+            .filter(GroupColumn::ProfileId.eq(profile_id))
+            .all(&self.db)
+            .await?;
+
+        // Strongest role wins; owners always count as admins
+        let mut roles: HashMap<GroupId, GroupMembership> = HashMap::new();
+        for (groups, role) in [
+            (joined, GroupRole::Member),
+            (administered, GroupRole::Admin),
+            (owned, GroupRole::Admin),
+        ] {
+            for group in groups {
+                let is_owner = group.profile_id == profile_id;
+                roles.insert(group.id, GroupMembership { group, role, is_owner });
+            }
+        }
+
+        let mut roles: Vec<GroupMembership> = roles.into_values().collect();
+        roles.sort_by_key(|membership| membership.group.id.into_uuid());
+        Ok(roles)
+    }
+
+    /// A profile's non-archived groups, each once with its strongest relationship,
+    /// ordered by group id
+    pub async fn _list_my_groups(
+        &self,
+        profile_id: ProfileId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<MyGroup>, GroupsServiceError> {
+        let page_size = config::runtime(&self.runtime).page_size(limit);
+        let groups = self
+            ._my_roles(profile_id)
+            .await?
+            .into_iter()
+            .filter(|membership| !membership.group.archived)
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(page_size).unwrap_or(usize::MAX))
+            .map(|membership| {
+                let relationship = match membership.role {
+                    _ if membership.is_owner => GroupRelationship::Owner,
+                    GroupRole::Admin => GroupRelationship::Admin,
+                    GroupRole::Member => GroupRelationship::Member,
+                };
+                MyGroup {
+                    group: membership.group,
+                    relationship,
+                }
+            })
+            .collect();
+
+        Ok(groups)
+    }
+
+    /// Non-archived groups both profiles are unbanned members of, ordered by group id
+    pub async fn _common_groups(
+        &self,
+        profile_a: ProfileId,
+        profile_b: ProfileId,
+    ) -> Result<Vec<GroupModel>, GroupsServiceError> {
+        let groups = Group::find()
+            .join(JoinType::InnerJoin, group::Relation::GroupUser.def())
+            .filter(GroupUserColumn::ProfileId.eq(profile_a))
+            .filter(
+                GroupColumn::Id.in_subquery(
+                    Query::select()
+                        .column(GroupUserColumn::GroupId)
+                        .from(GroupUser)
+                        .and_where(GroupUserColumn::ProfileId.eq(profile_b))
+                        .to_owned(),
+                ),
+            )
+            .filter(
+                GroupColumn::Id.not_in_subquery(
+                    Query::select()
+                        .column(GroupBannedColumn::GroupId)
+                        .from(GroupBanned)
+                        .and_where(GroupBannedColumn::IdentityId.is_in([profile_a, profile_b]))
+                        .to_owned(),
+                ),
+            )
+            .filter(GroupColumn::Archived.eq(false))
+            .order_by_asc(GroupColumn::Id)
+            .all(&self.db)
+            .await?;
+
+        Ok(groups)
+    }
+
     /// List all admins for a group
     pub async fn _list_admins(
         &self,
@@ -147,6 +582,95 @@ impl GroupsService {
         Ok(admins)
     }
 
+    /// List a group's admins as full profiles, by name
+    pub async fn _list_admin_profiles(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<ProfileModel>, GroupsServiceError> {
+        let profiles = Profile::find()
+            .join(JoinType::InnerJoin, group_admin::Relation::Profile.def().rev())
+            .filter(GroupAdminColumn::GroupId.eq(group_id))
+            .order_by_asc(ProfileColumn::Name)
+            .all(&self.db)
+            .await?;
+
+        Ok(profiles)
+    }
+
+    /// Make a profile an admin of a group (admin only); promoting an admin is a no-op.
+    ///
+    /// Banned profiles can't be promoted until their ban is lifted.
+    pub async fn _add_admin(
+        &self,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupAdminModel, GroupsServiceError> {
+        self._get_group(group_id).await?;
+        if !self._is_admin(group_id, caller_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if Profile::find_by_id(target_profile_id).one(&self.db).await?.is_none() {
+            return Err(GroupsServiceError::ProfileNotFound);
+        }
+        if is_banned(&self.db, group_id, target_profile_id).await? {
+            return Err(GroupsServiceError::Banned);
+        }
+
+        let admin = GroupAdminModel {
+            group_id,
+            identity_id: target_profile_id,
+        };
+        GroupAdmin::insert(GroupAdminActiveModel::from(admin.clone()))
+            .on_conflict(
+                OnConflict::columns([GroupAdminColumn::GroupId, GroupAdminColumn::IdentityId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(&self.db)
+            .await?;
+        Ok(admin)
+    }
+
+    /// Take a profile's admin role in a group away (admin only, including from
+    /// themselves); demoting a non-admin is a no-op.
+    ///
+    /// A group always keeps at least one admin, and the owner always stays one
+    /// (otherwise they could be banned from their own group).
+    pub async fn _remove_admin(
+        &self,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), GroupsServiceError> {
+        let group = self._get_group(group_id).await?;
+        if !self._is_admin(group_id, caller_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if target_profile_id == group.profile_id {
+            return Err(GroupsServiceError::CannotDemoteOwner);
+        }
+
+        // Counted in the write transaction so two admins can't demote each other at once
+        let txn = begin_write(&self.db).await?;
+        let admins = GroupAdmin::find()
+            .filter(GroupAdminColumn::GroupId.eq(group_id))
+            .all(&txn)
+            .await?;
+        if !admins.iter().any(|admin| admin.identity_id == target_profile_id) {
+            return Ok(());
+        }
+        if admins.len() == 1 {
+            return Err(GroupsServiceError::CannotRemoveLastAdmin);
+        }
+        GroupAdmin::delete_by_id((group_id, target_profile_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
     /// Add a user to a group
     pub async fn _add_user(
         &self,
@@ -166,11 +690,17 @@ impl GroupsService {
             return Err(GroupsServiceError::ProfileNotFound);
         }
 
+        if is_banned(&self.db, group_id, profile_id).await? {
+            return Err(GroupsServiceError::Banned);
+        }
+
         // Add user
         let user = GroupUserActiveModel {
             id: Set(crate::ids::UserId::new()),
             group_id: Set(group_id),
             profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(Some(self.clock.now_rfc3339())),
         };
 
         let result = GroupUser::insert(user)
@@ -180,54 +710,693 @@ impl GroupsService {
         Ok(result)
     }
 
-    /// List all users in a group
+    /// The profile's membership in the group, joining it first if needed.
+    ///
+    /// Lets a client get the `UserId` it posts with in one idempotent call.
+    /// Banned profiles are refused even if a stale `group_user` row is still
+    /// around for them, and archived groups take no new members.
+    pub async fn _ensure_membership(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<GroupUserModel, GroupsServiceError> {
+        let group = self._get_group(group_id).await?;
+
+        if is_banned(&self.db, group_id, profile_id).await? {
+            return Err(GroupsServiceError::Banned);
+        }
+
+        let existing = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .one(&self.db)
+            .await?;
+        if let Some(user) = existing {
+            return Ok(user);
+        }
+
+        if group.archived {
+            return Err(GroupsServiceError::GroupArchived);
+        }
+        self._add_user(group_id, profile_id).await
+    }
+
+    /// Add many profiles to a group at once (admin only).
+    ///
+    /// Missing or banned profiles and existing members are reported and skipped; the
+    /// rest are inserted in a single transaction.
+    pub async fn _add_users_bulk(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_ids: Vec<ProfileId>,
+    ) -> Result<Vec<BulkAddResult>, GroupsServiceError> {
+        if profile_ids.len() > MAX_BULK_ADD_USERS {
+            return Err(GroupsServiceError::TooManyProfiles);
+        }
+
+        // Verify group exists
+        self._get_group(group_id).await?;
+
+        if !self._is_admin(group_id, actor_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if profile_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let txn = begin_write(&self.db).await?;
+
+        let existing_profiles: HashSet<ProfileId> = Profile::find()
+            .filter(ProfileColumn::Id.is_in(profile_ids.clone()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|profile| profile.id)
+            .collect();
+
+        let mut members: HashSet<ProfileId> = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.is_in(profile_ids.clone()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|user| user.profile_id)
+            .collect();
+
+        let banned: HashSet<ProfileId> = GroupBanned::find()
+            .filter(GroupBannedColumn::GroupId.eq(group_id))
+            .filter(GroupBannedColumn::IdentityId.is_in(profile_ids.clone()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|ban| ban.identity_id)
+            .collect();
+
+        let mut results = Vec::with_capacity(profile_ids.len());
+        let mut new_users = Vec::new();
+        let created_at = self.clock.now_rfc3339();
+
+        for profile_id in profile_ids {
+            let status = if !existing_profiles.contains(&profile_id) {
+                BulkAddStatus::ProfileNotFound
+            } else if banned.contains(&profile_id) {
+                BulkAddStatus::Banned
+            } else if !members.insert(profile_id) {
+                // Also covers the same profile listed twice
+                BulkAddStatus::AlreadyMember
+            } else {
+                new_users.push(GroupUserActiveModel {
+                    id: Set(crate::ids::UserId::new()),
+                    group_id: Set(group_id),
+                    profile_id: Set(profile_id),
+                    nickname: Set(None),
+                    created_at: Set(Some(created_at.clone())),
+                });
+                BulkAddStatus::Added
+            };
+
+            results.push(BulkAddResult { profile_id, status });
+        }
+
+        if !new_users.is_empty() {
+            GroupUser::insert_many(new_users).exec(&txn).await?;
+        }
+
+        txn.commit().await?;
+        Ok(results)
+    }
+
+    /// List all unbanned users in a group
     pub async fn _list_users(
         &self,
         group_id: GroupId,
     ) -> Result<Vec<GroupUserModel>, GroupsServiceError> {
         let users = GroupUser::find()
             .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(not_banned(group_id))
             .all(&self.db)
             .await?;
 
         Ok(users)
     }
-}
 
-#[zel_service(name = "groups")]
-trait Groups {
-    #[doc = "Create a new group owned by the calling profile"]
-    #[method(name = "create_group")]
-    async fn create_group(&self, profile_id: ProfileId) -> Result<GroupModel, ResourceError>;
+    /// List a group's members under the names they go by in the group
+    pub async fn _list_members(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<GroupMember>, GroupsServiceError> {
+        let members = GroupUser::find()
+            .select_only()
+            .column_as(GroupUserColumn::Id, "user_id")
+            .column(GroupUserColumn::ProfileId)
+            .column_as(group_user::display_name(), "display_name")
+            .join(JoinType::InnerJoin, group_user::Relation::Profile.def())
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(not_banned(group_id))
+            .order_by_asc(GroupUserColumn::Id)
+            .into_model::<GroupMember>()
+            .all(&self.db)
+            .await?;
 
-    #[doc = "List all groups owned by a profile"]
-    #[method(name = "list_groups")]
-    async fn list_groups(&self, profile_id: ProfileId) -> Result<Vec<GroupModel>, ResourceError>;
+        Ok(members)
+    }
 
-    #[doc = "Get a specific group by ID"]
-    #[method(name = "get_group")]
-    async fn get_group(&self, group_id: GroupId) -> Result<GroupModel, ResourceError>;
+    /// List a group's members with their full profiles, in the same order as `list_members`
+    pub async fn _list_member_profiles(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<MemberProfile>, GroupsServiceError> {
+        let members = GroupUser::find()
+            .find_also_related(Profile)
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(not_banned(group_id))
+            .order_by_asc(GroupUserColumn::Id)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            // The profile foreign key cascades, so the join always matches
+            .filter_map(|(user, profile)| {
+                Some(MemberProfile {
+                    user_id: user.id,
+                    nickname: user.nickname,
+                    profile: profile?,
+                })
+            })
+            .collect();
+
+        Ok(members)
+    }
 
-    #[doc = "Delete a group"]
-    #[method(name = "delete_group")]
-    async fn delete_group(
+    /// Hand a group to another of its members (owner only).
+    ///
+    /// The new owner is made an admin if they aren't one already; the old owner
+    /// keeps their membership and admin role, and can then leave.
+    pub async fn _transfer_ownership(
         &self,
         group_id: GroupId,
-        profile_id: ProfileId,
-    ) -> Result<(), ResourceError>;
+        owner_profile_id: ProfileId,
+        new_owner_profile_id: ProfileId,
+    ) -> Result<GroupModel, GroupsServiceError> {
+        let group = self._get_group(group_id).await?;
+        if group.profile_id != owner_profile_id {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if !self._is_member(group_id, new_owner_profile_id).await? {
+            return Err(GroupsServiceError::NotAMember);
+        }
+        if new_owner_profile_id == owner_profile_id {
+            return Ok(group);
+        }
 
-    #[doc = "Check if a profile is an admin of a group"]
-    #[method(name = "is_admin")]
-    async fn is_admin(
+        let txn = begin_write(&self.db).await?;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(new_owner_profile_id),
+        })
+        .on_conflict(
+            OnConflict::columns([GroupAdminColumn::GroupId, GroupAdminColumn::IdentityId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(&txn)
+        .await?;
+
+        let mut group: GroupActiveModel = group.into();
+        group.profile_id = Set(new_owner_profile_id);
+        let group = group.update(&txn).await?;
+        let (entity, op) = (ChangeEntity::Group, ChangeOp::Update);
+        record_change(&txn, entity, op, group_id, &group, self.clock.now_rfc3339()).await?;
+        txn.commit().await?;
+        Ok(group)
+    }
+
+    /// Remove a member from a group (the member themselves or an admin).
+    ///
+    /// The owner can only leave on their own, and only as the last member, in
+    /// which case the group is deleted; with others still in it they have to
+    /// `_transfer_ownership` first so the group isn't left owned by a non-member.
+    pub async fn _remove_user(
         &self,
         group_id: GroupId,
+        actor_profile_id: ProfileId,
         profile_id: ProfileId,
-    ) -> Result<bool, ResourceError>;
-
+    ) -> Result<RemoveUserOutcome, GroupsServiceError> {
+        let group = self._get_group(group_id).await?;
+
+        let is_owner = group.profile_id == profile_id;
+        let allowed = if is_owner {
+            actor_profile_id == profile_id
+        } else {
+            actor_profile_id == profile_id || self._is_admin(group_id, actor_profile_id).await?
+        };
+        if !allowed {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+
+        // Members are counted in the write transaction so nobody joins between
+        // the check and the owner's delete
+        let txn = begin_write(&self.db).await?;
+        let members = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(not_banned(group_id))
+            .all(&txn)
+            .await?;
+        if !members.iter().any(|member| member.profile_id == profile_id) {
+            return Err(GroupsServiceError::NotAMember);
+        }
+
+        if is_owner {
+            if members.len() > 1 {
+                return Err(GroupsServiceError::OwnerMustTransferFirst);
+            }
+            Group::delete_by_id(group_id).exec(&txn).await?;
+            let (entity, op) = (ChangeEntity::Group, ChangeOp::Delete);
+            record_change(&txn, entity, op, group_id, &group, self.clock.now_rfc3339()).await?;
+            txn.commit().await?;
+            return Ok(RemoveUserOutcome::GroupDeleted);
+        }
+
+        GroupUser::delete_many()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(RemoveUserOutcome::Removed)
+    }
+
+    /// Set or clear a member's group nickname (the member themselves or an admin).
+    ///
+    /// The nickname is trimmed; `None` or a blank string clears it.
+    pub async fn _set_nickname(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_id: ProfileId,
+        nickname: Option<String>,
+    ) -> Result<GroupUserModel, GroupsServiceError> {
+        let nickname = nickname
+            .map(|nickname| nickname.trim().to_string())
+            .filter(|nickname| !nickname.is_empty());
+        if let Some(nickname) = &nickname {
+            if nickname.chars().count() > MAX_NICKNAME_CHARS
+                || nickname.chars().any(char::is_control)
+            {
+                return Err(GroupsServiceError::InvalidNickname);
+            }
+        }
+
+        // Verify group exists
+        self._get_group(group_id).await?;
+
+        if actor_profile_id != profile_id && !self._is_admin(group_id, actor_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+
+        let user = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .filter(not_banned(group_id))
+            .one(&self.db)
+            .await?
+            .ok_or(GroupsServiceError::NotAMember)?;
+
+        let mut user: GroupUserActiveModel = user.into();
+        user.nickname = Set(nickname);
+        Ok(user.update(&self.db).await?)
+    }
+
+    /// Ban a profile from a group (admin only), whether or not it is a member.
+    ///
+    /// Its membership row is kept, so its posts and the replies to them stay,
+    /// but it no longer counts as a member. Banning twice is a no-op; admins
+    /// can't be banned.
+    pub async fn _ban_user(
+        &self,
+        group_id: GroupId,
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupBannedModel, GroupsServiceError> {
+        self._get_group(group_id).await?;
+        if !self._is_admin(group_id, admin_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if Profile::find_by_id(target_profile_id).one(&self.db).await?.is_none() {
+            return Err(GroupsServiceError::ProfileNotFound);
+        }
+        if self._is_admin(group_id, target_profile_id).await? {
+            return Err(GroupsServiceError::CannotBanAdmin);
+        }
+
+        let ban = GroupBannedModel {
+            group_id,
+            identity_id: target_profile_id,
+        };
+        GroupBanned::insert(GroupBannedActiveModel::from(ban.clone()))
+            .on_conflict(
+                OnConflict::columns([GroupBannedColumn::GroupId, GroupBannedColumn::IdentityId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(&self.db)
+            .await?;
+
+        Ok(ban)
+    }
+
+    /// Lift a profile's ban from a group (admin only); lifting a missing ban is a no-op.
+    ///
+    /// A membership kept through the ban becomes active again; a profile banned
+    /// without one has to join.
+    pub async fn _unban_user(
+        &self,
+        group_id: GroupId,
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), GroupsServiceError> {
+        self._get_group(group_id).await?;
+        if !self._is_admin(group_id, admin_profile_id).await? {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+
+        GroupBanned::delete_by_id((group_id, target_profile_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Move everything in `source_group_id` into `dest_group_id`, then delete the
+    /// emptied source, in one transaction (admin of both only).
+    ///
+    /// Topics keep their posts, pinned ones going after the destination's pins.
+    /// Source members join the destination; those already in it keep their
+    /// destination membership, and their source posts are re-attributed to it.
+    /// Bans and admins are combined, but the destination's side wins a clash:
+    /// its admins can't be banned by the source's list, and profiles it bans
+    /// don't become admins or members. A source member it bans only comes along
+    /// when they have posts to keep, and then as a banned (inactive) membership.
+    pub async fn _merge_groups(
+        &self,
+        source_group_id: GroupId,
+        dest_group_id: GroupId,
+        actor_profile_id: ProfileId,
+    ) -> Result<GroupMerge, GroupsServiceError> {
+        if source_group_id == dest_group_id {
+            return Err(GroupsServiceError::MergeIntoSelf);
+        }
+        let source = self._get_group(source_group_id).await?;
+        let dest = self._get_group(dest_group_id).await?;
+        if !self._is_admin(source.id, actor_profile_id).await?
+            || !self._is_admin(dest.id, actor_profile_id).await?
+        {
+            return Err(GroupsServiceError::Unauthorized);
+        }
+        if dest.archived {
+            return Err(GroupsServiceError::GroupArchived);
+        }
+
+        let txn = begin_write(&self.db).await?;
+        let now = self.clock.now_rfc3339();
+
+        let dest_admins: HashSet<ProfileId> = GroupAdmin::find()
+            .filter(GroupAdminColumn::GroupId.eq(dest.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|admin| admin.identity_id)
+            .collect();
+        let dest_bans: HashSet<ProfileId> = GroupBanned::find()
+            .filter(GroupBannedColumn::GroupId.eq(dest.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|ban| ban.identity_id)
+            .collect();
+
+        let new_bans: Vec<GroupBannedActiveModel> = GroupBanned::find()
+            .filter(GroupBannedColumn::GroupId.eq(source.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .filter(|ban| {
+                !dest_admins.contains(&ban.identity_id) && !dest_bans.contains(&ban.identity_id)
+            })
+            .map(|ban| GroupBannedActiveModel {
+                group_id: Set(dest.id),
+                identity_id: Set(ban.identity_id),
+            })
+            .collect();
+        if !new_bans.is_empty() {
+            GroupBanned::insert_many(new_bans).exec(&txn).await?;
+        }
+
+        let new_admins: Vec<GroupAdminActiveModel> = GroupAdmin::find()
+            .filter(GroupAdminColumn::GroupId.eq(source.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .filter(|admin| {
+                !dest_admins.contains(&admin.identity_id) && !dest_bans.contains(&admin.identity_id)
+            })
+            .map(|admin| GroupAdminActiveModel {
+                group_id: Set(dest.id),
+                identity_id: Set(admin.identity_id),
+            })
+            .collect();
+        if !new_admins.is_empty() {
+            GroupAdmin::insert_many(new_admins).exec(&txn).await?;
+        }
+
+        let dest_members: HashMap<ProfileId, UserId> = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(dest.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|user| (user.profile_id, user.id))
+            .collect();
+        let source_members = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(source.id))
+            .all(&txn)
+            .await?;
+
+        let mut members_moved = 0;
+        for member in source_members {
+            match dest_members.get(&member.profile_id) {
+                Some(&dest_user_id) => {
+                    let posts = GroupPost::find()
+                        .filter(GroupPostColumn::UserId.eq(member.id))
+                        .all(&txn)
+                        .await?;
+                    for post in posts {
+                        let mut post: GroupPostActiveModel = post.into();
+                        post.user_id = Set(dest_user_id);
+                        let post = post.update(&txn).await?;
+                        let (entity, op) = (ChangeEntity::Post, ChangeOp::Update);
+                        record_change(&txn, entity, op, post.id, &post, now.clone()).await?;
+                    }
+                    // Views made through the old membership go with it
+                    GroupUser::delete_by_id(member.id).exec(&txn).await?;
+                }
+                None if dest_bans.contains(&member.profile_id) => {
+                    let has_posts = GroupPost::find()
+                        .filter(GroupPostColumn::UserId.eq(member.id))
+                        .count(&txn)
+                        .await?
+                        > 0;
+                    if has_posts {
+                        GroupUser::update_many()
+                            .col_expr(GroupUserColumn::GroupId, Expr::value(dest.id))
+                            .filter(GroupUserColumn::Id.eq(member.id))
+                            .exec(&txn)
+                            .await?;
+                    } else {
+                        GroupUser::delete_by_id(member.id).exec(&txn).await?;
+                    }
+                }
+                None => {
+                    GroupUser::update_many()
+                        .col_expr(GroupUserColumn::GroupId, Expr::value(dest.id))
+                        .filter(GroupUserColumn::Id.eq(member.id))
+                        .exec(&txn)
+                        .await?;
+                    members_moved += 1;
+                }
+            }
+        }
+
+        let last_pin: i64 = GroupTopic::find()
+            .select_only()
+            .column_as(GroupTopicColumn::PinOrder.max(), "last")
+            .filter(GroupTopicColumn::GroupId.eq(dest.id))
+            .into_tuple::<Option<i64>>()
+            .one(&txn)
+            .await?
+            .flatten()
+            .unwrap_or(0);
+        let topics = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(source.id))
+            .all(&txn)
+            .await?;
+        let topics_moved = topics.len() as u64;
+        for topic in topics {
+            let pin_order = topic.pin_order.map(|order| last_pin + order);
+            let mut topic: GroupTopicActiveModel = topic.into();
+            topic.group_id = Set(dest.id);
+            topic.pin_order = Set(pin_order);
+            let topic = topic.update(&txn).await?;
+            let (entity, op) = (ChangeEntity::Topic, ChangeOp::Update);
+            record_change(&txn, entity, op, topic.id, &topic, now.clone()).await?;
+        }
+
+        // Profiles last seen in the source resume in the destination
+        ProfileState::update_many()
+            .col_expr(ProfileStateColumn::LastGroupId, Expr::value(dest.id))
+            .filter(ProfileStateColumn::LastGroupId.eq(source.id))
+            .exec(&txn)
+            .await?;
+
+        Group::delete_by_id(source.id).exec(&txn).await?;
+        let (entity, op) = (ChangeEntity::Group, ChangeOp::Delete);
+        record_change(&txn, entity, op, source.id, &source, now).await?;
+        txn.commit().await?;
+
+        Ok(GroupMerge {
+            group: dest,
+            topics_moved,
+            members_moved,
+        })
+    }
+
+    /// List the profiles banned from a group
+    pub async fn _list_banned(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<GroupBannedModel>, GroupsServiceError> {
+        let banned = GroupBanned::find()
+            .filter(GroupBannedColumn::GroupId.eq(group_id))
+            .order_by_asc(GroupBannedColumn::IdentityId)
+            .all(&self.db)
+            .await?;
+
+        Ok(banned)
+    }
+}
+
+#[zel_service(name = "groups")]
+trait Groups {
+    #[doc = "Create a new group owned by the calling profile"]
+    #[method(name = "create_group")]
+    async fn create_group(&self, profile_id: ProfileId) -> Result<GroupModel, ResourceError>;
+
+    #[doc = "List every group a profile belongs to with its role there"]
+    #[method(name = "my_roles")]
+    async fn my_roles(&self, profile_id: ProfileId) -> Result<Vec<GroupMembership>, ResourceError>;
+
+    #[doc = "A profile's non-archived groups, each once, tagged owner, admin or member"]
+    #[method(name = "list_my_groups")]
+    async fn list_my_groups(
+        &self,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<MyGroup>, ResourceError>;
+
+    #[doc = "List the non-archived groups two profiles are both members of"]
+    #[method(name = "common_groups")]
+    async fn common_groups(
+        &self,
+        profile_a: ProfileId,
+        profile_b: ProfileId,
+    ) -> Result<Vec<GroupModel>, ResourceError>;
+
+    #[doc = "List all non-archived groups owned by a profile"]
+    #[method(name = "list_groups")]
+    async fn list_groups(&self, profile_id: ProfileId) -> Result<Vec<GroupModel>, ResourceError>;
+
+    #[doc = "List the archived groups owned by a profile"]
+    #[method(name = "list_archived_groups")]
+    async fn list_archived_groups(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<Vec<GroupModel>, ResourceError>;
+
+    #[doc = "Archive or unarchive a group (admin only)"]
+    #[method(name = "set_archived")]
+    async fn set_archived(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        archived: bool,
+    ) -> Result<GroupModel, ResourceError>;
+
+    #[doc = "Get a specific group by ID"]
+    #[method(name = "get_group")]
+    async fn get_group(&self, group_id: GroupId) -> Result<GroupModel, ResourceError>;
+
+    #[doc = "Get a specific group by ID, or nothing if it doesn't exist"]
+    #[method(name = "try_get_group")]
+    async fn try_get_group(&self, group_id: GroupId) -> Result<Option<GroupModel>, ResourceError>;
+
+    #[doc = "Get several groups by ID, in input order, skipping unknown ids"]
+    #[method(name = "get_groups_by_ids")]
+    async fn get_groups_by_ids(&self, ids: Vec<GroupId>) -> Result<Vec<GroupModel>, ResourceError>;
+
+    #[doc = "Delete a group"]
+    #[method(name = "delete_group")]
+    async fn delete_group(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<(), ResourceError>;
+
+    #[doc = "Check if a profile is an admin of a group"]
+    #[method(name = "is_admin")]
+    async fn is_admin(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, ResourceError>;
+
+    #[doc = "Check if a profile is a member of a group (banned profiles are not)"]
+    #[method(name = "is_member")]
+    async fn is_member(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, ResourceError>;
+
     #[doc = "List all admins for a group"]
     #[method(name = "list_admins")]
     async fn list_admins(&self, group_id: GroupId) -> Result<Vec<GroupAdminModel>, ResourceError>;
 
+    #[doc = "List a group's admins as full profiles"]
+    #[method(name = "list_admin_profiles")]
+    async fn list_admin_profiles(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<ProfileModel>, ResourceError>;
+
+    #[doc = "Make a profile an admin of a group (admin only)"]
+    #[method(name = "add_admin")]
+    async fn add_admin(
+        &self,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupAdminModel, ResourceError>;
+
+    #[doc = "Take a profile's admin role away, keeping at least one admin (admin only)"]
+    #[method(name = "remove_admin")]
+    async fn remove_admin(
+        &self,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), ResourceError>;
+
     #[doc = "Add a user to a group"]
     #[method(name = "add_user")]
     async fn add_user(
@@ -236,9 +1405,96 @@ trait Groups {
         profile_id: ProfileId,
     ) -> Result<GroupUserModel, ResourceError>;
 
+    #[doc = "A profile's membership in a group, joining it first if it isn't a member"]
+    #[method(name = "ensure_membership")]
+    async fn ensure_membership(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<GroupUserModel, ResourceError>;
+
+    #[doc = "Add many profiles to a group at once (admin only, capped per call)"]
+    #[method(name = "add_users_bulk")]
+    async fn add_users_bulk(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_ids: Vec<ProfileId>,
+    ) -> Result<Vec<BulkAddResult>, ResourceError>;
+
     #[doc = "List all users in a group"]
     #[method(name = "list_users")]
     async fn list_users(&self, group_id: GroupId) -> Result<Vec<GroupUserModel>, ResourceError>;
+
+    #[doc = "List a group's members with their in-group display names"]
+    #[method(name = "list_members")]
+    async fn list_members(&self, group_id: GroupId) -> Result<Vec<GroupMember>, ResourceError>;
+
+    #[doc = "List a group's members with their membership ids and full profiles"]
+    #[method(name = "list_member_profiles")]
+    async fn list_member_profiles(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<MemberProfile>, ResourceError>;
+
+    #[doc = "Hand a group to another of its members (owner only)"]
+    #[method(name = "transfer_ownership")]
+    async fn transfer_ownership(
+        &self,
+        group_id: GroupId,
+        owner_profile_id: ProfileId,
+        new_owner_profile_id: ProfileId,
+    ) -> Result<GroupModel, ResourceError>;
+
+    #[doc = "Remove a member (themselves or an admin); the owner may only leave as the last member"]
+    #[method(name = "remove_user")]
+    async fn remove_user(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_id: ProfileId,
+    ) -> Result<RemoveUserOutcome, ResourceError>;
+
+    #[doc = "Set or clear a member's group nickname (the member or an admin)"]
+    #[method(name = "set_nickname")]
+    async fn set_nickname(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_id: ProfileId,
+        nickname: Option<String>,
+    ) -> Result<GroupUserModel, ResourceError>;
+
+    #[doc = "Ban a profile from a group and remove its membership (admin only)"]
+    #[method(name = "ban_user")]
+    async fn ban_user(
+        &self,
+        group_id: GroupId,
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupBannedModel, ResourceError>;
+
+    #[doc = "Lift a profile's ban from a group (admin only)"]
+    #[method(name = "unban_user")]
+    async fn unban_user(
+        &self,
+        group_id: GroupId,
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), ResourceError>;
+
+    #[doc = "Move one group's topics, members, admins and bans into another, then delete it"]
+    #[method(name = "merge_groups")]
+    async fn merge_groups(
+        &self,
+        source_group_id: GroupId,
+        dest_group_id: GroupId,
+        actor_profile_id: ProfileId,
+    ) -> Result<GroupMerge, ResourceError>;
+
+    #[doc = "List the profiles banned from a group"]
+    #[method(name = "list_banned")]
+    async fn list_banned(&self, group_id: GroupId) -> Result<Vec<GroupBannedModel>, ResourceError>;
 }
 
 #[async_trait]
@@ -248,7 +1504,7 @@ impl GroupsServer for GroupsService {
         _ctx: RequestContext,
         profile_id: ProfileId,
     ) -> Result<GroupModel, ResourceError> {
-        Ok(self._create_group(profile_id).await?)
+        self.breaker.call(self._create_group(profile_id)).await
     }
 
     async fn list_groups(
@@ -256,7 +1512,57 @@ impl GroupsServer for GroupsService {
         _ctx: RequestContext,
         profile_id: ProfileId,
     ) -> Result<Vec<GroupModel>, ResourceError> {
-        Ok(self._list_groups(profile_id).await?)
+        self.breaker.call(self._list_groups(profile_id)).await
+    }
+
+    async fn my_roles(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<Vec<GroupMembership>, ResourceError> {
+        self.breaker.call(self._my_roles(profile_id)).await
+    }
+
+    async fn list_my_groups(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<MyGroup>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._list_my_groups(profile_id, limit, offset.unwrap_or(0)))
+            .await
+    }
+
+    async fn common_groups(
+        &self,
+        _ctx: RequestContext,
+        profile_a: ProfileId,
+        profile_b: ProfileId,
+    ) -> Result<Vec<GroupModel>, ResourceError> {
+        self.breaker.call(self._common_groups(profile_a, profile_b)).await
+    }
+
+    async fn list_archived_groups(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<Vec<GroupModel>, ResourceError> {
+        self.breaker.call(self._list_archived_groups(profile_id)).await
+    }
+
+    async fn set_archived(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        archived: bool,
+    ) -> Result<GroupModel, ResourceError> {
+        self.breaker
+            .call(self._set_archived(group_id, actor_profile_id, archived))
+            .await
     }
 
     async fn get_group(
@@ -264,7 +1570,23 @@ impl GroupsServer for GroupsService {
         _ctx: RequestContext,
         group_id: GroupId,
     ) -> Result<GroupModel, ResourceError> {
-        Ok(self._get_group(group_id).await?)
+        self.breaker.call(self._get_group(group_id)).await
+    }
+
+    async fn try_get_group(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Option<GroupModel>, ResourceError> {
+        self.breaker.call(self._try_get_group(group_id)).await
+    }
+
+    async fn get_groups_by_ids(
+        &self,
+        _ctx: RequestContext,
+        ids: Vec<GroupId>,
+    ) -> Result<Vec<GroupModel>, ResourceError> {
+        self.breaker.call(self._get_groups_by_ids(ids)).await
     }
 
     async fn delete_group(
@@ -273,48 +1595,210 @@ impl GroupsServer for GroupsService {
         group_id: GroupId,
         profile_id: ProfileId,
     ) -> Result<(), ResourceError> {
-        Ok(self._delete_group(group_id, profile_id).await?)
+        self.breaker.call(self._delete_group(group_id, profile_id)).await
+    }
+
+    async fn is_admin(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, ResourceError> {
+        self.breaker.call(self._is_admin(group_id, profile_id)).await
+    }
+
+    async fn is_member(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, ResourceError> {
+        self.breaker.call(self._is_member(group_id, profile_id)).await
+    }
+
+    async fn list_admins(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<GroupAdminModel>, ResourceError> {
+        self.breaker.call(self._list_admins(group_id)).await
+    }
+
+    async fn add_user(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<GroupUserModel, ResourceError> {
+        self.breaker.call(self._add_user(group_id, profile_id)).await
+    }
+
+    async fn ensure_membership(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<GroupUserModel, ResourceError> {
+        self.breaker.call(self._ensure_membership(group_id, profile_id)).await
+    }
+
+    async fn add_users_bulk(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_ids: Vec<ProfileId>,
+    ) -> Result<Vec<BulkAddResult>, ResourceError> {
+        self.breaker
+            .call(self._add_users_bulk(group_id, actor_profile_id, profile_ids))
+            .await
+    }
+
+    async fn list_users(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<GroupUserModel>, ResourceError> {
+        self.breaker.call(self._list_users(group_id)).await
+    }
+
+    async fn list_members(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<GroupMember>, ResourceError> {
+        self.breaker.call(self._list_members(group_id)).await
+    }
+
+    async fn list_admin_profiles(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<ProfileModel>, ResourceError> {
+        self.breaker.call(self._list_admin_profiles(group_id)).await
+    }
+
+    async fn add_admin(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupAdminModel, ResourceError> {
+        self.breaker
+            .call(self._add_admin(group_id, caller_profile_id, target_profile_id))
+            .await
+    }
+
+    async fn remove_admin(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        caller_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), ResourceError> {
+        self.breaker
+            .call(self._remove_admin(group_id, caller_profile_id, target_profile_id))
+            .await
+    }
+
+    async fn list_member_profiles(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<MemberProfile>, ResourceError> {
+        self.breaker.call(self._list_member_profiles(group_id)).await
+    }
+
+    async fn transfer_ownership(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        owner_profile_id: ProfileId,
+        new_owner_profile_id: ProfileId,
+    ) -> Result<GroupModel, ResourceError> {
+        self.breaker
+            .call(self._transfer_ownership(group_id, owner_profile_id, new_owner_profile_id))
+            .await
+    }
+
+    async fn remove_user(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_id: ProfileId,
+    ) -> Result<RemoveUserOutcome, ResourceError> {
+        self.breaker
+            .call(self._remove_user(group_id, actor_profile_id, profile_id))
+            .await
+    }
+
+    async fn set_nickname(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        profile_id: ProfileId,
+        nickname: Option<String>,
+    ) -> Result<GroupUserModel, ResourceError> {
+        self.breaker
+            .call(self._set_nickname(group_id, actor_profile_id, profile_id, nickname))
+            .await
     }
 
-    async fn is_admin(
+    async fn ban_user(
         &self,
         _ctx: RequestContext,
         group_id: GroupId,
-        profile_id: ProfileId,
-    ) -> Result<bool, ResourceError> {
-        Ok(self._is_admin(group_id, profile_id).await?)
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<GroupBannedModel, ResourceError> {
+        self.breaker
+            .call(self._ban_user(group_id, admin_profile_id, target_profile_id))
+            .await
     }
 
-    async fn list_admins(
+    async fn unban_user(
         &self,
         _ctx: RequestContext,
         group_id: GroupId,
-    ) -> Result<Vec<GroupAdminModel>, ResourceError> {
-        Ok(self._list_admins(group_id).await?)
+        admin_profile_id: ProfileId,
+        target_profile_id: ProfileId,
+    ) -> Result<(), ResourceError> {
+        self.breaker
+            .call(self._unban_user(group_id, admin_profile_id, target_profile_id))
+            .await
     }
 
-    async fn add_user(
+    async fn merge_groups(
         &self,
         _ctx: RequestContext,
-        group_id: GroupId,
-        profile_id: ProfileId,
-    ) -> Result<GroupUserModel, ResourceError> {
-        Ok(self._add_user(group_id, profile_id).await?)
+        source_group_id: GroupId,
+        dest_group_id: GroupId,
+        actor_profile_id: ProfileId,
+    ) -> Result<GroupMerge, ResourceError> {
+        self.breaker
+            .call(self._merge_groups(source_group_id, dest_group_id, actor_profile_id))
+            .await
     }
 
-    async fn list_users(
+    async fn list_banned(
         &self,
         _ctx: RequestContext,
         group_id: GroupId,
-    ) -> Result<Vec<GroupUserModel>, ResourceError> {
-        Ok(self._list_users(group_id).await?)
+    ) -> Result<Vec<GroupBannedModel>, ResourceError> {
+        self.breaker.call(self._list_banned(group_id)).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::migrator::Migrator;
+    use crate::{
+        models::migrator::Migrator,
+        service::{posts::PostsService, topics::TopicsService},
+    };
     use sea_orm::Database;
     use sea_orm_migration::MigratorTrait;
 
@@ -337,11 +1821,23 @@ mod tests {
             name: Set(format!("Test User {}", profile_id)), // Unique name
             desc: Set("Test".to_string()),
             picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
         };
         Profile::insert(profile).exec(&service.db).await.unwrap();
         profile_id
     }
 
+    #[tokio::test]
+    async fn test_connection_accessor() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+        service._create_group(profile_id).await.unwrap();
+
+        let count = Group::find().count(service.connection()).await.unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[tokio::test]
     async fn test_create_group() {
         let service = setup_test_service().await;
@@ -355,6 +1851,32 @@ mod tests {
         assert_eq!(group.profile_id, profile_id);
     }
 
+    #[tokio::test]
+    async fn test_create_methods_return_created_at() {
+        fn assert_recent(created_at: Option<String>) {
+            let created_at = created_at.expect("created_at should be set");
+            let created_at = chrono::DateTime::parse_from_rfc3339(&created_at).unwrap();
+            let age = chrono::Utc::now().signed_duration_since(created_at);
+            assert!(age >= chrono::Duration::zero() && age < chrono::Duration::seconds(60));
+        }
+
+        let service = setup_test_service().await;
+        let admin = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let bulk_member = create_test_profile(&service).await;
+
+        let group = service._create_group(admin).await.unwrap();
+        assert_recent(group.created_at);
+
+        let user = service._add_user(group.id, member).await.unwrap();
+        assert_recent(user.created_at);
+
+        service._add_users_bulk(group.id, admin, vec![bulk_member]).await.unwrap();
+        for user in service._list_users(group.id).await.unwrap() {
+            assert_recent(user.created_at);
+        }
+    }
+
     #[tokio::test]
     async fn test_create_group_makes_creator_admin() {
         let service = setup_test_service().await;
@@ -380,6 +1902,155 @@ mod tests {
         assert_eq!(groups.len(), 3, "Should have 3 groups");
     }
 
+    #[tokio::test]
+    async fn test_my_roles() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+        let other = create_test_profile(&service).await;
+
+        let owned = service._create_group(profile_id).await.unwrap();
+
+        let administered = service._create_group(other).await.unwrap();
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(administered.id),
+            identity_id: Set(profile_id),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let joined = service._create_group(other).await.unwrap();
+        service._add_user(joined.id, profile_id).await.unwrap();
+
+        // Not involved at all
+        service._create_group(other).await.unwrap();
+
+        let roles = service._my_roles(profile_id).await.unwrap();
+        let summary: Vec<(GroupId, GroupRole, bool)> = roles
+            .iter()
+            .map(|membership| (membership.group.id, membership.role, membership.is_owner))
+            .collect();
+        let mut expected = vec![
+            (owned.id, GroupRole::Admin, true),
+            (administered.id, GroupRole::Admin, false),
+            (joined.id, GroupRole::Member, false),
+        ];
+        expected.sort_by_key(|(id, _, _)| id.into_uuid());
+        assert_eq!(summary, expected);
+    }
+
+    #[tokio::test]
+    async fn test_list_my_groups() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+        let other = create_test_profile(&service).await;
+
+        // Owned and also joined as a member: still listed once, as owner
+        let owned = service._create_group(profile_id).await.unwrap();
+        service._add_user(owned.id, profile_id).await.unwrap();
+
+        // Admin and member at once
+        let administered = service._create_group(other).await.unwrap();
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(administered.id),
+            identity_id: Set(profile_id),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        service._add_user(administered.id, profile_id).await.unwrap();
+
+        let joined = service._create_group(other).await.unwrap();
+        service._add_user(joined.id, profile_id).await.unwrap();
+
+        // Archived and unrelated groups are left out
+        let archived = service._create_group(profile_id).await.unwrap();
+        service._set_archived(archived.id, profile_id, true).await.unwrap();
+        service._create_group(other).await.unwrap();
+
+        let groups = service._list_my_groups(profile_id, 10, 0).await.unwrap();
+        let summary: Vec<(GroupId, GroupRelationship)> =
+            groups.iter().map(|entry| (entry.group.id, entry.relationship)).collect();
+        let mut expected = vec![
+            (owned.id, GroupRelationship::Owner),
+            (administered.id, GroupRelationship::Admin),
+            (joined.id, GroupRelationship::Member),
+        ];
+        expected.sort_by_key(|(id, _)| id.into_uuid());
+        assert_eq!(summary, expected);
+
+        let page = service._list_my_groups(profile_id, 2, 1).await.unwrap();
+        assert_eq!(page, groups[1..]);
+        assert!(service._list_my_groups(ProfileId::new(), 10, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_common_groups() {
+        let service = setup_test_service().await;
+        let alice = create_test_profile(&service).await;
+        let bob = create_test_profile(&service).await;
+
+        let shared = service._create_group(alice).await.unwrap();
+        service._add_user(shared.id, alice).await.unwrap();
+        service._add_user(shared.id, bob).await.unwrap();
+
+        // Only one of them is a member
+        let alice_only = service._create_group(alice).await.unwrap();
+        service._add_user(alice_only.id, alice).await.unwrap();
+        let bob_only = service._create_group(bob).await.unwrap();
+        service._add_user(bob_only.id, bob).await.unwrap();
+
+        // Shared but archived
+        let archived = service._create_group(alice).await.unwrap();
+        service._add_user(archived.id, alice).await.unwrap();
+        service._add_user(archived.id, bob).await.unwrap();
+        service._set_archived(archived.id, alice, true).await.unwrap();
+
+        // Shared but bob is banned
+        let banned = service._create_group(alice).await.unwrap();
+        service._add_user(banned.id, alice).await.unwrap();
+        service._add_user(banned.id, bob).await.unwrap();
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(banned.id),
+            identity_id: Set(bob),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let common = service._common_groups(alice, bob).await.unwrap();
+        assert_eq!(common.iter().map(|group| group.id).collect::<Vec<_>>(), vec![shared.id]);
+
+        let reversed = service._common_groups(bob, alice).await.unwrap();
+        assert_eq!(reversed, common);
+    }
+
+    #[tokio::test]
+    async fn test_set_archived() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+        let other_profile = create_test_profile(&service).await;
+
+        let group = service._create_group(profile_id).await.unwrap();
+
+        let result = service._set_archived(group.id, other_profile, true).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+
+        let archived = service._set_archived(group.id, profile_id, true).await.unwrap();
+        assert!(archived.archived);
+        assert!(archived.archived_at.is_some());
+
+        // Archived groups drop out of the default listing
+        assert!(service._list_groups(profile_id).await.unwrap().is_empty());
+        let archived_list = service._list_archived_groups(profile_id).await.unwrap();
+        assert_eq!(archived_list.len(), 1);
+
+        let restored = service._set_archived(group.id, profile_id, false).await.unwrap();
+        assert!(!restored.archived);
+        assert_eq!(restored.archived_at, None);
+        assert_eq!(service._list_groups(profile_id).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_group() {
         let service = setup_test_service().await;
@@ -391,6 +2062,52 @@ mod tests {
         assert_eq!(created.id, fetched.id);
     }
 
+    #[tokio::test]
+    async fn test_get_groups_by_ids_preserves_order() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+
+        let mut groups = Vec::new();
+        for _ in 0..3 {
+            groups.push(service._create_group(profile_id).await.unwrap());
+        }
+
+        let ids = vec![groups[2].id, groups[0].id, groups[1].id];
+        let fetched = service._get_groups_by_ids(ids.clone()).await.unwrap();
+
+        let fetched_ids: Vec<GroupId> = fetched.iter().map(|g| g.id).collect();
+        assert_eq!(fetched_ids, ids);
+    }
+
+    #[tokio::test]
+    async fn test_get_groups_by_ids_empty_skips_db() {
+        let service = setup_test_service().await;
+
+        // A closed pool fails every query, so success means none was made
+        service.db.clone().close().await.unwrap();
+        assert!(service._get_groups_by_ids(Vec::new()).await.unwrap().is_empty());
+        assert!(service._get_groups_by_ids(vec![GroupId::new()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_groups_by_ids_skips_missing() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service).await;
+
+        let group = service._create_group(profile_id).await.unwrap();
+
+        let fetched = service
+            ._get_groups_by_ids(vec![GroupId::new(), group.id, GroupId::new()])
+            .await
+            .unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].id, group.id);
+
+        let too_many = (0..=MAX_GROUPS_BY_IDS).map(|_| GroupId::new()).collect();
+        let result = service._get_groups_by_ids(too_many).await;
+        assert!(matches!(result, Err(GroupsServiceError::TooManyGroups)));
+    }
+
     #[tokio::test]
     async fn test_delete_group_by_admin() {
         let service = setup_test_service().await;
@@ -453,6 +2170,566 @@ mod tests {
         assert_eq!(users.len(), 3, "Should have 3 users");
     }
 
+    #[tokio::test]
+    async fn test_list_admin_and_member_profiles() {
+        let service = setup_test_service().await;
+        let admin = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+
+        let group = service._create_group(admin).await.unwrap();
+        let user = service._add_user(group.id, member).await.unwrap();
+        service
+            ._set_nickname(group.id, member, member, Some("Nick".to_string()))
+            .await
+            .unwrap();
+
+        let admins = service._list_admin_profiles(group.id).await.unwrap();
+        assert_eq!(admins.len(), 1);
+        assert_eq!(admins[0].id, admin);
+        assert_eq!(admins[0].name, format!("Test User {admin}"));
+
+        let members = service._list_member_profiles(group.id).await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].user_id, user.id);
+        assert_eq!(members[0].nickname.as_deref(), Some("Nick"));
+        assert_eq!(members[0].profile.id, member);
+        assert_eq!(members[0].profile.name, format!("Test User {member}"));
+        assert_eq!(members[0].profile.desc, "Test");
+
+        let other = service._create_group(member).await.unwrap();
+        assert!(service._list_member_profiles(other.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_nickname() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let other = create_test_profile(&service).await;
+
+        let group = service._create_group(admin_profile).await.unwrap();
+        service._add_user(group.id, member).await.unwrap();
+        service._add_user(group.id, other).await.unwrap();
+
+        // Members may name themselves
+        let user = service
+            ._set_nickname(group.id, member, member, Some("  Nick  ".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(user.nickname.as_deref(), Some("Nick"));
+
+        // ...but not each other
+        let result = service
+            ._set_nickname(group.id, other, member, Some("Rude".to_string()))
+            .await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+
+        let result = service
+            ._set_nickname(group.id, member, member, Some("a\nb".to_string()))
+            .await;
+        assert!(matches!(result, Err(GroupsServiceError::InvalidNickname)));
+
+        // Admins aren't members here unless added
+        let result = service._set_nickname(group.id, admin_profile, admin_profile, None).await;
+        assert!(matches!(result, Err(GroupsServiceError::NotAMember)));
+
+        let members = service._list_members(group.id).await.unwrap();
+        let shown = |profile_id| {
+            members
+                .iter()
+                .find(|m| m.profile_id == profile_id)
+                .map(|m| m.display_name.clone())
+                .unwrap()
+        };
+        assert_eq!(shown(member), "Nick");
+        assert_eq!(shown(other), format!("Test User {}", other));
+
+        // An admin can clear it again
+        let user = service
+            ._set_nickname(group.id, admin_profile, member, Some("   ".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(user.nickname, None);
+    }
+
+    #[tokio::test]
+    async fn test_is_member() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let member_profile = create_test_profile(&service).await;
+        let outsider_profile = create_test_profile(&service).await;
+
+        let group = service._create_group(admin_profile).await.unwrap();
+        service._add_user(group.id, member_profile).await.unwrap();
+
+        assert!(service._is_member(group.id, member_profile).await.unwrap());
+        assert!(!service._is_member(group.id, outsider_profile).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_banned_profile_is_not_member() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let banned_profile = create_test_profile(&service).await;
+
+        let group = service._create_group(admin_profile).await.unwrap();
+        service._add_user(group.id, banned_profile).await.unwrap();
+
+        let ban = GroupBannedActiveModel {
+            group_id: Set(group.id),
+            identity_id: Set(banned_profile),
+        };
+        GroupBanned::insert(ban).exec(&service.db).await.unwrap();
+
+        assert!(
+            !service._is_member(group.id, banned_profile).await.unwrap(),
+            "Banned profile should not count as a member"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_users_bulk() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let existing_member = create_test_profile(&service).await;
+        let new_profile_1 = create_test_profile(&service).await;
+        let new_profile_2 = create_test_profile(&service).await;
+        let missing_profile = ProfileId::new();
+
+        let group = service._create_group(admin_profile).await.unwrap();
+        service._add_user(group.id, existing_member).await.unwrap();
+
+        let results = service
+            ._add_users_bulk(
+                group.id,
+                admin_profile,
+                vec![new_profile_1, existing_member, missing_profile, new_profile_2],
+            )
+            .await
+            .expect("Admin should be able to bulk add");
+
+        let statuses: Vec<BulkAddStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BulkAddStatus::Added,
+                BulkAddStatus::AlreadyMember,
+                BulkAddStatus::ProfileNotFound,
+                BulkAddStatus::Added,
+            ]
+        );
+        assert_eq!(results[2].profile_id, missing_profile);
+
+        let users = service._list_users(group.id).await.unwrap();
+        assert_eq!(users.len(), 3, "Two new members plus the existing one");
+    }
+
+    #[tokio::test]
+    async fn test_banned_profile_cannot_join() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let banned_profile = create_test_profile(&service).await;
+        let group = service._create_group(admin_profile).await.unwrap();
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(group.id),
+            identity_id: Set(banned_profile),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let result = service._add_user(group.id, banned_profile).await;
+        assert!(matches!(result, Err(GroupsServiceError::Banned)));
+        let results = service
+            ._add_users_bulk(group.id, admin_profile, vec![banned_profile])
+            .await
+            .unwrap();
+        assert_eq!(results[0].status, BulkAddStatus::Banned);
+        assert!(service._list_users(group.id).await.unwrap().is_empty());
+
+        // The ban is per group
+        let other = service._create_group(admin_profile).await.unwrap();
+        assert!(service._add_user(other.id, banned_profile).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_remove_user() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let other = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        for profile in [owner, member, other] {
+            service._add_user(group.id, profile).await.unwrap();
+        }
+
+        // Members can't remove each other, but admins and the member themselves can
+        let result = service._remove_user(group.id, member, other).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let outcome = service._remove_user(group.id, owner, other).await.unwrap();
+        assert_eq!(outcome, RemoveUserOutcome::Removed);
+        let outcome = service._remove_user(group.id, member, member).await.unwrap();
+        assert_eq!(outcome, RemoveUserOutcome::Removed);
+        let result = service._remove_user(group.id, member, member).await;
+        assert!(matches!(result, Err(GroupsServiceError::NotAMember)));
+        let users = service._list_users(group.id).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].profile_id, owner);
+    }
+
+    #[tokio::test]
+    async fn test_owner_must_transfer_before_leaving() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let admin = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        service._add_user(group.id, owner).await.unwrap();
+        service._add_user(group.id, admin).await.unwrap();
+        service._add_admin(group.id, owner, admin).await.unwrap();
+
+        // Nobody else removes the owner, and the owner can't walk out on members
+        let result = service._remove_user(group.id, admin, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let result = service._remove_user(group.id, owner, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::OwnerMustTransferFirst)));
+        assert!(service._is_member(group.id, owner).await.unwrap());
+
+        // Once the group is handed over, the old owner can leave like anyone else
+        let outcome = service._transfer_ownership(group.id, owner, admin).await.unwrap();
+        assert_eq!(outcome.profile_id, admin);
+        let outcome = service._remove_user(group.id, owner, owner).await.unwrap();
+        assert_eq!(outcome, RemoveUserOutcome::Removed);
+        assert_eq!(service._get_group(group.id).await.unwrap().profile_id, admin);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_ownership() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let outsider = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        service._add_user(group.id, owner).await.unwrap();
+        service._add_user(group.id, member).await.unwrap();
+
+        // Only the owner hands over, and only to a member
+        let result = service._transfer_ownership(group.id, member, member).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let result = service._transfer_ownership(group.id, owner, outsider).await;
+        assert!(matches!(result, Err(GroupsServiceError::NotAMember)));
+
+        let transferred = service._transfer_ownership(group.id, owner, member).await.unwrap();
+        assert_eq!(transferred.profile_id, member);
+        assert!(service._is_admin(group.id, member).await.unwrap());
+        assert!(service._is_admin(group.id, owner).await.unwrap());
+        let result = service._transfer_ownership(group.id, owner, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_owner_leaving_as_last_member_deletes_group() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        service._add_user(group.id, owner).await.unwrap();
+
+        let outcome = service._remove_user(group.id, owner, owner).await.unwrap();
+        assert_eq!(outcome, RemoveUserOutcome::GroupDeleted);
+        assert!(service._try_get_group(group.id).await.unwrap().is_none());
+        assert!(service._list_admins(group.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ban_and_unban_are_admin_only() {
+        let service = setup_test_service().await;
+        let admin = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let target = create_test_profile(&service).await;
+        let group = service._create_group(admin).await.unwrap();
+        service._add_user(group.id, member).await.unwrap();
+        let target_user = service._add_user(group.id, target).await.unwrap();
+
+        // Members can't ban, and nobody can ban an admin
+        let result = service._ban_user(group.id, member, target).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let result = service._ban_user(group.id, admin, admin).await;
+        assert!(matches!(result, Err(GroupsServiceError::CannotBanAdmin)));
+        assert!(service._list_banned(group.id).await.unwrap().is_empty());
+
+        // The ban ends the membership, and repeating it changes nothing
+        let ban = service._ban_user(group.id, admin, target).await.unwrap();
+        assert_eq!((ban.group_id, ban.identity_id), (group.id, target));
+        assert_eq!(service._ban_user(group.id, admin, target).await.unwrap(), ban);
+        assert_eq!(service._list_banned(group.id).await.unwrap(), vec![ban]);
+        assert!(!service._is_member(group.id, target).await.unwrap());
+        let users = service._list_users(group.id).await.unwrap();
+        assert!(users.iter().all(|user| user.profile_id != target));
+
+        let result = service._unban_user(group.id, member, target).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        assert!(matches!(
+            service._add_user(group.id, target).await,
+            Err(GroupsServiceError::Banned)
+        ));
+
+        // Unbanned, the kept membership counts again
+        service._unban_user(group.id, admin, target).await.unwrap();
+        service._unban_user(group.id, admin, target).await.unwrap();
+        assert!(service._list_banned(group.id).await.unwrap().is_empty());
+        assert!(service._is_member(group.id, target).await.unwrap());
+        let ensured = service._ensure_membership(group.id, target).await.unwrap();
+        assert_eq!(ensured.id, target_user.id);
+    }
+
+    #[tokio::test]
+    async fn test_ban_keeps_posts_and_replies() {
+        let service = setup_test_service().await;
+        let topics = TopicsService::new(service.db.clone());
+        let posts = PostsService::new(service.db.clone());
+        let admin = create_test_profile(&service).await;
+        let target = create_test_profile(&service).await;
+        let group = service._create_group(admin).await.unwrap();
+        let admin_user = service._add_user(group.id, admin).await.unwrap();
+        let target_user = service._add_user(group.id, target).await.unwrap();
+
+        let opened = topics
+            ._create_topic_with_post(group.id, target_user.id, "Topic".into(), "Body".into())
+            .await
+            .unwrap();
+        let reply = posts
+            ._create_reply(opened.post.id, admin_user.id, "Re".into(), "Reply".into())
+            .await
+            .unwrap();
+
+        service._ban_user(group.id, admin, target).await.unwrap();
+
+        // Neither the banned member's post nor the replies to it go anywhere
+        assert!(posts._try_get_post(opened.post.id).await.unwrap().is_some());
+        let replies = posts._list_replies(opened.post.id, 10, 0).await.unwrap();
+        assert_eq!(replies, vec![reply]);
+        let members = service._list_members(group.id).await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].profile_id, admin);
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_admin() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let member = create_test_profile(&service).await;
+        let banned = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        service._ban_user(group.id, owner, banned).await.unwrap();
+
+        // Only admins promote, and never a banned profile
+        let result = service._add_admin(group.id, member, member).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let result = service._add_admin(group.id, owner, banned).await;
+        assert!(matches!(result, Err(GroupsServiceError::Banned)));
+
+        let admin = service._add_admin(group.id, owner, member).await.unwrap();
+        assert_eq!((admin.group_id, admin.identity_id), (group.id, member));
+        assert_eq!(service._add_admin(group.id, owner, member).await.unwrap(), admin);
+        assert_eq!(service._list_admins(group.id).await.unwrap().len(), 2);
+
+        // Nobody demotes the owner, not even the owner, so they can't be banned next
+        for caller in [member, owner] {
+            let result = service._remove_admin(group.id, caller, owner).await;
+            assert!(matches!(result, Err(GroupsServiceError::CannotDemoteOwner)));
+        }
+        assert!(service._is_admin(group.id, owner).await.unwrap());
+        let result = service._ban_user(group.id, member, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::CannotBanAdmin)));
+
+        // The owner can demote other admins, and demoting a non-admin is a no-op
+        service._remove_admin(group.id, owner, member).await.unwrap();
+        assert!(!service._is_admin(group.id, member).await.unwrap());
+        let result = service._remove_admin(group.id, member, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        service._remove_admin(group.id, owner, member).await.unwrap();
+        assert_eq!(service._list_admins(group.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_old_owner_can_be_demoted_after_transfer() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let successor = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+        service._add_user(group.id, successor).await.unwrap();
+
+        // After a handover the old owner is an ordinary admin again
+        service._transfer_ownership(group.id, owner, successor).await.unwrap();
+        service._remove_admin(group.id, successor, owner).await.unwrap();
+        let admins = service._list_admins(group.id).await.unwrap();
+        assert_eq!(admins.len(), 1);
+        assert_eq!(admins[0].identity_id, successor);
+        let result = service._remove_admin(group.id, successor, successor).await;
+        assert!(matches!(result, Err(GroupsServiceError::CannotDemoteOwner)));
+    }
+
+    #[tokio::test]
+    async fn test_merge_groups_combines_both() {
+        let service = setup_test_service().await;
+        let topics = TopicsService::new(service.db.clone());
+        let owner = create_test_profile(&service).await;
+        let shared = create_test_profile(&service).await;
+        let newcomer = create_test_profile(&service).await;
+        let source_admin = create_test_profile(&service).await;
+        let banned_in_dest = create_test_profile(&service).await;
+        let banned_in_source = create_test_profile(&service).await;
+
+        let source = service._create_group(owner).await.unwrap();
+        let dest = service._create_group(owner).await.unwrap();
+        for profile in [owner, shared, newcomer, source_admin] {
+            service._add_user(source.id, profile).await.unwrap();
+        }
+        let dest_owner = service._add_user(dest.id, owner).await.unwrap();
+        let dest_shared = service._add_user(dest.id, shared).await.unwrap();
+        for (group_id, profile) in [(source.id, source_admin), (source.id, banned_in_dest)] {
+            GroupAdminActiveModel {
+                group_id: Set(group_id),
+                identity_id: Set(profile),
+            }
+            .insert(&service.db)
+            .await
+            .unwrap();
+        }
+        service._ban_user(source.id, owner, banned_in_source).await.unwrap();
+        service._ban_user(dest.id, owner, banned_in_dest).await.unwrap();
+
+        // A pinned topic on each side, the source's with a post by a shared member
+        let dest_topic = topics
+            ._create_topic_with_post(dest.id, dest_owner.id, "Dest".into(), "Body".into())
+            .await
+            .unwrap();
+        topics._set_pinned(dest_topic.topic.id, owner, true).await.unwrap();
+        let source_shared = service._ensure_membership(source.id, shared).await.unwrap();
+        let source_topic = topics
+            ._create_topic_with_post(source.id, source_shared.id, "Source".into(), "Body".into())
+            .await
+            .unwrap();
+        topics._set_pinned(source_topic.topic.id, owner, true).await.unwrap();
+
+        // Only an admin of both may merge, and never a group into itself
+        let result = service._merge_groups(source.id, dest.id, source_admin).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+        let result = service._merge_groups(dest.id, dest.id, owner).await;
+        assert!(matches!(result, Err(GroupsServiceError::MergeIntoSelf)));
+
+        let merge = service._merge_groups(source.id, dest.id, owner).await.unwrap();
+        assert_eq!(merge.group.id, dest.id);
+        assert_eq!((merge.topics_moved, merge.members_moved), (1, 2));
+        assert!(service._try_get_group(source.id).await.unwrap().is_none());
+
+        let users = service._list_users(dest.id).await.unwrap();
+        assert_eq!(users.len(), 4);
+        let members: HashSet<ProfileId> = users.iter().map(|user| user.profile_id).collect();
+        assert_eq!(members, HashSet::from([owner, shared, newcomer, source_admin]));
+
+        // Admins and bans are combined, without admitting the destination's bans
+        let admins = service._list_admins(dest.id).await.unwrap();
+        let admins: HashSet<ProfileId> = admins.iter().map(|admin| admin.identity_id).collect();
+        assert_eq!(admins, HashSet::from([owner, source_admin]));
+        let banned = service._list_banned(dest.id).await.unwrap();
+        let banned: HashSet<ProfileId> = banned.iter().map(|ban| ban.identity_id).collect();
+        assert_eq!(banned, HashSet::from([banned_in_dest, banned_in_source]));
+
+        // The source topic follows the destination's pins, its post re-attributed
+        let moved = GroupTopic::find_by_id(source_topic.topic.id)
+            .one(&service.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((moved.group_id, moved.pin_order), (dest.id, Some(2)));
+        let post = GroupPost::find_by_id(source_topic.post.id)
+            .one(&service.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(post.user_id, dest_shared.id);
+        assert!(GroupUser::find_by_id(source_shared.id).one(&service.db).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_groups_keeps_dest_bans_out() {
+        let service = setup_test_service().await;
+        let topics = TopicsService::new(service.db.clone());
+        let owner = create_test_profile(&service).await;
+        let poster = create_test_profile(&service).await;
+        let lurker = create_test_profile(&service).await;
+        let source = service._create_group(owner).await.unwrap();
+        let dest = service._create_group(owner).await.unwrap();
+        let source_poster = service._add_user(source.id, poster).await.unwrap();
+        let source_lurker = service._add_user(source.id, lurker).await.unwrap();
+        let topic = topics
+            ._create_topic_with_post(source.id, source_poster.id, "Source".into(), "Body".into())
+            .await
+            .unwrap();
+        service._ban_user(dest.id, owner, poster).await.unwrap();
+        service._ban_user(dest.id, owner, lurker).await.unwrap();
+
+        let merge = service._merge_groups(source.id, dest.id, owner).await.unwrap();
+        assert_eq!(merge.members_moved, 0);
+        assert!(service._list_users(dest.id).await.unwrap().is_empty());
+        assert!(!service._is_member(dest.id, poster).await.unwrap());
+        assert!(!service._is_member(dest.id, lurker).await.unwrap());
+
+        // The poster's row only comes along to keep their post; the lurker's is dropped
+        let post = GroupPost::find_by_id(topic.post.id)
+            .one(&service.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(post.user_id, source_poster.id);
+        let kept = GroupUser::find_by_id(source_poster.id).one(&service.db).await.unwrap();
+        assert_eq!(kept.unwrap().group_id, dest.id);
+        assert!(GroupUser::find_by_id(source_lurker.id).one(&service.db).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_add_users_bulk_requires_admin() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let other_profile = create_test_profile(&service).await;
+
+        let group = service._create_group(admin_profile).await.unwrap();
+
+        let result = service
+            ._add_users_bulk(group.id, other_profile, vec![other_profile])
+            .await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_add_users_bulk_rejects_oversized_batch() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+
+        let group = service._create_group(admin_profile).await.unwrap();
+
+        let profile_ids = (0..=MAX_BULK_ADD_USERS).map(|_| ProfileId::new()).collect();
+        let result = service
+            ._add_users_bulk(group.id, admin_profile, profile_ids)
+            .await;
+        assert!(matches!(result, Err(GroupsServiceError::TooManyProfiles)));
+    }
+
+    #[tokio::test]
+    async fn test_add_users_bulk_empty_batch() {
+        let service = setup_test_service().await;
+        let admin_profile = create_test_profile(&service).await;
+        let outsider = create_test_profile(&service).await;
+        let group = service._create_group(admin_profile).await.unwrap();
+
+        let added = service._add_users_bulk(group.id, admin_profile, Vec::new()).await.unwrap();
+        assert!(added.is_empty());
+
+        // Still checked before the early return
+        let result = service._add_users_bulk(group.id, outsider, Vec::new()).await;
+        assert!(matches!(result, Err(GroupsServiceError::Unauthorized)));
+    }
+
     #[tokio::test]
     async fn test_list_admins() {
         let service = setup_test_service().await;
@@ -484,4 +2761,98 @@ mod tests {
         let users = service._list_users(group.id).await.unwrap();
         assert_eq!(users.len(), 0, "Users should be cascade deleted");
     }
+
+    #[tokio::test]
+    async fn test_ensure_membership() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let joiner = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+
+        // Already a member: the existing row comes back
+        let member = service._add_user(group.id, owner).await.unwrap();
+        let ensured = service._ensure_membership(group.id, owner).await.unwrap();
+        assert_eq!(ensured, member);
+
+        // Not yet a member: joins once, then keeps returning that row
+        let joined = service._ensure_membership(group.id, joiner).await.unwrap();
+        assert_eq!(joined.profile_id, joiner);
+        let again = service._ensure_membership(group.id, joiner).await.unwrap();
+        assert_eq!(again.id, joined.id);
+        assert_eq!(service._list_users(group.id).await.unwrap().len(), 2);
+
+        let missing = service._ensure_membership(group.id, ProfileId::new()).await;
+        assert!(matches!(missing, Err(GroupsServiceError::ProfileNotFound)));
+        let missing = service._ensure_membership(GroupId::new(), joiner).await;
+        assert!(matches!(missing, Err(GroupsServiceError::GroupNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_membership_rejects_banned_and_archived() {
+        let service = setup_test_service().await;
+        let owner = create_test_profile(&service).await;
+        let banned = create_test_profile(&service).await;
+        let group = service._create_group(owner).await.unwrap();
+
+        // Banned with a stale membership row, and before ever joining
+        service._add_user(group.id, banned).await.unwrap();
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(group.id),
+            identity_id: Set(banned),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let result = service._ensure_membership(group.id, banned).await;
+        assert!(matches!(result, Err(GroupsServiceError::Banned)));
+
+        let other = service._create_group(owner).await.unwrap();
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(other.id),
+            identity_id: Set(banned),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let result = service._ensure_membership(other.id, banned).await;
+        assert!(matches!(result, Err(GroupsServiceError::Banned)));
+        assert_eq!(service._list_users(other.id).await.unwrap().len(), 0);
+
+        // Archived groups keep their members but take no new ones
+        let member = service._add_user(group.id, owner).await.unwrap();
+        service._set_archived(group.id, owner, true).await.unwrap();
+        let ensured = service._ensure_membership(group.id, owner).await.unwrap();
+        assert_eq!(ensured, member);
+        let newcomer = create_test_profile(&service).await;
+        let result = service._ensure_membership(group.id, newcomer).await;
+        assert!(matches!(result, Err(GroupsServiceError::GroupArchived)));
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let db_error = GroupsServiceError::DbError(DbErr::Custom("boom".to_string()));
+        let db_error = ResourceError::from(db_error);
+        assert_eq!(db_error.severity(), ErrorSeverity::Infrastructure);
+
+        for error in [
+            GroupsServiceError::GroupNotFound,
+            GroupsServiceError::ProfileNotFound,
+            GroupsServiceError::Unauthorized,
+            GroupsServiceError::TooManyProfiles,
+            GroupsServiceError::TooManyGroups,
+            GroupsServiceError::NotAMember,
+            GroupsServiceError::InvalidNickname,
+            GroupsServiceError::Banned,
+            GroupsServiceError::GroupArchived,
+            GroupsServiceError::CannotBanAdmin,
+            GroupsServiceError::MergeIntoSelf,
+            GroupsServiceError::CannotRemoveLastAdmin,
+            GroupsServiceError::OwnerMustTransferFirst,
+            GroupsServiceError::CannotDemoteOwner,
+        ] {
+            let message = error.to_string();
+            let mapped = ResourceError::from(error);
+            assert_eq!(mapped.severity(), ErrorSeverity::Application, "{message}");
+        }
+    }
 }
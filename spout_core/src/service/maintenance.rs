@@ -0,0 +1,305 @@
+use iroh::PublicKey;
+use sea_orm::DatabaseConnection;
+use thiserror::Error;
+use zel_core::prelude::*;
+
+use crate::{
+    config::{self, SharedRuntimeConfig},
+    entity::prelude::GroupPostModel,
+    ids::TopicId,
+    service::{
+        caller::Caller,
+        posts::{PostsService, PostsServiceError},
+        profiles::{ProfilesService, ProfilesServiceError},
+    },
+};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum MaintenanceServiceError {
+    #[error("unauthorized: not the operator")]
+    Unauthorized,
+
+    #[error(transparent)]
+    Posts(#[from] PostsServiceError),
+
+    #[error(transparent)]
+    Profiles(#[from] ProfilesServiceError),
+}
+
+/// `Unauthorized` maps to `ResourceError::app`, posts and profiles errors as their
+/// services map them
+impl From<MaintenanceServiceError> for ResourceError {
+    fn from(error: MaintenanceServiceError) -> Self {
+        match error {
+            MaintenanceServiceError::Unauthorized => ResourceError::app(error),
+            MaintenanceServiceError::Posts(error) => error.into(),
+            MaintenanceServiceError::Profiles(error) => error.into(),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
+        }
+    }
+}
+
+/// Operator controls for a running core. Only the local operator's node
+/// (the UI client endpoint) may change anything.
+#[derive(Clone)]
+pub struct MaintenanceService {
+    db: DatabaseConnection,
+    runtime: SharedRuntimeConfig,
+    operator: PublicKey,
+}
+
+impl MaintenanceService {
+    pub fn new(db: DatabaseConnection, runtime: SharedRuntimeConfig, operator: PublicKey) -> Self {
+        Self {
+            db,
+            runtime,
+            operator,
+        }
+    }
+
+    fn ensure_operator(&self, caller: PublicKey) -> Result<(), MaintenanceServiceError> {
+        if caller != self.operator {
+            return Err(MaintenanceServiceError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn posts(&self) -> PostsService {
+        PostsService::new(self.db.clone()).with_runtime_config(self.runtime.clone())
+    }
+
+    /// Freeze or unfreeze all post and topic writes, returning the new state
+    pub fn _set_posting_frozen(
+        &self,
+        caller: PublicKey,
+        frozen: bool,
+    ) -> Result<bool, MaintenanceServiceError> {
+        self.ensure_operator(caller)?;
+
+        self.runtime.write().unwrap_or_else(|e| e.into_inner()).posting_frozen = frozen;
+        Ok(frozen)
+    }
+
+    /// Whether post and topic writes are currently frozen
+    pub fn _posting_frozen(&self) -> bool {
+        config::runtime(&self.runtime).posting_frozen
+    }
+
+    /// Replies in a topic whose parent is missing or in another topic
+    pub async fn _audit_reply_integrity(
+        &self,
+        caller: PublicKey,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, MaintenanceServiceError> {
+        self.ensure_operator(caller)?;
+        Ok(self.posts()._audit_reply_integrity(topic_id).await?)
+    }
+
+    /// Turn the replies `_audit_reply_integrity` reports into top-level posts
+    pub async fn _repair_reply_integrity(
+        &self,
+        caller: PublicKey,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, MaintenanceServiceError> {
+        self.ensure_operator(caller)?;
+        Ok(self.posts()._repair_reply_integrity(topic_id).await?)
+    }
+
+    /// Restore lost `identity` rows from the profiles' owner node ids
+    pub async fn _rebuild_identities(
+        &self,
+        caller: PublicKey,
+    ) -> Result<u64, MaintenanceServiceError> {
+        self.ensure_operator(caller)?;
+        Ok(ProfilesService::new(self.db.clone())._rebuild_identities().await?)
+    }
+
+    /// RPC body of `set_posting_frozen`, usable without a live `RequestContext`
+    pub fn set_posting_frozen_as(
+        &self,
+        caller: &impl Caller,
+        frozen: bool,
+    ) -> Result<bool, ResourceError> {
+        Ok(self._set_posting_frozen(caller.remote_id(), frozen)?)
+    }
+
+    /// RPC body of `audit_reply_integrity`, usable without a live `RequestContext`
+    pub async fn audit_reply_integrity_as(
+        &self,
+        caller: &impl Caller,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        Ok(self._audit_reply_integrity(caller.remote_id(), topic_id).await?)
+    }
+
+    /// RPC body of `repair_reply_integrity`, usable without a live `RequestContext`
+    pub async fn repair_reply_integrity_as(
+        &self,
+        caller: &impl Caller,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        Ok(self._repair_reply_integrity(caller.remote_id(), topic_id).await?)
+    }
+
+    /// RPC body of `rebuild_identities`, usable without a live `RequestContext`
+    pub async fn rebuild_identities_as(&self, caller: &impl Caller) -> Result<u64, ResourceError> {
+        Ok(self._rebuild_identities(caller.remote_id()).await?)
+    }
+}
+
+#[zel_service(name = "maintenance")]
+trait Maintenance {
+    #[doc = "Freeze or unfreeze all post and topic writes (operator only)"]
+    #[method(name = "set_posting_frozen")]
+    async fn set_posting_frozen(&self, frozen: bool) -> Result<bool, ResourceError>;
+
+    #[doc = "Whether post and topic writes are currently frozen"]
+    #[method(name = "posting_frozen")]
+    async fn posting_frozen(&self) -> Result<bool, ResourceError>;
+
+    #[doc = "Replies in a topic whose parent is missing or in another topic (operator only)"]
+    #[method(name = "audit_reply_integrity")]
+    async fn audit_reply_integrity(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "Make a topic's inconsistent replies top-level posts (operator only)"]
+    #[method(name = "repair_reply_integrity")]
+    async fn repair_reply_integrity(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "Recreate missing identity rows from the profiles' owner node ids (operator only)"]
+    #[method(name = "rebuild_identities")]
+    async fn rebuild_identities(&self) -> Result<u64, ResourceError>;
+}
+
+#[async_trait]
+impl MaintenanceServer for MaintenanceService {
+    async fn set_posting_frozen(
+        &self,
+        ctx: RequestContext,
+        frozen: bool,
+    ) -> Result<bool, ResourceError> {
+        self.set_posting_frozen_as(&ctx, frozen)
+    }
+
+    async fn posting_frozen(&self, _ctx: RequestContext) -> Result<bool, ResourceError> {
+        Ok(self._posting_frozen())
+    }
+
+    async fn audit_reply_integrity(
+        &self,
+        ctx: RequestContext,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        self.audit_reply_integrity_as(&ctx, topic_id).await
+    }
+
+    async fn repair_reply_integrity(
+        &self,
+        ctx: RequestContext,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        self.repair_reply_integrity_as(&ctx, topic_id).await
+    }
+
+    async fn rebuild_identities(&self, ctx: RequestContext) -> Result<u64, ResourceError> {
+        self.rebuild_identities_as(&ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::migrator::Migrator, service::caller::TestCaller};
+    use iroh::SecretKey;
+    use sea_orm::Database;
+    use sea_orm_migration::MigratorTrait;
+
+    fn test_node_id() -> PublicKey {
+        SecretKey::generate(&mut rand::rng()).public()
+    }
+
+    async fn setup_test_service(
+        runtime: SharedRuntimeConfig,
+        operator: PublicKey,
+    ) -> MaintenanceService {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        MaintenanceService::new(db, runtime, operator)
+    }
+
+    #[tokio::test]
+    async fn test_only_operator_can_freeze() {
+        let operator = test_node_id();
+        let runtime = SharedRuntimeConfig::default();
+        let service = setup_test_service(runtime.clone(), operator).await;
+
+        let result = service._set_posting_frozen(test_node_id(), true);
+        assert!(matches!(result, Err(MaintenanceServiceError::Unauthorized)));
+        assert!(!service._posting_frozen());
+
+        assert!(service._set_posting_frozen(operator, true).unwrap());
+        assert!(config::runtime(&runtime).posting_frozen);
+
+        assert!(!service._set_posting_frozen(operator, false).unwrap());
+        assert!(!service._posting_frozen());
+
+        // The RPC body checks whoever is calling
+        let result = service.set_posting_frozen_as(&TestCaller(test_node_id()), true);
+        assert_eq!(result.unwrap_err().severity(), ErrorSeverity::Application);
+        assert!(service.set_posting_frozen_as(&TestCaller(operator), true).unwrap());
+        assert!(service._posting_frozen());
+    }
+
+    #[tokio::test]
+    async fn test_reply_integrity_is_operator_only() {
+        let operator = test_node_id();
+        let service = setup_test_service(SharedRuntimeConfig::default(), operator).await;
+
+        let result = service._audit_reply_integrity(test_node_id(), TopicId::new()).await;
+        assert!(matches!(result, Err(MaintenanceServiceError::Unauthorized)));
+        let result = service._repair_reply_integrity(test_node_id(), TopicId::new()).await;
+        assert!(matches!(result, Err(MaintenanceServiceError::Unauthorized)));
+
+        let result = service._audit_reply_integrity(operator, TopicId::new()).await;
+        assert!(matches!(
+            result,
+            Err(MaintenanceServiceError::Posts(PostsServiceError::TopicNotFound))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_identities_is_operator_only() {
+        let operator = test_node_id();
+        let service = setup_test_service(SharedRuntimeConfig::default(), operator).await;
+
+        let result = service._rebuild_identities(test_node_id()).await;
+        assert!(matches!(result, Err(MaintenanceServiceError::Unauthorized)));
+        assert_eq!(service._rebuild_identities(operator).await.unwrap(), 0);
+        assert!(service.rebuild_identities_as(&TestCaller(test_node_id())).await.is_err());
+        assert_eq!(service.rebuild_identities_as(&TestCaller(operator)).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let mapped = ResourceError::from(MaintenanceServiceError::Unauthorized);
+        assert_eq!(mapped.severity(), ErrorSeverity::Application);
+
+        let mapped = ResourceError::from(MaintenanceServiceError::Posts(
+            PostsServiceError::TopicNotFound,
+        ));
+        assert_eq!(mapped.severity(), ErrorSeverity::Application);
+
+        let mapped = ResourceError::from(MaintenanceServiceError::Profiles(
+            ProfilesServiceError::ProfileNotFound,
+        ));
+        assert_eq!(mapped.severity(), ErrorSeverity::Application);
+    }
+}
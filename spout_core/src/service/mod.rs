@@ -1,4 +1,19 @@
+// Getter convention: `_get_*` returns the model or the service's `*NotFound`
+// error, `_try_get_*` returns `Option` and leaves a missing row to the caller.
+
 pub mod identities;
 pub mod profiles;
 pub mod groups;
 pub mod posts;
+pub mod topics;
+pub mod breaker;
+pub mod caller;
+pub mod clock;
+pub mod feed;
+pub mod maintenance;
+pub mod page;
+pub mod retry;
+pub mod sync;
+pub mod system;
+pub mod validation;
+pub mod view_buffer;
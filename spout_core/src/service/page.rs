@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// One page of a listing, with how many rows the whole listing has so
+/// clients can render page controls
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Rows matching the listing's filters, across every page
+    pub total: u64,
+}
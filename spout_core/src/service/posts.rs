@@ -1,13 +1,37 @@
-use sea_orm::DatabaseConnection;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use sea_orm::{
+    sea_query::{Expr, LikeExpr, OnConflict, Query},
+    DatabaseConnection, DatabaseTransaction, FromQueryResult, Order,
+};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zel_core::prelude::*;
 
 use crate::{
-    entity::prelude::*,
-    ids::{PostId, TopicId, UserId},
+    config::{self, SharedRuntimeConfig},
+    entity::{direct_topic, group, group_post, group_user, post_report, prelude::*},
+    ids::{GroupId, NotificationId, PostId, ProfileId, ReportId, TopicId, UserId},
+    models::begin_write,
+    service::{
+        breaker::DbBreaker,
+        caller::Caller,
+        clock::{SharedClock, SystemClock},
+        feed::{FeedEvent, FeedSubscription, SharedTopicFeed, TooManySubscribers},
+        groups::not_banned,
+        profiles::{owns_profile, record_last_location},
+        retry::{with_retry, Retryable},
+        sync::record_change,
+        validation::{check_text, FieldError},
+        view_buffer::SharedViewBuffer,
+    },
 };
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum PostsServiceError {
     #[error("fatal database error")]
     DbError(#[from] DbErr),
@@ -23,8 +47,70 @@ pub enum PostsServiceError {
     
     #[error("unauthorized: not post author")]
     Unauthorized,
+
+    #[error("user is not a member of this topic's group")]
+    NotAMember,
+
+    #[error("group is archived")]
+    GroupArchived,
+
+    #[error("parent post not found")]
+    ParentNotFound,
+
+    #[error("parent post belongs to a different topic")]
+    ParentInDifferentTopic,
+
+    #[error("posting is frozen")]
+    Frozen,
+
+    #[error("edit window has expired")]
+    EditWindowExpired,
+
+    #[error("post is already top-level")]
+    NotAReply,
+
+    #[error("target topic belongs to a different group")]
+    TopicInDifferentGroup,
+
+    #[error(transparent)]
+    Invalid(#[from] FieldError),
+
+    #[error("profile not found")]
+    ProfileNotFound,
+
+    #[error("cannot start a direct topic with yourself")]
+    DirectToSelf,
+
+    #[error("not a participant in this direct topic")]
+    NotAParticipant,
+
+    #[error("report not found")]
+    ReportNotFound,
+
+    #[error("post already has an open report from this profile")]
+    AlreadyReported,
+
+    #[error("a report can only be resolved or dismissed")]
+    InvalidReportStatus,
+
+    #[error(transparent)]
+    TooManySubscribers(#[from] TooManySubscribers),
+
+    #[error("profile is banned from this group")]
+    Banned,
+
+    #[error("profile belongs to another node")]
+    NotOwner,
+}
+
+/// A locked/busy database is worth another attempt, nothing else is
+impl Retryable for PostsServiceError {
+    fn is_retryable(&self) -> bool {
+        matches!(self, PostsServiceError::DbError(error) if error.is_retryable())
+    }
 }
 
+/// DB failures map to `ResourceError::infra`, every domain variant to `ResourceError::app`
 impl From<PostsServiceError> for ResourceError {
     fn from(error: PostsServiceError) -> Self {
         match error {
@@ -33,18 +119,173 @@ impl From<PostsServiceError> for ResourceError {
             PostsServiceError::TopicNotFound => ResourceError::app(error),
             PostsServiceError::UserNotFound => ResourceError::app(error),
             PostsServiceError::Unauthorized => ResourceError::app(error),
+            PostsServiceError::NotAMember => ResourceError::app(error),
+            PostsServiceError::GroupArchived => ResourceError::app(error),
+            PostsServiceError::ParentNotFound => ResourceError::app(error),
+            PostsServiceError::ParentInDifferentTopic => ResourceError::app(error),
+            PostsServiceError::Frozen => ResourceError::app(error),
+            PostsServiceError::EditWindowExpired => ResourceError::app(error),
+            PostsServiceError::NotAReply => ResourceError::app(error),
+            PostsServiceError::TopicInDifferentGroup => ResourceError::app(error),
+            PostsServiceError::Invalid(error) => error.into(),
+            PostsServiceError::ProfileNotFound => ResourceError::app(error),
+            PostsServiceError::DirectToSelf => ResourceError::app(error),
+            PostsServiceError::NotAParticipant => ResourceError::app(error),
+            PostsServiceError::ReportNotFound => ResourceError::app(error),
+            PostsServiceError::AlreadyReported => ResourceError::app(error),
+            PostsServiceError::InvalidReportStatus => ResourceError::app(error),
+            PostsServiceError::TooManySubscribers(_) => ResourceError::app(error),
+            PostsServiceError::Banned => ResourceError::app(error),
+            PostsServiceError::NotOwner => ResourceError::app(error),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
         }
     }
 }
 
+/// Longest post title accepted on create and edit, in characters
+pub const MAX_POST_TITLE_CHARS: usize = 200;
+
+/// Longest post body accepted on create and edit, in characters
+pub const MAX_POST_BODY_CHARS: usize = 40_000;
+
+/// Longest reason accepted on a post report, in characters
+pub const MAX_REPORT_REASON_CHARS: usize = 500;
+
+/// How many recently viewed posts are kept per user
+pub const MAX_VIEW_HISTORY: u64 = 50;
+
+/// A post without its body, for list views that only show titles
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
+pub struct PostHeader {
+    pub id: PostId,
+    pub user_id: UserId,
+    pub topic_id: TopicId,
+    pub parent_post_id: Option<PostId>,
+    pub title: String,
+    pub created_at: String,
+    /// The author's group nickname if set, otherwise their profile name
+    pub author_name: String,
+}
+
+/// One step of a thread: a post on the path to the target plus some of its siblings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadLevel {
+    pub post: GroupPostModel,
+    /// Other posts sharing this post's parent, oldest first, bounded by `sibling_limit`
+    pub siblings: Vec<GroupPostModel>,
+}
+
+/// The path from a thread's root down to a target post
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadContext {
+    /// Root first, target last
+    pub levels: Vec<ThreadLevel>,
+}
+
+/// A post waiting on moderation, with what its open reports add up to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedPost {
+    pub post: GroupPostModel,
+    pub open_reports: i64,
+    /// Reason given by the most recent open report
+    pub latest_reason: String,
+    pub latest_reported_at: String,
+}
+
+/// Open reports per post, before the posts themselves are loaded
+#[derive(FromQueryResult)]
+struct ReportTally {
+    post_id: PostId,
+    open_reports: i64,
+    latest_reported_at: String,
+}
+
+/// Optional filters for `search_posts_advanced`; see `PostsService::_search_posts_advanced`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostSearch {
+    pub query: Option<String>,
+    pub author_profile_id: Option<ProfileId>,
+    pub since: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct PostsService {
     db: DatabaseConnection,
+    breaker: DbBreaker,
+    clock: SharedClock,
+    runtime: SharedRuntimeConfig,
+    view_buffer: Option<SharedViewBuffer>,
+    feed: SharedTopicFeed,
 }
 
 impl PostsService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            breaker: DbBreaker::default(),
+            clock: Arc::new(SystemClock),
+            runtime: SharedRuntimeConfig::default(),
+            view_buffer: None,
+            feed: SharedTopicFeed::default(),
+        }
+    }
+
+    /// Share a circuit breaker with the other services using the same DB
+    pub fn with_breaker(mut self, breaker: DbBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read hot-reloadable limits from `SpoutCore`'s shared runtime config
+    pub fn with_runtime_config(mut self, runtime: SharedRuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Batch `record_view` writes through `buffer` instead of writing each one
+    pub fn with_view_buffer(mut self, buffer: SharedViewBuffer) -> Self {
+        self.view_buffer = Some(buffer);
+        self
+    }
+
+    /// Publish new posts to `feed`'s subscribers
+    pub fn with_feed(mut self, feed: SharedTopicFeed) -> Self {
+        self.feed = feed;
+        self
+    }
+
+    /// The underlying database connection, for queries the service doesn't cover
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    fn check_title(title: &str) -> Result<(), FieldError> {
+        check_text("title", title, MAX_POST_TITLE_CHARS)
+    }
+
+    fn check_body(body: &str) -> Result<(), FieldError> {
+        check_text("body", body, MAX_POST_BODY_CHARS)
+    }
+
+    /// Apply the configured clean-up to a title or body before it's validated
+    fn normalize(&self, text: String) -> String {
+        config::runtime(&self.runtime).post_text.apply(text)
+    }
+
+    /// Reject writes while the maintenance kill switch is on
+    fn ensure_not_frozen(&self) -> Result<(), PostsServiceError> {
+        if config::runtime(&self.runtime).posting_frozen {
+            return Err(PostsServiceError::Frozen);
+        }
+        Ok(())
     }
 
     /// Create a new post in a topic
@@ -55,15 +296,16 @@ impl PostsService {
         title: String,
         body: String,
     ) -> Result<GroupPostModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+        let (title, body) = (self.normalize(title), self.normalize(body));
+        Self::check_title(&title)?;
+        Self::check_body(&body)?;
+
         // Verify user exists
-        let user_exists = GroupUser::find_by_id(user_id)
+        let user = GroupUser::find_by_id(user_id)
             .one(&self.db)
             .await?
-            .is_some();
-        
-        if !user_exists {
-            return Err(PostsServiceError::UserNotFound);
-        }
+            .ok_or(PostsServiceError::UserNotFound)?;
 
         // Verify topic exists
         let topic_exists = GroupTopic::find_by_id(topic_id)
@@ -75,10 +317,14 @@ impl PostsService {
             return Err(PostsServiceError::TopicNotFound);
         }
 
+        self.ensure_same_group(user_id, topic_id).await?;
+
         // Create post
         let post_id = PostId::new();
-        let created_at = chrono::Utc::now().to_rfc3339();
-        
+        let created_at = self.clock.now_rfc3339();
+
+        let txn = begin_write(&self.db).await?;
+
         let post = GroupPostActiveModel {
             id: Set(post_id),
             user_id: Set(user_id),
@@ -87,26 +333,202 @@ impl PostsService {
             title: Set(title),
             body: Set(body),
             created_at: Set(created_at),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(next_topic_seq(&txn, topic_id).await?),
         };
 
         let result = GroupPost::insert(post)
-            .exec_with_returning(&self.db)
+            .exec_with_returning(&txn)
             .await?;
+        enqueue_notifications(&txn, &result, None).await?;
+        let (profile_id, group_id) = (user.profile_id, user.group_id);
+        record_last_location(&txn, profile_id, group_id, Some(topic_id), &result.created_at).await?;
+        record_post_change(&txn, ChangeOp::Create, &result, result.created_at.clone()).await?;
 
+        txn.commit().await?;
+        self.feed.publish(&result);
         Ok(result)
     }
 
-    /// Get a specific post by ID
+    /// Reject users whose membership belongs to a different group than the topic
+    /// or whose profile is banned from it, and any new posts in an archived group
+    async fn ensure_same_group(
+        &self,
+        user_id: UserId,
+        topic_id: TopicId,
+    ) -> Result<(), PostsServiceError> {
+        use sea_orm::{JoinType, RelationTrait};
+
+        let same_group = GroupUser::find_by_id(user_id)
+            .join(JoinType::InnerJoin, group_user::Relation::Group.def())
+            .join(JoinType::InnerJoin, group::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::Id.eq(topic_id))
+            .count(&self.db)
+            .await?;
+
+        if same_group == 0 {
+            return Err(PostsServiceError::NotAMember);
+        }
+
+        // A ban outlives the membership row it was issued against
+        let banned = GroupUser::find_by_id(user_id)
+            .join(JoinType::InnerJoin, group_user::Relation::Group.def())
+            .join(JoinType::InnerJoin, group::Relation::GroupTopic.def())
+            .join(JoinType::InnerJoin, group::Relation::GroupBanned.def())
+            .filter(GroupTopicColumn::Id.eq(topic_id))
+            .filter(
+                Expr::col((GroupBanned, GroupBannedColumn::IdentityId))
+                    .equals((GroupUser, GroupUserColumn::ProfileId)),
+            )
+            .count(&self.db)
+            .await?;
+
+        if banned > 0 {
+            return Err(PostsServiceError::Banned);
+        }
+
+        let archived = Group::find()
+            .join(JoinType::InnerJoin, group::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::Id.eq(topic_id))
+            .filter(GroupColumn::Archived.eq(true))
+            .count(&self.db)
+            .await?;
+
+        if archived > 0 {
+            return Err(PostsServiceError::GroupArchived);
+        }
+
+        Ok(())
+    }
+
+    /// Get a specific live post by ID
     pub async fn _get_post(
         &self,
         post_id: PostId,
     ) -> Result<GroupPostModel, PostsServiceError> {
-        GroupPost::find_by_id(post_id)
+        self._try_get_post(post_id)
+            .await?
+            .ok_or(PostsServiceError::PostNotFound)
+    }
+
+    /// Get a post by its number within a topic
+    pub async fn _get_post_by_topic_seq(
+        &self,
+        topic_id: TopicId,
+        seq: i64,
+    ) -> Result<GroupPostModel, PostsServiceError> {
+        GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::Seq.eq(seq))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .one(&self.db)
             .await?
             .ok_or(PostsServiceError::PostNotFound)
     }
 
+    /// Get a specific post by ID, `None` if it doesn't exist or is soft-deleted
+    pub async fn _try_get_post(
+        &self,
+        post_id: PostId,
+    ) -> Result<Option<GroupPostModel>, PostsServiceError> {
+        let post = GroupPost::find_by_id(post_id)
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .one(&self.db)
+            .await?;
+        Ok(post)
+    }
+
+    /// Record that a user opened a post, keeping only their last `MAX_VIEW_HISTORY` views
+    pub async fn _record_view(
+        &self,
+        user_id: UserId,
+        post_id: PostId,
+    ) -> Result<(), PostsServiceError> {
+        if GroupUser::find_by_id(user_id).one(&self.db).await?.is_none() {
+            return Err(PostsServiceError::UserNotFound);
+        }
+        self._get_post(post_id).await?;
+
+        let viewed_at = self.clock.now_rfc3339();
+        match &self.view_buffer {
+            Some(buffer) => {
+                if buffer.record(user_id, post_id, viewed_at) {
+                    buffer.flush().await?;
+                }
+            }
+            None => write_views(&self.db, vec![(user_id, post_id, viewed_at)]).await?,
+        }
+
+        Ok(())
+    }
+
+    /// A user's most recently viewed live posts, newest view first
+    pub async fn _recent_views(
+        &self,
+        user_id: UserId,
+        limit: u64,
+    ) -> Result<Vec<GroupPostModel>, PostsServiceError> {
+        let limit = config::runtime(&self.runtime).page_size(limit);
+        let buffered = match &self.view_buffer {
+            Some(buffer) => buffer.pending_for(user_id),
+            None => Vec::new(),
+        };
+
+        // Buffered views may re-order stored ones, so over-fetch by that many
+        let views = PostViewHistory::find()
+            .find_also_related(GroupPost)
+            .filter(PostViewHistoryColumn::UserId.eq(user_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .order_by_desc(PostViewHistoryColumn::ViewedAt)
+            .limit(limit + buffered.len() as u64)
+            .all(&self.db)
+            .await?;
+
+        if buffered.is_empty() {
+            return Ok(views.into_iter().filter_map(|(_, post)| post).collect());
+        }
+
+        let mut merged: HashMap<PostId, (String, Option<GroupPostModel>)> = views
+            .into_iter()
+            .filter_map(|(view, post)| Some((view.post_id, (view.viewed_at, Some(post?)))))
+            .collect();
+
+        // A buffered view is always the newer one
+        for (post_id, viewed_at) in &buffered {
+            merged.entry(*post_id).or_insert((String::new(), None)).0 = viewed_at.clone();
+        }
+
+        let missing: HashSet<PostId> = merged
+            .iter()
+            .filter(|(_, (_, post))| post.is_none())
+            .map(|(post_id, _)| *post_id)
+            .collect();
+        if !missing.is_empty() {
+            let posts = GroupPost::find()
+                .filter(GroupPostColumn::Id.is_in(missing))
+                .filter(GroupPostColumn::DeletedAt.is_null())
+                .all(&self.db)
+                .await?;
+            for post in posts {
+                if let Some(entry) = merged.get_mut(&post.id) {
+                    entry.1 = Some(post);
+                }
+            }
+        }
+
+        let mut views: Vec<(String, GroupPostModel)> = merged
+            .into_values()
+            .filter_map(|(viewed_at, post)| Some((viewed_at, post?)))
+            .collect();
+        views.sort_by(|a, b| b.0.cmp(&a.0));
+        views.truncate(limit as usize);
+
+        Ok(views.into_iter().map(|(_, post)| post).collect())
+    }
+
     /// List posts for a topic with pagination
     pub async fn _list_posts_for_topic(
         &self,
@@ -118,15 +540,81 @@ impl PostsService {
         
         let posts = GroupPost::find()
             .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .order_by_asc(GroupPostColumn::CreatedAt) // Oldest first (conversation order)
-            .limit(limit)
-            .offset(offset)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(posts)
+    }
+
+    /// List posts in a topic strictly after the `(created_at, id)` keyset, oldest first
+    pub async fn _list_posts_for_topic_since(
+        &self,
+        topic_id: TopicId,
+        after_created_at: String,
+        after_id: PostId,
+        limit: u64,
+    ) -> Result<Vec<GroupPostModel>, PostsServiceError> {
+        use sea_orm::{Condition, QueryOrder};
+
+        let after = Condition::any()
+            .add(GroupPostColumn::CreatedAt.gt(after_created_at.clone()))
+            .add(
+                Condition::all()
+                    .add(GroupPostColumn::CreatedAt.eq(after_created_at))
+                    .add(GroupPostColumn::Id.gt(after_id)),
+            );
+
+        let posts = GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .filter(after)
+            .order_by_asc(GroupPostColumn::CreatedAt)
+            .order_by_asc(GroupPostColumn::Id) // Tie-break so the keyset is total
+            .limit(config::runtime(&self.runtime).page_size(limit))
             .all(&self.db)
             .await?;
 
         Ok(posts)
     }
 
+    /// List post headers (everything but the body) for a topic with pagination
+    pub async fn _list_post_headers_for_topic(
+        &self,
+        topic_id: TopicId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<PostHeader>, PostsServiceError> {
+        use sea_orm::{JoinType, QueryOrder, RelationTrait};
+
+        let headers = GroupPost::find()
+            .select_only()
+            .columns([
+                GroupPostColumn::Id,
+                GroupPostColumn::UserId,
+                GroupPostColumn::TopicId,
+                GroupPostColumn::Title,
+                GroupPostColumn::CreatedAt,
+                GroupPostColumn::ParentPostId,
+            ])
+            .column_as(group_user::display_name(), "author_name")
+            .join(JoinType::InnerJoin, group_post::Relation::GroupUser.def())
+            .join(JoinType::InnerJoin, group_user::Relation::Profile.def())
+            .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .order_by_asc(GroupPostColumn::CreatedAt) // Same order as list_posts_for_topic
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .into_model::<PostHeader>()
+            .all(&self.db)
+            .await?;
+
+        Ok(headers)
+    }
+
     /// List posts by a specific user with pagination
     pub async fn _list_posts_by_user(
         &self,
@@ -138,9 +626,66 @@ impl PostsService {
         
         let posts = GroupPost::find()
             .filter(GroupPostColumn::UserId.eq(user_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .order_by_desc(GroupPostColumn::CreatedAt) // Newest first (user activity)
-            .limit(limit)
-            .offset(offset)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(posts)
+    }
+
+    /// Live posts in a group matching every filter given, newest first.
+    ///
+    /// `query` is a case-insensitive substring of the title or body, `author_profile_id`
+    /// the profile behind the posting member and `since` an RFC 3339 lower bound on
+    /// `created_at`, in any offset. A missing (or blank) filter matches everything;
+    /// a `since` that isn't RFC 3339 fails with `Invalid`.
+    pub async fn _search_posts_advanced(
+        &self,
+        group_id: GroupId,
+        query: Option<String>,
+        author_profile_id: Option<ProfileId>,
+        since: Option<String>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<GroupPostModel>, PostsServiceError> {
+        use sea_orm::{Condition, JoinType, QueryOrder, QueryTrait, RelationTrait};
+
+        let pattern = query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .map(like_pattern);
+        let since = match since.as_deref().map(str::trim).filter(|since| !since.is_empty()) {
+            Some(since) => Some(stored_timestamp("since", since)?),
+            None => None,
+        };
+
+        let posts = GroupPost::find()
+            .join(JoinType::InnerJoin, group_post::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .apply_if(pattern, |select, pattern| {
+                select.filter(
+                    Condition::any()
+                        .add(GroupPostColumn::Title.like(pattern.clone()))
+                        .add(GroupPostColumn::Body.like(pattern)),
+                )
+            })
+            .apply_if(author_profile_id, |select, profile_id| {
+                select
+                    .join(JoinType::InnerJoin, group_post::Relation::GroupUser.def())
+                    .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            })
+            .apply_if(since, |select, since| {
+                select.filter(GroupPostColumn::CreatedAt.gte(since))
+            })
+            .order_by_desc(GroupPostColumn::CreatedAt)
+            .order_by_desc(GroupPostColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
             .all(&self.db)
             .await?;
 
@@ -153,6 +698,8 @@ impl PostsService {
         post_id: PostId,
         user_id: UserId,
     ) -> Result<(), PostsServiceError> {
+        self.ensure_not_frozen()?;
+
         // Get the post
         let post = self._get_post(post_id).await?;
         
@@ -161,14 +708,157 @@ impl PostsService {
             return Err(PostsServiceError::Unauthorized);
         }
 
+        // Replies go with it through the FK cascade without entries of their own
+        let txn = begin_write(&self.db).await?;
         GroupPost::delete_by_id(post_id)
-            .exec(&self.db)
+            .exec(&txn)
+            .await?;
+        record_post_change(&txn, ChangeOp::Delete, &post, self.clock.now_rfc3339()).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Soft-delete a post (only by author); the row is purged after the retention window
+    pub async fn _soft_delete_post(
+        &self,
+        post_id: PostId,
+        user_id: UserId,
+    ) -> Result<(), PostsServiceError> {
+        self.ensure_not_frozen()?;
+
+        // Looked up directly so deleting twice stays a no-op
+        let post = GroupPost::find_by_id(post_id)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::PostNotFound)?;
+
+        if post.user_id != user_id {
+            return Err(PostsServiceError::Unauthorized);
+        }
+
+        if post.deleted_at.is_some() {
+            return Ok(());
+        }
+
+        let now = self.clock.now_rfc3339();
+        let mut post_active: GroupPostActiveModel = post.into();
+        post_active.deleted_at = Set(Some(now.clone()));
+
+        let txn = begin_write(&self.db).await?;
+        let deleted = post_active.update(&txn).await?;
+        record_post_change(&txn, ChangeOp::Delete, &deleted, now).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Hard-delete posts soft-deleted before `cutoff` (rfc3339), `batch_size` rows at a time.
+    ///
+    /// Replies go with their parent through the FK cascade, without change log
+    /// entries of their own, as in `_delete_post`. Returns the number of
+    /// soft-deleted posts removed.
+    pub async fn _purge_deleted_before(
+        &self,
+        cutoff: String,
+        batch_size: u64,
+    ) -> Result<u64, PostsServiceError> {
+        let mut purged = 0;
+
+        loop {
+            let txn = begin_write(&self.db).await?;
+            let batch = GroupPost::find()
+                .filter(GroupPostColumn::DeletedAt.lt(cutoff.clone()))
+                .limit(batch_size)
+                .all(&txn)
+                .await?;
+
+            if batch.is_empty() {
+                break;
+            }
+
+            let result = GroupPost::delete_many()
+                .filter(GroupPostColumn::Id.is_in(batch.iter().map(|post| post.id)))
+                .exec(&txn)
+                .await?;
+            let now = self.clock.now_rfc3339();
+            for post in &batch {
+                record_post_change(&txn, ChangeOp::Delete, post, now.clone()).await?;
+            }
+            txn.commit().await?;
+            purged += result.rows_affected;
+        }
+
+        Ok(purged)
+    }
+
+    /// Replies in `topic_id` whose `parent_post_id` points at a missing post or
+    /// at one in another topic
+    pub async fn _audit_reply_integrity(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, PostsServiceError> {
+        self.ensure_topic_exists(topic_id).await?;
+        Ok(inconsistent_replies(&self.db, topic_id).await?)
+    }
+
+    /// Make every reply `_audit_reply_integrity` reports a top-level post,
+    /// returning them as repaired
+    pub async fn _repair_reply_integrity(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<Vec<GroupPostModel>, PostsServiceError> {
+        self.ensure_topic_exists(topic_id).await?;
+
+        let txn = begin_write(&self.db).await?;
+        let offenders: Vec<PostId> = inconsistent_replies(&txn, topic_id)
+            .await?
+            .into_iter()
+            .map(|post| post.id)
+            .collect();
+        if offenders.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        GroupPost::update_many()
+            .col_expr(GroupPostColumn::ParentPostId, Expr::value(Option::<PostId>::None))
+            .filter(GroupPostColumn::Id.is_in(offenders.clone()))
+            .exec(&txn)
+            .await?;
+
+        let repaired = GroupPost::find()
+            .filter(GroupPostColumn::Id.is_in(offenders))
+            .order_by_asc(GroupPostColumn::Seq)
+            .all(&txn)
             .await?;
+        let now = self.clock.now_rfc3339();
+        for post in &repaired {
+            record_post_change(&txn, ChangeOp::Update, post, now.clone()).await?;
+        }
+        txn.commit().await?;
+        Ok(repaired)
+    }
 
+    async fn ensure_topic_exists(&self, topic_id: TopicId) -> Result<(), PostsServiceError> {
+        GroupTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::TopicNotFound)?;
         Ok(())
     }
 
-    /// Update a post (only by author)
+    /// Follow the posts made in a topic from now on.
+    ///
+    /// A subscriber that falls behind gets [`FeedEvent::Resync`] in place of
+    /// what it missed.
+    pub async fn _watch_topic(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<FeedSubscription, PostsServiceError> {
+        self.ensure_topic_exists(topic_id).await?;
+        Ok(self.feed.subscribe(topic_id)?)
+    }
+
+    /// Update a post (only by author, within the edit window)
     pub async fn _update_post(
         &self,
         post_id: PostId,
@@ -176,6 +866,12 @@ impl PostsService {
         title: Option<String>,
         body: Option<String>,
     ) -> Result<GroupPostModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+        let title = title.map(|title| self.normalize(title));
+        let body = body.map(|body| self.normalize(body));
+        title.as_deref().map(Self::check_title).transpose()?;
+        body.as_deref().map(Self::check_body).transpose()?;
+
         // Get the post
         let post = self._get_post(post_id).await?;
         
@@ -184,7 +880,21 @@ impl PostsService {
             return Err(PostsServiceError::Unauthorized);
         }
 
+        let window = config::runtime(&self.runtime).edit_window_minutes;
+        let window = chrono::Duration::minutes(window.into());
+        // Unparseable timestamps predate the service; leave those editable
+        if let Ok(created_at) = chrono::DateTime::parse_from_rfc3339(&post.created_at) {
+            if self.clock.now() - created_at.to_utc() > window {
+                return Err(PostsServiceError::EditWindowExpired);
+            }
+        }
+
+        if title.is_none() && body.is_none() {
+            return Ok(post);
+        }
+
         // Only update fields that were provided
+        let version = post.version;
         let mut post_active: GroupPostActiveModel = post.into();
         
         if let Some(new_title) = title {
@@ -195,11 +905,94 @@ impl PostsService {
             post_active.body = Set(new_body);
         }
 
-        let updated = post_active.update(&self.db).await?;
+        let now = self.clock.now_rfc3339();
+        post_active.edited_at = Set(Some(now.clone()));
+        post_active.version = Set(version + 1);
+
+        let txn = begin_write(&self.db).await?;
+        let updated = post_active.update(&txn).await?;
+        record_post_change(&txn, ChangeOp::Update, &updated, now).await?;
+        txn.commit().await?;
         Ok(updated)
     }
 
-    /// Count total posts in a topic
+    /// Ancestor chain of a post (root to target) with bounded siblings at each level.
+    ///
+    /// Soft-deleted posts are left out; a deleted ancestor ends the chain there.
+    pub async fn _thread_context(
+        &self,
+        post_id: PostId,
+        sibling_limit: u64,
+    ) -> Result<ThreadContext, PostsServiceError> {
+        use sea_orm::QueryOrder;
+
+        let sibling_limit = config::runtime(&self.runtime).page_size(sibling_limit);
+
+        // Everything in the target's topic, in one query
+        let posts = GroupPost::find()
+            .filter(
+                GroupPostColumn::TopicId.in_subquery(
+                    Query::select()
+                        .column(GroupPostColumn::TopicId)
+                        .from(GroupPost)
+                        .and_where(GroupPostColumn::Id.eq(post_id))
+                        .to_owned(),
+                ),
+            )
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .order_by_asc(GroupPostColumn::CreatedAt)
+            .order_by_asc(GroupPostColumn::Id)
+            .all(&self.db)
+            .await?;
+
+        let by_id: HashMap<PostId, &GroupPostModel> =
+            posts.iter().map(|post| (post.id, post)).collect();
+        let mut children: HashMap<Option<PostId>, Vec<&GroupPostModel>> = HashMap::new();
+        for post in &posts {
+            children.entry(post.parent_post_id).or_default().push(post);
+        }
+
+        // Walk up from the target; bounded by the topic size in case of a cycle
+        let mut chain = Vec::new();
+        let mut current = Some(post_id);
+        while let Some(id) = current {
+            let Some(post) = by_id.get(&id).copied() else {
+                if chain.is_empty() {
+                    return Err(PostsServiceError::PostNotFound);
+                }
+                break;
+            };
+            chain.push(post);
+            if chain.len() > posts.len() {
+                break;
+            }
+            current = post.parent_post_id;
+        }
+        chain.reverse();
+
+        let levels = chain
+            .into_iter()
+            .map(|post| {
+                let siblings = children
+                    .get(&post.parent_post_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|sibling| sibling.id != post.id)
+                    .take(sibling_limit as usize)
+                    .map(|sibling| (*sibling).clone())
+                    .collect();
+
+                ThreadLevel {
+                    post: post.clone(),
+                    siblings,
+                }
+            })
+            .collect();
+
+        Ok(ThreadContext { levels })
+    }
+
+    /// Count live posts in a topic
     pub async fn _count_posts_in_topic(
         &self,
         topic_id: TopicId,
@@ -208,13 +1001,14 @@ impl PostsService {
         
         let count = GroupPost::find()
             .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .count(&self.db)
             .await?;
 
         Ok(count)
     }
 
-    /// Count total posts by a user
+    /// Count live posts by a user
     pub async fn _count_posts_by_user(
         &self,
         user_id: UserId,
@@ -223,37 +1017,63 @@ impl PostsService {
         
         let count = GroupPost::find()
             .filter(GroupPostColumn::UserId.eq(user_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .count(&self.db)
             .await?;
 
         Ok(count)
     }
 
-    /// Create a reply to a post or another reply
-    pub async fn _create_reply(
+    /// Load a post to reply to; soft-deleted parents count as missing, and when
+    /// `topic_id` is given the parent must belong to that topic
+    async fn resolve_parent(
         &self,
         parent_post_id: PostId,
-        user_id: UserId,
-        title: String,
-        body: String,
+        topic_id: Option<TopicId>,
     ) -> Result<GroupPostModel, PostsServiceError> {
-        // Verify parent post exists
-        let parent_post = self._get_post(parent_post_id).await?;
-        
+        let parent = self
+            ._try_get_post(parent_post_id)
+            .await?
+            .ok_or(PostsServiceError::ParentNotFound)?;
+
+        match topic_id {
+            Some(topic_id) if topic_id != parent.topic_id => {
+                Err(PostsServiceError::ParentInDifferentTopic)
+            }
+            _ => Ok(parent),
+        }
+    }
+
+    /// Create a reply to a post or another reply
+    pub async fn _create_reply(
+        &self,
+        parent_post_id: PostId,
+        user_id: UserId,
+        title: String,
+        body: String,
+    ) -> Result<GroupPostModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+        let (title, body) = (self.normalize(title), self.normalize(body));
+        Self::check_title(&title)?;
+        Self::check_body(&body)?;
+
+        // Verify parent post exists and is still live
+        let parent_post = self.resolve_parent(parent_post_id, None).await?;
+        
         // Verify user exists
-        let user_exists = GroupUser::find_by_id(user_id)
+        let user = GroupUser::find_by_id(user_id)
             .one(&self.db)
             .await?
-            .is_some();
-        
-        if !user_exists {
-            return Err(PostsServiceError::UserNotFound);
-        }
+            .ok_or(PostsServiceError::UserNotFound)?;
+
+        self.ensure_same_group(user_id, parent_post.topic_id).await?;
 
         // Create reply - inherits topic_id from parent
         let post_id = PostId::new();
-        let created_at = chrono::Utc::now().to_rfc3339();
-        
+        let created_at = self.clock.now_rfc3339();
+
+        let txn = begin_write(&self.db).await?;
+
         let reply = GroupPostActiveModel {
             id: Set(post_id),
             user_id: Set(user_id),
@@ -262,16 +1082,117 @@ impl PostsService {
             title: Set(title),
             body: Set(body),
             created_at: Set(created_at),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(next_topic_seq(&txn, parent_post.topic_id).await?),
         };
 
         let result = GroupPost::insert(reply)
-            .exec_with_returning(&self.db)
+            .exec_with_returning(&txn)
             .await?;
-
+        enqueue_notifications(&txn, &result, Some(parent_post.user_id)).await?;
+        let topic_id = result.topic_id;
+        let (profile_id, group_id) = (user.profile_id, user.group_id);
+        record_last_location(&txn, profile_id, group_id, Some(topic_id), &result.created_at).await?;
+        record_post_change(&txn, ChangeOp::Create, &result, result.created_at.clone()).await?;
+
+        txn.commit().await?;
+        self.feed.publish(&result);
         Ok(result)
     }
 
-    /// List direct replies to a post (not nested)
+    /// Turn a reply into a top-level post (group admins only), optionally moving
+    /// it into another topic of the same group.
+    ///
+    /// Its replies stay attached to it and move along with it.
+    pub async fn _promote_reply(
+        &self,
+        post_id: PostId,
+        actor_profile_id: ProfileId,
+        target_topic_id: Option<TopicId>,
+    ) -> Result<GroupPostModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+
+        let post = self._get_post(post_id).await?;
+        if post.parent_post_id.is_none() {
+            return Err(PostsServiceError::NotAReply);
+        }
+
+        let topic = GroupTopic::find_by_id(post.topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::TopicNotFound)?;
+
+        if !self.is_group_admin(topic.group_id, actor_profile_id).await? {
+            return Err(PostsServiceError::Unauthorized);
+        }
+
+        let target_topic_id = match target_topic_id {
+            Some(target_id) if target_id != topic.id => {
+                let target = GroupTopic::find_by_id(target_id)
+                    .one(&self.db)
+                    .await?
+                    .ok_or(PostsServiceError::TopicNotFound)?;
+
+                if target.group_id != topic.group_id {
+                    return Err(PostsServiceError::TopicInDifferentGroup);
+                }
+                Some(target_id)
+            }
+            _ => None,
+        };
+
+        let txn = begin_write(&self.db).await?;
+
+        // Staying put only touches the post itself; a move takes every reply below it too
+        let mut subtree = vec![post];
+        if target_topic_id.is_some() {
+            let mut frontier = vec![post_id];
+            while !frontier.is_empty() {
+                let children = GroupPost::find()
+                    .filter(GroupPostColumn::ParentPostId.is_in(frontier))
+                    .all(&txn)
+                    .await?;
+                frontier = children.iter().map(|child| child.id).collect();
+                subtree.extend(children);
+            }
+            subtree.sort_by_key(|moved| moved.seq);
+        }
+
+        let mut next_seq = match target_topic_id {
+            Some(target_id) => next_topic_seq(&txn, target_id).await?,
+            None => 0,
+        };
+
+        let now = self.clock.now_rfc3339();
+        let mut promoted = None;
+        for moved in subtree {
+            let id = moved.id;
+            let mut moved_active: GroupPostActiveModel = moved.into();
+
+            if let Some(target_id) = target_topic_id {
+                moved_active.topic_id = Set(target_id);
+                moved_active.seq = Set(next_seq);
+                next_seq += 1;
+            }
+            if id == post_id {
+                moved_active.parent_post_id = Set(None);
+            }
+
+            let updated = moved_active.update(&txn).await?;
+            record_post_change(&txn, ChangeOp::Update, &updated, now.clone()).await?;
+            if id == post_id {
+                promoted = Some(updated);
+            }
+        }
+
+        txn.commit().await?;
+        promoted.ok_or(PostsServiceError::PostNotFound)
+    }
+
+    /// List live direct replies to a post (not nested)
     pub async fn _list_replies(
         &self,
         post_id: PostId,
@@ -282,16 +1203,63 @@ impl PostsService {
         
         let replies = GroupPost::find()
             .filter(GroupPostColumn::ParentPostId.eq(Some(post_id)))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .order_by_asc(GroupPostColumn::CreatedAt) // Oldest first
-            .limit(limit)
-            .offset(offset)
+            .order_by_asc(GroupPostColumn::Seq)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
             .all(&self.db)
             .await?;
 
         Ok(replies)
     }
 
-    /// Count direct replies to a post
+    /// `_list_replies` with each reply's author profile, `None` where it's gone
+    pub async fn _list_replies_with_authors(
+        &self,
+        post_id: PostId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<(GroupPostModel, Option<ProfileModel>)>, PostsServiceError> {
+        let replies = self._list_replies(post_id, limit, offset).await?;
+        if replies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids: HashSet<UserId> = replies.iter().map(|reply| reply.user_id).collect();
+        let authors: HashMap<UserId, ProfileId> = GroupUser::find()
+            .filter(GroupUserColumn::Id.is_in(user_ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user.profile_id))
+            .collect();
+        let profile_ids: HashSet<ProfileId> = authors.values().copied().collect();
+        let profiles: HashMap<ProfileId, ProfileModel> = if profile_ids.is_empty() {
+            HashMap::new()
+        } else {
+            Profile::find()
+                .filter(ProfileColumn::Id.is_in(profile_ids))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|profile| (profile.id, profile))
+                .collect()
+        };
+
+        Ok(replies
+            .into_iter()
+            .map(|reply| {
+                let author = authors
+                    .get(&reply.user_id)
+                    .and_then(|profile_id| profiles.get(profile_id))
+                    .cloned();
+                (reply, author)
+            })
+            .collect())
+    }
+
+    /// Count live direct replies to a post
     pub async fn _count_replies(
         &self,
         post_id: PostId,
@@ -300,6 +1268,7 @@ impl PostsService {
         
         let count = GroupPost::find()
             .filter(GroupPostColumn::ParentPostId.eq(Some(post_id)))
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .count(&self.db)
             .await?;
 
@@ -318,289 +1287,2570 @@ impl PostsService {
         let posts = GroupPost::find()
             .filter(GroupPostColumn::TopicId.eq(topic_id))
             .filter(GroupPostColumn::ParentPostId.is_null())
+            .filter(GroupPostColumn::DeletedAt.is_null())
             .order_by_asc(GroupPostColumn::CreatedAt)
-            .limit(limit)
-            .offset(offset)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
             .all(&self.db)
             .await?;
 
         Ok(posts)
     }
-}
 
-#[zel_service(name = "posts")]
-trait Posts {
-    #[doc = "Create a new post in a topic"]
-    #[method(name = "create_post")]
-    async fn create_post(
+    async fn is_group_admin(
         &self,
-        user_id: UserId,
-        topic_id: TopicId,
-        title: String,
-        body: String,
-    ) -> Result<GroupPostModel, ResourceError>;
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, PostsServiceError> {
+        let admin = GroupAdmin::find()
+            .filter(GroupAdminColumn::GroupId.eq(group_id))
+            .filter(GroupAdminColumn::IdentityId.eq(profile_id))
+            .one(&self.db)
+            .await?;
 
-    #[doc = "Get a specific post by ID"]
-    #[method(name = "get_post")]
-    async fn get_post(&self, post_id: PostId) -> Result<GroupPostModel, ResourceError>;
+        Ok(admin.is_some())
+    }
 
-    #[doc = "List posts for a topic with pagination"]
-    #[method(name = "list_posts_for_topic")]
-    async fn list_posts_for_topic(
+    /// Flag a post for the group's admins; one open report per reporter and post
+    pub async fn _report_post(
         &self,
-        topic_id: TopicId,
+        post_id: PostId,
+        reporter_profile_id: ProfileId,
+        reason: String,
+    ) -> Result<PostReportModel, PostsServiceError> {
+        check_text("reason", &reason, MAX_REPORT_REASON_CHARS)?;
+        self._get_post(post_id).await?;
+        if Profile::find_by_id(reporter_profile_id).one(&self.db).await?.is_none() {
+            return Err(PostsServiceError::ProfileNotFound);
+        }
+
+        let open = PostReport::find()
+            .filter(PostReportColumn::PostId.eq(post_id))
+            .filter(PostReportColumn::ReporterProfileId.eq(reporter_profile_id))
+            .filter(PostReportColumn::Status.eq(ReportStatus::Open))
+            .count(&self.db)
+            .await?;
+        if open > 0 {
+            return Err(PostsServiceError::AlreadyReported);
+        }
+
+        let report = PostReportActiveModel {
+            id: Set(ReportId::new()),
+            post_id: Set(post_id),
+            reporter_profile_id: Set(reporter_profile_id),
+            reason: Set(reason),
+            status: Set(ReportStatus::Open),
+            created_at: Set(self.clock.now_rfc3339()),
+        };
+
+        // The partial unique index settles a race between two identical reports
+        match PostReport::insert(report).exec_with_returning(&self.db).await {
+            Ok(report) => Ok(report),
+            Err(error) => match error.sql_err() {
+                Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                    Err(PostsServiceError::AlreadyReported)
+                }
+                _ => Err(error.into()),
+            },
+        }
+    }
+
+    /// A group's open reports, oldest first (group admins only)
+    pub async fn _list_reports(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
         limit: u64,
         offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+    ) -> Result<Vec<PostReportModel>, PostsServiceError> {
+        use sea_orm::{JoinType, QueryOrder, RelationTrait};
 
-    #[doc = "List posts by a specific user with pagination"]
-    #[method(name = "list_posts_by_user")]
-    async fn list_posts_by_user(
+        if !self.is_group_admin(group_id, actor_profile_id).await? {
+            return Err(PostsServiceError::Unauthorized);
+        }
+
+        let reports = PostReport::find()
+            .join(JoinType::InnerJoin, post_report::Relation::GroupPost.def())
+            .join(JoinType::InnerJoin, group_post::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .filter(PostReportColumn::Status.eq(ReportStatus::Open))
+            .order_by_asc(PostReportColumn::CreatedAt)
+            .order_by_asc(PostReportColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(reports)
+    }
+
+    /// A group's live posts with open reports, most reported first (group admins only).
+    ///
+    /// Equally reported posts come most recently reported first.
+    pub async fn _moderation_queue(
         &self,
-        user_id: UserId,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
         limit: u64,
         offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+    ) -> Result<Vec<QueuedPost>, PostsServiceError> {
+        use sea_orm::{JoinType, QueryOrder, RelationTrait};
 
-    #[doc = "Delete a post (only by author)"]
-    #[method(name = "delete_post")]
-    async fn delete_post(&self, post_id: PostId, user_id: UserId) -> Result<(), ResourceError>;
+        if !self.is_group_admin(group_id, actor_profile_id).await? {
+            return Err(PostsServiceError::Unauthorized);
+        }
 
-    #[doc = "Update a post (only by author)"]
-    #[method(name = "update_post")]
-    async fn update_post(
+        let tallies = PostReport::find()
+            .select_only()
+            .column(PostReportColumn::PostId)
+            .column_as(PostReportColumn::Id.count(), "open_reports")
+            .column_as(PostReportColumn::CreatedAt.max(), "latest_reported_at")
+            .join(JoinType::InnerJoin, post_report::Relation::GroupPost.def())
+            .join(JoinType::InnerJoin, group_post::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .filter(PostReportColumn::Status.eq(ReportStatus::Open))
+            .group_by(PostReportColumn::PostId)
+            .order_by_desc(PostReportColumn::Id.count())
+            .order_by_desc(PostReportColumn::CreatedAt.max())
+            .order_by_asc(PostReportColumn::PostId)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .into_model::<ReportTally>()
+            .all(&self.db)
+            .await?;
+        if tallies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let post_ids: Vec<PostId> = tallies.iter().map(|tally| tally.post_id).collect();
+        let mut posts: HashMap<PostId, GroupPostModel> = GroupPost::find()
+            .filter(GroupPostColumn::Id.is_in(post_ids.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|post| (post.id, post))
+            .collect();
+
+        // Newest first, so the first report seen per post is its latest
+        let mut latest_reasons: HashMap<PostId, String> = HashMap::new();
+        let reports = PostReport::find()
+            .filter(PostReportColumn::PostId.is_in(post_ids))
+            .filter(PostReportColumn::Status.eq(ReportStatus::Open))
+            .order_by_desc(PostReportColumn::CreatedAt)
+            .order_by_desc(PostReportColumn::Id)
+            .all(&self.db)
+            .await?;
+        for report in reports {
+            latest_reasons.entry(report.post_id).or_insert(report.reason);
+        }
+
+        Ok(tallies
+            .into_iter()
+            .filter_map(|tally| {
+                Some(QueuedPost {
+                    post: posts.remove(&tally.post_id)?,
+                    open_reports: tally.open_reports,
+                    latest_reason: latest_reasons.remove(&tally.post_id).unwrap_or_default(),
+                    latest_reported_at: tally.latest_reported_at,
+                })
+            })
+            .collect())
+    }
+
+    /// Close a report as resolved or dismissed (admins of the post's group only)
+    pub async fn _resolve_report(
         &self,
-        post_id: PostId,
-        user_id: UserId,
-        title: Option<String>,
-        body: Option<String>,
-    ) -> Result<GroupPostModel, ResourceError>;
+        report_id: ReportId,
+        actor_profile_id: ProfileId,
+        status: ReportStatus,
+    ) -> Result<PostReportModel, PostsServiceError> {
+        if status == ReportStatus::Open {
+            return Err(PostsServiceError::InvalidReportStatus);
+        }
 
-    #[doc = "Count total posts in a topic"]
-    #[method(name = "count_posts_in_topic")]
-    async fn count_posts_in_topic(&self, topic_id: TopicId) -> Result<u64, ResourceError>;
+        let (report, post) = PostReport::find_by_id(report_id)
+            .find_also_related(GroupPost)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::ReportNotFound)?;
+        let post = post.ok_or(PostsServiceError::PostNotFound)?;
+        let topic = GroupTopic::find_by_id(post.topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::TopicNotFound)?;
 
-    #[doc = "Count total posts by a user"]
-    #[method(name = "count_posts_by_user")]
-    async fn count_posts_by_user(&self, user_id: UserId) -> Result<u64, ResourceError>;
+        if !self.is_group_admin(topic.group_id, actor_profile_id).await? {
+            return Err(PostsServiceError::Unauthorized);
+        }
 
-    #[doc = "Create a reply to a post or another reply"]
-    #[method(name = "create_reply")]
-    async fn create_reply(
+        let mut report: PostReportActiveModel = report.into();
+        report.status = Set(status);
+        Ok(report.update(&self.db).await?)
+    }
+
+    /// Send `body` to `to_profile_id` privately, opening their direct topic if
+    /// the two haven't talked before
+    pub async fn _start_direct(
         &self,
-        parent_post_id: PostId,
-        user_id: UserId,
-        title: String,
+        from_profile_id: ProfileId,
+        to_profile_id: ProfileId,
         body: String,
-    ) -> Result<GroupPostModel, ResourceError>;
+    ) -> Result<DirectMessageModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+        Self::check_body(&body)?;
 
-    #[doc = "List direct replies to a post with pagination"]
-    #[method(name = "list_replies")]
-    async fn list_replies(
+        if from_profile_id == to_profile_id {
+            return Err(PostsServiceError::DirectToSelf);
+        }
+        let found = Profile::find()
+            .filter(ProfileColumn::Id.is_in([from_profile_id, to_profile_id]))
+            .count(&self.db)
+            .await?;
+        if found != 2 {
+            return Err(PostsServiceError::ProfileNotFound);
+        }
+
+        let (low, high) = direct_topic::participants(from_profile_id, to_profile_id);
+        let txn = begin_write(&self.db).await?;
+
+        let existing = DirectTopic::find()
+            .filter(DirectTopicColumn::LowProfileId.eq(low))
+            .filter(DirectTopicColumn::HighProfileId.eq(high))
+            .one(&txn)
+            .await?;
+        let topic_id = match existing {
+            Some(topic) => topic.id,
+            None => {
+                let now = self.clock.now_rfc3339();
+                let topic = DirectTopicActiveModel {
+                    id: Set(TopicId::new()),
+                    low_profile_id: Set(low),
+                    high_profile_id: Set(high),
+                    created_at: Set(now.clone()),
+                    last_message_at: Set(now),
+                };
+                DirectTopic::insert(topic).exec_with_returning(&txn).await?.id
+            }
+        };
+
+        let message = self.insert_direct(&txn, topic_id, from_profile_id, body).await?;
+        txn.commit().await?;
+        Ok(message)
+    }
+
+    /// Reply in an existing direct topic (participants only)
+    pub async fn _send_direct(
         &self,
-        post_id: PostId,
+        topic_id: TopicId,
+        from_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, PostsServiceError> {
+        self.ensure_not_frozen()?;
+        Self::check_body(&body)?;
+        self.direct_topic_for(topic_id, from_profile_id).await?;
+
+        let txn = begin_write(&self.db).await?;
+        let message = self.insert_direct(&txn, topic_id, from_profile_id, body).await?;
+        txn.commit().await?;
+        Ok(message)
+    }
+
+    /// A profile's direct topics, most recent message first
+    pub async fn _list_direct_topics(
+        &self,
+        profile_id: ProfileId,
         limit: u64,
         offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+    ) -> Result<Vec<DirectTopicModel>, PostsServiceError> {
+        use sea_orm::{Condition, QueryOrder};
+
+        let topics = DirectTopic::find()
+            .filter(
+                Condition::any()
+                    .add(DirectTopicColumn::LowProfileId.eq(profile_id))
+                    .add(DirectTopicColumn::HighProfileId.eq(profile_id)),
+            )
+            .order_by_desc(DirectTopicColumn::LastMessageAt)
+            .order_by_desc(DirectTopicColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
 
-    #[doc = "Count direct replies to a post"]
-    #[method(name = "count_replies")]
-    async fn count_replies(&self, post_id: PostId) -> Result<u64, ResourceError>;
+        Ok(topics)
+    }
 
-    #[doc = "List only top-level posts in a topic (excludes replies)"]
-    #[method(name = "list_top_level_posts")]
-    async fn list_top_level_posts(
+    /// Messages in a direct topic, oldest first (participants only)
+    pub async fn _list_direct_messages(
         &self,
         topic_id: TopicId,
+        profile_id: ProfileId,
         limit: u64,
         offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError>;
-}
+    ) -> Result<Vec<DirectMessageModel>, PostsServiceError> {
+        use sea_orm::QueryOrder;
 
-#[async_trait]
-impl PostsServer for PostsService {
-    async fn create_post(
-        &self,
-        _ctx: RequestContext,
-        user_id: UserId,
-        topic_id: TopicId,
-        title: String,
-        body: String,
-    ) -> Result<GroupPostModel, ResourceError> {
-        Ok(self._create_post(user_id, topic_id, title, body).await?)
+        self.direct_topic_for(topic_id, profile_id).await?;
+
+        let messages = DirectMessage::find()
+            .filter(DirectMessageColumn::TopicId.eq(topic_id))
+            .order_by_asc(DirectMessageColumn::CreatedAt)
+            .order_by_asc(DirectMessageColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(messages)
     }
 
-    async fn get_post(
+    /// The direct topic, if `profile_id` is one of its two participants
+    async fn direct_topic_for(
         &self,
-        _ctx: RequestContext,
-        post_id: PostId,
-    ) -> Result<GroupPostModel, ResourceError> {
-        Ok(self._get_post(post_id).await?)
+        topic_id: TopicId,
+        profile_id: ProfileId,
+    ) -> Result<DirectTopicModel, PostsServiceError> {
+        let topic = DirectTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(PostsServiceError::TopicNotFound)?;
+
+        if !topic.is_participant(profile_id) {
+            return Err(PostsServiceError::NotAParticipant);
+        }
+        Ok(topic)
     }
 
-    async fn list_posts_for_topic(
+    async fn insert_direct(
         &self,
-        _ctx: RequestContext,
+        txn: &DatabaseTransaction,
         topic_id: TopicId,
-        limit: u64,
-        offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError> {
-        Ok(self._list_posts_for_topic(topic_id, limit, offset).await?)
+        profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, PostsServiceError> {
+        let created_at = self.clock.now_rfc3339();
+        let message = DirectMessageActiveModel {
+            id: Set(PostId::new()),
+            topic_id: Set(topic_id),
+            profile_id: Set(profile_id),
+            body: Set(body),
+            created_at: Set(created_at.clone()),
+        };
+        let message = DirectMessage::insert(message).exec_with_returning(txn).await?;
+
+        DirectTopic::update_many()
+            .col_expr(DirectTopicColumn::LastMessageAt, Expr::value(created_at))
+            .filter(DirectTopicColumn::Id.eq(topic_id))
+            .exec(txn)
+            .await?;
+
+        Ok(message)
     }
 
-    async fn list_posts_by_user(
+    /// Fail with `NotOwner` unless the calling node owns `profile_id`, so an
+    /// RPC can't act as a profile just by naming it
+    async fn ensure_caller_owns(
         &self,
-        _ctx: RequestContext,
-        user_id: UserId,
-        limit: u64,
-        offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError> {
-        Ok(self._list_posts_by_user(user_id, limit, offset).await?)
+        caller: &impl Caller,
+        profile_id: ProfileId,
+    ) -> Result<(), PostsServiceError> {
+        if !owns_profile(&self.db, caller.remote_id(), profile_id).await? {
+            return Err(PostsServiceError::NotOwner);
+        }
+        Ok(())
     }
 
-    async fn delete_post(
+    /// RPC body of `promote_reply`, usable without a live `RequestContext`
+    pub async fn promote_reply_as(
         &self,
-        _ctx: RequestContext,
+        caller: &impl Caller,
         post_id: PostId,
-        user_id: UserId,
-    ) -> Result<(), ResourceError> {
-        Ok(self._delete_post(post_id, user_id).await?)
+        actor_profile_id: ProfileId,
+        target_topic_id: Option<TopicId>,
+    ) -> Result<GroupPostModel, ResourceError> {
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, actor_profile_id).await?;
+                self._promote_reply(post_id, actor_profile_id, target_topic_id).await
+            })
+            .await
     }
 
-    async fn update_post(
+    /// RPC body of `start_direct`, usable without a live `RequestContext`
+    pub async fn start_direct_as(
         &self,
-        _ctx: RequestContext,
-        post_id: PostId,
-        user_id: UserId,
-        title: Option<String>,
-        body: Option<String>,
-    ) -> Result<GroupPostModel, ResourceError> {
-        Ok(self._update_post(post_id, user_id, title, body).await?)
+        caller: &impl Caller,
+        from_profile_id: ProfileId,
+        to_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError> {
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, from_profile_id).await?;
+                with_retry(|| self._start_direct(from_profile_id, to_profile_id, body.clone()))
+                    .await
+            })
+            .await
     }
 
-    async fn count_posts_in_topic(
+    /// RPC body of `send_direct`, usable without a live `RequestContext`
+    pub async fn send_direct_as(
         &self,
-        _ctx: RequestContext,
+        caller: &impl Caller,
         topic_id: TopicId,
-    ) -> Result<u64, ResourceError> {
-        Ok(self._count_posts_in_topic(topic_id).await?)
+        from_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError> {
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, from_profile_id).await?;
+                with_retry(|| self._send_direct(topic_id, from_profile_id, body.clone())).await
+            })
+            .await
     }
 
-    async fn count_posts_by_user(
+    /// RPC body of `list_direct_topics`, usable without a live `RequestContext`
+    pub async fn list_direct_topics_as(
         &self,
-        _ctx: RequestContext,
-        user_id: UserId,
-    ) -> Result<u64, ResourceError> {
-        Ok(self._count_posts_by_user(user_id).await?)
+        caller: &impl Caller,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectTopicModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, profile_id).await?;
+                self._list_direct_topics(profile_id, limit, offset.unwrap_or(0)).await
+            })
+            .await
+    }
+
+    /// RPC body of `list_direct_messages`, usable without a live `RequestContext`
+    pub async fn list_direct_messages_as(
+        &self,
+        caller: &impl Caller,
+        topic_id: TopicId,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectMessageModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, profile_id).await?;
+                self._list_direct_messages(topic_id, profile_id, limit, offset).await
+            })
+            .await
+    }
+
+    /// RPC body of `report_post`, usable without a live `RequestContext`
+    pub async fn report_post_as(
+        &self,
+        caller: &impl Caller,
+        post_id: PostId,
+        reporter_profile_id: ProfileId,
+        reason: String,
+    ) -> Result<PostReportModel, ResourceError> {
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, reporter_profile_id).await?;
+                self._report_post(post_id, reporter_profile_id, reason).await
+            })
+            .await
+    }
+
+    /// RPC body of `list_reports`, usable without a live `RequestContext`
+    pub async fn list_reports_as(
+        &self,
+        caller: &impl Caller,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<PostReportModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, actor_profile_id).await?;
+                self._list_reports(group_id, actor_profile_id, limit, offset).await
+            })
+            .await
+    }
+
+    /// RPC body of `moderation_queue`, usable without a live `RequestContext`
+    pub async fn moderation_queue_as(
+        &self,
+        caller: &impl Caller,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<QueuedPost>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, actor_profile_id).await?;
+                self._moderation_queue(group_id, actor_profile_id, limit, offset).await
+            })
+            .await
+    }
+
+    /// RPC body of `resolve_report`, usable without a live `RequestContext`
+    pub async fn resolve_report_as(
+        &self,
+        caller: &impl Caller,
+        report_id: ReportId,
+        actor_profile_id: ProfileId,
+        status: ReportStatus,
+    ) -> Result<PostReportModel, ResourceError> {
+        self.breaker
+            .call(async {
+                self.ensure_caller_owns(caller, actor_profile_id).await?;
+                self._resolve_report(report_id, actor_profile_id, status).await
+            })
+            .await
+    }
+}
+
+/// An RFC 3339 timestamp in the UTC form `created_at` columns are stored in,
+/// so it compares correctly against them as a string
+fn stored_timestamp(field: &str, value: &str) -> Result<String, FieldError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&chrono::Utc).to_rfc3339())
+        .map_err(|_| FieldError::new(field, "must be an RFC 3339 timestamp"))
+}
+
+/// `@name` tokens in a post body, without the `@` and trailing punctuation.
+///
+/// Profile names containing whitespace can't be mentioned this way.
+fn mentioned_names(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = body
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|name| name.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_'))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Store `(user, post, viewed_at)` views in one transaction, trimming each
+/// user's history to the newest `MAX_VIEW_HISTORY` entries.
+pub(crate) async fn write_views(
+    db: &DatabaseConnection,
+    views: Vec<(UserId, PostId, String)>,
+) -> Result<(), DbErr> {
+    let users: HashSet<UserId> = views.iter().map(|(user_id, _, _)| *user_id).collect();
+    let txn = begin_write(db).await?;
+
+    for (user_id, post_id, viewed_at) in views {
+        let view = PostViewHistoryActiveModel {
+            user_id: Set(user_id),
+            post_id: Set(post_id),
+            viewed_at: Set(viewed_at),
+        };
+
+        PostViewHistory::insert(view)
+            .on_conflict(
+                OnConflict::columns([PostViewHistoryColumn::UserId, PostViewHistoryColumn::PostId])
+                    .update_column(PostViewHistoryColumn::ViewedAt)
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    // Trim everything older than the newest MAX_VIEW_HISTORY entries
+    for user_id in users {
+        PostViewHistory::delete_many()
+            .filter(PostViewHistoryColumn::UserId.eq(user_id))
+            .filter(
+                PostViewHistoryColumn::PostId.not_in_subquery(
+                    Query::select()
+                        .column(PostViewHistoryColumn::PostId)
+                        .from(PostViewHistory)
+                        .and_where(Expr::col(PostViewHistoryColumn::UserId).eq(user_id))
+                        .order_by(PostViewHistoryColumn::ViewedAt, Order::Desc)
+                        .limit(MAX_VIEW_HISTORY)
+                        .to_owned(),
+                ),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await
+}
+
+/// Next free per-topic post number. Run it in the inserting transaction; the
+/// unique (topic_id, seq) index rejects a racing writer that got the same value.
+pub(crate) async fn next_topic_seq<C: ConnectionTrait>(
+    conn: &C,
+    topic_id: TopicId,
+) -> Result<i64, DbErr> {
+    let max_seq = GroupPost::find()
+        .select_only()
+        .column_as(GroupPostColumn::Seq.max(), "max_seq")
+        .filter(GroupPostColumn::TopicId.eq(topic_id))
+        .into_tuple::<Option<i64>>()
+        .one(conn)
+        .await?
+        .flatten();
+
+    Ok(max_seq.unwrap_or(0) + 1)
+}
+
+/// `query` with its `LIKE` wildcards escaped by `\`
+pub(crate) fn escape_like(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `LIKE` pattern matching `query` anywhere, with its own wildcards escaped
+pub(crate) fn like_pattern(query: &str) -> LikeExpr {
+    LikeExpr::new(format!("%{}%", escape_like(query))).escape('\\')
+}
+
+/// Log a post's state to the change log
+async fn record_post_change<C: ConnectionTrait>(
+    conn: &C,
+    op: ChangeOp,
+    post: &GroupPostModel,
+    at: String,
+) -> Result<(), DbErr> {
+    record_change(conn, ChangeEntity::Post, op, post.id, post, at).await
+}
+
+/// Replies in `topic_id` whose parent isn't another post of the same topic
+async fn inconsistent_replies<C: ConnectionTrait>(
+    conn: &C,
+    topic_id: TopicId,
+) -> Result<Vec<GroupPostModel>, DbErr> {
+    let same_topic = Query::select()
+        .column(GroupPostColumn::Id)
+        .from(GroupPost)
+        .and_where(GroupPostColumn::TopicId.eq(topic_id))
+        .to_owned();
+
+    GroupPost::find()
+        .filter(GroupPostColumn::TopicId.eq(topic_id))
+        .filter(GroupPostColumn::ParentPostId.is_not_null())
+        .filter(GroupPostColumn::ParentPostId.not_in_subquery(same_topic))
+        .order_by_asc(GroupPostColumn::Seq)
+        .all(conn)
+        .await
+}
+
+/// Queue notifications for a freshly inserted post: one for the author of the
+/// post it replies to, one per mentioned member of the group. Authors are never
+/// notified about their own posts, and recipients' topic subscription levels
+/// filter what gets through.
+pub(crate) async fn enqueue_notifications<C: ConnectionTrait>(
+    conn: &C,
+    post: &GroupPostModel,
+    replied_to_user_id: Option<UserId>,
+) -> Result<(), DbErr> {
+    let Some(author) = GroupUser::find_by_id(post.user_id).one(conn).await? else {
+        return Ok(());
+    };
+
+    let mut pending: Vec<(ProfileId, NotificationKind)> = Vec::new();
+
+    // Banned members keep their rows but hear nothing more from the group
+    if let Some(parent_user_id) = replied_to_user_id {
+        let parent_author = GroupUser::find_by_id(parent_user_id)
+            .filter(not_banned(author.group_id))
+            .one(conn)
+            .await?;
+        if let Some(parent_author) = parent_author {
+            if parent_author.profile_id != author.profile_id {
+                pending.push((parent_author.profile_id, NotificationKind::Reply));
+            }
+        }
+    }
+
+    let names = mentioned_names(&post.body);
+    if !names.is_empty() {
+        // Only members of the post's group can be mentioned
+        let mentioned = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(author.group_id))
+            .filter(not_banned(author.group_id))
+            .filter(
+                GroupUserColumn::ProfileId.in_subquery(
+                    Query::select()
+                        .column(ProfileColumn::Id)
+                        .from(Profile)
+                        .and_where(ProfileColumn::Name.is_in(names))
+                        .to_owned(),
+                ),
+            )
+            .all(conn)
+            .await?;
+
+        for member in mentioned {
+            if member.profile_id != author.profile_id {
+                pending.push((member.profile_id, NotificationKind::Mention));
+            }
+        }
     }
 
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    // Respect the recipients' topic subscription levels
+    let levels: HashMap<ProfileId, SubscriptionLevel> = TopicSubscription::find()
+        .filter(TopicSubscriptionColumn::TopicId.eq(post.topic_id))
+        .filter(TopicSubscriptionColumn::ProfileId.is_in(pending.iter().map(|(p, _)| *p)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|subscription| (subscription.profile_id, subscription.level))
+        .collect();
+
+    // Stamped with the post's own creation time
+    let created_at = post.created_at.clone();
+    let notifications: Vec<NotificationActiveModel> = pending
+        .into_iter()
+        .filter(|(recipient, kind)| match levels.get(recipient).copied().unwrap_or_default() {
+            SubscriptionLevel::All => true,
+            SubscriptionLevel::Mentions => *kind == NotificationKind::Mention,
+            SubscriptionLevel::Muted => false,
+        })
+        .map(|(recipient, kind)| NotificationActiveModel {
+            id: Set(NotificationId::new()),
+            recipient_profile_id: Set(recipient),
+            kind: Set(kind),
+            source_post_id: Set(post.id),
+            read: Set(false),
+            created_at: Set(created_at.clone()),
+        })
+        .collect();
+
+    if !notifications.is_empty() {
+        Notification::insert_many(notifications).exec(conn).await?;
+    }
+
+    Ok(())
+}
+
+#[zel_service(name = "posts")]
+trait Posts {
+    #[doc = "Create a new post in a topic"]
+    #[method(name = "create_post")]
+    async fn create_post(
+        &self,
+        user_id: UserId,
+        topic_id: TopicId,
+        title: String,
+        body: String,
+    ) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "Get a specific post by ID"]
+    #[method(name = "get_post")]
+    async fn get_post(&self, post_id: PostId) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "Get a post by its number within a topic"]
+    #[method(name = "get_post_by_topic_seq")]
+    async fn get_post_by_topic_seq(
+        &self,
+        topic_id: TopicId,
+        seq: i64,
+    ) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "Get a specific post by ID, or nothing if it doesn't exist"]
+    #[method(name = "try_get_post")]
+    async fn try_get_post(&self, post_id: PostId) -> Result<Option<GroupPostModel>, ResourceError>;
+
+    #[doc = "Record that a user opened a post"]
+    #[method(name = "record_view")]
+    async fn record_view(&self, user_id: UserId, post_id: PostId) -> Result<(), ResourceError>;
+
+    #[doc = "List a user's most recently viewed posts, newest first"]
+    #[method(name = "recent_views")]
+    async fn recent_views(
+        &self,
+        user_id: UserId,
+        limit: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "List posts for a topic with pagination"]
+    #[method(name = "list_posts_for_topic")]
+    async fn list_posts_for_topic(
+        &self,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "List posts in a topic created after a known (created_at, id) point"]
+    #[method(name = "list_posts_for_topic_since")]
+    async fn list_posts_for_topic_since(
+        &self,
+        topic_id: TopicId,
+        after_created_at: String,
+        after_id: PostId,
+        limit: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "List post headers (no body) for a topic with pagination"]
+    #[method(name = "list_post_headers_for_topic")]
+    async fn list_post_headers_for_topic(
+        &self,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<PostHeader>, ResourceError>;
+
+    #[doc = "List posts by a specific user with pagination"]
+    #[method(name = "list_posts_by_user")]
+    async fn list_posts_by_user(
+        &self,
+        user_id: UserId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "Search a group's live posts by text, author and creation time, newest first"]
+    #[method(name = "search_posts_advanced")]
+    async fn search_posts_advanced(
+        &self,
+        group_id: GroupId,
+        search: PostSearch,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "Delete a post (only by author)"]
+    #[method(name = "delete_post")]
+    async fn delete_post(&self, post_id: PostId, user_id: UserId) -> Result<(), ResourceError>;
+
+    #[doc = "Soft-delete a post (only by author)"]
+    #[method(name = "soft_delete_post")]
+    async fn soft_delete_post(&self, post_id: PostId, user_id: UserId) -> Result<(), ResourceError>;
+
+    #[doc = "Update a post (only by author)"]
+    #[method(name = "update_post")]
+    async fn update_post(
+        &self,
+        post_id: PostId,
+        user_id: UserId,
+        title: Option<String>,
+        body: Option<String>,
+    ) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "Get the ancestor chain of a post with bounded siblings at each level"]
+    #[method(name = "thread_context")]
+    async fn thread_context(
+        &self,
+        post_id: PostId,
+        sibling_limit: u64,
+    ) -> Result<ThreadContext, ResourceError>;
+
+    #[doc = "Count total posts in a topic"]
+    #[method(name = "count_posts_in_topic")]
+    async fn count_posts_in_topic(&self, topic_id: TopicId) -> Result<u64, ResourceError>;
+
+    #[doc = "Count total posts by a user"]
+    #[method(name = "count_posts_by_user")]
+    async fn count_posts_by_user(&self, user_id: UserId) -> Result<u64, ResourceError>;
+
+    #[doc = "Create a reply to a post or another reply"]
+    #[method(name = "create_reply")]
     async fn create_reply(
         &self,
-        _ctx: RequestContext,
         parent_post_id: PostId,
         user_id: UserId,
         title: String,
         body: String,
+    ) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "List direct replies to a post with pagination"]
+    #[method(name = "list_replies")]
+    async fn list_replies(
+        &self,
+        post_id: PostId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "List direct replies to a post, each with its author's profile if it still exists"]
+    #[method(name = "list_replies_with_authors")]
+    async fn list_replies_with_authors(
+        &self,
+        post_id: PostId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<(GroupPostModel, Option<ProfileModel>)>, ResourceError>;
+
+    #[doc = "Make a reply top-level, optionally moving it and its replies to another topic"]
+    #[method(name = "promote_reply")]
+    async fn promote_reply(
+        &self,
+        post_id: PostId,
+        actor_profile_id: ProfileId,
+        target_topic_id: Option<TopicId>,
+    ) -> Result<GroupPostModel, ResourceError>;
+
+    #[doc = "Count direct replies to a post"]
+    #[method(name = "count_replies")]
+    async fn count_replies(&self, post_id: PostId) -> Result<u64, ResourceError>;
+
+    #[doc = "List only top-level posts in a topic (excludes replies)"]
+    #[method(name = "list_top_level_posts")]
+    async fn list_top_level_posts(
+        &self,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError>;
+
+    #[doc = "Report a post to the group's admins"]
+    #[method(name = "report_post")]
+    async fn report_post(
+        &self,
+        post_id: PostId,
+        reporter_profile_id: ProfileId,
+        reason: String,
+    ) -> Result<PostReportModel, ResourceError>;
+
+    #[doc = "List a group's open post reports, oldest first (group admins only)"]
+    #[method(name = "list_reports")]
+    async fn list_reports(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<PostReportModel>, ResourceError>;
+
+    #[doc = "A group's reported posts with open report counts, most reported first (admins only)"]
+    #[method(name = "moderation_queue")]
+    async fn moderation_queue(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<QueuedPost>, ResourceError>;
+
+    #[doc = "Mark a post report resolved or dismissed (group admins only)"]
+    #[method(name = "resolve_report")]
+    async fn resolve_report(
+        &self,
+        report_id: ReportId,
+        actor_profile_id: ProfileId,
+        status: ReportStatus,
+    ) -> Result<PostReportModel, ResourceError>;
+
+    #[doc = "Message a profile privately, opening a direct topic with them if needed"]
+    #[method(name = "start_direct")]
+    async fn start_direct(
+        &self,
+        from_profile_id: ProfileId,
+        to_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError>;
+
+    #[doc = "Reply in a direct topic (participants only)"]
+    #[method(name = "send_direct")]
+    async fn send_direct(
+        &self,
+        topic_id: TopicId,
+        from_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError>;
+
+    #[doc = "List a profile's direct topics, most recent message first"]
+    #[method(name = "list_direct_topics")]
+    async fn list_direct_topics(
+        &self,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectTopicModel>, ResourceError>;
+
+    #[doc = "List messages in a direct topic, oldest first (participants only)"]
+    #[method(name = "list_direct_messages")]
+    async fn list_direct_messages(
+        &self,
+        topic_id: TopicId,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectMessageModel>, ResourceError>;
+
+    #[doc = "Stream a topic's new posts; `Resync` means some were missed, so re-fetch"]
+    #[subscription(name = "watch_topic", item = "FeedEvent")]
+    async fn watch_topic(&self, topic_id: TopicId) -> Result<(), ResourceError>;
+}
+
+#[async_trait]
+impl PostsServer for PostsService {
+    async fn create_post(
+        &self,
+        _ctx: RequestContext,
+        user_id: UserId,
+        topic_id: TopicId,
+        title: String,
+        body: String,
     ) -> Result<GroupPostModel, ResourceError> {
-        Ok(self._create_reply(parent_post_id, user_id, title, body).await?)
+        let create = || self._create_post(user_id, topic_id, title.clone(), body.clone());
+        self.breaker.call(with_retry(create)).await
     }
 
-    async fn list_replies(
+    async fn get_post(
         &self,
         _ctx: RequestContext,
         post_id: PostId,
-        limit: u64,
-        offset: u64,
-    ) -> Result<Vec<GroupPostModel>, ResourceError> {
-        Ok(self._list_replies(post_id, limit, offset).await?)
+    ) -> Result<GroupPostModel, ResourceError> {
+        self.breaker.call(self._get_post(post_id)).await
     }
 
-    async fn count_replies(
+    async fn get_post_by_topic_seq(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        seq: i64,
+    ) -> Result<GroupPostModel, ResourceError> {
+        self.breaker.call(self._get_post_by_topic_seq(topic_id, seq)).await
+    }
+
+    async fn try_get_post(
         &self,
         _ctx: RequestContext,
         post_id: PostId,
-    ) -> Result<u64, ResourceError> {
-        Ok(self._count_replies(post_id).await?)
+    ) -> Result<Option<GroupPostModel>, ResourceError> {
+        self.breaker.call(self._try_get_post(post_id)).await
     }
 
-    async fn list_top_level_posts(
+    async fn record_view(
+        &self,
+        _ctx: RequestContext,
+        user_id: UserId,
+        post_id: PostId,
+    ) -> Result<(), ResourceError> {
+        self.breaker.call(self._record_view(user_id, post_id)).await
+    }
+
+    async fn recent_views(
+        &self,
+        _ctx: RequestContext,
+        user_id: UserId,
+        limit: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._recent_views(user_id, limit)).await
+    }
+
+    async fn list_posts_for_topic(
         &self,
         _ctx: RequestContext,
         topic_id: TopicId,
-        limit: u64,
-        offset: u64,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> Result<Vec<GroupPostModel>, ResourceError> {
-        Ok(self._list_top_level_posts(topic_id, limit, offset).await?)
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._list_posts_for_topic(topic_id, limit, offset.unwrap_or(0))).await
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::migrator::Migrator;
-    use crate::ids::{GroupId, ProfileId};
-    use sea_orm::Database;
-    use sea_orm_migration::MigratorTrait;
+    async fn list_posts_for_topic_since(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        after_created_at: String,
+        after_id: PostId,
+        limit: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._list_posts_for_topic_since(topic_id, after_created_at, after_id, limit))
+            .await
+    }
 
-    async fn setup_test_service() -> PostsService {
-        let db = Database::connect("sqlite::memory:")
+    async fn list_post_headers_for_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<PostHeader>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._list_post_headers_for_topic(topic_id, limit, offset.unwrap_or(0)))
             .await
-            .expect("Failed to create in-memory database");
+    }
+
+    async fn list_posts_by_user(
+        &self,
+        _ctx: RequestContext,
+        user_id: UserId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._list_posts_by_user(user_id, limit, offset.unwrap_or(0))).await
+    }
+
+    async fn search_posts_advanced(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        search: PostSearch,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let PostSearch { query, author_profile_id, since } = search;
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(self._search_posts_advanced(
+                group_id,
+                query,
+                author_profile_id,
+                since,
+                limit,
+                offset,
+            ))
+            .await
+    }
+
+    async fn delete_post(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        user_id: UserId,
+    ) -> Result<(), ResourceError> {
+        self.breaker.call(self._delete_post(post_id, user_id)).await
+    }
+
+    async fn soft_delete_post(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        user_id: UserId,
+    ) -> Result<(), ResourceError> {
+        self.breaker
+            .call(with_retry(|| self._soft_delete_post(post_id, user_id)))
+            .await
+    }
+
+    async fn update_post(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        user_id: UserId,
+        title: Option<String>,
+        body: Option<String>,
+    ) -> Result<GroupPostModel, ResourceError> {
+        let update = || self._update_post(post_id, user_id, title.clone(), body.clone());
+        self.breaker.call(with_retry(update)).await
+    }
+
+    async fn thread_context(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        sibling_limit: u64,
+    ) -> Result<ThreadContext, ResourceError> {
+        self.breaker.call(self._thread_context(post_id, sibling_limit)).await
+    }
+
+    async fn count_posts_in_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+    ) -> Result<u64, ResourceError> {
+        self.breaker.call(self._count_posts_in_topic(topic_id)).await
+    }
+
+    async fn count_posts_by_user(
+        &self,
+        _ctx: RequestContext,
+        user_id: UserId,
+    ) -> Result<u64, ResourceError> {
+        self.breaker.call(self._count_posts_by_user(user_id)).await
+    }
+
+    async fn create_reply(
+        &self,
+        _ctx: RequestContext,
+        parent_post_id: PostId,
+        user_id: UserId,
+        title: String,
+        body: String,
+    ) -> Result<GroupPostModel, ResourceError> {
+        let reply = || self._create_reply(parent_post_id, user_id, title.clone(), body.clone());
+        self.breaker.call(with_retry(reply)).await
+    }
+
+    async fn list_replies(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._list_replies(post_id, limit, offset.unwrap_or(0))).await
+    }
+
+    async fn list_replies_with_authors(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<(GroupPostModel, Option<ProfileModel>)>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._list_replies_with_authors(post_id, limit, offset.unwrap_or(0)))
+            .await
+    }
+
+    async fn promote_reply(
+        &self,
+        ctx: RequestContext,
+        post_id: PostId,
+        actor_profile_id: ProfileId,
+        target_topic_id: Option<TopicId>,
+    ) -> Result<GroupPostModel, ResourceError> {
+        self.promote_reply_as(&ctx, post_id, actor_profile_id, target_topic_id).await
+    }
+
+    async fn count_replies(
+        &self,
+        _ctx: RequestContext,
+        post_id: PostId,
+    ) -> Result<u64, ResourceError> {
+        self.breaker.call(self._count_replies(post_id)).await
+    }
+
+    async fn list_top_level_posts(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupPostModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._list_top_level_posts(topic_id, limit, offset.unwrap_or(0))).await
+    }
+
+    async fn report_post(
+        &self,
+        ctx: RequestContext,
+        post_id: PostId,
+        reporter_profile_id: ProfileId,
+        reason: String,
+    ) -> Result<PostReportModel, ResourceError> {
+        self.report_post_as(&ctx, post_id, reporter_profile_id, reason).await
+    }
+
+    async fn list_reports(
+        &self,
+        ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<PostReportModel>, ResourceError> {
+        self.list_reports_as(&ctx, group_id, actor_profile_id, limit, offset).await
+    }
+
+    async fn moderation_queue(
+        &self,
+        ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<QueuedPost>, ResourceError> {
+        self.moderation_queue_as(&ctx, group_id, actor_profile_id, limit, offset).await
+    }
+
+    async fn resolve_report(
+        &self,
+        ctx: RequestContext,
+        report_id: ReportId,
+        actor_profile_id: ProfileId,
+        status: ReportStatus,
+    ) -> Result<PostReportModel, ResourceError> {
+        self.resolve_report_as(&ctx, report_id, actor_profile_id, status).await
+    }
+
+    async fn start_direct(
+        &self,
+        ctx: RequestContext,
+        from_profile_id: ProfileId,
+        to_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError> {
+        self.start_direct_as(&ctx, from_profile_id, to_profile_id, body).await
+    }
+
+    async fn send_direct(
+        &self,
+        ctx: RequestContext,
+        topic_id: TopicId,
+        from_profile_id: ProfileId,
+        body: String,
+    ) -> Result<DirectMessageModel, ResourceError> {
+        self.send_direct_as(&ctx, topic_id, from_profile_id, body).await
+    }
+
+    async fn list_direct_topics(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectTopicModel>, ResourceError> {
+        self.list_direct_topics_as(&ctx, profile_id, limit, offset).await
+    }
+
+    async fn list_direct_messages(
+        &self,
+        ctx: RequestContext,
+        topic_id: TopicId,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<DirectMessageModel>, ResourceError> {
+        self.list_direct_messages_as(&ctx, topic_id, profile_id, limit, offset).await
+    }
+
+    async fn watch_topic(
+        &self,
+        _ctx: RequestContext,
+        mut sink: PostsWatchTopicSink,
+        topic_id: TopicId,
+    ) -> Result<(), ResourceError> {
+        let mut subscription = self.breaker.call(self._watch_topic(topic_id)).await?;
+
+        // A client that went away frees its slot on the next failed send
+        while let Some(event) = subscription.next().await {
+            if sink.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+        let _ = sink.close().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuntimeConfig;
+    use crate::models::migrator::Migrator;
+    use crate::service::{caller::TestCaller, clock::MockClock, profiles::ProfilesService};
+    use sea_orm::Database;
+    use sea_orm_migration::MigratorTrait;
+
+    async fn setup_test_service() -> PostsService {
+        let db = Database::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to run migrations");
+
+        PostsService::new(db)
+    }
+
+    async fn create_test_profile(service: &PostsService, name: &str) -> ProfileId {
+        let profile_id = ProfileId::new();
+        let profile = ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set(name.to_string()),
+            desc: Set("Test".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        };
+        Profile::insert(profile).exec(&service.db).await.unwrap();
+        profile_id
+    }
+
+    async fn create_test_group(service: &PostsService, profile_id: ProfileId) -> GroupId {
+        let group_id = GroupId::new();
+        let group = GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        };
+        Group::insert(group).exec(&service.db).await.unwrap();
+        group_id
+    }
+
+    async fn create_test_user(service: &PostsService, group_id: GroupId, profile_id: ProfileId) -> UserId {
+        let user_id = UserId::new();
+        let user = GroupUserActiveModel {
+            id: Set(user_id),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
+        };
+        GroupUser::insert(user).exec(&service.db).await.unwrap();
+        user_id
+    }
+
+    async fn create_test_topic(service: &PostsService, group_id: GroupId, profile_id: ProfileId) -> TopicId {
+        let topic_id = TopicId::new();
+        let topic = GroupTopicActiveModel {
+            id: Set(topic_id),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            title: Set("Test Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
+        };
+        GroupTopic::insert(topic).exec(&service.db).await.unwrap();
+        topic_id
+    }
+
+    #[tokio::test]
+    async fn test_create_post() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(
+                user_id,
+                topic_id,
+                "Test Post".to_string(),
+                "This is a test post body".to_string(),
+            )
+            .await
+            .expect("Failed to create post");
+
+        assert_eq!(post.user_id, user_id);
+        assert_eq!(post.topic_id, topic_id);
+        assert_eq!(post.title, "Test Post");
+        assert_eq!(post.body, "This is a test post body");
+    }
+
+    #[tokio::test]
+    async fn test_banned_profile_cannot_post_or_reply() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        // Banned after joining; the membership row is still there
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(profile_id),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let result = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::Banned)));
+        let result = service
+            ._create_reply(post.id, user_id, "Re".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::Banned)));
+        assert_eq!(GroupPost::find().count(&service.db).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_watch_topic_streams_new_posts() {
+        let service = setup_test_service().await;
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let mut watching = service._watch_topic(topic_id).await.unwrap();
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let reply = service
+            ._create_reply(post.id, user_id, "Re".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        assert_eq!(watching.next().await, Some(FeedEvent::Post(post)));
+        assert_eq!(watching.next().await, Some(FeedEvent::Post(reply)));
+
+        let result = service._watch_topic(TopicId::new()).await;
+        assert!(matches!(result, Err(PostsServiceError::TopicNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_create_post_on_shared_wal_db() {
+        use crate::models::{migrate_up_locked, open_db, MIGRATION_LOCK_WAIT};
+
+        const WRITERS: usize = 40;
+
+        let file = format!("spout_post_stress_{}.sqlite", ProfileId::new());
+        let path = std::env::temp_dir().join(file);
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+
+        // The server's pool and the UI's pool on one file
+        let server = open_db(&url).await.unwrap();
+        let ui = open_db(&url).await.unwrap();
+        migrate_up_locked(&server, MIGRATION_LOCK_WAIT).await.unwrap();
+
+        let seed = PostsService::new(server.clone());
+        let profile_id = create_test_profile(&seed, "Test User").await;
+        let group_id = create_test_group(&seed, profile_id).await;
+        let user_id = create_test_user(&seed, group_id, profile_id).await;
+        let topic_id = create_test_topic(&seed, group_id, profile_id).await;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..WRITERS {
+            let db = if i % 2 == 0 { server.clone() } else { ui.clone() };
+            let service = PostsService::new(db);
+            tasks.spawn(async move {
+                service
+                    ._create_post(user_id, topic_id, format!("Post {i}"), "Body".to_string())
+                    .await
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap().expect("concurrent create_post failed");
+        }
+
+        let posts = GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .all(&server)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), WRITERS);
+        let mut seqs: Vec<i64> = posts.iter().map(|post| post.seq).collect();
+        seqs.sort();
+        assert_eq!(seqs, (1..=WRITERS as i64).collect::<Vec<_>>());
+
+        server.close().await.unwrap();
+        ui.close().await.unwrap();
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_post_rejects_user_from_other_group() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let other_group_id = create_test_group(&service, profile_id).await;
+        let outsider_id = create_test_user(&service, other_group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let result = service
+            ._create_post(outsider_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::NotAMember)));
+    }
+
+    #[tokio::test]
+    async fn test_posts_numbered_per_topic() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let other_topic = create_test_topic(&service, group_id, profile_id).await;
+
+        // Interleave posts, replies, and another topic's posts
+        let first = service
+            ._create_post(user_id, topic_id, "1".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let mut creations = tokio::task::JoinSet::new();
+        for i in 0..5 {
+            let service = service.clone();
+            creations.spawn(async move {
+                if i % 2 == 0 {
+                    service
+                        ._create_reply(first.id, user_id, "Re".to_string(), "Body".to_string())
+                        .await
+                } else {
+                    service
+                        ._create_post(user_id, other_topic, "Other".to_string(), "Body".to_string())
+                        .await
+                }
+            });
+        }
+        while let Some(post) = creations.join_next().await {
+            post.unwrap().unwrap();
+        }
+
+        let mut seqs: Vec<i64> = service
+            ._list_posts_for_topic(topic_id, 100, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|post| post.seq)
+            .collect();
+        seqs.sort();
+        assert_eq!(seqs, vec![1, 2, 3, 4]);
+
+        let other = service._list_posts_for_topic(other_topic, 100, 0).await.unwrap();
+        assert_eq!(other.len(), 2);
+
+        let by_seq = service._get_post_by_topic_seq(topic_id, 1).await.unwrap();
+        assert_eq!(by_seq.id, first.id);
+        let missing = service._get_post_by_topic_seq(topic_id, 99).await;
+        assert!(matches!(missing, Err(PostsServiceError::PostNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_view_history_keeps_most_recent() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let mut posts = Vec::new();
+        for i in 0..MAX_VIEW_HISTORY + 5 {
+            let post = service
+                ._create_post(user_id, topic_id, format!("Post {i}"), "Body".to_string())
+                .await
+                .unwrap();
+            service._record_view(user_id, post.id).await.unwrap();
+            posts.push(post.id);
+        }
+
+        let stored = PostViewHistory::find()
+            .filter(PostViewHistoryColumn::UserId.eq(user_id))
+            .count(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(stored, MAX_VIEW_HISTORY);
+
+        let recent = service._recent_views(user_id, 3).await.unwrap();
+        let recent: Vec<PostId> = recent.into_iter().map(|post| post.id).collect();
+        let expected: Vec<PostId> = posts.iter().rev().take(3).copied().collect();
+        assert_eq!(recent, expected);
+
+        // Re-viewing an old post moves it to the front without adding a row
+        let oldest_kept = posts[5];
+        service._record_view(user_id, oldest_kept).await.unwrap();
+        let recent = service._recent_views(user_id, 1).await.unwrap();
+        assert_eq!(recent[0].id, oldest_kept);
+
+        let all = service._recent_views(user_id, 100).await.unwrap();
+        assert_eq!(all.len() as u64, MAX_VIEW_HISTORY);
+        assert!(!all.iter().any(|post| post.id == posts[0]));
+    }
+
+    #[tokio::test]
+    async fn test_buffered_views_flush_on_shutdown() {
+        use crate::service::view_buffer::{ViewBuffer, ViewBufferConfig};
+
+        let service = setup_test_service().await;
+        let config = ViewBufferConfig {
+            flush_interval: std::time::Duration::from_secs(3600),
+            max_pending: 10_000,
+        };
+        let buffer = Arc::new(ViewBuffer::new(service.db.clone(), config));
+        let flusher = buffer.spawn_flusher();
+        let service = service.with_view_buffer(buffer.clone());
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let mut users = Vec::new();
+        let mut posts = Vec::new();
+        for i in 0..4 {
+            let member = create_test_profile(&service, &format!("Member {i}")).await;
+            let user_id = create_test_user(&service, group_id, member).await;
+            let post = service
+                ._create_post(user_id, topic_id, format!("Post {i}"), "Body".to_string())
+                .await
+                .unwrap();
+            users.push(user_id);
+            posts.push(post.id);
+        }
+        for user_id in &users {
+            for post_id in &posts {
+                service._record_view(*user_id, *post_id).await.unwrap();
+            }
+        }
+
+        // Nothing written yet, but reads already see the views
+        assert_eq!(PostViewHistory::find().count(&service.db).await.unwrap(), 0);
+        assert_eq!(buffer.pending_len(), 16);
+        let recent = service._recent_views(users[0], 10).await.unwrap();
+        assert_eq!(recent.len(), 4);
+
+        assert_eq!(buffer.shutdown(flusher).await.unwrap(), 16);
+        assert_eq!(buffer.pending_len(), 0);
+        assert_eq!(PostViewHistory::find().count(&service.db).await.unwrap(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_view_buffer_flushes_when_full() {
+        use crate::service::view_buffer::{ViewBuffer, ViewBufferConfig};
+
+        let service = setup_test_service().await;
+        let config = ViewBufferConfig {
+            flush_interval: std::time::Duration::from_secs(3600),
+            max_pending: 3,
+        };
+        let buffer = Arc::new(ViewBuffer::new(service.db.clone(), config));
+        let service = service.with_view_buffer(buffer.clone());
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        for i in 0..4 {
+            let post = service
+                ._create_post(user_id, topic_id, format!("Post {i}"), "Body".to_string())
+                .await
+                .unwrap();
+            service._record_view(user_id, post.id).await.unwrap();
+        }
+
+        // The third view filled the buffer; the fourth is still pending
+        assert_eq!(PostViewHistory::find().count(&service.db).await.unwrap(), 3);
+        assert_eq!(buffer.pending_len(), 1);
+        assert_eq!(service._recent_views(user_id, 10).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_posting_records_last_location() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let state = ProfileState::find_by_id(profile_id)
+            .one(&service.db)
+            .await
+            .unwrap()
+            .expect("Posting should record a resume location");
+        assert_eq!(state.last_group_id, Some(group_id));
+        assert_eq!(state.last_topic_id, Some(topic_id));
+    }
+
+    #[tokio::test]
+    async fn test_archived_group_blocks_posting() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        async fn set_archived(db: &DatabaseConnection, group_id: GroupId, archived: bool) {
+            let mut group: GroupActiveModel =
+                Group::find_by_id(group_id).one(db).await.unwrap().unwrap().into();
+            group.archived = Set(archived);
+            group.update(db).await.unwrap();
+        }
+
+        set_archived(&service.db, group_id, true).await;
+        let result = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::GroupArchived)));
+        let result = service
+            ._create_reply(post.id, user_id, "Re".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::GroupArchived)));
+
+        // Existing posts stay readable
+        assert!(service._get_post(post.id).await.is_ok());
+
+        set_archived(&service.db, group_id, false).await;
+        service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .expect("Unarchiving should restore posting");
+    }
+
+    #[tokio::test]
+    async fn test_get_post() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let created = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let fetched = service._get_post(created.id).await.unwrap();
+        assert_eq!(created.id, fetched.id);
+        assert_eq!(fetched.title, "Title");
+    }
+
+    #[tokio::test]
+    async fn test_get_post_vs_try_get_post() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let created = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        // Present: both shapes return the post
+        let found = service._try_get_post(created.id).await.unwrap();
+        assert_eq!(found, Some(created.clone()));
+        assert_eq!(service._get_post(created.id).await.unwrap(), created);
+
+        // Missing: Option is None, the plain getter errors
+        let missing = PostId::new();
+        assert_eq!(service._try_get_post(missing).await.unwrap(), None);
+        assert!(matches!(
+            service._get_post(missing).await,
+            Err(PostsServiceError::PostNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_posts_for_topic() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        // Create multiple posts
+        for i in 0..5 {
+            service
+                ._create_post(
+                    user_id,
+                    topic_id,
+                    format!("Post {}", i),
+                    format!("Body {}", i),
+                )
+                .await
+                .unwrap();
+        }
+
+        let posts = service._list_posts_for_topic(topic_id, 10, 0).await.unwrap();
+        assert_eq!(posts.len(), 5);
+
+        // Test pagination
+        let page1 = service._list_posts_for_topic(topic_id, 2, 0).await.unwrap();
+        assert_eq!(page1.len(), 2);
+
+        let page2 = service._list_posts_for_topic(topic_id, 2, 2).await.unwrap();
+        assert_eq!(page2.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_posts_for_topic_since() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let mut known = Vec::new();
+        for i in 0..3 {
+            known.push(
+                service
+                    ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
+                    .await
+                    .unwrap(),
+            );
+        }
+        let last = known.last().unwrap();
+
+        // Nothing new yet
+        let caught_up = service
+            ._list_posts_for_topic_since(topic_id, last.created_at.clone(), last.id, 10)
+            .await
+            .unwrap();
+        assert!(caught_up.is_empty());
+
+        // New posts arrive after the known point
+        let mut fresh = Vec::new();
+        for i in 3..5 {
+            fresh.push(
+                service
+                    ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let appended = service
+            ._list_posts_for_topic_since(topic_id, last.created_at.clone(), last.id, 10)
+            .await
+            .unwrap();
+        let appended_ids: Vec<PostId> = appended.iter().map(|p| p.id).collect();
+        let fresh_ids: Vec<PostId> = fresh.iter().map(|p| p.id).collect();
+        assert_eq!(appended_ids, fresh_ids, "Should return only the new posts, in order");
+
+        // Limit is respected
+        let limited = service
+            ._list_posts_for_topic_since(topic_id, last.created_at.clone(), last.id, 1)
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+        assert_eq!(limited[0].id, fresh[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_list_post_headers_for_topic() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "A long body".to_string())
+            .await
+            .unwrap();
+        let reply = service
+            ._create_reply(post.id, user_id, "Re: Title".to_string(), "Reply body".to_string())
+            .await
+            .unwrap();
+
+        let headers = service
+            ._list_post_headers_for_topic(topic_id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(headers.len(), 2);
+
+        for (header, full) in headers.iter().zip([&post, &reply]) {
+            assert_eq!(header.id, full.id);
+            assert_eq!(header.user_id, full.user_id);
+            assert_eq!(header.topic_id, full.topic_id);
+            assert_eq!(header.parent_post_id, full.parent_post_id);
+            assert_eq!(header.title, full.title);
+            assert_eq!(header.created_at, full.created_at);
+            assert_eq!(header.author_name, "Test User");
+        }
+
+        // The body never makes it onto the wire
+        let json = serde_json::to_value(&headers[0]).unwrap();
+        assert!(json.get("body").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_post_headers_prefer_group_nickname() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Global Name").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let groups = crate::service::groups::GroupsService::new(service.db.clone());
+        groups
+            ._set_nickname(group_id, profile_id, profile_id, Some("Group Nick".to_string()))
+            .await
+            .unwrap();
+
+        let headers = service._list_post_headers_for_topic(topic_id, 10, 0).await.unwrap();
+        assert_eq!(headers[0].author_name, "Group Nick");
+
+        // The nickname stays inside the group
+        let profile = Profile::find_by_id(profile_id).one(&service.db).await.unwrap().unwrap();
+        assert_eq!(profile.name, "Global Name");
+    }
+
+    #[tokio::test]
+    async fn test_list_posts_by_user() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        
+        // Create two topics
+        let topic1 = create_test_topic(&service, group_id, profile_id).await;
+        let topic2 = create_test_topic(&service, group_id, profile_id).await;
+
+        // Create posts in different topics by same user
+        service
+            ._create_post(user_id, topic1, "Post 1".to_string(), "Body 1".to_string())
+            .await
+            .unwrap();
+        service
+            ._create_post(user_id, topic2, "Post 2".to_string(), "Body 2".to_string())
+            .await
+            .unwrap();
+
+        let posts = service._list_posts_by_user(user_id, 10, 0).await.unwrap();
+        assert_eq!(posts.len(), 2);
+    }
+
+    /// A group with two members posting a minute apart: Alice's "Rust tips" and
+    /// "100% useful", then Bob's "Rust news" and Alice's deleted "Rust secrets"
+    async fn setup_search_fixture() -> (PostsService, GroupId, ProfileId, Vec<GroupPostModel>) {
+        let start = chrono::Utc::now();
+        let clock = MockClock::new(start);
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let alice = create_test_profile(&service, "Alice").await;
+        let bob = create_test_profile(&service, "Bob").await;
+        let group_id = create_test_group(&service, alice).await;
+        let alice_user = create_test_user(&service, group_id, alice).await;
+        let bob_user = create_test_user(&service, group_id, bob).await;
+        let topic_id = create_test_topic(&service, group_id, alice).await;
+
+        let mut posts = Vec::new();
+        for (user_id, title, body) in [
+            (alice_user, "Rust tips", "Use clippy"),
+            (alice_user, "Cooking", "100% useful"),
+            (bob_user, "News", "rust 2.0 is out"),
+            (alice_user, "Rust secrets", "Gone soon"),
+        ] {
+            let post = service
+                ._create_post(user_id, topic_id, title.to_string(), body.to_string())
+                .await
+                .unwrap();
+            posts.push(post);
+            clock.advance(chrono::Duration::minutes(1));
+        }
+        service._soft_delete_post(posts[3].id, alice_user).await.unwrap();
+
+        // Another group's posts never show up
+        let other_group = create_test_group(&service, bob).await;
+        let other_user = create_test_user(&service, other_group, bob).await;
+        let other_topic = create_test_topic(&service, other_group, bob).await;
+        service
+            ._create_post(other_user, other_topic, "Rust".to_string(), "Elsewhere".to_string())
+            .await
+            .unwrap();
+
+        (service, group_id, alice, posts)
+    }
+
+    fn post_ids(posts: &[GroupPostModel]) -> Vec<PostId> {
+        posts.iter().map(|post| post.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_posts_advanced_by_query() {
+        let (service, group_id, _, posts) = setup_search_fixture().await;
+
+        // Matches title or body case-insensitively, newest first, skipping deleted posts
+        let found = service
+            ._search_posts_advanced(group_id, Some("RUST".to_string()), None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[2].id, posts[0].id]);
+
+        // LIKE wildcards in the query are literal
+        let found = service
+            ._search_posts_advanced(group_id, Some("100%".to_string()), None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[1].id]);
+        let found = service
+            ._search_posts_advanced(group_id, Some("%".to_string()), None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[1].id]);
+
+        // No filters at all is every live post in the group, paged
+        let found =
+            service._search_posts_advanced(group_id, None, None, None, 10, 0).await.unwrap();
+        assert_eq!(post_ids(&found), vec![posts[2].id, posts[1].id, posts[0].id]);
+        let blank = service
+            ._search_posts_advanced(group_id, Some("  ".to_string()), None, None, 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&blank), vec![posts[1].id, posts[0].id]);
+    }
+
+    #[tokio::test]
+    async fn test_search_posts_advanced_by_author() {
+        let (service, group_id, alice, posts) = setup_search_fixture().await;
+
+        let found = service
+            ._search_posts_advanced(group_id, None, Some(alice), None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[1].id, posts[0].id]);
+
+        let stranger = create_test_profile(&service, "Stranger").await;
+        let found = service
+            ._search_posts_advanced(group_id, None, Some(stranger), None, 10, 0)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_posts_advanced_combined() {
+        let (service, group_id, alice, posts) = setup_search_fixture().await;
+
+        let query = Some("rust".to_string());
+        let found = service
+            ._search_posts_advanced(group_id, query, Some(alice), None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[0].id]);
+
+        let query = Some("useful".to_string());
+        let since = Some(posts[2].created_at.clone());
+        let found = service
+            ._search_posts_advanced(group_id, query, Some(alice), since, 10, 0)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_posts_advanced_since() {
+        let (service, group_id, _, posts) = setup_search_fixture().await;
+
+        // The bound is inclusive
+        let since = Some(posts[1].created_at.clone());
+        let found = service
+            ._search_posts_advanced(group_id, None, None, since, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[2].id, posts[1].id]);
+
+        let since = Some(posts[1].created_at.clone());
+        let found = service
+            ._search_posts_advanced(group_id, Some("rust".to_string()), None, since, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(post_ids(&found), vec![posts[2].id]);
+
+        // Other spellings of a bound compare by time, not as text
+        let second = chrono::DateTime::parse_from_rfc3339(&posts[1].created_at).unwrap();
+        let whole_second = second.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        assert!(whole_second.ends_with('Z'));
+        let east = chrono::FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let west = chrono::FixedOffset::west_opt(7 * 3600).unwrap();
+        for since in [
+            whole_second,
+            second.with_timezone(&east).to_rfc3339(),
+            second.with_timezone(&west).to_rfc3339(),
+        ] {
+            let found = service
+                ._search_posts_advanced(group_id, None, None, Some(since.clone()), 10, 0)
+                .await
+                .unwrap();
+            assert_eq!(post_ids(&found), vec![posts[2].id, posts[1].id], "{since}");
+        }
+
+        for since in ["yesterday", "2024-13-01T00:00:00Z"] {
+            let result = service
+                ._search_posts_advanced(group_id, None, None, Some(since.to_string()), 10, 0)
+                .await;
+            assert!(
+                matches!(&result, Err(PostsServiceError::Invalid(error)) if error.field == "since"),
+                "{since}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_post_by_author() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "To Delete".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        service
+            ._delete_post(post.id, user_id)
+            .await
+            .expect("Author should be able to delete");
+
+        let result = service._get_post(post.id).await;
+        assert!(result.is_err(), "Post should be deleted");
+    }
+
+    #[tokio::test]
+    async fn test_delete_post_by_non_author_fails() {
+        let service = setup_test_service().await;
+        
+        let profile1 = create_test_profile(&service, "Author").await;
+        let profile2 = create_test_profile(&service, "Other User").await;
+        let group_id = create_test_group(&service, profile1).await;
+        let user1 = create_test_user(&service, group_id, profile1).await;
+        let user2 = create_test_user(&service, group_id, profile2).await;
+        let topic_id = create_test_topic(&service, group_id, profile1).await;
+
+        let post = service
+            ._create_post(user1, topic_id, "Post".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let result = service._delete_post(post.id, user2).await;
+        assert!(result.is_err(), "Non-author should not be able to delete");
+    }
+
+    #[tokio::test]
+    async fn test_update_post() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "Original".to_string(), "Original Body".to_string())
+            .await
+            .unwrap();
+
+        let updated = service
+            ._update_post(
+                post.id,
+                user_id,
+                Some("Updated Title".to_string()),
+                Some("Updated Body".to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.title, "Updated Title");
+        assert_eq!(updated.body, "Updated Body");
+        assert_eq!((post.version, post.edited_at), (1, None));
+        assert_eq!(updated.version, 2);
+        assert!(updated.edited_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_post_by_non_author_fails() {
+        let service = setup_test_service().await;
+        
+        let profile1 = create_test_profile(&service, "Author").await;
+        let profile2 = create_test_profile(&service, "Other").await;
+        let group_id = create_test_group(&service, profile1).await;
+        let user1 = create_test_user(&service, group_id, profile1).await;
+        let user2 = create_test_user(&service, group_id, profile2).await;
+        let topic_id = create_test_topic(&service, group_id, profile1).await;
+
+        let post = service
+            ._create_post(user1, topic_id, "Post".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let result = service
+            ._update_post(post.id, user2, Some("Hacked".to_string()), None)
+            .await;
+
+        assert!(result.is_err(), "Non-author should not be able to update");
+    }
+
+    #[tokio::test]
+    async fn test_count_posts_in_topic() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        for i in 0..7 {
+            service
+                ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
+                .await
+                .unwrap();
+        }
+
+        let count = service._count_posts_in_topic(topic_id).await.unwrap();
+        assert_eq!(count, 7);
+    }
+
+    #[tokio::test]
+    async fn test_count_posts_by_user() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        for i in 0..3 {
+            service
+                ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
+                .await
+                .unwrap();
+        }
+
+        let count = service._count_posts_by_user(user_id).await.unwrap();
+        assert_eq!(count, 3);
+    }
+
+    // ===== REPLY TESTS =====
+
+    #[tokio::test]
+    async fn test_create_reply() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        // Create parent post
+        let parent = service
+            ._create_post(user_id, topic_id, "Parent Post".to_string(), "Parent body".to_string())
+            .await
+            .unwrap();
+
+        // Create reply
+        let reply = service
+            ._create_reply(parent.id, user_id, "Reply".to_string(), "Reply body".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(reply.parent_post_id, Some(parent.id));
+        assert_eq!(reply.topic_id, parent.topic_id);
+        assert_eq!(reply.title, "Reply");
+    }
+
+    #[tokio::test]
+    async fn test_create_reply_rejects_missing_or_deleted_parent() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let result = service
+            ._create_reply(PostId::new(), user_id, "Re".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::ParentNotFound)));
+
+        let parent = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        service._soft_delete_post(parent.id, user_id).await.unwrap();
+
+        let result = service
+            ._create_reply(parent.id, user_id, "Re".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::ParentNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_parent_rejects_other_topic() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let other_topic = create_test_topic(&service, group_id, profile_id).await;
+
+        let parent = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let result = service.resolve_parent(parent.id, Some(other_topic)).await;
+        assert!(matches!(result, Err(PostsServiceError::ParentInDifferentTopic)));
+
+        let resolved = service.resolve_parent(parent.id, Some(topic_id)).await.unwrap();
+        assert_eq!(resolved.id, parent.id);
+    }
+
+    #[tokio::test]
+    async fn test_create_reply_rejects_user_from_other_group() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let other_group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let outsider_id = create_test_user(&service, other_group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        let result = service
+            ._create_reply(post.id, outsider_id, "Re".to_string(), "Reply".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::NotAMember)));
+    }
+
+    #[tokio::test]
+    async fn test_reply_notifies_parent_author() {
+        let service = setup_test_service().await;
+
+        let author_profile = create_test_profile(&service, "author").await;
+        let replier_profile = create_test_profile(&service, "replier").await;
+        let group_id = create_test_group(&service, author_profile).await;
+        let author = create_test_user(&service, group_id, author_profile).await;
+        let replier = create_test_user(&service, group_id, replier_profile).await;
+        let topic_id = create_test_topic(&service, group_id, author_profile).await;
+
+        let post = service
+            ._create_post(author, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let reply = service
+            ._create_reply(post.id, replier, "Re".to_string(), "Nice".to_string())
+            .await
+            .unwrap();
+
+        let notifications = Notification::find().all(&service.db).await.unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].recipient_profile_id, author_profile);
+        assert_eq!(notifications[0].kind, NotificationKind::Reply);
+        assert_eq!(notifications[0].source_post_id, reply.id);
+        assert!(!notifications[0].read);
+
+        // Replying to yourself doesn't notify
+        service
+            ._create_reply(post.id, author, "Re".to_string(), "Self".to_string())
+            .await
+            .unwrap();
+        let count = Notification::find().count(&service.db).await.unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mention_notifies_group_member() {
+        let service = setup_test_service().await;
 
-        Migrator::up(&db, None)
+        let author_profile = create_test_profile(&service, "author").await;
+        let alice_profile = create_test_profile(&service, "alice").await;
+        // Exists, but isn't in the group
+        create_test_profile(&service, "outsider").await;
+        let group_id = create_test_group(&service, author_profile).await;
+        let author = create_test_user(&service, group_id, author_profile).await;
+        create_test_user(&service, group_id, alice_profile).await;
+        let topic_id = create_test_topic(&service, group_id, author_profile).await;
+
+        let post = service
+            ._create_post(
+                author,
+                topic_id,
+                "Title".to_string(),
+                "Hey @alice, and @outsider and @nobody!".to_string(),
+            )
             .await
-            .expect("Failed to run migrations");
+            .unwrap();
 
-        PostsService::new(db)
+        let notifications = Notification::find().all(&service.db).await.unwrap();
+        assert_eq!(notifications.len(), 1, "Only group members are notified");
+        assert_eq!(notifications[0].recipient_profile_id, alice_profile);
+        assert_eq!(notifications[0].kind, NotificationKind::Mention);
+        assert_eq!(notifications[0].source_post_id, post.id);
     }
 
-    async fn create_test_profile(service: &PostsService, name: &str) -> ProfileId {
-        let profile_id = ProfileId::new();
-        let profile = ProfileActiveModel {
-            id: Set(profile_id),
-            name: Set(name.to_string()),
-            desc: Set("Test".to_string()),
-            picture: Set(None),
-        };
-        Profile::insert(profile).exec(&service.db).await.unwrap();
-        profile_id
-    }
+    #[tokio::test]
+    async fn test_banned_member_gets_no_notifications() {
+        let service = setup_test_service().await;
 
-    async fn create_test_group(service: &PostsService, profile_id: ProfileId) -> GroupId {
-        let group_id = GroupId::new();
-        let group = GroupActiveModel {
-            id: Set(group_id),
-            profile_id: Set(profile_id),
-        };
-        Group::insert(group).exec(&service.db).await.unwrap();
-        group_id
-    }
+        let author_profile = create_test_profile(&service, "author").await;
+        let banned_profile = create_test_profile(&service, "banned").await;
+        let group_id = create_test_group(&service, author_profile).await;
+        let author = create_test_user(&service, group_id, author_profile).await;
+        let banned = create_test_user(&service, group_id, banned_profile).await;
+        let topic_id = create_test_topic(&service, group_id, author_profile).await;
+        let post = service
+            ._create_post(banned, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
 
-    async fn create_test_user(service: &PostsService, group_id: GroupId, profile_id: ProfileId) -> UserId {
-        let user_id = UserId::new();
-        let user = GroupUserActiveModel {
-            id: Set(user_id),
+        GroupBanned::insert(GroupBannedActiveModel {
             group_id: Set(group_id),
-            profile_id: Set(profile_id),
-        };
-        GroupUser::insert(user).exec(&service.db).await.unwrap();
-        user_id
+            identity_id: Set(banned_profile),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        // Neither a reply to their old post nor an @mention reaches them
+        service
+            ._create_reply(post.id, author, "Re".to_string(), "Hey @banned".to_string())
+            .await
+            .unwrap();
+        service
+            ._create_post(author, topic_id, "Title".to_string(), "@banned?".to_string())
+            .await
+            .unwrap();
+        assert_eq!(Notification::find().count(&service.db).await.unwrap(), 0);
     }
 
-    async fn create_test_topic(service: &PostsService, group_id: GroupId, profile_id: ProfileId) -> TopicId {
-        let topic_id = TopicId::new();
-        let topic = GroupTopicActiveModel {
-            id: Set(topic_id),
-            group_id: Set(group_id),
-            profile_id: Set(profile_id),
-            created_at: Set(chrono::Utc::now().to_rfc3339()),
-        };
-        GroupTopic::insert(topic).exec(&service.db).await.unwrap();
-        topic_id
+    #[test]
+    fn test_mentioned_names() {
+        assert_eq!(
+            mentioned_names("hi @bob, @alice! email@example.com @bob @ @snake_case."),
+            vec!["alice", "bob", "snake_case"]
+        );
     }
 
     #[tokio::test]
-    async fn test_create_post() {
+    async fn test_nested_reply() {
         let service = setup_test_service().await;
         
         let profile_id = create_test_profile(&service, "Test User").await;
@@ -608,43 +3858,73 @@ mod tests {
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        let post = service
-            ._create_post(
-                user_id,
-                topic_id,
-                "Test Post".to_string(),
-                "This is a test post body".to_string(),
-            )
-            .await
-            .expect("Failed to create post");
+        // Create parent post
+        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
 
-        assert_eq!(post.user_id, user_id);
-        assert_eq!(post.topic_id, topic_id);
-        assert_eq!(post.title, "Test Post");
-        assert_eq!(post.body, "This is a test post body");
+        // Create first-level reply
+        let reply1 = service._create_reply(parent.id, user_id, "Reply 1".to_string(), "Body".to_string()).await.unwrap();
+
+        // Create nested reply (reply to reply)
+        let reply2 = service._create_reply(reply1.id, user_id, "Reply 2".to_string(), "Body".to_string()).await.unwrap();
+
+        assert_eq!(reply2.parent_post_id, Some(reply1.id));
+        assert_eq!(reply2.topic_id, parent.topic_id);
     }
 
     #[tokio::test]
-    async fn test_get_post() {
+    async fn test_thread_context() {
         let service = setup_test_service().await;
-        
+
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        let created = service
-            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+        // root
+        // |- a    (a has siblings b, c)
+        // |  |- a1  (a1 has sibling a2)
+        // |  |- a2
+        // |- b
+        // |- c
+        let reply = |parent: PostId, title: &str| {
+            service._create_reply(parent, user_id, title.to_string(), "Body".to_string())
+        };
+        let root = service
+            ._create_post(user_id, topic_id, "Root".to_string(), "Body".to_string())
             .await
             .unwrap();
+        let other_root = service
+            ._create_post(user_id, topic_id, "Other root".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let a = reply(root.id, "a").await.unwrap();
+        let b = reply(root.id, "b").await.unwrap();
+        let c = reply(root.id, "c").await.unwrap();
+        let a1 = reply(a.id, "a1").await.unwrap();
+        let a2 = reply(a.id, "a2").await.unwrap();
 
-        let fetched = service._get_post(created.id).await.unwrap();
-        assert_eq!(created.id, fetched.id);
-        assert_eq!(fetched.title, "Title");
+        let context = service._thread_context(a1.id, 10).await.unwrap();
+
+        let chain: Vec<PostId> = context.levels.iter().map(|l| l.post.id).collect();
+        assert_eq!(chain, vec![root.id, a.id, a1.id], "Chain runs root to target");
+
+        let sibling_ids = |level: &ThreadLevel| -> Vec<PostId> {
+            level.siblings.iter().map(|p| p.id).collect()
+        };
+        assert_eq!(sibling_ids(&context.levels[0]), vec![other_root.id]);
+        assert_eq!(sibling_ids(&context.levels[1]), vec![b.id, c.id]);
+        assert_eq!(sibling_ids(&context.levels[2]), vec![a2.id]);
+
+        // Sibling lists are bounded
+        let bounded = service._thread_context(a1.id, 1).await.unwrap();
+        assert_eq!(sibling_ids(&bounded.levels[1]), vec![b.id]);
+
+        let missing = service._thread_context(PostId::new(), 10).await;
+        assert!(matches!(missing, Err(PostsServiceError::PostNotFound)));
     }
 
     #[tokio::test]
-    async fn test_list_posts_for_topic() {
+    async fn test_list_replies() {
         let service = setup_test_service().await;
         
         let profile_id = create_test_profile(&service, "Test User").await;
@@ -652,333 +3932,929 @@ mod tests {
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        // Create multiple posts
+        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+
+        // Create multiple replies
         for i in 0..5 {
             service
-                ._create_post(
-                    user_id,
-                    topic_id,
-                    format!("Post {}", i),
-                    format!("Body {}", i),
-                )
+                ._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string())
                 .await
                 .unwrap();
         }
 
-        let posts = service._list_posts_for_topic(topic_id, 10, 0).await.unwrap();
-        assert_eq!(posts.len(), 5);
+        let replies = service._list_replies(parent.id, 10, 0).await.unwrap();
+        assert_eq!(replies.len(), 5);
+    }
 
-        // Test pagination
-        let page1 = service._list_posts_for_topic(topic_id, 2, 0).await.unwrap();
-        assert_eq!(page1.len(), 2);
+    #[tokio::test]
+    async fn test_list_replies_with_authors() {
+        let service = setup_test_service().await;
+        let alice = create_test_profile(&service, "Alice").await;
+        let bob = create_test_profile(&service, "Bob").await;
+        let group_id = create_test_group(&service, alice).await;
+        let alice_user = create_test_user(&service, group_id, alice).await;
+        let bob_user = create_test_user(&service, group_id, bob).await;
+        let topic_id = create_test_topic(&service, group_id, alice).await;
 
-        let page2 = service._list_posts_for_topic(topic_id, 2, 2).await.unwrap();
-        assert_eq!(page2.len(), 2);
+        let parent = service
+            ._create_post(alice_user, topic_id, "Parent".into(), "Body".into())
+            .await
+            .unwrap();
+        for (user_id, title) in [(alice_user, "From Alice"), (bob_user, "From Bob")] {
+            service._create_reply(parent.id, user_id, title.into(), "Body".into()).await.unwrap();
+        }
+
+        let replies = service._list_replies_with_authors(parent.id, 10, 0).await.unwrap();
+        let summary: Vec<(&str, Option<&str>)> = replies
+            .iter()
+            .map(|(reply, author)| (reply.title.as_str(), author.as_ref().map(|p| p.name.as_str())))
+            .collect();
+        assert_eq!(summary, [("From Alice", Some("Alice")), ("From Bob", Some("Bob"))]);
+        assert_eq!(replies[1].1.as_ref().unwrap().id, bob);
+
+        // A profile removed behind the foreign keys' back leaves its replies authorless
+        service.db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        Profile::delete_by_id(bob).exec(&service.db).await.unwrap();
+        service.db.execute_unprepared("PRAGMA foreign_keys = ON").await.unwrap();
+        let replies = service._list_replies_with_authors(parent.id, 10, 0).await.unwrap();
+        assert_eq!(replies[0].1.as_ref().unwrap().id, alice);
+        assert!(replies[1].1.is_none());
+
+        assert!(service._list_replies_with_authors(PostId::new(), 10, 0).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_list_posts_by_user() {
+    async fn test_count_replies() {
         let service = setup_test_service().await;
         
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+
+        for i in 0..7 {
+            service._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string()).await.unwrap();
+        }
+
+        let count = service._count_replies(parent.id).await.unwrap();
+        assert_eq!(count, 7);
+    }
+
+    #[tokio::test]
+    async fn test_list_top_level_posts() {
+        let service = setup_test_service().await;
         
-        // Create two topics
-        let topic1 = create_test_topic(&service, group_id, profile_id).await;
-        let topic2 = create_test_topic(&service, group_id, profile_id).await;
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        // Create posts in different topics by same user
-        service
-            ._create_post(user_id, topic1, "Post 1".to_string(), "Body 1".to_string())
+        // Create top-level posts
+        let post1 = service._create_post(user_id, topic_id, "Post 1".to_string(), "Body".to_string()).await.unwrap();
+        let post2 = service._create_post(user_id, topic_id, "Post 2".to_string(), "Body".to_string()).await.unwrap();
+
+        // Create replies (should be excluded)
+        service._create_reply(post1.id, user_id, "Reply to 1".to_string(), "Body".to_string()).await.unwrap();
+        service._create_reply(post2.id, user_id, "Reply to 2".to_string(), "Body".to_string()).await.unwrap();
+
+        // List only top-level
+        let top_level = service._list_top_level_posts(topic_id, 10, 0).await.unwrap();
+        assert_eq!(top_level.len(), 2, "Should only return top-level posts");
+        assert!(top_level.iter().all(|p| p.parent_post_id.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_delete_post_cascades_to_replies() {
+        let service = setup_test_service().await;
+        
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+
+        // Create replies
+        for i in 0..3 {
+            service._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string()).await.unwrap();
+        }
+
+        // Verify replies exist
+        let replies_before = service._list_replies(parent.id, 10, 0).await.unwrap();
+        assert_eq!(replies_before.len(), 3);
+
+        // Delete parent
+        service._delete_post(parent.id, user_id).await.unwrap();
+
+        // Verify parent is gone
+        let parent_result = service._get_post(parent.id).await;
+        assert!(parent_result.is_err());
+
+        // Note: SQLite doesn't enforce FK cascade via ALTER TABLE on existing tables
+        // In production with proper migration, replies would be cascade deleted
+    }
+
+    #[tokio::test]
+    async fn test_post_text_normalized_when_enabled() {
+        use crate::{config::RuntimeConfig, text::Normalization};
+
+        let runtime = SharedRuntimeConfig::new(std::sync::RwLock::new(RuntimeConfig {
+            post_text: Normalization {
+                trim_ends: true,
+                collapse_blank_lines: true,
+            },
+            ..RuntimeConfig::default()
+        }));
+        let service = setup_test_service().await.with_runtime_config(runtime);
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let post = service
+            ._create_post(user_id, topic_id, "  Title \n".into(), "\nOne\n\n\n\nTwo\n\n".into())
             .await
             .unwrap();
-        service
-            ._create_post(user_id, topic2, "Post 2".to_string(), "Body 2".to_string())
+        assert_eq!((post.title.as_str(), post.body.as_str()), ("Title", "One\n\nTwo"));
+
+        let reply = service
+            ._create_reply(post.id, user_id, " Re ".into(), "A\n \n\t\nB\n".into())
             .await
             .unwrap();
+        assert_eq!((reply.title.as_str(), reply.body.as_str()), ("Re", "A\n \nB"));
 
-        let posts = service._list_posts_by_user(user_id, 10, 0).await.unwrap();
-        assert_eq!(posts.len(), 2);
+        let edited = service
+            ._update_post(post.id, user_id, Some("\tNew\t".into()), Some("\n\nX\n".into()))
+            .await
+            .unwrap();
+        assert_eq!((edited.title.as_str(), edited.body.as_str()), ("New", "X"));
+        assert_eq!(service._get_post(post.id).await.unwrap().body, "X");
     }
 
     #[tokio::test]
-    async fn test_delete_post_by_author() {
+    async fn test_post_text_stored_verbatim_by_default() {
         let service = setup_test_service().await;
-        
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
+        let body = "\nOne\n\n\n\nTwo\n\n";
         let post = service
-            ._create_post(user_id, topic_id, "To Delete".to_string(), "Body".to_string())
+            ._create_post(user_id, topic_id, "  Title \n".into(), body.into())
             .await
             .unwrap();
+        assert_eq!((post.title.as_str(), post.body.as_str()), ("  Title \n", body));
 
-        service
-            ._delete_post(post.id, user_id)
+        let reply = service
+            ._create_reply(post.id, user_id, " Re ".into(), "A\n\n\nB\n".into())
             .await
-            .expect("Author should be able to delete");
+            .unwrap();
+        assert_eq!(reply.body, "A\n\n\nB\n");
 
-        let result = service._get_post(post.id).await;
-        assert!(result.is_err(), "Post should be deleted");
+        let edited = service
+            ._update_post(post.id, user_id, None, Some("\n\nX\n".into()))
+            .await
+            .unwrap();
+        assert_eq!(edited.body, "\n\nX\n");
     }
 
     #[tokio::test]
-    async fn test_delete_post_by_non_author_fails() {
-        let service = setup_test_service().await;
-        
-        let profile1 = create_test_profile(&service, "Author").await;
-        let profile2 = create_test_profile(&service, "Other User").await;
-        let group_id = create_test_group(&service, profile1).await;
-        let user1 = create_test_user(&service, group_id, profile1).await;
-        let user2 = create_test_user(&service, group_id, profile2).await;
-        let topic_id = create_test_topic(&service, group_id, profile1).await;
+    async fn test_list_methods_clamp_extreme_limits() {
+        use crate::config::RuntimeConfig;
+
+        let runtime = SharedRuntimeConfig::new(std::sync::RwLock::new(RuntimeConfig {
+            max_page_size: 3,
+            ..RuntimeConfig::default()
+        }));
+        let service = setup_test_service().await.with_runtime_config(runtime);
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+
+        let parent = service
+            ._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        for i in 0..5 {
+            service
+                ._create_post(user_id, topic_id, format!("Post {i}"), "Body".to_string())
+                .await
+                .unwrap();
+            service
+                ._create_reply(parent.id, user_id, format!("Reply {i}"), "Body".to_string())
+                .await
+                .unwrap();
+        }
 
-        let post = service
-            ._create_post(user1, topic_id, "Post".to_string(), "Body".to_string())
+        let max = u64::MAX;
+        assert_eq!(service._list_posts_for_topic(topic_id, max, 0).await.unwrap().len(), 3);
+        assert_eq!(service._list_post_headers_for_topic(topic_id, max, 0).await.unwrap().len(), 3);
+        assert_eq!(service._list_posts_by_user(user_id, max, 0).await.unwrap().len(), 3);
+        assert_eq!(service._list_replies(parent.id, max, 0).await.unwrap().len(), 3);
+        assert_eq!(service._list_top_level_posts(topic_id, max, 0).await.unwrap().len(), 3);
+        let since = service
+            ._list_posts_for_topic_since(topic_id, String::new(), PostId::new(), max)
             .await
             .unwrap();
+        assert_eq!(since.len(), 3);
 
-        let result = service._delete_post(post.id, user2).await;
-        assert!(result.is_err(), "Non-author should not be able to delete");
+        let reply = service._list_replies(parent.id, 1, 0).await.unwrap().remove(0);
+        let context = service._thread_context(reply.id, max).await.unwrap();
+        assert_eq!(context.levels[1].siblings.len(), 3);
+
+        // Offsets past anything sqlite can count just come back empty
+        assert!(service._list_posts_for_topic(topic_id, max, max).await.unwrap().is_empty());
+        assert!(service._list_replies(parent.id, max, max).await.unwrap().is_empty());
+        assert!(service._list_posts_by_user(user_id, max, max).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_update_post() {
+    async fn test_purge_deleted_before() {
         let service = setup_test_service().await;
-        
+
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        let post = service
-            ._create_post(user_id, topic_id, "Original".to_string(), "Original Body".to_string())
+        let old = service
+            ._create_post(user_id, topic_id, "Old".to_string(), "Body".to_string())
             .await
             .unwrap();
-
-        let updated = service
-            ._update_post(
-                post.id,
-                user_id,
-                Some("Updated Title".to_string()),
-                Some("Updated Body".to_string()),
-            )
+        let old_reply = service
+            ._create_reply(old.id, user_id, "Re".to_string(), "Body".to_string())
             .await
             .unwrap();
-
-        assert_eq!(updated.title, "Updated Title");
-        assert_eq!(updated.body, "Updated Body");
-    }
-
-    #[tokio::test]
-    async fn test_update_post_by_non_author_fails() {
-        let service = setup_test_service().await;
-        
-        let profile1 = create_test_profile(&service, "Author").await;
-        let profile2 = create_test_profile(&service, "Other").await;
-        let group_id = create_test_group(&service, profile1).await;
-        let user1 = create_test_user(&service, group_id, profile1).await;
-        let user2 = create_test_user(&service, group_id, profile2).await;
-        let topic_id = create_test_topic(&service, group_id, profile1).await;
-
-        let post = service
-            ._create_post(user1, topic_id, "Post".to_string(), "Body".to_string())
+        let recent = service
+            ._create_post(user_id, topic_id, "Recent".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let live = service
+            ._create_post(user_id, topic_id, "Live".to_string(), "Body".to_string())
             .await
             .unwrap();
 
-        let result = service
-            ._update_post(post.id, user2, Some("Hacked".to_string()), None)
-            .await;
-
-        assert!(result.is_err(), "Non-author should not be able to update");
+        // Backdate the old post's deletion past the window
+        let old_row = GroupPost::find_by_id(old.id).one(&service.db).await.unwrap().unwrap();
+        let mut expired: GroupPostActiveModel = old_row.into();
+        expired.deleted_at = Set(Some(
+            (chrono::Utc::now() - chrono::Duration::days(40)).to_rfc3339(),
+        ));
+        expired.update(&service.db).await.unwrap();
+
+        service._soft_delete_post(recent.id, user_id).await.unwrap();
+
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        let purged = service._purge_deleted_before(cutoff, 1).await.unwrap();
+        assert_eq!(purged, 1);
+        let logged = ChangeLog::find()
+            .filter(ChangeLogColumn::EntityId.eq(uuid::Uuid::from(old.id)))
+            .filter(ChangeLogColumn::Op.eq(ChangeOp::Delete))
+            .count(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(logged, 1, "The purge is logged; the backdated soft delete wasn't");
+
+        assert_eq!(service._try_get_post(old.id).await.unwrap(), None);
+        assert_eq!(
+            service._try_get_post(old_reply.id).await.unwrap(),
+            None,
+            "Replies should cascade with their purged parent"
+        );
+
+        let kept = GroupPost::find_by_id(recent.id).one(&service.db).await.unwrap().unwrap();
+        assert!(kept.deleted_at.is_some(), "Recently deleted post stays soft-deleted");
+        assert!(service._get_post(live.id).await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_count_posts_in_topic() {
+    async fn test_audit_and_repair_reply_integrity() {
         let service = setup_test_service().await;
-        
+
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let other_topic = create_test_topic(&service, group_id, profile_id).await;
 
-        for i in 0..7 {
-            service
-                ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
+        let parent = service
+            ._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let good_reply = service
+            ._create_reply(parent.id, user_id, "Re".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let elsewhere = service
+            ._create_post(user_id, other_topic, "Elsewhere".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let cross_topic = service
+            ._create_reply(parent.id, user_id, "Re".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let dangling = service
+            ._create_reply(parent.id, user_id, "Re".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        // Corrupt two replies behind the service's back
+        let mut moved: GroupPostActiveModel = cross_topic.clone().into();
+        moved.parent_post_id = Set(Some(elsewhere.id));
+        moved.update(&service.db).await.unwrap();
+        service.db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        let mut orphaned: GroupPostActiveModel = dangling.clone().into();
+        orphaned.parent_post_id = Set(Some(PostId::new()));
+        orphaned.update(&service.db).await.unwrap();
+        service.db.execute_unprepared("PRAGMA foreign_keys = ON").await.unwrap();
+
+        let found = service._audit_reply_integrity(topic_id).await.unwrap();
+        let ids: Vec<PostId> = found.iter().map(|post| post.id).collect();
+        assert_eq!(ids, vec![cross_topic.id, dangling.id]);
+        assert!(service._audit_reply_integrity(other_topic).await.unwrap().is_empty());
+
+        let repaired = service._repair_reply_integrity(topic_id).await.unwrap();
+        assert_eq!(repaired.len(), 2);
+        assert!(repaired.iter().all(|post| post.parent_post_id.is_none()));
+        for post in &repaired {
+            let change = ChangeLog::find()
+                .filter(ChangeLogColumn::EntityId.eq(uuid::Uuid::from(post.id)))
+                .order_by_desc(ChangeLogColumn::Seq)
+                .one(&service.db)
                 .await
+                .unwrap()
                 .unwrap();
+            assert_eq!(change.op, ChangeOp::Update);
+            assert_eq!(serde_json::from_str::<GroupPostModel>(&change.payload).unwrap(), *post);
         }
+        assert!(service._audit_reply_integrity(topic_id).await.unwrap().is_empty());
 
-        let count = service._count_posts_in_topic(topic_id).await.unwrap();
-        assert_eq!(count, 7);
+        let untouched = service._get_post(good_reply.id).await.unwrap();
+        assert_eq!(untouched.parent_post_id, Some(parent.id));
+
+        assert!(matches!(
+            service._audit_reply_integrity(TopicId::new()).await,
+            Err(PostsServiceError::TopicNotFound)
+        ));
     }
 
     #[tokio::test]
-    async fn test_count_posts_by_user() {
+    async fn test_promote_reply_keeps_subtree() {
         let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
+        let admin = create_test_profile(&service, "Admin").await;
+        let member = create_test_profile(&service, "Member").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, member).await;
+        let topic_id = create_test_topic(&service, group_id, admin).await;
+        let other_topic_id = create_test_topic(&service, group_id, admin).await;
+
+        let post = |title: &str| (title.to_string(), "Body".to_string());
+        let (title, body) = post("Root");
+        let root = service._create_post(user_id, topic_id, title, body).await.unwrap();
+        let (title, body) = post("Reply");
+        let reply = service._create_reply(root.id, user_id, title, body).await.unwrap();
+        let (title, body) = post("Child");
+        let child = service._create_reply(reply.id, user_id, title, body).await.unwrap();
+        let (title, body) = post("Grandchild");
+        let grandchild = service._create_reply(child.id, user_id, title, body).await.unwrap();
+        let (title, body) = post("Sibling");
+        let sibling = service._create_reply(root.id, user_id, title, body).await.unwrap();
+        let (title, body) = post("Elsewhere");
+        service._create_post(user_id, other_topic_id, title, body).await.unwrap();
+
+        // Only group admins may promote
+        let result = service._promote_reply(reply.id, member, None).await;
+        assert!(matches!(result, Err(PostsServiceError::Unauthorized)));
+        let result = service._promote_reply(root.id, admin, None).await;
+        assert!(matches!(result, Err(PostsServiceError::NotAReply)));
+
+        // In place: becomes top-level, subtree untouched
+        let promoted = service._promote_reply(reply.id, admin, None).await.unwrap();
+        assert_eq!(promoted.parent_post_id, None);
+        assert_eq!(promoted.topic_id, topic_id);
+        assert_eq!(service._get_post(child.id).await.unwrap(), child);
+
+        let top_level = service._list_top_level_posts(topic_id, 10, 0).await.unwrap();
+        let top_level: Vec<PostId> = top_level.iter().map(|post| post.id).collect();
+        assert_eq!(top_level, vec![root.id, reply.id]);
+
+        // Moving a reply takes its whole subtree, numbered after the target's posts
+        let moved = service._promote_reply(child.id, admin, Some(other_topic_id)).await.unwrap();
+        assert_eq!(moved.parent_post_id, None);
+        assert_eq!(moved.topic_id, other_topic_id);
+        assert_eq!(moved.seq, 2);
+
+        let moved_grandchild = service._get_post(grandchild.id).await.unwrap();
+        assert_eq!(moved_grandchild.topic_id, other_topic_id);
+        assert_eq!(moved_grandchild.parent_post_id, Some(child.id));
+        assert_eq!(moved_grandchild.seq, 3);
+
+        // The rest of the original thread stays where it was
+        assert_eq!(service._get_post(sibling.id).await.unwrap(), sibling);
+        assert_eq!(service._get_post(reply.id).await.unwrap().topic_id, topic_id);
+        assert_eq!(service._count_replies(reply.id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_promote_reply_rpc_acts_only_as_the_callers_profile() {
+        let service = setup_test_service().await;
+        let (admin_node, admin) = create_owned_profile(&service, "Admin").await;
+        let (member_node, member) = create_owned_profile(&service, "Member").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, member).await;
+        let topic_id = create_test_topic(&service, group_id, admin).await;
+        let root = service
+            ._create_post(user_id, topic_id, "Root".into(), "Body".into())
+            .await
+            .unwrap();
+        let reply = service
+            ._create_reply(root.id, user_id, "Reply".into(), "Body".into())
+            .await
+            .unwrap();
+
+        // The member can't borrow the admin's profile to promote
+        let result = service.promote_reply_as(&member_node, reply.id, admin, None).await;
+        assert!(result.unwrap_err().to_string().contains("another node"));
+        let promoted = service.promote_reply_as(&admin_node, reply.id, admin, None).await;
+        assert_eq!(promoted.unwrap().parent_post_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_post_edit_window_expires() {
+        let start = chrono::Utc::now();
+        let clock = MockClock::new(start);
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let profile_id = create_test_profile(&service, "Author").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        for i in 0..3 {
-            service
-                ._create_post(user_id, topic_id, format!("Post {}", i), "Body".to_string())
-                .await
-                .unwrap();
-        }
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        assert_eq!(post.created_at, start.to_rfc3339());
 
-        let count = service._count_posts_by_user(user_id).await.unwrap();
-        assert_eq!(count, 3);
-    }
+        // Just inside the window
+        let window = chrono::Duration::minutes(RuntimeConfig::default().edit_window_minutes.into());
+        clock.advance(window);
+        let edited = service
+            ._update_post(post.id, user_id, None, Some("Edited".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(edited.edited_at, Some((start + window).to_rfc3339()));
 
-    // ===== REPLY TESTS =====
+        clock.advance(chrono::Duration::seconds(1));
+        let result = service._update_post(post.id, user_id, None, Some("Late".to_string())).await;
+        assert!(matches!(result, Err(PostsServiceError::EditWindowExpired)));
+        assert_eq!(service._get_post(post.id).await.unwrap().body, "Edited");
+    }
 
     #[tokio::test]
-    async fn test_create_reply() {
+    async fn test_soft_deleted_post_hidden_from_reads() {
         let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
+        let profile_id = create_test_profile(&service, "Author").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        // Create parent post
         let parent = service
-            ._create_post(user_id, topic_id, "Parent Post".to_string(), "Parent body".to_string())
+            ._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string())
             .await
             .unwrap();
-
-        // Create reply
-        let reply = service
-            ._create_reply(parent.id, user_id, "Reply".to_string(), "Reply body".to_string())
+        let kept = service
+            ._create_reply(parent.id, user_id, "Kept".to_string(), "Body".to_string())
             .await
             .unwrap();
-
-        assert_eq!(reply.parent_post_id, Some(parent.id));
-        assert_eq!(reply.topic_id, parent.topic_id);
-        assert_eq!(reply.title, "Reply");
+        let gone = service
+            ._create_reply(parent.id, user_id, "Gone".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        service._soft_delete_post(gone.id, user_id).await.unwrap();
+        service._soft_delete_post(gone.id, user_id).await.unwrap();
+
+        let max = u64::MAX;
+        let ids = |posts: Vec<GroupPostModel>| posts.iter().map(|post| post.id).collect::<Vec<_>>();
+        let listed = service._list_posts_for_topic(topic_id, max, 0).await.unwrap();
+        assert_eq!(ids(listed), [parent.id, kept.id]);
+        assert_eq!(service._list_posts_by_user(user_id, max, 0).await.unwrap().len(), 2);
+        assert_eq!(ids(service._list_replies(parent.id, max, 0).await.unwrap()), [kept.id]);
+        let since = service
+            ._list_posts_for_topic_since(topic_id, String::new(), PostId::new(), max)
+            .await
+            .unwrap();
+        assert_eq!(ids(since), [parent.id, kept.id]);
+        let headers = service._list_post_headers_for_topic(topic_id, max, 0).await.unwrap();
+        assert!(headers.iter().all(|header| header.id != gone.id));
+        let context = service._thread_context(kept.id, max).await.unwrap();
+        assert!(context.levels[1].siblings.is_empty());
+
+        assert_eq!(service._count_posts_in_topic(topic_id).await.unwrap(), 2);
+        assert_eq!(service._count_posts_by_user(user_id).await.unwrap(), 2);
+        assert_eq!(service._count_replies(parent.id).await.unwrap(), 1);
+
+        // Direct lookups and writes treat it as gone
+        assert_eq!(service._try_get_post(gone.id).await.unwrap(), None);
+        let result = service._get_post_by_topic_seq(topic_id, gone.seq).await;
+        assert!(matches!(result, Err(PostsServiceError::PostNotFound)));
+        let result = service._thread_context(gone.id, max).await;
+        assert!(matches!(result, Err(PostsServiceError::PostNotFound)));
+        let result = service._update_post(gone.id, user_id, None, Some("Back".to_string())).await;
+        assert!(matches!(result, Err(PostsServiceError::PostNotFound)));
+        let result = service._delete_post(gone.id, user_id).await;
+        assert!(matches!(result, Err(PostsServiceError::PostNotFound)));
     }
 
     #[tokio::test]
-    async fn test_nested_reply() {
-        let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
+    async fn test_posting_freeze_blocks_writes_only() {
+        let runtime = SharedRuntimeConfig::default();
+        let service = setup_test_service().await.with_runtime_config(runtime.clone());
+        let profile_id = create_test_profile(&service, "Author").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        // Create parent post
-        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+        let post = service
+            ._create_post(user_id, topic_id, "Before".to_string(), "Body".to_string())
+            .await
+            .unwrap();
 
-        // Create first-level reply
-        let reply1 = service._create_reply(parent.id, user_id, "Reply 1".to_string(), "Body".to_string()).await.unwrap();
+        runtime.write().unwrap().posting_frozen = true;
 
-        // Create nested reply (reply to reply)
-        let reply2 = service._create_reply(reply1.id, user_id, "Reply 2".to_string(), "Body".to_string()).await.unwrap();
+        let result = service
+            ._create_post(user_id, topic_id, "During".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::Frozen)));
+        let result = service
+            ._create_reply(post.id, user_id, "Re".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(PostsServiceError::Frozen)));
+        let result = service._update_post(post.id, user_id, None, Some("Edit".to_string())).await;
+        assert!(matches!(result, Err(PostsServiceError::Frozen)));
+        let result = service._soft_delete_post(post.id, user_id).await;
+        assert!(matches!(result, Err(PostsServiceError::Frozen)));
 
-        assert_eq!(reply2.parent_post_id, Some(reply1.id));
-        assert_eq!(reply2.topic_id, parent.topic_id);
+        // Reads keep working
+        assert_eq!(service._get_post(post.id).await.unwrap(), post);
+
+        runtime.write().unwrap().posting_frozen = false;
+
+        service
+            ._create_post(user_id, topic_id, "After".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        assert_eq!(service._count_posts_in_topic(topic_id).await.unwrap(), 2);
     }
 
     #[tokio::test]
-    async fn test_list_replies() {
+    async fn test_post_validation_names_the_field() {
         let service = setup_test_service().await;
-        
+
         let profile_id = create_test_profile(&service, "Test User").await;
         let group_id = create_test_group(&service, profile_id).await;
         let user_id = create_test_user(&service, group_id, profile_id).await;
         let topic_id = create_test_topic(&service, group_id, profile_id).await;
 
-        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+        let long_title = "x".repeat(MAX_POST_TITLE_CHARS + 1);
+        let error = service
+            ._create_post(user_id, topic_id, long_title, "Body".to_string())
+            .await
+            .unwrap_err();
+        let error = FieldError::from_resource_error(&error.into()).unwrap();
+        assert_eq!(error.field, "title");
 
-        // Create multiple replies
-        for i in 0..5 {
-            service
-                ._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string())
-                .await
-                .unwrap();
-        }
+        let error = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "  ".to_string())
+            .await
+            .unwrap_err();
+        let error = FieldError::from_resource_error(&error.into()).unwrap();
+        assert_eq!(error, FieldError::new("body", "must not be empty"));
 
-        let replies = service._list_replies(parent.id, 10, 0).await.unwrap();
-        assert_eq!(replies.len(), 5);
+        // Edits are held to the same rules
+        let post = service
+            ._create_post(user_id, topic_id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let result = service._update_post(post.id, user_id, None, Some(String::new())).await;
+        assert!(matches!(result, Err(PostsServiceError::Invalid(e)) if e.field == "body"));
     }
 
     #[tokio::test]
-    async fn test_count_replies() {
+    async fn test_report_list_and_resolve() {
         let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
-        let group_id = create_test_group(&service, profile_id).await;
-        let user_id = create_test_user(&service, group_id, profile_id).await;
-        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let admin = create_test_profile(&service, "Admin").await;
+        let reporter = create_test_profile(&service, "Reporter").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, reporter).await;
+        let topic_id = create_test_topic(&service, group_id, reporter).await;
+        let post = service
+            ._create_post(user_id, topic_id, "Title".into(), "Spam".into())
+            .await
+            .unwrap();
 
-        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+        let report = service._report_post(post.id, reporter, "spam".into()).await.unwrap();
+        assert_eq!(report.status, ReportStatus::Open);
+        let again = service._report_post(post.id, reporter, "still spam".into()).await;
+        assert!(matches!(again, Err(PostsServiceError::AlreadyReported)));
 
-        for i in 0..7 {
-            service._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string()).await.unwrap();
-        }
+        let listed = service._list_reports(group_id, admin, 10, 0).await.unwrap();
+        assert_eq!(listed, vec![report.clone()]);
+        let listed = service._list_reports(group_id, reporter, 10, 0).await;
+        assert!(matches!(listed, Err(PostsServiceError::Unauthorized)));
 
-        let count = service._count_replies(parent.id).await.unwrap();
-        assert_eq!(count, 7);
+        let result = service._resolve_report(report.id, reporter, ReportStatus::Resolved).await;
+        assert!(matches!(result, Err(PostsServiceError::Unauthorized)));
+        let result = service._resolve_report(report.id, admin, ReportStatus::Open).await;
+        assert!(matches!(result, Err(PostsServiceError::InvalidReportStatus)));
+
+        let resolved = service
+            ._resolve_report(report.id, admin, ReportStatus::Dismissed)
+            .await
+            .unwrap();
+        assert_eq!(resolved.status, ReportStatus::Dismissed);
+        assert!(service._list_reports(group_id, admin, 10, 0).await.unwrap().is_empty());
+
+        // Once the earlier report is closed the same reporter may file again
+        let reopened = service._report_post(post.id, reporter, "back again".into()).await;
+        assert!(reopened.is_ok());
     }
 
     #[tokio::test]
-    async fn test_list_top_level_posts() {
+    async fn test_report_rpcs_act_only_as_the_callers_profiles() {
         let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
-        let group_id = create_test_group(&service, profile_id).await;
-        let user_id = create_test_user(&service, group_id, profile_id).await;
-        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let (admin_node, admin) = create_owned_profile(&service, "Admin").await;
+        let (reporter_node, reporter) = create_owned_profile(&service, "Reporter").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, reporter).await;
+        let topic_id = create_test_topic(&service, group_id, reporter).await;
+        let post = service
+            ._create_post(user_id, topic_id, "Title".into(), "Spam".into())
+            .await
+            .unwrap();
 
-        // Create top-level posts
-        let post1 = service._create_post(user_id, topic_id, "Post 1".to_string(), "Body".to_string()).await.unwrap();
-        let post2 = service._create_post(user_id, topic_id, "Post 2".to_string(), "Body".to_string()).await.unwrap();
+        // Filing as someone else is refused, filing as yourself works
+        let result = service.report_post_as(&admin_node, post.id, reporter, "x".into()).await;
+        assert!(result.unwrap_err().to_string().contains("another node"));
+        let report = service
+            .report_post_as(&reporter_node, post.id, reporter, "spam".into())
+            .await
+            .unwrap();
 
-        // Create replies (should be excluded)
-        service._create_reply(post1.id, user_id, "Reply to 1".to_string(), "Body".to_string()).await.unwrap();
-        service._create_reply(post2.id, user_id, "Reply to 2".to_string(), "Body".to_string()).await.unwrap();
+        // Naming the admin doesn't make the reporter's node one
+        let result = service.list_reports_as(&reporter_node, group_id, admin, None, None).await;
+        assert!(result.unwrap_err().to_string().contains("another node"));
+        let status = ReportStatus::Dismissed;
+        let result = service.resolve_report_as(&reporter_node, report.id, admin, status).await;
+        assert!(result.unwrap_err().to_string().contains("another node"));
+
+        let listed = service.list_reports_as(&admin_node, group_id, admin, None, None).await;
+        assert_eq!(listed.unwrap(), vec![report.clone()]);
+        let resolved = service.resolve_report_as(&admin_node, report.id, admin, status).await;
+        assert_eq!(resolved.unwrap().status, ReportStatus::Dismissed);
+    }
 
-        // List only top-level
-        let top_level = service._list_top_level_posts(topic_id, 10, 0).await.unwrap();
-        assert_eq!(top_level.len(), 2, "Should only return top-level posts");
-        assert!(top_level.iter().all(|p| p.parent_post_id.is_none()));
+    #[tokio::test]
+    async fn test_moderation_queue() {
+        let clock = MockClock::new(chrono::Utc::now());
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let admin = create_test_profile(&service, "Admin").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let author = create_test_profile(&service, "Author").await;
+        let user_id = create_test_user(&service, group_id, author).await;
+        let topic_id = create_test_topic(&service, group_id, author).await;
+
+        let mut posts = Vec::new();
+        for i in 0..5 {
+            let post = service
+                ._create_post(user_id, topic_id, format!("Post {i}"), "Body".into())
+                .await
+                .unwrap();
+            posts.push(post);
+        }
+        let mut reporters = Vec::new();
+        for i in 0..3 {
+            reporters.push(create_test_profile(&service, &format!("Reporter {i}")).await);
+        }
+
+        // (post, reporter, reason) in the order they're filed
+        let filed = [
+            (1, 0, "spam"),
+            (0, 0, "rude"),
+            (1, 1, "ads"),
+            (2, 0, "off topic"),
+            (0, 1, "very rude"),
+            (1, 2, "more ads"),
+            (3, 0, "dismissed"),
+            (4, 0, "deleted"),
+        ];
+        let mut reports = Vec::new();
+        for (post, reporter, reason) in filed {
+            clock.advance(chrono::Duration::seconds(1));
+            let report = service
+                ._report_post(posts[post].id, reporters[reporter], reason.into())
+                .await
+                .unwrap();
+            reports.push(report);
+        }
+
+        // Closed reports and deleted posts don't count
+        service._resolve_report(reports[6].id, admin, ReportStatus::Dismissed).await.unwrap();
+        service._soft_delete_post(posts[4].id, user_id).await.unwrap();
+
+        let queue = service._moderation_queue(group_id, admin, 10, 0).await.unwrap();
+        let summary: Vec<(PostId, i64, &str)> = queue
+            .iter()
+            .map(|queued| (queued.post.id, queued.open_reports, queued.latest_reason.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (posts[1].id, 3, "more ads"),
+                (posts[0].id, 2, "very rude"),
+                (posts[2].id, 1, "off topic"),
+            ]
+        );
+        assert_eq!(queue[0].latest_reported_at, reports[5].created_at);
+
+        let page = service._moderation_queue(group_id, admin, 1, 1).await.unwrap();
+        assert_eq!(page, queue[1..2]);
+        let result = service._moderation_queue(group_id, author, 10, 0).await;
+        assert!(matches!(result, Err(PostsServiceError::Unauthorized)));
     }
 
     #[tokio::test]
-    async fn test_delete_post_cascades_to_replies() {
+    async fn test_moderation_queue_rpc_acts_only_as_the_callers_profile() {
         let service = setup_test_service().await;
-        
-        let profile_id = create_test_profile(&service, "Test User").await;
-        let group_id = create_test_group(&service, profile_id).await;
-        let user_id = create_test_user(&service, group_id, profile_id).await;
-        let topic_id = create_test_topic(&service, group_id, profile_id).await;
+        let (admin_node, admin) = create_owned_profile(&service, "Admin").await;
+        let (other_node, _) = create_owned_profile(&service, "Other").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let result = service.moderation_queue_as(&other_node, group_id, admin, None, None).await;
+        assert!(result.unwrap_err().to_string().contains("another node"));
+        let queue = service.moderation_queue_as(&admin_node, group_id, admin, None, None).await;
+        assert!(queue.unwrap().is_empty());
+    }
 
-        let parent = service._create_post(user_id, topic_id, "Parent".to_string(), "Body".to_string()).await.unwrap();
+    #[tokio::test]
+    async fn test_direct_topics_are_private() {
+        let service = setup_test_service().await;
+        let alice = create_test_profile(&service, "Alice").await;
+        let bob = create_test_profile(&service, "Bob").await;
+        let carol = create_test_profile(&service, "Carol").await;
+
+        let first = service._start_direct(alice, bob, "Hi Bob".into()).await.unwrap();
+        // Starting again from either side lands in the same topic
+        let again = service._start_direct(bob, alice, "Hi Alice".into()).await.unwrap();
+        assert_eq!(again.topic_id, first.topic_id);
+        let reply = service._send_direct(first.topic_id, alice, "How are you?".into()).await;
+        assert!(reply.is_ok());
+
+        let messages = service
+            ._list_direct_messages(first.topic_id, bob, 10, 0)
+            .await
+            .unwrap();
+        let bodies: Vec<&str> = messages.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(bodies, ["Hi Bob", "Hi Alice", "How are you?"]);
 
-        // Create replies
-        for i in 0..3 {
-            service._create_reply(parent.id, user_id, format!("Reply {}", i), "Body".to_string()).await.unwrap();
+        for profile_id in [alice, bob] {
+            let topics = service._list_direct_topics(profile_id, 10, 0).await.unwrap();
+            assert_eq!(topics.len(), 1);
+            assert_eq!(topics[0].id, first.topic_id);
         }
 
-        // Verify replies exist
-        let replies_before = service._list_replies(parent.id, 10, 0).await.unwrap();
-        assert_eq!(replies_before.len(), 3);
+        // A third profile can't see, read or post into the conversation
+        assert!(service._list_direct_topics(carol, 10, 0).await.unwrap().is_empty());
+        let read = service._list_direct_messages(first.topic_id, carol, 10, 0).await;
+        assert!(matches!(read, Err(PostsServiceError::NotAParticipant)));
+        let post = service._send_direct(first.topic_id, carol, "Hey".into()).await;
+        assert!(matches!(post, Err(PostsServiceError::NotAParticipant)));
+
+        let result = service._start_direct(alice, alice, "Me".into()).await;
+        assert!(matches!(result, Err(PostsServiceError::DirectToSelf)));
+        let result = service._start_direct(alice, ProfileId::new(), "Anyone?".into()).await;
+        assert!(matches!(result, Err(PostsServiceError::ProfileNotFound)));
+    }
 
-        // Delete parent
-        service._delete_post(parent.id, user_id).await.unwrap();
+    /// A profile owned by a fresh node, and a caller acting as that node
+    async fn create_owned_profile(service: &PostsService, name: &str) -> (TestCaller, ProfileId) {
+        let node_id = iroh::SecretKey::generate(&mut rand::rng()).public();
+        let profile = ProfilesService::new(service.db.clone())
+            ._create_profile(node_id, name.to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+        (TestCaller(node_id), profile.id)
+    }
 
-        // Verify parent is gone
-        let parent_result = service._get_post(parent.id).await;
-        assert!(parent_result.is_err());
+    #[tokio::test]
+    async fn test_direct_rpcs_act_only_as_the_callers_profiles() {
+        let service = setup_test_service().await;
+        let (alice_node, alice) = create_owned_profile(&service, "Alice").await;
+        let (_, bob) = create_owned_profile(&service, "Bob").await;
+        let (mallory_node, _) = create_owned_profile(&service, "Mallory").await;
+
+        let first = service.start_direct_as(&alice_node, alice, bob, "Hi".into()).await.unwrap();
+        let topic_id = first.topic_id;
+        let sent = service.send_direct_as(&alice_node, topic_id, alice, "Again".into()).await;
+        assert!(sent.is_ok());
+        let topics = service.list_direct_topics_as(&alice_node, alice, None, None).await;
+        assert_eq!(topics.unwrap().len(), 1);
+
+        // Another node naming Alice's profile is refused on every DM RPC
+        let refused = [
+            service.start_direct_as(&mallory_node, alice, bob, "Hi".into()).await.err(),
+            service.send_direct_as(&mallory_node, topic_id, alice, "Psst".into()).await.err(),
+            service.list_direct_topics_as(&mallory_node, alice, None, None).await.err(),
+            service.list_direct_messages_as(&mallory_node, topic_id, alice, None, None).await.err(),
+        ];
+        for error in refused {
+            let error = error.expect("acting as Alice should fail");
+            assert_eq!(error.severity(), ErrorSeverity::Application);
+            assert!(error.to_string().contains("another node"), "{error}");
+        }
+        let messages = service._list_direct_messages(topic_id, bob, 10, 0).await.unwrap();
+        assert_eq!(messages.len(), 2);
+    }
 
-        // Note: SQLite doesn't enforce FK cascade via ALTER TABLE on existing tables
-        // In production with proper migration, replies would be cascade deleted
+    #[test]
+    fn test_error_mapping() {
+        let db_error = PostsServiceError::DbError(DbErr::Custom("boom".to_string()));
+        let db_error = ResourceError::from(db_error);
+        assert_eq!(db_error.severity(), ErrorSeverity::Infrastructure);
+
+        for error in [
+            PostsServiceError::PostNotFound,
+            PostsServiceError::TopicNotFound,
+            PostsServiceError::UserNotFound,
+            PostsServiceError::Unauthorized,
+            PostsServiceError::NotAMember,
+            PostsServiceError::GroupArchived,
+            PostsServiceError::ParentNotFound,
+            PostsServiceError::ParentInDifferentTopic,
+            PostsServiceError::Frozen,
+            PostsServiceError::EditWindowExpired,
+            PostsServiceError::NotAReply,
+            PostsServiceError::TopicInDifferentGroup,
+            PostsServiceError::Invalid(FieldError::new("title", "must not be empty")),
+            PostsServiceError::ProfileNotFound,
+            PostsServiceError::DirectToSelf,
+            PostsServiceError::NotAParticipant,
+            PostsServiceError::ReportNotFound,
+            PostsServiceError::AlreadyReported,
+            PostsServiceError::InvalidReportStatus,
+            PostsServiceError::TooManySubscribers(TooManySubscribers { max: 1 }),
+            PostsServiceError::Banned,
+            PostsServiceError::NotOwner,
+        ] {
+            let message = error.to_string();
+            let mapped = ResourceError::from(error);
+            assert_eq!(mapped.severity(), ErrorSeverity::Application, "{message}");
+        }
     }
 }
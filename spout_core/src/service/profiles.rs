@@ -1,34 +1,216 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use iroh::PublicKey;
-use sea_orm::{DatabaseConnection, TransactionTrait};
+use sea_orm::{
+    sea_query::{Expr, LikeExpr, OnConflict, Query},
+    DatabaseConnection, FromQueryResult,
+};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zel_core::prelude::*;
 
-use crate::{entity::prelude::*, ids::ProfileId};
+use crate::{
+    config::{self, SharedRuntimeConfig},
+    entity::{identity::CorruptIdentity, prelude::*},
+    ids::{GroupId, NotificationId, ProfileId, TopicId, UploadId},
+    models::begin_write,
+    service::{
+        breaker::DbBreaker,
+        caller::Caller,
+        clock::{SharedClock, SystemClock},
+        page::Page,
+        posts::{escape_like, like_pattern},
+        validation::{check_text, FieldError},
+    },
+};
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ProfilesServiceError {
     #[error("fatal database error")]
     DbError(#[from] DbErr),
+
+    #[error("profile not found")]
+    ProfileNotFound,
+
+    #[error("notification not found")]
+    NotificationNotFound,
+
+    #[error("upload not found or expired")]
+    UploadNotFound,
+
+    #[error("chunk {got} out of order, expected {expected}")]
+    ChunkOutOfOrder { expected: u32, got: u32 },
+
+    #[error("picture too large (max {MAX_PICTURE_BYTES} bytes, {MAX_PICTURE_CHUNK_BYTES}/chunk)")]
+    PictureTooLarge,
+
+    #[error("picture is not a PNG, JPEG, GIF or WebP image")]
+    InvalidPicture,
+
+    #[error(transparent)]
+    CorruptIdentity(#[from] CorruptIdentity),
+
+    #[error("profile limit reached for this identity")]
+    ProfileLimitReached,
+
+    #[error(transparent)]
+    Invalid(#[from] FieldError),
+
+    #[error("profile name is already taken")]
+    NameTaken,
+
+    #[error("profile is already linked to this node")]
+    ProfileAlreadyLinked,
+
+    #[error("too many profiles created recently, try again later")]
+    RateLimited,
+
+    #[error("profile belongs to another node")]
+    NotOwner,
+}
+
+/// `conflict` when `error` is a unique constraint violation, otherwise the DB error itself
+fn on_unique_violation(error: DbErr, conflict: ProfilesServiceError) -> ProfilesServiceError {
+    match error.sql_err() {
+        Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => conflict,
+        _ => error.into(),
+    }
 }
 
-// TODO : need to actually dig into each error type
-// and correctly flag
+/// DB failures map to `ResourceError::infra`, every domain variant to `ResourceError::app`
 impl From<ProfilesServiceError> for ResourceError {
     fn from(error: ProfilesServiceError) -> Self {
         match error {
             ProfilesServiceError::DbError(error) => ResourceError::infra(error),
+            ProfilesServiceError::ProfileNotFound => ResourceError::app(error),
+            ProfilesServiceError::NotificationNotFound => ResourceError::app(error),
+            ProfilesServiceError::UploadNotFound => ResourceError::app(error),
+            ProfilesServiceError::ChunkOutOfOrder { .. } => ResourceError::app(error),
+            ProfilesServiceError::PictureTooLarge => {
+                FieldError::new("picture", error.to_string()).into()
+            }
+            ProfilesServiceError::InvalidPicture => {
+                FieldError::new("picture", error.to_string()).into()
+            }
+            ProfilesServiceError::CorruptIdentity(_) => ResourceError::app(error),
+            ProfilesServiceError::ProfileLimitReached => ResourceError::app(error),
+            ProfilesServiceError::Invalid(error) => error.into(),
+            ProfilesServiceError::NameTaken => ResourceError::app(error),
+            ProfilesServiceError::ProfileAlreadyLinked => ResourceError::app(error),
+            ProfilesServiceError::RateLimited => ResourceError::app(error),
+            ProfilesServiceError::NotOwner => ResourceError::app(error),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
         }
     }
 }
 
+/// Longest profile name accepted by `_create_profile`, in characters
+pub const MAX_PROFILE_NAME_CHARS: usize = 64;
+
+/// Largest assembled picture accepted by `finish_picture_upload`
+pub const MAX_PICTURE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Largest single `upload_chunk` payload
+pub const MAX_PICTURE_CHUNK_BYTES: usize = 256 * 1024;
+
+/// How long a partial upload may sit idle before it is dropped
+pub const PICTURE_UPLOAD_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// A finished picture upload
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PictureUpload {
+    pub profile: ProfileModel,
+    /// Hex blake3 of the stored picture, usable as a cache key
+    pub hash: String,
+}
+
+/// How `list_profiles_paged` orders a node's profiles; ties go by id
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileOrder {
+    /// Alphabetically by name
+    #[default]
+    Name,
+    /// Oldest first; profiles without a recorded creation time come first
+    Oldest,
+    /// Newest first
+    Newest,
+}
+
+/// A profile and the groups it created
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileWithGroups {
+    pub profile: ProfileModel,
+    pub groups: Vec<GroupModel>,
+}
+
+/// A profile as listed in the directory, without its picture bytes
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub id: ProfileId,
+    pub name: String,
+    pub desc: String,
+    pub has_picture: bool,
+}
+
+/// A picture being uploaded chunk by chunk
+struct PendingUpload {
+    node_id: PublicKey,
+    profile_id: ProfileId,
+    next_seq: u32,
+    bytes: Vec<u8>,
+    touched: Instant,
+}
+
 #[derive(Clone)]
 pub struct ProfilesService {
     db: DatabaseConnection,
+    breaker: DbBreaker,
+    clock: SharedClock,
+    runtime: SharedRuntimeConfig,
+    uploads: Arc<Mutex<HashMap<UploadId, PendingUpload>>>,
+    upload_ttl: Duration,
 }
 
 impl ProfilesService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            breaker: DbBreaker::default(),
+            clock: Arc::new(SystemClock),
+            runtime: SharedRuntimeConfig::default(),
+            uploads: Arc::default(),
+            upload_ttl: PICTURE_UPLOAD_TTL,
+        }
+    }
+
+    /// Share a circuit breaker with the other services using the same DB
+    pub fn with_breaker(mut self, breaker: DbBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read hot-reloadable limits from `SpoutCore`'s shared runtime config
+    pub fn with_runtime_config(mut self, runtime: SharedRuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// The underlying database connection, for queries the service doesn't cover
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.db
     }
 
     pub async fn _create_profile(
@@ -38,32 +220,107 @@ impl ProfilesService {
         desc: String,
         picture: Option<Vec<u8>>,
     ) -> Result<ProfileModel, ProfilesServiceError> {
-        let txn = self.db.begin().await?;
+        check_text("name", &name, MAX_PROFILE_NAME_CHARS)?;
+
+        let txn = begin_write(&self.db).await?;
+
+        let owned = Identity::find()
+            .filter(IdentityColumn::NodeId.eq(node_id.as_bytes().to_vec()))
+            .count(&txn)
+            .await?;
+        if owned >= config::runtime(&self.runtime).max_profiles_per_identity {
+            return Err(ProfilesServiceError::ProfileLimitReached);
+        }
+        self.take_creation_token(&txn, &node_id).await?;
 
         // Create profile
         let profile_id = ProfileId::new();
+        let node_id_bytes = node_id.as_bytes().to_vec();
         let profile = ProfileActiveModel {
             id: Set(profile_id),
             name: Set(name),
             desc: Set(desc),
             picture: Set(picture),
+            created_at: Set(Some(self.clock.now_rfc3339())),
+            owner_node_id: Set(Some(node_id_bytes.clone())),
         };
 
-        let profile_result = Profile::insert(profile).exec_with_returning(&txn).await?;
+        let profile_result = Profile::insert(profile)
+            .exec_with_returning(&txn)
+            .await
+            .map_err(|error| on_unique_violation(error, ProfilesServiceError::NameTaken))?;
+
+        // The node's first profile becomes its default
+        let has_default = Identity::find()
+            .filter(IdentityColumn::NodeId.eq(node_id_bytes.clone()))
+            .filter(IdentityColumn::IsDefault.eq(true))
+            .one(&txn)
+            .await?
+            .is_some();
 
         // Create identity linking node_id to profile
-        let node_id_bytes = node_id.as_bytes().to_vec();
         let identity = IdentityActiveModel {
             node_id: Set(node_id_bytes),
             profile_id: Set(profile_id),
+            is_default: Set(!has_default),
         };
 
-        Identity::insert(identity).exec(&txn).await?;
+        Identity::insert(identity)
+            .exec(&txn)
+            .await
+            .map_err(|error| {
+                on_unique_violation(error, ProfilesServiceError::ProfileAlreadyLinked)
+            })?;
 
         txn.commit().await?;
         Ok(profile_result)
     }
 
+    /// Spend one of `node_id`'s profile-creation tokens inside `txn`, so a
+    /// creation that fails and rolls back doesn't use one up.
+    ///
+    /// The bucket holds `profile_creations_per_hour` tokens and refills at that
+    /// rate; a node never seen before starts full.
+    async fn take_creation_token<C: ConnectionTrait>(
+        &self,
+        txn: &C,
+        node_id: &PublicKey,
+    ) -> Result<(), ProfilesServiceError> {
+        let capacity = f64::from(config::runtime(&self.runtime).profile_creations_per_hour);
+        let now = self.clock.now();
+        let node_id = node_id.as_bytes().to_vec();
+
+        let state = RateState::find_by_id(node_id.clone()).one(txn).await?;
+        let tokens = match &state {
+            Some(state) => {
+                let updated_at = chrono::DateTime::parse_from_rfc3339(&state.updated_at)
+                    .map_err(|error| DbErr::Type(error.to_string()))?;
+                let elapsed = now.signed_duration_since(updated_at).num_milliseconds().max(0);
+                let refilled = elapsed as f64 / 3_600_000.0 * capacity;
+                (state.tokens + refilled).min(capacity)
+            }
+            None => capacity,
+        };
+        if tokens < 1.0 {
+            return Err(ProfilesServiceError::RateLimited);
+        }
+
+        let updated = RateStateActiveModel {
+            node_id: Set(node_id),
+            tokens: Set(tokens - 1.0),
+            updated_at: Set(now.to_rfc3339()),
+        };
+        RateState::insert(updated)
+            .on_conflict(
+                OnConflict::column(RateStateColumn::NodeId)
+                    .update_columns([RateStateColumn::Tokens, RateStateColumn::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(txn)
+            .await?;
+        Ok(())
+    }
+
     pub async fn _list_profiles(
         &self,
         node_id: PublicKey,
@@ -87,204 +344,1075 @@ impl ProfilesService {
 
         Ok(profiles)
     }
-}
 
-#[zel_service(name = "profile")]
-trait Profiles {
-    #[doc = "Create a profile given current identity of the calling peer"]
-    #[method(name = "create_profile")]
-    async fn create_profile(
+    /// Load a profile together with the groups it owns, in one query
+    pub async fn _get_profile_with_owned_groups(
         &self,
-        name: String,
-        desc: String,
-        picture: Option<Vec<u8>>,
-    ) -> Result<ProfileModel, ResourceError>;
+        profile_id: ProfileId,
+    ) -> Result<ProfileWithGroups, ProfilesServiceError> {
+        let (profile, groups) = Profile::find_by_id(profile_id)
+            .find_with_related(Group)
+            .all(&self.db)
+            .await?
+            .pop()
+            .ok_or(ProfilesServiceError::ProfileNotFound)?;
 
-    #[doc = "List all profiles associated with the identity of the calling peer"]
-    #[method(name = "list_profiles")]
-    async fn list_profiles(&self) -> Result<Vec<ProfileModel>, ResourceError>;
-}
+        Ok(ProfileWithGroups { profile, groups })
+    }
 
-#[async_trait]
-impl ProfilesServer for ProfilesService {
-    async fn create_profile(
+    /// One page of the profiles linked to `node_id`, in a stable `order`
+    pub async fn _list_profiles_paged(
         &self,
-        ctx: RequestContext,
-        name: String,
-        desc: String,
-        picture: Option<Vec<u8>>,
-    ) -> Result<ProfileModel, ResourceError> {
-        Ok(self
-            ._create_profile(ctx.remote_id(), name, desc, picture)
-            .await?)
-    }
+        node_id: PublicKey,
+        order: ProfileOrder,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<ProfileModel>, ProfilesServiceError> {
+        use sea_orm::QueryOrder;
 
-    async fn list_profiles(&self, ctx: RequestContext) -> Result<Vec<ProfileModel>, ResourceError> {
-        Ok(self._list_profiles(ctx.remote_id()).await?)
+        let node_profiles = Query::select()
+            .column(IdentityColumn::ProfileId)
+            .from(Identity)
+            .and_where(IdentityColumn::NodeId.eq(node_id.as_bytes().to_vec()))
+            .to_owned();
+
+        let query = Profile::find().filter(ProfileColumn::Id.in_subquery(node_profiles));
+        let query = match order {
+            ProfileOrder::Name => query
+                .order_by_asc(ProfileColumn::Name)
+                .order_by_asc(ProfileColumn::Id),
+            ProfileOrder::Oldest => query
+                .order_by_asc(ProfileColumn::CreatedAt)
+                .order_by_asc(ProfileColumn::Id),
+            ProfileOrder::Newest => query
+                .order_by_desc(ProfileColumn::CreatedAt)
+                .order_by_desc(ProfileColumn::Id),
+        };
+
+        let profiles = query
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(profiles)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::migrator::Migrator;
-    use iroh::SecretKey;
-    use sea_orm::Database;
-    use sea_orm_migration::MigratorTrait;
+    /// Page through every known profile by name, optionally only those whose name
+    /// contains `search` (names starting with it first). Pictures are left out.
+    ///
+    /// `total` counts every profile the search matches, not just this page.
+    pub async fn _directory(
+        &self,
+        limit: u64,
+        offset: u64,
+        search: Option<String>,
+    ) -> Result<Page<ProfileSummary>, ProfilesServiceError> {
+        use sea_orm::{Order, QueryOrder, QuerySelect, QueryTrait};
 
-    async fn setup_test_service() -> ProfilesService {
-        let db = Database::connect("sqlite::memory:")
-            .await
-            .expect("Failed to create in-memory database");
+        let search = search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty())
+            .map(str::to_string);
 
-        Migrator::up(&db, None)
-            .await
-            .expect("Failed to run migrations");
+        let total = Profile::find()
+            .apply_if(search.as_deref(), |select, search| {
+                select.filter(ProfileColumn::Name.like(like_pattern(search)))
+            })
+            .count(&self.db)
+            .await?;
+        let items = Profile::find()
+            .select_only()
+            .columns([ProfileColumn::Id, ProfileColumn::Name, ProfileColumn::Desc])
+            .column_as(ProfileColumn::Picture.is_not_null(), "has_picture")
+            .apply_if(search, |select, search| {
+                let prefix = LikeExpr::new(format!("{}%", escape_like(&search))).escape('\\');
+                select
+                    .filter(ProfileColumn::Name.like(like_pattern(&search)))
+                    .order_by(ProfileColumn::Name.like(prefix), Order::Desc)
+            })
+            .order_by_asc(ProfileColumn::Name)
+            .order_by_asc(ProfileColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .into_model::<ProfileSummary>()
+            .all(&self.db)
+            .await?;
 
-        ProfilesService::new(db)
+        Ok(Page { items, total })
     }
 
-    fn test_node_id() -> PublicKey {
-        let secret_key = SecretKey::generate(&mut rand::thread_rng());
-        secret_key.public()
+    /// Get the node id a profile belongs to
+    pub async fn _get_profile_node(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<PublicKey, ProfilesServiceError> {
+        let identity = Identity::find()
+            .filter(IdentityColumn::ProfileId.eq(profile_id))
+            .one(&self.db)
+            .await?
+            .ok_or(ProfilesServiceError::ProfileNotFound)?;
+
+        Ok(identity.public_key()?)
     }
 
-    #[tokio::test]
-    async fn test_create_profile() {
-        let service = setup_test_service().await;
-        let node_id = test_node_id();
+    /// Get the default (primary) profile of a node
+    pub async fn _get_default_profile(
+        &self,
+        node_id: PublicKey,
+    ) -> Result<ProfileModel, ProfilesServiceError> {
+        let node_id_bytes = node_id.as_bytes().to_vec();
 
-        let profile = service
-            ._create_profile(
-                node_id,
-                "Test User".to_string(),
-                "Test Description".to_string(),
-                None,
-            )
-            .await
-            .expect("Failed to create profile");
+        let (_, profile) = Identity::find()
+            .filter(IdentityColumn::NodeId.eq(node_id_bytes))
+            .filter(IdentityColumn::IsDefault.eq(true))
+            .find_also_related(Profile)
+            .one(&self.db)
+            .await?
+            .ok_or(ProfilesServiceError::ProfileNotFound)?;
 
-        assert_eq!(profile.name, "Test User");
-        assert_eq!(profile.desc, "Test Description");
-        assert_eq!(profile.picture, None);
+        profile.ok_or(ProfilesServiceError::ProfileNotFound)
     }
 
-    #[tokio::test]
-    async fn test_create_profile_with_picture() {
-        let service = setup_test_service().await;
-        let node_id = test_node_id();
-        let picture_data = vec![1, 2, 3, 4, 5];
+    /// Make one of the node's own profiles its default
+    pub async fn _set_default_profile(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<ProfileModel, ProfilesServiceError> {
+        self.ensure_can_mutate(node_id, profile_id).await?;
+        let node_id_bytes = node_id.as_bytes().to_vec();
 
-        let profile = service
-            ._create_profile(
-                node_id,
-                "User with Picture".to_string(),
-                "Has avatar".to_string(),
-                Some(picture_data.clone()),
-            )
-            .await
-            .expect("Failed to create profile");
+        let (identity, profile) = Identity::find()
+            .filter(IdentityColumn::NodeId.eq(node_id_bytes.clone()))
+            .filter(IdentityColumn::ProfileId.eq(profile_id))
+            .find_also_related(Profile)
+            .one(&self.db)
+            .await?
+            .ok_or(ProfilesServiceError::ProfileNotFound)?;
+        let profile = profile.ok_or(ProfilesServiceError::ProfileNotFound)?;
 
-        assert_eq!(profile.name, "User with Picture");
-        assert_eq!(profile.picture, Some(picture_data));
-    }
+        if identity.is_default {
+            return Ok(profile);
+        }
 
-    #[tokio::test]
-    async fn test_list_profiles_empty() {
-        let service = setup_test_service().await;
-        let node_id = test_node_id();
+        let txn = begin_write(&self.db).await?;
 
-        let profiles = service
-            ._list_profiles(node_id)
-            .await
-            .expect("Failed to list profiles");
+        Identity::update_many()
+            .col_expr(IdentityColumn::IsDefault, Expr::value(false))
+            .filter(IdentityColumn::NodeId.eq(node_id_bytes.clone()))
+            .exec(&txn)
+            .await?;
 
-        assert_eq!(profiles.len(), 0, "New identity should have no profiles");
+        Identity::update_many()
+            .col_expr(IdentityColumn::IsDefault, Expr::value(true))
+            .filter(IdentityColumn::NodeId.eq(node_id_bytes))
+            .filter(IdentityColumn::ProfileId.eq(profile_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(profile)
     }
 
-    #[tokio::test]
-    async fn test_list_profiles_single() {
-        let service = setup_test_service().await;
-        let node_id = test_node_id();
+    /// Recreate missing `identity` rows from each profile's `owner_node_id`,
+    /// returning how many were restored.
+    ///
+    /// Which profile was a node's default isn't recoverable: a node left without
+    /// one gets its oldest profile as default.
+    pub async fn _rebuild_identities(&self) -> Result<u64, ProfilesServiceError> {
+        use sea_orm::QueryOrder;
 
-        // Create a profile
-        let created = service
-            ._create_profile(
-                node_id,
-                "Profile 1".to_string(),
-                "First profile".to_string(),
-                None,
-            )
-            .await
-            .expect("Failed to create profile");
+        let txn = begin_write(&self.db).await?;
 
-        // List profiles
-        let profiles = service
-            ._list_profiles(node_id)
-            .await
-            .expect("Failed to list profiles");
+        let linked = Query::select()
+            .column(IdentityColumn::ProfileId)
+            .from(Identity)
+            .to_owned();
+        let unlinked = Profile::find()
+            .filter(ProfileColumn::OwnerNodeId.is_not_null())
+            .filter(ProfileColumn::Id.not_in_subquery(linked))
+            .order_by_asc(ProfileColumn::CreatedAt)
+            .order_by_asc(ProfileColumn::Id)
+            .all(&txn)
+            .await?;
 
-        assert_eq!(profiles.len(), 1);
-        assert_eq!(profiles[0].id, created.id);
-        assert_eq!(profiles[0].name, "Profile 1");
+        let mut restored = 0;
+        for profile in unlinked {
+            let Some(node_id) = profile.owner_node_id else {
+                continue;
+            };
+            let has_default = Identity::find()
+                .filter(IdentityColumn::NodeId.eq(node_id.clone()))
+                .filter(IdentityColumn::IsDefault.eq(true))
+                .one(&txn)
+                .await?
+                .is_some();
+
+            Identity::insert(IdentityActiveModel {
+                node_id: Set(node_id),
+                profile_id: Set(profile.id),
+                is_default: Set(!has_default),
+            })
+            .exec(&txn)
+            .await?;
+            restored += 1;
+        }
+
+        txn.commit().await?;
+        Ok(restored)
     }
 
-    #[tokio::test]
-    async fn test_list_profiles_multiple() {
-        let service = setup_test_service().await;
-        let node_id = test_node_id();
+    /// List a node's notifications for one of its profiles, newest first
+    pub async fn _list_notifications(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+        unread_only: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<NotificationModel>, ProfilesServiceError> {
+        use sea_orm::QueryOrder;
 
-        // Create multiple profiles for the same identity
-        for i in 0..3 {
-            service
-                ._create_profile(
-                    node_id,
-                    format!("Profile {}", i),
-                    format!("Description {}", i),
-                    None,
-                )
-                .await
-                .expect("Failed to create profile");
-        }
+        self.ensure_owned(node_id, profile_id).await?;
 
-        // List all profiles
-        let profiles = service
-            ._list_profiles(node_id)
-            .await
-            .expect("Failed to list profiles");
+        let mut query = Notification::find()
+            .filter(NotificationColumn::RecipientProfileId.eq(profile_id));
+        if unread_only {
+            query = query.filter(NotificationColumn::Read.eq(false));
+        }
 
-        assert_eq!(
-            profiles.len(),
-            3,
-            "Should have 3 profiles for this identity"
-        );
+        let notifications = query
+            .order_by_desc(NotificationColumn::CreatedAt)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
 
-        // Verify all profiles are present
-        let names: Vec<String> = profiles.iter().map(|p| p.name.clone()).collect();
-        assert!(names.contains(&"Profile 0".to_string()));
-        assert!(names.contains(&"Profile 1".to_string()));
-        assert!(names.contains(&"Profile 2".to_string()));
+        Ok(notifications)
     }
 
-    #[tokio::test]
-    async fn test_list_profiles_isolated_by_identity() {
-        let service = setup_test_service().await;
-        let node_id_1 = test_node_id();
-        let node_id_2 = test_node_id();
+    /// Mark a notification as read; only the recipient's node may do so
+    pub async fn _mark_read(
+        &self,
+        node_id: PublicKey,
+        notification_id: NotificationId,
+    ) -> Result<NotificationModel, ProfilesServiceError> {
+        let notification = Notification::find_by_id(notification_id)
+            .one(&self.db)
+            .await?
+            .ok_or(ProfilesServiceError::NotificationNotFound)?;
 
-        // Create profiles for first identity
-        service
-            ._create_profile(
-                node_id_1,
-                "Identity 1 Profile".to_string(),
-                "Desc".to_string(),
-                None,
-            )
+        // Someone else's notification looks missing
+        self.ensure_owned(node_id, notification.recipient_profile_id)
             .await
-            .expect("Failed to create profile");
+            .map_err(|error| match error {
+                ProfilesServiceError::ProfileNotFound => ProfilesServiceError::NotificationNotFound,
+                error => error,
+            })?;
 
-        // Create profiles for second identity
-        service
+        if notification.read {
+            return Ok(notification);
+        }
+
+        let mut notification: NotificationActiveModel = notification.into();
+        notification.read = Set(true);
+        Ok(notification.update(&self.db).await?)
+    }
+
+    /// Remember where a profile was last active
+    pub async fn _set_last_location(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+        group_id: GroupId,
+        topic_id: Option<TopicId>,
+    ) -> Result<ProfileStateModel, ProfilesServiceError> {
+        self.ensure_can_mutate(node_id, profile_id).await?;
+
+        let now = self.clock.now_rfc3339();
+        Ok(record_last_location(&self.db, profile_id, group_id, topic_id, &now).await?)
+    }
+
+    /// Where a profile was last active, `None` until it has posted or set a location
+    pub async fn _get_last_location(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<Option<ProfileStateModel>, ProfilesServiceError> {
+        self.ensure_owned(node_id, profile_id).await?;
+
+        Ok(ProfileState::find_by_id(profile_id).one(&self.db).await?)
+    }
+
+    /// Start a chunked picture upload for one of the node's profiles
+    pub async fn _begin_picture_upload(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<UploadId, ProfilesServiceError> {
+        self.ensure_can_mutate(node_id, profile_id).await?;
+
+        let upload_id = UploadId::new();
+        let mut uploads = self.lock_uploads();
+        uploads.insert(
+            upload_id,
+            PendingUpload {
+                node_id,
+                profile_id,
+                next_seq: 0,
+                bytes: Vec::new(),
+                touched: Instant::now(),
+            },
+        );
+        Ok(upload_id)
+    }
+
+    /// Append chunk `seq` (0-based, strictly in order) to an upload
+    pub async fn _upload_chunk(
+        &self,
+        node_id: PublicKey,
+        upload_id: UploadId,
+        seq: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), ProfilesServiceError> {
+        let mut uploads = self.lock_uploads();
+        let upload = uploads
+            .get_mut(&upload_id)
+            .filter(|upload| upload.node_id == node_id)
+            .ok_or(ProfilesServiceError::UploadNotFound)?;
+
+        if seq != upload.next_seq {
+            return Err(ProfilesServiceError::ChunkOutOfOrder {
+                expected: upload.next_seq,
+                got: seq,
+            });
+        }
+        if bytes.len() > MAX_PICTURE_CHUNK_BYTES
+            || upload.bytes.len() + bytes.len() > MAX_PICTURE_BYTES
+        {
+            uploads.remove(&upload_id);
+            return Err(ProfilesServiceError::PictureTooLarge);
+        }
+
+        upload.bytes.extend_from_slice(&bytes);
+        upload.next_seq += 1;
+        upload.touched = Instant::now();
+        Ok(())
+    }
+
+    /// Assemble, validate, hash, and store an upload as the profile's picture
+    pub async fn _finish_picture_upload(
+        &self,
+        node_id: PublicKey,
+        upload_id: UploadId,
+    ) -> Result<PictureUpload, ProfilesServiceError> {
+        let upload = {
+            let mut uploads = self.lock_uploads();
+            match uploads.get(&upload_id) {
+                Some(upload) if upload.node_id == node_id => uploads.remove(&upload_id),
+                _ => None,
+            }
+        }
+        .ok_or(ProfilesServiceError::UploadNotFound)?;
+
+        if !is_supported_image(&upload.bytes) {
+            return Err(ProfilesServiceError::InvalidPicture);
+        }
+
+        // Ownership may have changed while the chunks were in flight
+        self.ensure_can_mutate(node_id, upload.profile_id).await?;
+
+        let profile = Profile::find_by_id(upload.profile_id)
+            .one(&self.db)
+            .await?
+            .ok_or(ProfilesServiceError::ProfileNotFound)?;
+
+        let hash = blake3::hash(&upload.bytes).to_hex().to_string();
+        let mut profile: ProfileActiveModel = profile.into();
+        profile.picture = Set(Some(upload.bytes));
+
+        Ok(PictureUpload {
+            profile: profile.update(&self.db).await?,
+            hash,
+        })
+    }
+
+    /// Drop uploads idle for longer than the TTL, returning how many were dropped
+    pub fn prune_picture_uploads(&self) -> usize {
+        let mut uploads = self.uploads.lock().unwrap_or_else(|e| e.into_inner());
+        let before = uploads.len();
+        uploads.retain(|_, upload| upload.touched.elapsed() < self.upload_ttl);
+        before - uploads.len()
+    }
+
+    /// The pending uploads, with abandoned ones already pruned
+    fn lock_uploads(&self) -> std::sync::MutexGuard<'_, HashMap<UploadId, PendingUpload>> {
+        let mut uploads = self.uploads.lock().unwrap_or_else(|e| e.into_inner());
+        uploads.retain(|_, upload| upload.touched.elapsed() < self.upload_ttl);
+        uploads
+    }
+
+    /// RPC body of `create_profile`, usable without a live `RequestContext`
+    pub async fn create_profile_as(
+        &self,
+        caller: &impl Caller,
+        name: String,
+        desc: String,
+        picture: Option<Vec<u8>>,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.breaker.call(self._create_profile(caller.remote_id(), name, desc, picture)).await
+    }
+
+    /// RPC body of `list_profiles`, usable without a live `RequestContext`
+    pub async fn list_profiles_as(
+        &self,
+        caller: &impl Caller,
+    ) -> Result<Vec<ProfileModel>, ResourceError> {
+        self.breaker.call(self._list_profiles(caller.remote_id())).await
+    }
+
+    /// RPC body of `list_profiles_paged`, usable without a live `RequestContext`
+    pub async fn list_profiles_paged_as(
+        &self,
+        caller: &impl Caller,
+        order: Option<ProfileOrder>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProfileModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let (order, offset) = (order.unwrap_or_default(), offset.unwrap_or(0));
+        self.breaker
+            .call(self._list_profiles_paged(caller.remote_id(), order, limit, offset))
+            .await
+    }
+
+    /// RPC body of `get_default_profile`, usable without a live `RequestContext`
+    pub async fn get_default_profile_as(
+        &self,
+        caller: &impl Caller,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.breaker.call(self._get_default_profile(caller.remote_id())).await
+    }
+
+    /// RPC body of `list_notifications`, usable without a live `RequestContext`
+    pub async fn list_notifications_as(
+        &self,
+        caller: &impl Caller,
+        profile_id: ProfileId,
+        unread_only: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<NotificationModel>, ResourceError> {
+        let node_id = caller.remote_id();
+        self.breaker
+            .call(self._list_notifications(node_id, profile_id, unread_only, limit, offset))
+            .await
+    }
+
+    /// RPC body of `mark_read`, usable without a live `RequestContext`
+    pub async fn mark_read_as(
+        &self,
+        caller: &impl Caller,
+        notification_id: NotificationId,
+    ) -> Result<NotificationModel, ResourceError> {
+        self.breaker.call(self._mark_read(caller.remote_id(), notification_id)).await
+    }
+
+    /// RPC body of `begin_picture_upload`, usable without a live `RequestContext`
+    pub async fn begin_picture_upload_as(
+        &self,
+        caller: &impl Caller,
+        profile_id: ProfileId,
+    ) -> Result<UploadId, ResourceError> {
+        self.breaker
+            .call(self._begin_picture_upload(caller.remote_id(), profile_id))
+            .await
+    }
+
+    /// RPC body of `upload_chunk`, usable without a live `RequestContext`
+    pub async fn upload_chunk_as(
+        &self,
+        caller: &impl Caller,
+        upload_id: UploadId,
+        seq: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), ResourceError> {
+        self.breaker
+            .call(self._upload_chunk(caller.remote_id(), upload_id, seq, bytes))
+            .await
+    }
+
+    /// RPC body of `finish_picture_upload`, usable without a live `RequestContext`
+    pub async fn finish_picture_upload_as(
+        &self,
+        caller: &impl Caller,
+        upload_id: UploadId,
+    ) -> Result<PictureUpload, ResourceError> {
+        self.breaker
+            .call(self._finish_picture_upload(caller.remote_id(), upload_id))
+            .await
+    }
+
+    /// RPC body of `set_last_location`, usable without a live `RequestContext`
+    pub async fn set_last_location_as(
+        &self,
+        caller: &impl Caller,
+        profile_id: ProfileId,
+        group_id: GroupId,
+        topic_id: Option<TopicId>,
+    ) -> Result<ProfileStateModel, ResourceError> {
+        self.breaker
+            .call(self._set_last_location(caller.remote_id(), profile_id, group_id, topic_id))
+            .await
+    }
+
+    /// RPC body of `get_last_location`, usable without a live `RequestContext`
+    pub async fn get_last_location_as(
+        &self,
+        caller: &impl Caller,
+        profile_id: ProfileId,
+    ) -> Result<Option<ProfileStateModel>, ResourceError> {
+        self.breaker
+            .call(self._get_last_location(caller.remote_id(), profile_id))
+            .await
+    }
+
+    /// RPC body of `set_default_profile`, usable without a live `RequestContext`
+    pub async fn set_default_profile_as(
+        &self,
+        caller: &impl Caller,
+        profile_id: ProfileId,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.breaker
+            .call(self._set_default_profile(caller.remote_id(), profile_id))
+            .await
+    }
+
+    /// Whether `node_id` has an `identity` row linking it to `profile_id`
+    pub async fn _owns_profile(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<bool, ProfilesServiceError> {
+        Ok(owns_profile(&self.db, node_id, profile_id).await?)
+    }
+
+    /// Fail with `ProfileNotFound` unless `profile_id` is linked to `node_id`;
+    /// reads don't reveal that another node's profile exists
+    async fn ensure_owned(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<(), ProfilesServiceError> {
+        match self._owns_profile(node_id, profile_id).await? {
+            true => Ok(()),
+            false => Err(ProfilesServiceError::ProfileNotFound),
+        }
+    }
+
+    /// Like `ensure_owned`, but an existing profile of another node fails with
+    /// `NotOwner`, so a mutation is refused rather than looking like a typo
+    async fn ensure_can_mutate(
+        &self,
+        node_id: PublicKey,
+        profile_id: ProfileId,
+    ) -> Result<(), ProfilesServiceError> {
+        if self._owns_profile(node_id, profile_id).await? {
+            return Ok(());
+        }
+        match Profile::find_by_id(profile_id).one(&self.db).await? {
+            Some(_) => Err(ProfilesServiceError::NotOwner),
+            None => Err(ProfilesServiceError::ProfileNotFound),
+        }
+    }
+}
+
+/// Sniff the magic bytes of the image formats we accept as profile pictures
+fn is_supported_image(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\x89PNG\r\n\x1a\n")
+        || bytes.starts_with(&[0xFF, 0xD8, 0xFF])
+        || bytes.starts_with(b"GIF87a")
+        || bytes.starts_with(b"GIF89a")
+        || (bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP")
+}
+
+/// Whether `node_id` has an `identity` row linking it to `profile_id`; also
+/// used by the posts service to check who an RPC acts as
+pub(crate) async fn owns_profile<C: ConnectionTrait>(
+    conn: &C,
+    node_id: PublicKey,
+    profile_id: ProfileId,
+) -> Result<bool, DbErr> {
+    let identity = Identity::find()
+        .filter(IdentityColumn::NodeId.eq(node_id.as_bytes().to_vec()))
+        .filter(IdentityColumn::ProfileId.eq(profile_id))
+        .one(conn)
+        .await?;
+
+    Ok(identity.is_some())
+}
+
+/// Upsert a profile's resume location; also called from the posting paths
+pub(crate) async fn record_last_location<C: ConnectionTrait>(
+    conn: &C,
+    profile_id: ProfileId,
+    group_id: GroupId,
+    topic_id: Option<TopicId>,
+    updated_at: &str,
+) -> Result<ProfileStateModel, DbErr> {
+    let state = ProfileStateActiveModel {
+        profile_id: Set(profile_id),
+        last_group_id: Set(Some(group_id)),
+        last_topic_id: Set(topic_id),
+        updated_at: Set(updated_at.to_string()),
+    };
+
+    ProfileState::insert(state)
+        .on_conflict(
+            OnConflict::column(ProfileStateColumn::ProfileId)
+                .update_columns([
+                    ProfileStateColumn::LastGroupId,
+                    ProfileStateColumn::LastTopicId,
+                    ProfileStateColumn::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(conn)
+        .await
+}
+
+#[zel_service(name = "profile")]
+trait Profiles {
+    #[doc = "Create a profile given current identity of the calling peer"]
+    #[method(name = "create_profile")]
+    async fn create_profile(
+        &self,
+        name: String,
+        desc: String,
+        picture: Option<Vec<u8>>,
+    ) -> Result<ProfileModel, ResourceError>;
+
+    #[doc = "List all profiles associated with the identity of the calling peer"]
+    #[method(name = "list_profiles")]
+    async fn list_profiles(&self) -> Result<Vec<ProfileModel>, ResourceError>;
+
+    #[doc = "List one page of the calling peer's profiles in a stable order"]
+    #[method(name = "list_profiles_paged")]
+    async fn list_profiles_paged(
+        &self,
+        order: Option<ProfileOrder>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProfileModel>, ResourceError>;
+
+    #[doc = "Page through profiles by name with a search filter and match total; no pictures"]
+    #[method(name = "directory")]
+    async fn directory(
+        &self,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        search: Option<String>,
+    ) -> Result<Page<ProfileSummary>, ResourceError>;
+
+    #[doc = "Get the node id a profile belongs to"]
+    #[method(name = "get_profile_node")]
+    async fn get_profile_node(&self, profile_id: ProfileId) -> Result<PublicKey, ResourceError>;
+
+    #[doc = "Get the default profile of the calling peer"]
+    #[method(name = "get_default_profile")]
+    async fn get_default_profile(&self) -> Result<ProfileModel, ResourceError>;
+
+    #[doc = "List notifications for one of the calling peer's profiles, newest first"]
+    #[method(name = "list_notifications")]
+    async fn list_notifications(
+        &self,
+        profile_id: ProfileId,
+        unread_only: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<NotificationModel>, ResourceError>;
+
+    #[doc = "Mark one of the calling peer's notifications as read"]
+    #[method(name = "mark_read")]
+    async fn mark_read(
+        &self,
+        notification_id: NotificationId,
+    ) -> Result<NotificationModel, ResourceError>;
+
+    #[doc = "Remember where one of the calling peer's profiles was last active"]
+    #[method(name = "set_last_location")]
+    async fn set_last_location(
+        &self,
+        profile_id: ProfileId,
+        group_id: GroupId,
+        topic_id: Option<TopicId>,
+    ) -> Result<ProfileStateModel, ResourceError>;
+
+    #[doc = "Get where one of the calling peer's profiles was last active"]
+    #[method(name = "get_last_location")]
+    async fn get_last_location(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<Option<ProfileStateModel>, ResourceError>;
+
+    #[doc = "Start a chunked picture upload for one of the calling peer's profiles"]
+    #[method(name = "begin_picture_upload")]
+    async fn begin_picture_upload(&self, profile_id: ProfileId) -> Result<UploadId, ResourceError>;
+
+    #[doc = "Append the next chunk (0-based seq) to a picture upload"]
+    #[method(name = "upload_chunk")]
+    async fn upload_chunk(
+        &self,
+        upload_id: UploadId,
+        seq: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), ResourceError>;
+
+    #[doc = "Validate and store a completed picture upload"]
+    #[method(name = "finish_picture_upload")]
+    async fn finish_picture_upload(
+        &self,
+        upload_id: UploadId,
+    ) -> Result<PictureUpload, ResourceError>;
+
+    #[doc = "Make one of the calling peer's profiles its default"]
+    #[method(name = "set_default_profile")]
+    async fn set_default_profile(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<ProfileModel, ResourceError>;
+}
+
+#[async_trait]
+impl ProfilesServer for ProfilesService {
+    async fn create_profile(
+        &self,
+        ctx: RequestContext,
+        name: String,
+        desc: String,
+        picture: Option<Vec<u8>>,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.create_profile_as(&ctx, name, desc, picture).await
+    }
+
+    async fn list_profiles(&self, ctx: RequestContext) -> Result<Vec<ProfileModel>, ResourceError> {
+        self.list_profiles_as(&ctx).await
+    }
+
+    async fn list_profiles_paged(
+        &self,
+        ctx: RequestContext,
+        order: Option<ProfileOrder>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProfileModel>, ResourceError> {
+        self.list_profiles_paged_as(&ctx, order, limit, offset).await
+    }
+
+    async fn directory(
+        &self,
+        _ctx: RequestContext,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        search: Option<String>,
+    ) -> Result<Page<ProfileSummary>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker.call(self._directory(limit, offset, search)).await
+    }
+
+    async fn get_profile_node(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<PublicKey, ResourceError> {
+        self.breaker.call(self._get_profile_node(profile_id)).await
+    }
+
+    async fn get_default_profile(
+        &self,
+        ctx: RequestContext,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.get_default_profile_as(&ctx).await
+    }
+
+    async fn list_notifications(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+        unread_only: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<NotificationModel>, ResourceError> {
+        self.list_notifications_as(&ctx, profile_id, unread_only, limit, offset).await
+    }
+
+    async fn mark_read(
+        &self,
+        ctx: RequestContext,
+        notification_id: NotificationId,
+    ) -> Result<NotificationModel, ResourceError> {
+        self.mark_read_as(&ctx, notification_id).await
+    }
+
+    async fn begin_picture_upload(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<UploadId, ResourceError> {
+        self.begin_picture_upload_as(&ctx, profile_id).await
+    }
+
+    async fn upload_chunk(
+        &self,
+        ctx: RequestContext,
+        upload_id: UploadId,
+        seq: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), ResourceError> {
+        self.upload_chunk_as(&ctx, upload_id, seq, bytes).await
+    }
+
+    async fn finish_picture_upload(
+        &self,
+        ctx: RequestContext,
+        upload_id: UploadId,
+    ) -> Result<PictureUpload, ResourceError> {
+        self.finish_picture_upload_as(&ctx, upload_id).await
+    }
+
+    async fn set_last_location(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+        group_id: GroupId,
+        topic_id: Option<TopicId>,
+    ) -> Result<ProfileStateModel, ResourceError> {
+        self.set_last_location_as(&ctx, profile_id, group_id, topic_id).await
+    }
+
+    async fn get_last_location(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<Option<ProfileStateModel>, ResourceError> {
+        self.get_last_location_as(&ctx, profile_id).await
+    }
+
+    async fn set_default_profile(
+        &self,
+        ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<ProfileModel, ResourceError> {
+        self.set_default_profile_as(&ctx, profile_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::migrator::Migrator, service::caller::TestCaller};
+    use iroh::SecretKey;
+    use sea_orm::Database;
+    use sea_orm_migration::MigratorTrait;
+
+    async fn setup_test_service() -> ProfilesService {
+        let db = Database::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to run migrations");
+
+        ProfilesService::new(db)
+    }
+
+    fn test_node_id() -> PublicKey {
+        let secret_key = SecretKey::generate(&mut rand::rng());
+        secret_key.public()
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_identities_after_identity_loss() {
+        use crate::service::clock::MockClock;
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let (alice, bob) = (test_node_id(), test_node_id());
+
+        let mut created = Vec::new();
+        for (node_id, name) in [(alice, "Alice"), (alice, "Alice Alt"), (bob, "Bob")] {
+            let profile = service
+                ._create_profile(node_id, name.to_string(), "Desc".to_string(), None)
+                .await
+                .unwrap();
+            created.push(profile);
+            clock.advance(chrono::Duration::seconds(1));
+        }
+        service._set_default_profile(alice, created[1].id).await.unwrap();
+        let alice_before = service._list_profiles(alice).await.unwrap();
+
+        Identity::delete_many().exec(&service.db).await.unwrap();
+        assert!(service._list_profiles(alice).await.unwrap().is_empty());
+
+        assert_eq!(service._rebuild_identities().await.unwrap(), 3);
+        assert_eq!(service._list_profiles(alice).await.unwrap(), alice_before);
+        assert_eq!(service._list_profiles(bob).await.unwrap(), vec![created[2].clone()]);
+        assert_eq!(service._get_profile_node(created[1].id).await.unwrap(), alice);
+
+        // Each node gets exactly one default again, its oldest profile
+        assert_eq!(service._get_default_profile(alice).await.unwrap().id, created[0].id);
+        assert_eq!(service._get_default_profile(bob).await.unwrap().id, created[2].id);
+
+        // Nothing left to restore
+        assert_eq!(service._rebuild_identities().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_profile() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let profile = service
+            ._create_profile(
+                node_id,
+                "Test User".to_string(),
+                "Test Description".to_string(),
+                None,
+            )
+            .await
+            .expect("Failed to create profile");
+
+        assert_eq!(profile.name, "Test User");
+        assert_eq!(profile.desc, "Test Description");
+        assert_eq!(profile.picture, None);
+
+        // Returned straight from the insert, not left for a follow-up get
+        let created_at = profile.created_at.expect("created_at should be set");
+        let created_at = chrono::DateTime::parse_from_rfc3339(&created_at).unwrap();
+        let age = chrono::Utc::now().signed_duration_since(created_at);
+        assert!(age >= chrono::Duration::zero() && age < chrono::Duration::seconds(60));
+    }
+
+    #[tokio::test]
+    async fn test_create_profile_with_picture() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+        let picture_data = vec![1, 2, 3, 4, 5];
+
+        let profile = service
+            ._create_profile(
+                node_id,
+                "User with Picture".to_string(),
+                "Has avatar".to_string(),
+                Some(picture_data.clone()),
+            )
+            .await
+            .expect("Failed to create profile");
+
+        assert_eq!(profile.name, "User with Picture");
+        assert_eq!(profile.picture, Some(picture_data));
+    }
+
+    #[tokio::test]
+    async fn test_list_profiles_empty() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let profiles = service
+            ._list_profiles(node_id)
+            .await
+            .expect("Failed to list profiles");
+
+        assert_eq!(profiles.len(), 0, "New identity should have no profiles");
+    }
+
+    #[tokio::test]
+    async fn test_list_profiles_single() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        // Create a profile
+        let created = service
+            ._create_profile(
+                node_id,
+                "Profile 1".to_string(),
+                "First profile".to_string(),
+                None,
+            )
+            .await
+            .expect("Failed to create profile");
+
+        // List profiles
+        let profiles = service
+            ._list_profiles(node_id)
+            .await
+            .expect("Failed to list profiles");
+
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, created.id);
+        assert_eq!(profiles[0].name, "Profile 1");
+    }
+
+    #[tokio::test]
+    async fn test_list_profiles_multiple() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        // Create multiple profiles for the same identity
+        for i in 0..3 {
+            service
+                ._create_profile(
+                    node_id,
+                    format!("Profile {}", i),
+                    format!("Description {}", i),
+                    None,
+                )
+                .await
+                .expect("Failed to create profile");
+        }
+
+        // List all profiles
+        let profiles = service
+            ._list_profiles(node_id)
+            .await
+            .expect("Failed to list profiles");
+
+        assert_eq!(
+            profiles.len(),
+            3,
+            "Should have 3 profiles for this identity"
+        );
+
+        // Verify all profiles are present
+        let names: Vec<String> = profiles.iter().map(|p| p.name.clone()).collect();
+        assert!(names.contains(&"Profile 0".to_string()));
+        assert!(names.contains(&"Profile 1".to_string()));
+        assert!(names.contains(&"Profile 2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_list_profiles_isolated_by_identity() {
+        let service = setup_test_service().await;
+        let node_id_1 = test_node_id();
+        let node_id_2 = test_node_id();
+
+        // Create profiles for first identity
+        service
+            ._create_profile(
+                node_id_1,
+                "Identity 1 Profile".to_string(),
+                "Desc".to_string(),
+                None,
+            )
+            .await
+            .expect("Failed to create profile");
+
+        // Create profiles for second identity
+        service
             ._create_profile(
                 node_id_2,
                 "Identity 2 Profile".to_string(),
@@ -312,6 +1440,111 @@ mod tests {
         assert_eq!(profiles_2[0].name, "Identity 2 Profile");
     }
 
+    #[tokio::test]
+    async fn test_get_profile_with_owned_groups() {
+        use crate::service::groups::GroupsService;
+
+        let service = setup_test_service().await;
+        let groups = GroupsService::new(service.db.clone());
+        let owner = service
+            ._create_profile(test_node_id(), "Owner".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let other = service
+            ._create_profile(test_node_id(), "Other".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        let first = groups._create_group(owner.id).await.unwrap();
+        let second = groups._create_group(owner.id).await.unwrap();
+        groups._create_group(other.id).await.unwrap();
+        // Membership alone doesn't make a group owned
+        let joined = groups._create_group(other.id).await.unwrap();
+        groups._add_user(joined.id, owner.id).await.unwrap();
+
+        let loaded = service._get_profile_with_owned_groups(owner.id).await.unwrap();
+        assert_eq!(loaded.profile, owner);
+        assert_eq!(loaded.groups.len(), 2);
+        assert!(loaded.groups.iter().all(|group| [first.id, second.id].contains(&group.id)));
+
+        let lonely = service
+            ._create_profile(test_node_id(), "Lonely".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let loaded = service._get_profile_with_owned_groups(lonely.id).await.unwrap();
+        assert!(loaded.groups.is_empty());
+
+        assert!(matches!(
+            service._get_profile_with_owned_groups(ProfileId::new()).await,
+            Err(ProfilesServiceError::ProfileNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_profiles_paged() {
+        use crate::{config::RuntimeConfig, service::clock::MockClock};
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let runtime = SharedRuntimeConfig::new(std::sync::RwLock::new(RuntimeConfig {
+            max_profiles_per_identity: 50,
+            profile_creations_per_hour: 50,
+            ..RuntimeConfig::default()
+        }));
+        let service = setup_test_service()
+            .await
+            .with_clock(Arc::new(clock.clone()))
+            .with_runtime_config(runtime);
+        let node_id = test_node_id();
+
+        // Created in the reverse of name order
+        let mut created = Vec::new();
+        for i in (0..25).rev() {
+            clock.advance(chrono::Duration::seconds(1));
+            let name = format!("Profile {i:02}");
+            let profile = service
+                ._create_profile(node_id, name, "Desc".to_string(), None)
+                .await
+                .unwrap();
+            created.push(profile.id);
+        }
+        service
+            ._create_profile(test_node_id(), "Elsewhere".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        let mut names = Vec::new();
+        for offset in (0..30).step_by(10) {
+            let page = service
+                ._list_profiles_paged(node_id, ProfileOrder::Name, 10, offset)
+                .await
+                .unwrap();
+            names.extend(page.into_iter().map(|profile| profile.name));
+        }
+        let expected: Vec<String> = (0..25).map(|i| format!("Profile {i:02}")).collect();
+        assert_eq!(names, expected);
+
+        let oldest = service
+            ._list_profiles_paged(node_id, ProfileOrder::Oldest, 5, 0)
+            .await
+            .unwrap();
+        let oldest: Vec<ProfileId> = oldest.iter().map(|profile| profile.id).collect();
+        assert_eq!(oldest, created[..5]);
+
+        let newest = service
+            ._list_profiles_paged(node_id, ProfileOrder::Newest, 3, 1)
+            .await
+            .unwrap();
+        let newest: Vec<ProfileId> = newest.iter().map(|profile| profile.id).collect();
+        let expected: Vec<ProfileId> = created.iter().rev().skip(1).take(3).copied().collect();
+        assert_eq!(newest, expected);
+
+        let past_end = service
+            ._list_profiles_paged(node_id, ProfileOrder::Name, 10, 30)
+            .await
+            .unwrap();
+        assert!(past_end.is_empty());
+    }
+
     #[tokio::test]
     async fn test_create_profile_creates_identity_link() {
         let service = setup_test_service().await;
@@ -332,6 +1565,420 @@ mod tests {
         assert_eq!(profiles[0].id, profile.id);
     }
 
+    #[tokio::test]
+    async fn test_create_profile_rpc_links_caller_identity() {
+        let service = setup_test_service().await;
+        let caller = TestCaller(test_node_id());
+
+        let profile = service
+            .create_profile_as(&caller, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .expect("Failed to create profile");
+
+        let profiles = service._list_profiles(caller.0).await.unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, profile.id);
+
+        // Another caller doesn't see it
+        let other = service._list_profiles(test_node_id()).await.unwrap();
+        assert!(other.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rpcs_act_as_the_caller() {
+        let service = setup_test_service().await;
+        let (owner, other) = (TestCaller(test_node_id()), TestCaller(test_node_id()));
+        let profile = service
+            .create_profile_as(&owner, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(service.list_profiles_as(&owner).await.unwrap(), vec![profile.clone()]);
+        let paged = service.list_profiles_paged_as(&owner, None, None, None).await.unwrap();
+        assert_eq!(paged, vec![profile.clone()]);
+        assert_eq!(service.get_default_profile_as(&owner).await.unwrap(), profile);
+        assert!(service.list_profiles_as(&other).await.unwrap().is_empty());
+
+        // Another node's caller can't touch the profile through the RPC bodies
+        let result = service.set_default_profile_as(&other, profile.id).await;
+        assert_eq!(result.unwrap_err().severity(), ErrorSeverity::Application);
+        let result = service.begin_picture_upload_as(&other, profile.id).await;
+        assert_eq!(result.unwrap_err().severity(), ErrorSeverity::Application);
+        assert!(service.begin_picture_upload_as(&owner, profile.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_first_profile_is_default() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let seeded = service
+            ._create_profile(node_id, "Default".to_string(), "Default profile".to_string(), None)
+            .await
+            .unwrap();
+        service
+            ._create_profile(node_id, "Second".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        let default = service._get_default_profile(node_id).await.unwrap();
+        assert_eq!(default.id, seeded.id, "Seeded profile should stay the default");
+    }
+
+    #[tokio::test]
+    async fn test_get_default_profile_none() {
+        let service = setup_test_service().await;
+
+        let result = service._get_default_profile(test_node_id()).await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_set_default_profile() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        service
+            ._create_profile(node_id, "Default".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let second = service
+            ._create_profile(node_id, "Second".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        service._set_default_profile(node_id, second.id).await.unwrap();
+
+        let default = service._get_default_profile(node_id).await.unwrap();
+        assert_eq!(default.id, second.id);
+
+        // Exactly one default per node
+        let defaults = Identity::find()
+            .filter(IdentityColumn::IsDefault.eq(true))
+            .count(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(defaults, 1);
+    }
+
+    #[tokio::test]
+    async fn test_set_default_profile_rejects_other_nodes_profile() {
+        let service = setup_test_service().await;
+        let node_id_1 = test_node_id();
+        let node_id_2 = test_node_id();
+
+        let theirs = service
+            ._create_profile(node_id_2, "Theirs".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        let result = service._set_default_profile(node_id_1, theirs.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+        let result = service._set_default_profile(node_id_1, ProfileId::new()).await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_owns_profile() {
+        let service = setup_test_service().await;
+        let node_id_1 = test_node_id();
+        let node_id_2 = test_node_id();
+
+        let profile = service
+            ._create_profile(node_id_1, "Mine".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        assert!(service._owns_profile(node_id_1, profile.id).await.unwrap());
+        assert!(!service._owns_profile(node_id_2, profile.id).await.unwrap());
+        assert!(!service._owns_profile(node_id_1, ProfileId::new()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_second_node_cannot_mutate_first_nodes_profile() {
+        let service = setup_test_service().await;
+        let first = test_node_id();
+        let second = test_node_id();
+
+        let profile = service
+            ._create_profile(first, "First".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        service
+            ._create_profile(second, "Second".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        let result = service._set_default_profile(second, profile.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+        let result = service._begin_picture_upload(second, profile.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+
+        // An upload started by the owner can't be finished once the link is gone
+        let upload_id = service._begin_picture_upload(first, profile.id).await.unwrap();
+        service._upload_chunk(first, upload_id, 0, test_png(64)).await.unwrap();
+        Identity::delete_by_id((first.as_bytes().to_vec(), profile.id))
+            .exec(&service.db)
+            .await
+            .unwrap();
+        let result = service._finish_picture_upload(first, upload_id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+
+        let unchanged = Profile::find_by_id(profile.id).one(&service.db).await.unwrap().unwrap();
+        assert_eq!(unchanged, profile);
+    }
+
+    async fn insert_test_notification(
+        service: &ProfilesService,
+        recipient: ProfileId,
+        read: bool,
+    ) -> NotificationId {
+        use crate::ids::{GroupId, PostId, TopicId, UserId};
+
+        // A notification needs a real source post
+        let group_id = GroupId::new();
+        Group::insert(GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(recipient),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = UserId::new();
+        GroupUser::insert(GroupUserActiveModel {
+            id: Set(user_id),
+            group_id: Set(group_id),
+            profile_id: Set(recipient),
+            nickname: Set(None),
+            created_at: Set(None),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let topic_id = TopicId::new();
+        let now = chrono::Utc::now().to_rfc3339();
+        GroupTopic::insert(GroupTopicActiveModel {
+            id: Set(topic_id),
+            group_id: Set(group_id),
+            profile_id: Set(recipient),
+            created_at: Set(now.clone()),
+            title: Set("Test Topic".to_string()),
+            updated_at: Set(None),
+            pin_order: Set(None),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let post_id = PostId::new();
+        GroupPost::insert(GroupPostActiveModel {
+            id: Set(post_id),
+            user_id: Set(user_id),
+            topic_id: Set(topic_id),
+            parent_post_id: Set(None),
+            title: Set("Title".to_string()),
+            body: Set("Body".to_string()),
+            created_at: Set(now.clone()),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(1),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let notification_id = NotificationId::new();
+        Notification::insert(NotificationActiveModel {
+            id: Set(notification_id),
+            recipient_profile_id: Set(recipient),
+            kind: Set(NotificationKind::Reply),
+            source_post_id: Set(post_id),
+            read: Set(read),
+            created_at: Set(now),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        notification_id
+    }
+
+    #[tokio::test]
+    async fn test_list_and_mark_notifications() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let profile = service
+            ._create_profile(node_id, "Me".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let unread = insert_test_notification(&service, profile.id, false).await;
+        insert_test_notification(&service, profile.id, true).await;
+
+        let all = service
+            ._list_notifications(node_id, profile.id, false, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let unread_only = service
+            ._list_notifications(node_id, profile.id, true, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(unread_only.len(), 1);
+        assert_eq!(unread_only[0].id, unread);
+
+        let marked = service._mark_read(node_id, unread).await.unwrap();
+        assert!(marked.read);
+
+        let unread_only = service
+            ._list_notifications(node_id, profile.id, true, 10, 0)
+            .await
+            .unwrap();
+        assert!(unread_only.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notifications_are_owner_checked() {
+        let service = setup_test_service().await;
+        let owner = test_node_id();
+        let stranger = test_node_id();
+
+        let profile = service
+            ._create_profile(owner, "Me".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let notification = insert_test_notification(&service, profile.id, false).await;
+
+        let listed = service
+            ._list_notifications(stranger, profile.id, false, 10, 0)
+            .await;
+        assert!(matches!(listed, Err(ProfilesServiceError::ProfileNotFound)));
+
+        let marked = service._mark_read(stranger, notification).await;
+        assert!(matches!(marked, Err(ProfilesServiceError::NotificationNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_set_and_get_last_location() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+        let profile = service
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+
+        let none = service._get_last_location(node_id, profile.id).await.unwrap();
+        assert_eq!(none, None);
+
+        let group_id = GroupId::new();
+        Group::insert(GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(profile.id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        service._set_last_location(node_id, profile.id, group_id, None).await.unwrap();
+        let state = service._get_last_location(node_id, profile.id).await.unwrap().unwrap();
+        assert_eq!(state.last_group_id, Some(group_id));
+        assert_eq!(state.last_topic_id, None);
+
+        // Other nodes can neither read nor move the resume point
+        let other = test_node_id();
+        let result = service._get_last_location(other, profile.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileNotFound)));
+        let result = service._set_last_location(other, profile.id, group_id, None).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+    }
+
+    fn test_png(len: usize) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.resize(len, 7);
+        png
+    }
+
+    #[tokio::test]
+    async fn test_chunked_picture_upload() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+        let profile = service
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+
+        let picture = test_png(MAX_PICTURE_CHUNK_BYTES * 2 + 100);
+        let upload_id = service._begin_picture_upload(node_id, profile.id).await.unwrap();
+        for (seq, chunk) in picture.chunks(MAX_PICTURE_CHUNK_BYTES).enumerate() {
+            service
+                ._upload_chunk(node_id, upload_id, seq as u32, chunk.to_vec())
+                .await
+                .unwrap();
+        }
+
+        let finished = service._finish_picture_upload(node_id, upload_id).await.unwrap();
+        assert_eq!(finished.profile.picture.as_ref(), Some(&picture));
+        assert_eq!(finished.hash, blake3::hash(&picture).to_hex().to_string());
+
+        // The upload is consumed
+        let again = service._finish_picture_upload(node_id, upload_id).await;
+        assert!(matches!(again, Err(ProfilesServiceError::UploadNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_picture_upload_rejects_bad_input() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+        let profile = service
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+
+        let upload_id = service._begin_picture_upload(node_id, profile.id).await.unwrap();
+        let result = service._upload_chunk(node_id, upload_id, 1, vec![1]).await;
+        assert!(matches!(
+            result,
+            Err(ProfilesServiceError::ChunkOutOfOrder { expected: 0, got: 1 })
+        ));
+
+        // Other nodes can't touch the upload or start one for this profile
+        let other = test_node_id();
+        let result = service._upload_chunk(other, upload_id, 0, vec![1]).await;
+        assert!(matches!(result, Err(ProfilesServiceError::UploadNotFound)));
+        let result = service._begin_picture_upload(other, profile.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::NotOwner)));
+
+        service._upload_chunk(node_id, upload_id, 0, b"not an image".to_vec()).await.unwrap();
+        let result = service._finish_picture_upload(node_id, upload_id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::InvalidPicture)));
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_picture_uploads_expire() {
+        let mut service = setup_test_service().await;
+        service.upload_ttl = Duration::from_millis(20);
+        let node_id = test_node_id();
+        let profile = service
+            ._create_profile(node_id, "Test".to_string(), "Test".to_string(), None)
+            .await
+            .unwrap();
+
+        let abandoned = service._begin_picture_upload(node_id, profile.id).await.unwrap();
+        service._begin_picture_upload(node_id, profile.id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert_eq!(service.prune_picture_uploads(), 2);
+        let result = service._upload_chunk(node_id, abandoned, 0, test_png(16)).await;
+        assert!(matches!(result, Err(ProfilesServiceError::UploadNotFound)));
+    }
+
     #[tokio::test]
     async fn test_profile_unique_constraint_enforced() {
         let service = setup_test_service().await;
@@ -355,6 +2002,7 @@ mod tests {
         let identity = IdentityActiveModel {
             node_id: Set(node_id_2_bytes),
             profile_id: Set(profile.id),
+            is_default: Set(false),
         };
 
         let result = Identity::insert(identity).exec(&service.db).await;
@@ -364,4 +2012,276 @@ mod tests {
             "Should fail: profile cannot belong to multiple identities"
         );
     }
+
+    #[tokio::test]
+    async fn test_create_profile_enforces_limit() {
+        let runtime = SharedRuntimeConfig::default();
+        runtime.write().unwrap().max_profiles_per_identity = 3;
+        let service = setup_test_service().await.with_runtime_config(runtime);
+        let node_id = test_node_id();
+
+        for i in 0..3 {
+            service
+                ._create_profile(node_id, format!("Profile {i}"), "Desc".to_string(), None)
+                .await
+                .unwrap();
+        }
+
+        let result = service
+            ._create_profile(node_id, "One too many".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileLimitReached)));
+        assert_eq!(service._list_profiles(node_id).await.unwrap().len(), 3);
+
+        // The limit is per node
+        assert!(service
+            ._create_profile(test_node_id(), "Other".to_string(), "Desc".to_string(), None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_profile_is_rate_limited_per_node() {
+        use crate::service::clock::MockClock;
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let runtime = SharedRuntimeConfig::default();
+        runtime.write().unwrap().profile_creations_per_hour = 3;
+        let service = setup_test_service()
+            .await
+            .with_clock(Arc::new(clock.clone()))
+            .with_runtime_config(runtime);
+        let node_id = test_node_id();
+
+        for i in 0..3 {
+            service
+                ._create_profile(node_id, format!("Profile {i}"), "Desc".to_string(), None)
+                .await
+                .unwrap();
+        }
+        let result = service
+            ._create_profile(node_id, "Squatted".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::RateLimited)));
+
+        // Other nodes have their own bucket
+        assert!(service
+            ._create_profile(test_node_id(), "Other".to_string(), "Desc".to_string(), None)
+            .await
+            .is_ok());
+
+        // A failed creation doesn't spend a token, and a third of an hour buys one back
+        clock.advance(chrono::Duration::minutes(20));
+        assert!(matches!(
+            service
+                ._create_profile(node_id, "Other".to_string(), "Desc".to_string(), None)
+                .await,
+            Err(ProfilesServiceError::NameTaken)
+        ));
+        service
+            ._create_profile(node_id, "Squatted".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let result = service
+            ._create_profile(node_id, "Too soon".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::RateLimited)));
+
+        // The bucket is in the database, so a fresh service sees it drained
+        let restarted = ProfilesService::new(service.db.clone()).with_clock(Arc::new(clock));
+        let result = restarted
+            ._create_profile(node_id, "Too soon".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::RateLimited)));
+    }
+
+    #[tokio::test]
+    async fn test_directory_pages_searches_and_skips_pictures() {
+        let service = setup_test_service().await;
+        let picture = b"\x89PNG\r\n\x1a\nrest".to_vec();
+        for (name, picture) in [
+            ("Zelda", Some(picture)),
+            ("Malice", None),
+            ("Bob", None),
+            ("Alice", None),
+            ("100% Bob", None),
+        ] {
+            service
+                ._create_profile(test_node_id(), name.to_string(), "Desc".to_string(), picture)
+                .await
+                .unwrap();
+        }
+
+        let names = |profiles: &[ProfileSummary]| -> Vec<String> {
+            profiles.iter().map(|profile| profile.name.clone()).collect()
+        };
+        let all = service._directory(10, 0, None).await.unwrap().items;
+        assert_eq!(names(&all), ["100% Bob", "Alice", "Bob", "Malice", "Zelda"]);
+        assert_eq!(service._directory(2, 2, None).await.unwrap().items, all[2..4]);
+
+        // Only the flag comes back, never the bytes
+        let flags: Vec<bool> = all.iter().map(|profile| profile.has_picture).collect();
+        assert_eq!(flags, [false, false, false, false, true]);
+        let wire = serde_json::to_value(&all[4]).unwrap();
+        assert!(wire.get("picture").is_none());
+
+        // Prefix matches lead, then the rest by name; case doesn't matter
+        let found = service._directory(10, 0, Some(" ALI ".to_string())).await.unwrap();
+        assert_eq!(names(&found.items), ["Alice", "Malice"]);
+        let found = service._directory(10, 0, Some("b".to_string())).await.unwrap();
+        assert_eq!(names(&found.items), ["Bob", "100% Bob"]);
+
+        // Wildcards are searched for literally, and a blank search lists everyone
+        let found = service._directory(10, 0, Some("%".to_string())).await.unwrap();
+        assert_eq!(names(&found.items), ["100% Bob"]);
+        let blank = service._directory(10, 0, Some("  ".to_string())).await.unwrap();
+        assert_eq!(blank.items, all);
+    }
+
+    #[tokio::test]
+    async fn test_directory_total_counts_every_match() {
+        let service = setup_test_service().await;
+        for name in ["Ann", "Anna", "Joanne", "Bob"] {
+            service
+                ._create_profile(test_node_id(), name.to_string(), "Desc".to_string(), None)
+                .await
+                .unwrap();
+        }
+
+        let page = service._directory(2, 0, None).await.unwrap();
+        assert_eq!((page.items.len(), page.total), (2, 4));
+
+        // The total follows the search, whatever slice of it is asked for
+        let page = service._directory(2, 0, Some("ann".to_string())).await.unwrap();
+        assert_eq!((page.items.len(), page.total), (2, 3));
+        let page = service._directory(2, 2, Some("ann".to_string())).await.unwrap();
+        assert_eq!((page.items.len(), page.total), (1, 3));
+        let page = service._directory(2, 10, Some("ann".to_string())).await.unwrap();
+        assert_eq!((page.items.len(), page.total), (0, 3));
+        let page = service._directory(2, 0, Some("zed".to_string())).await.unwrap();
+        assert_eq!(page, Page { items: vec![], total: 0 });
+    }
+
+    #[tokio::test]
+    async fn test_create_profile_reports_name_taken() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        service
+            ._create_profile(node_id, "Taken".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        let result = service
+            ._create_profile(test_node_id(), "Taken".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::NameTaken)));
+        assert_eq!(Profile::find().count(&service.db).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_profile_reports_already_linked() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        service
+            ._create_profile(node_id, "First".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+
+        // Stand in for a node whose identity row already exists
+        service
+            .db
+            .execute_unprepared("CREATE UNIQUE INDEX one_profile_per_node ON identity (node_id)")
+            .await
+            .unwrap();
+        let result = service
+            ._create_profile(node_id, "Second".to_string(), "Desc".to_string(), None)
+            .await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileAlreadyLinked)));
+
+        // The profile insert rolled back with the identity
+        assert_eq!(Profile::find().count(&service.db).await.unwrap(), 1);
+        assert_eq!(service._list_profiles(node_id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_profile_validates_name() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let long_name = "x".repeat(MAX_PROFILE_NAME_CHARS + 1);
+        let error = service
+            ._create_profile(node_id, long_name, "Desc".to_string(), None)
+            .await
+            .unwrap_err();
+        let error = FieldError::from_resource_error(&error.into()).unwrap();
+        assert_eq!(error.field, "name");
+        assert!(service._list_profiles(node_id).await.unwrap().is_empty());
+
+        // Picture failures point at the picture
+        let error = ResourceError::from(ProfilesServiceError::InvalidPicture);
+        assert_eq!(FieldError::from_resource_error(&error).unwrap().field, "picture");
+    }
+
+    #[tokio::test]
+    async fn test_get_profile_node_rejects_corrupt_identity() {
+        let service = setup_test_service().await;
+        let node_id = test_node_id();
+
+        let profile = service
+            ._create_profile(node_id, "Valid".to_string(), "Desc".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(service._get_profile_node(profile.id).await.unwrap(), node_id);
+
+        // A truncated key, as a corrupt or hand-edited row would hold
+        let corrupt = ProfileActiveModel {
+            id: Set(ProfileId::new()),
+            name: Set("Corrupt".to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        };
+        let corrupt = Profile::insert(corrupt).exec_with_returning(&service.db).await.unwrap();
+        let identity = IdentityActiveModel {
+            node_id: Set(node_id.as_bytes()[..31].to_vec()),
+            profile_id: Set(corrupt.id),
+            is_default: Set(false),
+        };
+        Identity::insert(identity).exec(&service.db).await.unwrap();
+
+        let result = service._get_profile_node(corrupt.id).await;
+        assert!(matches!(result, Err(ProfilesServiceError::CorruptIdentity(_))));
+
+        let result = service._get_profile_node(ProfileId::new()).await;
+        assert!(matches!(result, Err(ProfilesServiceError::ProfileNotFound)));
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let db_error = ProfilesServiceError::DbError(DbErr::Custom("boom".to_string()));
+        let db_error = ResourceError::from(db_error);
+        assert_eq!(db_error.severity(), ErrorSeverity::Infrastructure);
+
+        for error in [
+            ProfilesServiceError::ProfileNotFound,
+            ProfilesServiceError::NotificationNotFound,
+            ProfilesServiceError::UploadNotFound,
+            ProfilesServiceError::ChunkOutOfOrder { expected: 1, got: 2 },
+            ProfilesServiceError::PictureTooLarge,
+            ProfilesServiceError::InvalidPicture,
+            ProfilesServiceError::CorruptIdentity(CorruptIdentity),
+            ProfilesServiceError::ProfileLimitReached,
+            ProfilesServiceError::Invalid(FieldError::new("name", "must not be empty")),
+            ProfilesServiceError::NameTaken,
+            ProfilesServiceError::ProfileAlreadyLinked,
+            ProfilesServiceError::RateLimited,
+            ProfilesServiceError::NotOwner,
+        ] {
+            let message = error.to_string();
+            let mapped = ResourceError::from(error);
+            assert_eq!(mapped.severity(), ErrorSeverity::Application, "{message}");
+        }
+    }
 }
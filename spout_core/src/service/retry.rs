@@ -0,0 +1,201 @@
+use std::{future::Future, time::Duration};
+
+use sea_orm::{DbErr, RuntimeErr};
+
+/// sqlite primary result codes worth another attempt
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+/// Bounds for [`with_retry`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled after every failed retry
+    pub base_delay: Duration,
+    /// Longest single wait between attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Errors that can go away by running the same operation again
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+/// Only sqlite's busy/locked codes are transient; everything else is fatal
+impl Retryable for DbErr {
+    fn is_retryable(&self) -> bool {
+        let runtime = match self {
+            DbErr::Conn(error) | DbErr::Exec(error) | DbErr::Query(error) => error,
+            _ => return false,
+        };
+        let RuntimeErr::SqlxError(sqlx::Error::Database(error)) = runtime else {
+            return false;
+        };
+
+        // Extended codes (e.g. SQLITE_BUSY_SNAPSHOT) keep the primary code in the low byte
+        error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+    }
+}
+
+/// Run `op` with [`RetryConfig::default`] bounds; see [`with_retry_config`]
+pub async fn with_retry<T, E, F, Fut>(op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Retryable,
+{
+    with_retry_config(RetryConfig::default(), op).await
+}
+
+/// Run `op`, running it again with exponential backoff while it fails with a
+/// retryable error. Gives up after `config.max_attempts` and returns the last
+/// error; fatal errors are returned straight away.
+///
+/// `op` must be safe to repeat, i.e. a whole transaction rather than part of one.
+pub async fn with_retry_config<T, E, F, Fut>(config: RetryConfig, mut op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Retryable,
+{
+    let mut delay = config.base_delay;
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(error) if error.is_retryable() && attempt < config.max_attempts => {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(config.max_delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, error::Error as StdError, fmt};
+
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    use super::*;
+
+    /// A stand-in for the sqlite error behind "database is locked"
+    #[derive(Debug)]
+    struct SimulatedDbError(&'static str);
+
+    impl fmt::Display for SimulatedDbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "simulated sqlite error {}", self.0)
+        }
+    }
+
+    impl StdError for SimulatedDbError {}
+
+    impl DatabaseError for SimulatedDbError {
+        fn message(&self) -> &str {
+            "database is locked"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn sqlite_error(code: &'static str) -> DbErr {
+        let error = sqlx::Error::Database(Box::new(SimulatedDbError(code)));
+        DbErr::Exec(RuntimeErr::SqlxError(error))
+    }
+
+    fn fast() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+        }
+    }
+
+    #[test]
+    fn test_retryable_classification() {
+        assert!(sqlite_error("5").is_retryable());
+        assert!(sqlite_error("6").is_retryable());
+        // SQLITE_BUSY_SNAPSHOT
+        assert!(sqlite_error("517").is_retryable());
+
+        // SQLITE_CONSTRAINT_UNIQUE
+        assert!(!sqlite_error("2067").is_retryable());
+        assert!(!DbErr::RecordNotFound("gone".to_string()).is_retryable());
+        assert!(!DbErr::Custom("disk full".to_string()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_recovers_from_lock_errors() {
+        let mut attempts = 0;
+        let result = with_retry_config(fast(), || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt <= 2 {
+                    Err(sqlite_error("5"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_gives_up_and_skips_fatal_errors() {
+        let mut attempts = 0;
+        let result: Result<(), DbErr> = with_retry_config(fast(), || {
+            attempts += 1;
+            async { Err(sqlite_error("5")) }
+        })
+        .await;
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(attempts, fast().max_attempts);
+
+        let mut attempts = 0;
+        let result: Result<(), DbErr> = with_retry_config(fast(), || {
+            attempts += 1;
+            async { Err(sqlite_error("2067")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1, "Fatal errors aren't retried");
+    }
+}
@@ -0,0 +1,246 @@
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+use zel_core::prelude::*;
+
+use crate::{
+    config::{self, SharedRuntimeConfig},
+    entity::prelude::*,
+    service::breaker::DbBreaker,
+};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SyncServiceError {
+    #[error("fatal database error")]
+    DbError(#[from] DbErr),
+}
+
+/// DB failures map to `ResourceError::infra`
+impl From<SyncServiceError> for ResourceError {
+    fn from(error: SyncServiceError) -> Self {
+        match error {
+            SyncServiceError::DbError(error) => ResourceError::infra(error),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
+        }
+    }
+}
+
+/// Append a change to the `change_log`.
+///
+/// Call it on the transaction doing the write, so the change is logged
+/// exactly when the write commits.
+pub(crate) async fn record_change<C: ConnectionTrait, T: Serialize>(
+    conn: &C,
+    entity: ChangeEntity,
+    op: ChangeOp,
+    entity_id: impl Into<Uuid>,
+    row: &T,
+    created_at: String,
+) -> Result<(), DbErr> {
+    let payload = serde_json::to_string(row).map_err(|error| DbErr::Json(error.to_string()))?;
+
+    ChangeLog::insert(ChangeLogActiveModel {
+        entity: Set(entity),
+        op: Set(op),
+        entity_id: Set(entity_id.into()),
+        payload: Set(payload),
+        created_at: Set(created_at),
+        ..Default::default()
+    })
+    .exec(conn)
+    .await?;
+    Ok(())
+}
+
+/// Read side of the change log, for replicating to other devices
+#[derive(Clone)]
+pub struct SyncService {
+    db: DatabaseConnection,
+    breaker: DbBreaker,
+    runtime: SharedRuntimeConfig,
+}
+
+impl SyncService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            breaker: DbBreaker::default(),
+            runtime: SharedRuntimeConfig::default(),
+        }
+    }
+
+    /// Share a circuit breaker with the other services using the same DB
+    pub fn with_breaker(mut self, breaker: DbBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Read hot-reloadable limits from `SpoutCore`'s shared runtime config
+    pub fn with_runtime_config(mut self, runtime: SharedRuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Changes appended after `after_seq`, oldest first; pass the last seen
+    /// `seq` (or 0) to resume
+    pub async fn _changes_since(
+        &self,
+        after_seq: i64,
+        limit: u64,
+    ) -> Result<Vec<ChangeLogModel>, SyncServiceError> {
+        let changes = ChangeLog::find()
+            .filter(ChangeLogColumn::Seq.gt(after_seq))
+            .order_by_asc(ChangeLogColumn::Seq)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .all(&self.db)
+            .await?;
+
+        Ok(changes)
+    }
+}
+
+#[zel_service(name = "sync")]
+trait Changes {
+    #[doc = "Group, topic and post changes after a given seq, oldest first"]
+    #[method(name = "changes_since")]
+    async fn changes_since(
+        &self,
+        after_seq: i64,
+        limit: Option<u64>,
+    ) -> Result<Vec<ChangeLogModel>, ResourceError>;
+}
+
+#[async_trait]
+impl ChangesServer for SyncService {
+    async fn changes_since(
+        &self,
+        _ctx: RequestContext,
+        after_seq: i64,
+        limit: Option<u64>,
+    ) -> Result<Vec<ChangeLogModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker.call(self._changes_since(after_seq, limit)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ids::ProfileId,
+        models::migrator::Migrator,
+        service::{groups::GroupsService, posts::PostsService, topics::TopicsService},
+    };
+    use sea_orm::Database;
+    use sea_orm_migration::MigratorTrait;
+
+    async fn setup_test_service() -> SyncService {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        SyncService::new(db)
+    }
+
+    async fn create_test_profile(service: &SyncService, name: &str) -> ProfileId {
+        let profile_id = ProfileId::new();
+        ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set(name.to_string()),
+            desc: Set("Desc".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        }
+        .insert(&service.db)
+        .await
+        .unwrap();
+        profile_id
+    }
+
+    #[tokio::test]
+    async fn test_writes_append_ordered_changes() {
+        let service = setup_test_service().await;
+        let groups = GroupsService::new(service.db.clone());
+        let topics = TopicsService::new(service.db.clone());
+        let posts = PostsService::new(service.db.clone());
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group = groups._create_group(profile_id).await.unwrap();
+        let user = groups._add_user(group.id, profile_id).await.unwrap();
+        let started = topics
+            ._create_topic_with_post(group.id, user.id, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let topic_id = started.topic.id;
+        let reply = posts
+            ._create_reply(started.post.id, user.id, "Re".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        posts
+            ._update_post(reply.id, user.id, None, Some("Edited".to_string()))
+            .await
+            .unwrap();
+        topics._rename_topic(topic_id, profile_id, "Renamed".to_string()).await.unwrap();
+        posts._soft_delete_post(reply.id, user.id).await.unwrap();
+        groups._set_archived(group.id, profile_id, true).await.unwrap();
+
+        let changes = service._changes_since(0, 100).await.unwrap();
+        let summary: Vec<(ChangeEntity, ChangeOp, Uuid)> =
+            changes.iter().map(|change| (change.entity, change.op, change.entity_id)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeEntity::Group, ChangeOp::Create, group.id.into()),
+                (ChangeEntity::Topic, ChangeOp::Create, topic_id.into()),
+                (ChangeEntity::Post, ChangeOp::Create, started.post.id.into()),
+                (ChangeEntity::Post, ChangeOp::Create, reply.id.into()),
+                (ChangeEntity::Post, ChangeOp::Update, reply.id.into()),
+                (ChangeEntity::Topic, ChangeOp::Update, topic_id.into()),
+                (ChangeEntity::Post, ChangeOp::Delete, reply.id.into()),
+                (ChangeEntity::Group, ChangeOp::Update, group.id.into()),
+            ]
+        );
+        assert!(changes.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+
+        // Payloads are the rows as of each change
+        let edited: GroupPostModel = serde_json::from_str(&changes[4].payload).unwrap();
+        assert_eq!(edited.body, "Edited");
+        let renamed: GroupTopicModel = serde_json::from_str(&changes[5].payload).unwrap();
+        assert_eq!(renamed.title, "Renamed");
+
+        // Resuming from a seq skips what was already seen
+        let resumed = service._changes_since(changes[5].seq, 100).await.unwrap();
+        assert_eq!(resumed, changes[6..]);
+        let page = service._changes_since(0, 3).await.unwrap();
+        assert_eq!(page, changes[..3]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_logs_nothing() {
+        let service = setup_test_service().await;
+        let groups = GroupsService::new(service.db.clone());
+
+        let owner = create_test_profile(&service, "Owner").await;
+        let outsider = create_test_profile(&service, "Outsider").await;
+        let group = groups._create_group(owner).await.unwrap();
+        assert!(groups._set_archived(group.id, outsider, true).await.is_err());
+        assert!(groups._delete_group(group.id, outsider).await.is_err());
+
+        let changes = service._changes_since(0, 100).await.unwrap();
+        assert_eq!(changes.len(), 1);
+
+        groups._delete_group(group.id, owner).await.unwrap();
+        let changes = service._changes_since(changes[0].seq, 100).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].entity, changes[0].op), (ChangeEntity::Group, ChangeOp::Delete));
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let mapped = ResourceError::from(SyncServiceError::DbError(DbErr::Custom("x".into())));
+        assert_eq!(mapped.severity(), ErrorSeverity::Infrastructure);
+    }
+}
@@ -0,0 +1,73 @@
+use iroh::{Endpoint, EndpointAddr};
+use thiserror::Error;
+use zel_core::prelude::*;
+
+/// A shared node address that doesn't decode
+#[derive(Debug, Error)]
+#[error("invalid node address")]
+pub struct NodeAddrParseError(#[source] serde_json::Error);
+
+/// Encode `addr` as a single line to hand out of band (invites, QR codes)
+pub fn encode_node_addr(addr: &EndpointAddr) -> String {
+    serde_json::to_string(addr).expect("EndpointAddr always serializes")
+}
+
+/// Decode what [`encode_node_addr`] produced, ready to pass to `Endpoint::connect`
+pub fn decode_node_addr(encoded: &str) -> Result<EndpointAddr, NodeAddrParseError> {
+    serde_json::from_str(encoded.trim()).map_err(NodeAddrParseError)
+}
+
+/// Facts about the running node itself, for peers and the UI
+#[derive(Clone)]
+pub struct SystemService {
+    endpoint: Endpoint,
+}
+
+impl SystemService {
+    /// `endpoint` is the server endpoint peers connect to
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self { endpoint }
+    }
+
+    /// The server's id plus the relay and direct addresses it's currently reachable on
+    pub fn _node_addr(&self) -> EndpointAddr {
+        self.endpoint.addr()
+    }
+}
+
+#[zel_service(name = "system")]
+trait System {
+    #[doc = "The server's node address, encoded for sharing out of band"]
+    #[method(name = "node_addr")]
+    async fn node_addr(&self) -> Result<String, ResourceError>;
+}
+
+#[async_trait]
+impl SystemServer for SystemService {
+    async fn node_addr(&self, _ctx: RequestContext) -> Result<String, ResourceError> {
+        Ok(encode_node_addr(&self._node_addr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::{SecretKey, TransportAddr};
+
+    #[test]
+    fn test_node_addr_round_trips() {
+        let id = SecretKey::generate(&mut rand::rng()).public();
+        let addr = EndpointAddr::from_parts(
+            id,
+            [
+                TransportAddr::Ip("127.0.0.1:4433".parse().unwrap()),
+                TransportAddr::Relay("https://relay.example.com".parse().unwrap()),
+            ],
+        );
+
+        let encoded = encode_node_addr(&addr);
+        assert!(!encoded.contains('\n'));
+        assert_eq!(decode_node_addr(&format!(" {encoded}\n")).unwrap(), addr);
+        assert!(decode_node_addr("not an address").is_err());
+    }
+}
@@ -0,0 +1,2450 @@
+use std::{collections::HashSet, sync::Arc};
+
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, IntoCondition, OnConflict, Query, SimpleExpr},
+    DatabaseConnection, FromQueryResult, JoinType, Order, RelationDef, TryInsertResult,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zel_core::prelude::*;
+
+use crate::{
+    config::{self, SharedRuntimeConfig},
+    entity::{group, group_topic, topic_tag, prelude::*},
+    ids::{GroupId, PostId, ProfileId, TopicId, UserId},
+    models::begin_write,
+    service::{
+        breaker::DbBreaker,
+        clock::{SharedClock, SystemClock},
+        posts::{enqueue_notifications, MAX_POST_BODY_CHARS, MAX_POST_TITLE_CHARS},
+        profiles::record_last_location,
+        retry::{with_retry, Retryable},
+        sync::record_change,
+        validation::{check_text, FieldError},
+    },
+};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TopicsServiceError {
+    #[error("fatal database error")]
+    DbError(#[from] DbErr),
+
+    #[error("group not found")]
+    GroupNotFound,
+
+    #[error("user not found")]
+    UserNotFound,
+
+    #[error("user is not a member of this group")]
+    NotAMember,
+
+    #[error("group is archived")]
+    GroupArchived,
+
+    #[error("topic not found")]
+    TopicNotFound,
+
+    #[error("profile not found")]
+    ProfileNotFound,
+
+    #[error("posting is frozen")]
+    Frozen,
+
+    #[error("unauthorized: not the topic creator or a group admin")]
+    Unauthorized,
+
+    #[error("topic title must be 1-{MAX_TOPIC_TITLE_CHARS} characters with no control characters")]
+    InvalidTitle,
+
+    #[error("tag must be 1-{MAX_TAG_CHARS} characters with no whitespace or control characters")]
+    InvalidTag,
+
+    #[error("pin order must list each of the group's pinned topics exactly once")]
+    PinSetMismatch,
+
+    #[error(transparent)]
+    Invalid(#[from] FieldError),
+
+    #[error("profile is banned from the group")]
+    Banned,
+}
+
+/// A locked/busy database is worth another attempt, nothing else is
+impl Retryable for TopicsServiceError {
+    fn is_retryable(&self) -> bool {
+        matches!(self, TopicsServiceError::DbError(error) if error.is_retryable())
+    }
+}
+
+/// DB failures map to `ResourceError::infra`, every domain variant to `ResourceError::app`
+impl From<TopicsServiceError> for ResourceError {
+    fn from(error: TopicsServiceError) -> Self {
+        match error {
+            TopicsServiceError::DbError(error) => ResourceError::infra(error),
+            TopicsServiceError::GroupNotFound => ResourceError::app(error),
+            TopicsServiceError::UserNotFound => ResourceError::app(error),
+            TopicsServiceError::NotAMember => ResourceError::app(error),
+            TopicsServiceError::GroupArchived => ResourceError::app(error),
+            TopicsServiceError::TopicNotFound => ResourceError::app(error),
+            TopicsServiceError::ProfileNotFound => ResourceError::app(error),
+            TopicsServiceError::Frozen => ResourceError::app(error),
+            TopicsServiceError::Unauthorized => ResourceError::app(error),
+            TopicsServiceError::InvalidTitle => ResourceError::app(error),
+            TopicsServiceError::InvalidTag => ResourceError::app(error),
+            TopicsServiceError::PinSetMismatch => ResourceError::app(error),
+            TopicsServiceError::Invalid(error) => error.into(),
+            TopicsServiceError::Banned => ResourceError::app(error),
+            // Variants added without an explicit arm surface as internal failures
+            #[allow(unreachable_patterns)]
+            _ => ResourceError::system(error),
+        }
+    }
+}
+
+/// Longest topic title accepted by the create and rename methods, in characters
+pub const MAX_TOPIC_TITLE_CHARS: usize = 200;
+
+/// Longest tag accepted by `_tag_topic`, in characters
+pub const MAX_TAG_CHARS: usize = 32;
+
+/// A freshly created topic together with its opening post
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopicWithPost {
+    pub topic: GroupTopicModel,
+    pub post: GroupPostModel,
+}
+
+/// A topic a profile started, with how many live posts it has.
+///
+/// Groups don't carry a name yet, so callers resolve `group_id` themselves.
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
+pub struct StartedTopic {
+    pub id: TopicId,
+    pub group_id: GroupId,
+    pub profile_id: ProfileId,
+    pub created_at: String,
+    pub post_count: i64,
+}
+
+/// A topic on a profile's home screen, across all of its groups
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
+pub struct RecentTopic {
+    pub id: TopicId,
+    pub group_id: GroupId,
+    pub title: String,
+    pub created_at: String,
+    /// Latest live post, or the topic's creation if it has none
+    pub last_activity_at: String,
+}
+
+/// What `delete_topic_with_stats` removed along with the topic.
+///
+/// Posts have no reactions yet, so there is nothing else to count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopicDeletion {
+    /// Every post in the topic, replies and soft-deleted ones included
+    pub posts_deleted: u64,
+    /// The replies among `posts_deleted`
+    pub replies_deleted: u64,
+}
+
+/// One thing that happened in a topic, for its timeline.
+///
+/// Pins come from the change log, so they carry no actor. Topics can't be
+/// locked yet, so there are no lock events.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimelineEvent {
+    PostCreated { post_id: PostId, user_id: UserId, at: String },
+    /// Only a post's latest edit is kept, so earlier ones don't show up
+    PostEdited { post_id: PostId, user_id: UserId, at: String },
+    Pinned { at: String },
+    Unpinned { at: String },
+}
+
+impl TimelineEvent {
+    /// When it happened, as RFC 3339
+    pub fn at(&self) -> &str {
+        match self {
+            TimelineEvent::PostCreated { at, .. }
+            | TimelineEvent::PostEdited { at, .. }
+            | TimelineEvent::Pinned { at }
+            | TimelineEvent::Unpinned { at } => at,
+        }
+    }
+}
+
+/// How `list_topics` orders the topics after the pinned ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopicSort {
+    /// Most recently created first
+    #[default]
+    Newest,
+    /// Oldest first
+    Oldest,
+    /// Latest live post first; a topic without posts counts from its creation
+    RecentlyActive,
+    /// Most live posts first, newest first among equals
+    MostPosts,
+}
+
+/// Log a topic's new state to the change log
+async fn record_topic_update<C: ConnectionTrait>(
+    conn: &C,
+    topic: &GroupTopicModel,
+    at: String,
+) -> Result<(), DbErr> {
+    record_change(conn, ChangeEntity::Topic, ChangeOp::Update, topic.id, topic, at).await
+}
+
+/// Trim a topic title, rejecting empty, overlong or control-character ones
+fn check_title(title: &str) -> Result<String, TopicsServiceError> {
+    let title = title.trim();
+    let length = title.chars().count();
+    if length == 0 || length > MAX_TOPIC_TITLE_CHARS || title.chars().any(char::is_control) {
+        return Err(TopicsServiceError::InvalidTitle);
+    }
+    Ok(title.to_string())
+}
+
+/// Trim and lowercase a tag, rejecting empty, overlong or whitespace-containing ones
+fn normalize_tag(tag: &str) -> Result<String, TopicsServiceError> {
+    let tag = tag.trim().to_lowercase();
+    let length = tag.chars().count();
+    if length == 0
+        || length > MAX_TAG_CHARS
+        || tag.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(TopicsServiceError::InvalidTag);
+    }
+    Ok(tag)
+}
+
+/// The newest live post's time, falling back to the topic's own creation.
+/// Needs `live_posts()` joined and the query grouped by topic.
+fn last_activity() -> SimpleExpr {
+    Func::coalesce([
+        Expr::col((GroupPost, GroupPostColumn::CreatedAt)).max(),
+        Expr::col((GroupTopic, GroupTopicColumn::CreatedAt)).into(),
+    ])
+    .into()
+}
+
+/// `group_topic` to its posts, skipping soft-deleted ones
+fn live_posts() -> RelationDef {
+    group_topic::Relation::GroupPost
+        .def()
+        .on_condition(|_topic, post| {
+            Expr::col((post, GroupPostColumn::DeletedAt))
+                .is_null()
+                .into_condition()
+        })
+}
+
+#[derive(Clone)]
+pub struct TopicsService {
+    db: DatabaseConnection,
+    breaker: DbBreaker,
+    clock: SharedClock,
+    runtime: SharedRuntimeConfig,
+}
+
+impl TopicsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            breaker: DbBreaker::default(),
+            clock: Arc::new(SystemClock),
+            runtime: SharedRuntimeConfig::default(),
+        }
+    }
+
+    /// Share a circuit breaker with the other services using the same DB
+    pub fn with_breaker(mut self, breaker: DbBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read hot-reloadable limits from `SpoutCore`'s shared runtime config
+    pub fn with_runtime_config(mut self, runtime: SharedRuntimeConfig) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// The underlying database connection, for queries the service doesn't cover
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    /// Reject writes while the maintenance kill switch is on
+    fn ensure_not_frozen(&self) -> Result<(), TopicsServiceError> {
+        if config::runtime(&self.runtime).posting_frozen {
+            return Err(TopicsServiceError::Frozen);
+        }
+        Ok(())
+    }
+
+    /// Create a topic and its first top-level post in a single transaction
+    pub async fn _create_topic_with_post(
+        &self,
+        group_id: GroupId,
+        author_user_id: UserId,
+        title: String,
+        body: String,
+    ) -> Result<TopicWithPost, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let runtime = config::runtime(&self.runtime);
+        let (title, body) = (runtime.post_text.apply(title), runtime.post_text.apply(body));
+        let title = check_title(&title)?;
+        // The opening post gets the same checks as `PostsService::_create_post`
+        check_text("title", &title, MAX_POST_TITLE_CHARS)?;
+        check_text("body", &body, MAX_POST_BODY_CHARS)?;
+
+        // Verify group exists and is open for new topics
+        let group = Group::find_by_id(group_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::GroupNotFound)?;
+
+        if group.archived {
+            return Err(TopicsServiceError::GroupArchived);
+        }
+
+        // Verify the author is a member of this group
+        let author = GroupUser::find_by_id(author_user_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::UserNotFound)?;
+
+        if author.group_id != group_id {
+            return Err(TopicsServiceError::NotAMember);
+        }
+        if self.is_banned(group_id, author.profile_id).await? {
+            return Err(TopicsServiceError::Banned);
+        }
+
+        let txn = begin_write(&self.db).await?;
+        let created_at = self.clock.now_rfc3339();
+
+        // Create topic
+        let topic = GroupTopicActiveModel {
+            id: Set(TopicId::new()),
+            group_id: Set(group_id),
+            profile_id: Set(author.profile_id),
+            created_at: Set(created_at.clone()),
+            title: Set(title.clone()),
+            updated_at: Set(None),
+            pin_order: Set(None),
+        };
+
+        let topic = GroupTopic::insert(topic).exec_with_returning(&txn).await?;
+
+        // Create the opening post
+        let post = GroupPostActiveModel {
+            id: Set(PostId::new()),
+            user_id: Set(author_user_id),
+            topic_id: Set(topic.id),
+            parent_post_id: Set(None), // Top-level post
+            title: Set(title),
+            body: Set(body),
+            created_at: Set(created_at),
+            deleted_at: Set(None),
+            edited_at: Set(None),
+            version: Set(1),
+            format: Set(PostFormat::Plain),
+            seq: Set(1), // Opening post of a fresh topic
+        };
+
+        let post = GroupPost::insert(post).exec_with_returning(&txn).await?;
+        enqueue_notifications(&txn, &post, None).await?;
+        let profile_id = author.profile_id;
+        record_last_location(&txn, profile_id, group_id, Some(topic.id), &post.created_at).await?;
+        let (at, op) = (&post.created_at, ChangeOp::Create);
+        record_change(&txn, ChangeEntity::Topic, op, topic.id, &topic, at.clone()).await?;
+        record_change(&txn, ChangeEntity::Post, op, post.id, &post, at.clone()).await?;
+
+        txn.commit().await?;
+        Ok(TopicWithPost { topic, post })
+    }
+
+    /// Start an empty topic in a group `profile_id` belongs to
+    pub async fn _create_topic(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+        title: String,
+    ) -> Result<GroupTopicModel, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let title = check_title(&title)?;
+
+        let group = Group::find_by_id(group_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::GroupNotFound)?;
+        if group.archived {
+            return Err(TopicsServiceError::GroupArchived);
+        }
+
+        let member = GroupUser::find()
+            .filter(GroupUserColumn::GroupId.eq(group_id))
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .one(&self.db)
+            .await?;
+        if member.is_none() {
+            return Err(TopicsServiceError::NotAMember);
+        }
+        if self.is_banned(group_id, profile_id).await? {
+            return Err(TopicsServiceError::Banned);
+        }
+
+        let txn = begin_write(&self.db).await?;
+        let created_at = self.clock.now_rfc3339();
+        let topic = GroupTopicActiveModel {
+            id: Set(TopicId::new()),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            created_at: Set(created_at.clone()),
+            title: Set(title),
+            updated_at: Set(None),
+            pin_order: Set(None),
+        };
+
+        let topic = GroupTopic::insert(topic).exec_with_returning(&txn).await?;
+        record_last_location(&txn, profile_id, group_id, Some(topic.id), &created_at).await?;
+        record_change(&txn, ChangeEntity::Topic, ChangeOp::Create, topic.id, &topic, created_at)
+            .await?;
+
+        txn.commit().await?;
+        Ok(topic)
+    }
+
+    /// Get a specific topic by ID
+    pub async fn _get_topic(
+        &self,
+        topic_id: TopicId,
+    ) -> Result<GroupTopicModel, TopicsServiceError> {
+        GroupTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::TopicNotFound)
+    }
+
+    /// A group's topics in the default `list_topics` order: pinned first, then newest
+    pub async fn _list_topics_for_group(
+        &self,
+        group_id: GroupId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<GroupTopicModel>, TopicsServiceError> {
+        self._list_topics(group_id, TopicSort::default(), limit, offset).await
+    }
+
+    /// Delete a topic with all of its posts (group admin only)
+    pub async fn _delete_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<(), TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let topic = self._get_topic(topic_id).await?;
+        if !self.is_admin(topic.group_id, actor_profile_id).await? {
+            return Err(TopicsServiceError::Unauthorized);
+        }
+
+        let txn = begin_write(&self.db).await?;
+        self.remove_topic(&txn, &topic).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Delete a topic (creator or group admin only), reporting how many posts went with it.
+    ///
+    /// The counts are taken in the deleting transaction, so they match exactly
+    /// what was removed.
+    pub async fn _delete_topic_with_stats(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<TopicDeletion, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let topic = self.managed_topic(topic_id, actor_profile_id).await?;
+
+        let txn = begin_write(&self.db).await?;
+        let posts = GroupPost::find().filter(GroupPostColumn::TopicId.eq(topic_id));
+        let stats = TopicDeletion {
+            posts_deleted: posts.clone().count(&txn).await?,
+            replies_deleted: posts
+                .filter(GroupPostColumn::ParentPostId.is_not_null())
+                .count(&txn)
+                .await?,
+        };
+        self.remove_topic(&txn, &topic).await?;
+        txn.commit().await?;
+
+        Ok(stats)
+    }
+
+    /// Delete `topic` inside `txn`. Posts, tags and subscriptions cascade;
+    /// only the topic is logged.
+    async fn remove_topic<C: ConnectionTrait>(
+        &self,
+        txn: &C,
+        topic: &GroupTopicModel,
+    ) -> Result<(), DbErr> {
+        GroupTopic::delete_by_id(topic.id).exec(txn).await?;
+        let (entity, op, now) = (ChangeEntity::Topic, ChangeOp::Delete, self.clock.now_rfc3339());
+        record_change(txn, entity, op, topic.id, topic, now).await
+    }
+
+    /// Rename a topic (creator or group admin only)
+    pub async fn _rename_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        new_title: String,
+    ) -> Result<GroupTopicModel, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let title = check_title(&new_title)?;
+
+        let topic = self.managed_topic(topic_id, actor_profile_id).await?;
+
+        let now = self.clock.now_rfc3339();
+        let mut topic_active: GroupTopicActiveModel = topic.into();
+        topic_active.title = Set(title);
+        topic_active.updated_at = Set(Some(now.clone()));
+
+        let txn = begin_write(&self.db).await?;
+        let topic = topic_active.update(&txn).await?;
+        record_topic_update(&txn, &topic, now).await?;
+        txn.commit().await?;
+        Ok(topic)
+    }
+
+    /// Tag a topic (creator or group admin only); returns the tag as stored.
+    ///
+    /// Tags are trimmed and lowercased, and tagging twice is a no-op.
+    pub async fn _tag_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        tag: String,
+    ) -> Result<String, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let tag = normalize_tag(&tag)?;
+
+        let topic = self.managed_topic(topic_id, actor_profile_id).await?;
+
+        // Tags have no change log entity of their own, so a new one logs its topic
+        let txn = begin_write(&self.db).await?;
+        let inserted = TopicTag::insert(TopicTagActiveModel {
+            topic_id: Set(topic_id),
+            tag: Set(tag.clone()),
+        })
+        .on_conflict(
+            OnConflict::columns([TopicTagColumn::TopicId, TopicTagColumn::Tag])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(&txn)
+        .await?;
+        if matches!(inserted, TryInsertResult::Inserted(_)) {
+            record_topic_update(&txn, &topic, self.clock.now_rfc3339()).await?;
+        }
+        txn.commit().await?;
+
+        Ok(tag)
+    }
+
+    /// Tags used in a group with how many topics carry each, most used first
+    pub async fn _list_group_tags(
+        &self,
+        group_id: GroupId,
+    ) -> Result<Vec<(String, u64)>, TopicsServiceError> {
+        let tags: Vec<(String, i64)> = TopicTag::find()
+            .select_only()
+            .column(TopicTagColumn::Tag)
+            .column_as(TopicTagColumn::TopicId.count(), "topic_count")
+            .join(JoinType::InnerJoin, topic_tag::Relation::GroupTopic.def())
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .group_by(TopicTagColumn::Tag)
+            .order_by_desc(TopicTagColumn::TopicId.count())
+            .order_by_asc(TopicTagColumn::Tag) // Stable order among equal counts
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(tags
+            .into_iter()
+            .map(|(tag, count)| (tag, count as u64))
+            .collect())
+    }
+
+    /// A group's topics carrying every tag (`match_all`) or any of them, newest first.
+    ///
+    /// Tags are normalized like `_tag_topic` does and duplicates ignored; no tags
+    /// matches no topics.
+    pub async fn _list_topics_by_tags(
+        &self,
+        group_id: GroupId,
+        tags: Vec<String>,
+        match_all: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<GroupTopicModel>, TopicsServiceError> {
+        let mut wanted = Vec::new();
+        for tag in &tags {
+            let tag = normalize_tag(tag)?;
+            if !wanted.contains(&tag) {
+                wanted.push(tag);
+            }
+        }
+        if wanted.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // (topic_id, tag) is unique, so a topic with all n tags has n matching rows
+        let wanted_count = wanted.len() as i64;
+        let mut tagged = Query::select();
+        tagged
+            .column(TopicTagColumn::TopicId)
+            .from(TopicTag)
+            .and_where(TopicTagColumn::Tag.is_in(wanted));
+        if match_all {
+            tagged
+                .group_by_col(TopicTagColumn::TopicId)
+                .and_having(Expr::col(TopicTagColumn::Tag).count().eq(wanted_count));
+        }
+
+        let topics = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .filter(GroupTopicColumn::Id.in_subquery(tagged.to_owned()))
+            .order_by_desc(GroupTopicColumn::CreatedAt)
+            .order_by_desc(GroupTopicColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(topics)
+    }
+
+    /// Load a topic the actor may manage: its creator or one of its group's admins
+    async fn managed_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<GroupTopicModel, TopicsServiceError> {
+        let topic = GroupTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::TopicNotFound)?;
+
+        if topic.profile_id != actor_profile_id
+            && !self.is_admin(topic.group_id, actor_profile_id).await?
+        {
+            return Err(TopicsServiceError::Unauthorized);
+        }
+
+        Ok(topic)
+    }
+
+    async fn is_admin(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, TopicsServiceError> {
+        let admin = GroupAdmin::find()
+            .filter(GroupAdminColumn::GroupId.eq(group_id))
+            .filter(GroupAdminColumn::IdentityId.eq(profile_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(admin.is_some())
+    }
+
+    async fn is_banned(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> Result<bool, TopicsServiceError> {
+        let ban = GroupBanned::find_by_id((group_id, profile_id)).one(&self.db).await?;
+        Ok(ban.is_some())
+    }
+
+    /// List a group's topics: pinned ones first in pin order, then the rest by `sort`
+    pub async fn _list_topics(
+        &self,
+        group_id: GroupId,
+        sort: TopicSort,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<GroupTopicModel>, TopicsServiceError> {
+        let query = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .order_by(GroupTopicColumn::PinOrder.is_null(), Order::Asc)
+            .order_by_asc(GroupTopicColumn::PinOrder);
+
+        let query = match sort {
+            TopicSort::Newest => query.order_by_desc(GroupTopicColumn::CreatedAt),
+            TopicSort::Oldest => query.order_by_asc(GroupTopicColumn::CreatedAt),
+            TopicSort::RecentlyActive => query
+                .join(JoinType::LeftJoin, live_posts())
+                .group_by(GroupTopicColumn::Id)
+                .order_by(last_activity(), Order::Desc),
+            TopicSort::MostPosts => query
+                .join(JoinType::LeftJoin, live_posts())
+                .group_by(GroupTopicColumn::Id)
+                .order_by_desc(GroupPostColumn::Id.count())
+                .order_by_desc(GroupTopicColumn::CreatedAt),
+        };
+
+        // Ties (and same-second creations) still come back in a stable order
+        let id_order = match sort {
+            TopicSort::Oldest => Order::Asc,
+            _ => Order::Desc,
+        };
+
+        let topics = query
+            .order_by(GroupTopicColumn::Id, id_order)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .all(&self.db)
+            .await?;
+
+        Ok(topics)
+    }
+
+    /// Count the topics in a group
+    pub async fn _count_topics_for_group(
+        &self,
+        group_id: GroupId,
+    ) -> Result<u64, TopicsServiceError> {
+        let count = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .count(&self.db)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Count the topics a profile started, across all groups
+    pub async fn _count_topics_for_profile(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<u64, TopicsServiceError> {
+        let count = GroupTopic::find()
+            .filter(GroupTopicColumn::ProfileId.eq(profile_id))
+            .count(&self.db)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// A topic's live posts, their edits and its pins, oldest first.
+    ///
+    /// Events from the same instant keep the order they were written in.
+    pub async fn _topic_timeline(
+        &self,
+        topic_id: TopicId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<TimelineEvent>, TopicsServiceError> {
+        GroupTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::TopicNotFound)?;
+
+        let posts = GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(topic_id))
+            .filter(GroupPostColumn::DeletedAt.is_null())
+            .order_by_asc(GroupPostColumn::Seq)
+            .all(&self.db)
+            .await?;
+
+        let mut events = Vec::new();
+        for post in posts {
+            events.push(TimelineEvent::PostCreated {
+                post_id: post.id,
+                user_id: post.user_id,
+                at: post.created_at,
+            });
+            if let Some(at) = post.edited_at {
+                events.push(TimelineEvent::PostEdited {
+                    post_id: post.id,
+                    user_id: post.user_id,
+                    at,
+                });
+            }
+        }
+
+        // Every change logs the whole row, so pins show up as `pin_order` appearing
+        let changes = ChangeLog::find()
+            .filter(ChangeLogColumn::Entity.eq(ChangeEntity::Topic))
+            .filter(ChangeLogColumn::EntityId.eq(uuid::Uuid::from(topic_id)))
+            .order_by_asc(ChangeLogColumn::Seq)
+            .all(&self.db)
+            .await?;
+        let mut was_pinned = false;
+        for change in changes {
+            let topic: GroupTopicModel = serde_json::from_str(&change.payload)
+                .map_err(|error| DbErr::Json(error.to_string()))?;
+            let pinned = topic.pin_order.is_some();
+            match (was_pinned, pinned) {
+                (false, true) => events.push(TimelineEvent::Pinned { at: change.created_at }),
+                (true, false) => events.push(TimelineEvent::Unpinned { at: change.created_at }),
+                _ => {}
+            }
+            was_pinned = pinned;
+        }
+
+        // Stable, so ties keep post order and edits stay after their post
+        events.sort_by(|a, b| a.at().cmp(b.at()));
+        let page_size = config::runtime(&self.runtime).page_size(limit);
+        Ok(events
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(page_size).unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Pin or unpin a topic (group admin only). Newly pinned topics go last.
+    pub async fn _set_pinned(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        pinned: bool,
+    ) -> Result<GroupTopicModel, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        let topic = GroupTopic::find_by_id(topic_id)
+            .one(&self.db)
+            .await?
+            .ok_or(TopicsServiceError::TopicNotFound)?;
+
+        if !self.is_admin(topic.group_id, actor_profile_id).await? {
+            return Err(TopicsServiceError::Unauthorized);
+        }
+        if topic.pin_order.is_some() == pinned {
+            return Ok(topic);
+        }
+
+        let txn = begin_write(&self.db).await?;
+        let pin_order = if pinned {
+            let last: Option<i64> = GroupTopic::find()
+                .select_only()
+                .column_as(GroupTopicColumn::PinOrder.max(), "last")
+                .filter(GroupTopicColumn::GroupId.eq(topic.group_id))
+                .into_tuple::<Option<i64>>()
+                .one(&txn)
+                .await?
+                .flatten();
+            Some(last.unwrap_or(0) + 1)
+        } else {
+            None
+        };
+
+        let mut topic_active: GroupTopicActiveModel = topic.into();
+        topic_active.pin_order = Set(pin_order);
+        let topic = topic_active.update(&txn).await?;
+        record_topic_update(&txn, &topic, self.clock.now_rfc3339()).await?;
+        txn.commit().await?;
+        Ok(topic)
+    }
+
+    /// Rewrite the order of a group's pinned topics (group admin only).
+    ///
+    /// `ordered_topic_ids` must name every pinned topic exactly once.
+    pub async fn _reorder_pins(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        ordered_topic_ids: Vec<TopicId>,
+    ) -> Result<Vec<GroupTopicModel>, TopicsServiceError> {
+        self.ensure_not_frozen()?;
+        if !self.is_admin(group_id, actor_profile_id).await? {
+            return Err(TopicsServiceError::Unauthorized);
+        }
+
+        let txn = begin_write(&self.db).await?;
+
+        let pinned: HashSet<TopicId> = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .filter(GroupTopicColumn::PinOrder.is_not_null())
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|topic| topic.id)
+            .collect();
+
+        let requested: HashSet<TopicId> = ordered_topic_ids.iter().copied().collect();
+        if requested.len() != ordered_topic_ids.len() || requested != pinned {
+            return Err(TopicsServiceError::PinSetMismatch);
+        }
+
+        let now = self.clock.now_rfc3339();
+        let mut reordered = Vec::with_capacity(ordered_topic_ids.len());
+        for (position, topic_id) in ordered_topic_ids.into_iter().enumerate() {
+            let topic = GroupTopicActiveModel {
+                id: Set(topic_id),
+                pin_order: Set(Some(position as i64 + 1)),
+                ..Default::default()
+            };
+            let topic = topic.update(&txn).await?;
+            record_topic_update(&txn, &topic, now.clone()).await?;
+            reordered.push(topic);
+        }
+
+        txn.commit().await?;
+        Ok(reordered)
+    }
+
+    /// Set how much a profile hears about a topic
+    pub async fn _set_subscription(
+        &self,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+        level: SubscriptionLevel,
+    ) -> Result<TopicSubscriptionModel, TopicsServiceError> {
+        if Profile::find_by_id(profile_id).one(&self.db).await?.is_none() {
+            return Err(TopicsServiceError::ProfileNotFound);
+        }
+        if GroupTopic::find_by_id(topic_id).one(&self.db).await?.is_none() {
+            return Err(TopicsServiceError::TopicNotFound);
+        }
+
+        let subscription = TopicSubscriptionActiveModel {
+            profile_id: Set(profile_id),
+            topic_id: Set(topic_id),
+            level: Set(level),
+        };
+
+        Ok(TopicSubscription::insert(subscription)
+            .on_conflict(
+                OnConflict::columns([
+                    TopicSubscriptionColumn::ProfileId,
+                    TopicSubscriptionColumn::TopicId,
+                ])
+                .update_column(TopicSubscriptionColumn::Level)
+                .to_owned(),
+            )
+            .exec_with_returning(&self.db)
+            .await?)
+    }
+
+    /// A profile's level for a topic, `All` unless it was changed
+    pub async fn _get_subscription(
+        &self,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+    ) -> Result<SubscriptionLevel, TopicsServiceError> {
+        let subscription = TopicSubscription::find_by_id((profile_id, topic_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(subscription.map(|s| s.level).unwrap_or_default())
+    }
+
+    /// List topics a profile started across all groups, newest first, with post counts
+    pub async fn _topics_started_by(
+        &self,
+        profile_id: ProfileId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<StartedTopic>, TopicsServiceError> {
+        let topics = GroupTopic::find()
+            .select_only()
+            .columns([
+                GroupTopicColumn::Id,
+                GroupTopicColumn::GroupId,
+                GroupTopicColumn::ProfileId,
+                GroupTopicColumn::CreatedAt,
+            ])
+            .column_as(GroupPostColumn::Id.count(), "post_count")
+            // Soft-deleted posts don't count
+            .join(JoinType::LeftJoin, live_posts())
+            .filter(GroupTopicColumn::ProfileId.eq(profile_id))
+            .group_by(GroupTopicColumn::Id)
+            .order_by_desc(GroupTopicColumn::CreatedAt)
+            .order_by_desc(GroupTopicColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .offset(config::page_offset(offset))
+            .into_model::<StartedTopic>()
+            .all(&self.db)
+            .await?;
+
+        Ok(topics)
+    }
+
+    /// The most recently active topics across every group `profile_id` is a
+    /// member of, skipping groups that are archived or have banned them
+    pub async fn _recent_topics_for_profile(
+        &self,
+        profile_id: ProfileId,
+        limit: u64,
+    ) -> Result<Vec<RecentTopic>, TopicsServiceError> {
+        let banned_from = Query::select()
+            .column(GroupBannedColumn::GroupId)
+            .from(GroupBanned)
+            .and_where(GroupBannedColumn::IdentityId.eq(profile_id))
+            .to_owned();
+
+        let topics = GroupTopic::find()
+            .select_only()
+            .columns([
+                GroupTopicColumn::Id,
+                GroupTopicColumn::GroupId,
+                GroupTopicColumn::Title,
+                GroupTopicColumn::CreatedAt,
+            ])
+            .column_as(last_activity(), "last_activity_at")
+            .join(JoinType::InnerJoin, group_topic::Relation::Group.def())
+            .join(JoinType::InnerJoin, group::Relation::GroupUser.def())
+            .join(JoinType::LeftJoin, live_posts())
+            .filter(GroupUserColumn::ProfileId.eq(profile_id))
+            .filter(GroupColumn::Archived.eq(false))
+            .filter(GroupTopicColumn::GroupId.not_in_subquery(banned_from))
+            .group_by(GroupTopicColumn::Id)
+            .order_by(Expr::col(Alias::new("last_activity_at")), Order::Desc)
+            .order_by_desc(GroupTopicColumn::Id)
+            .limit(config::runtime(&self.runtime).page_size(limit))
+            .into_model::<RecentTopic>()
+            .all(&self.db)
+            .await?;
+
+        Ok(topics)
+    }
+}
+
+#[zel_service(name = "topics")]
+trait Topics {
+    #[doc = "Create a topic together with its first post"]
+    #[method(name = "create_topic_with_post")]
+    async fn create_topic_with_post(
+        &self,
+        group_id: GroupId,
+        author_user_id: UserId,
+        title: String,
+        body: String,
+    ) -> Result<TopicWithPost, ResourceError>;
+
+    #[doc = "Start an empty topic in a group the profile belongs to"]
+    #[method(name = "create_topic")]
+    async fn create_topic(
+        &self,
+        group_id: GroupId,
+        profile_id: ProfileId,
+        title: String,
+    ) -> Result<GroupTopicModel, ResourceError>;
+
+    #[doc = "Get a specific topic by ID"]
+    #[method(name = "get_topic")]
+    async fn get_topic(&self, topic_id: TopicId) -> Result<GroupTopicModel, ResourceError>;
+
+    #[doc = "List a group's topics, pinned first, then newest first"]
+    #[method(name = "list_topics_for_group")]
+    async fn list_topics_for_group(
+        &self,
+        group_id: GroupId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError>;
+
+    #[doc = "Delete a topic and its posts (group admin only)"]
+    #[method(name = "delete_topic")]
+    async fn delete_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<(), ResourceError>;
+
+    #[doc = "Delete a topic (creator or group admin only) and report how many posts it took"]
+    #[method(name = "delete_topic_with_stats")]
+    async fn delete_topic_with_stats(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<TopicDeletion, ResourceError>;
+
+    #[doc = "Set how much a profile hears about a topic: all, mentions, or muted"]
+    #[method(name = "set_subscription")]
+    async fn set_subscription(
+        &self,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+        level: SubscriptionLevel,
+    ) -> Result<TopicSubscriptionModel, ResourceError>;
+
+    #[doc = "Get a profile's subscription level for a topic"]
+    #[method(name = "get_subscription")]
+    async fn get_subscription(
+        &self,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+    ) -> Result<SubscriptionLevel, ResourceError>;
+
+    #[doc = "Rename a topic (creator or group admin only)"]
+    #[method(name = "rename_topic")]
+    async fn rename_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        new_title: String,
+    ) -> Result<GroupTopicModel, ResourceError>;
+
+    #[doc = "Tag a topic (creator or group admin only); returns the normalized tag"]
+    #[method(name = "tag_topic")]
+    async fn tag_topic(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        tag: String,
+    ) -> Result<String, ResourceError>;
+
+    #[doc = "List a group's tags with their topic counts, most used first"]
+    #[method(name = "list_group_tags")]
+    async fn list_group_tags(&self, group_id: GroupId) -> Result<Vec<(String, u64)>, ResourceError>;
+
+    #[doc = "List a group's topics tagged with all (`match_all`) or any of `tags`, newest first"]
+    #[method(name = "list_topics_by_tags")]
+    async fn list_topics_by_tags(
+        &self,
+        group_id: GroupId,
+        tags: Vec<String>,
+        match_all: bool,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError>;
+
+    #[doc = "List a group's topics, pinned first in pin order, then by `sort` (default newest)"]
+    #[method(name = "list_topics")]
+    async fn list_topics(
+        &self,
+        group_id: GroupId,
+        sort: Option<TopicSort>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError>;
+
+    #[doc = "Count the topics in a group"]
+    #[method(name = "count_topics_for_group")]
+    async fn count_topics_for_group(&self, group_id: GroupId) -> Result<u64, ResourceError>;
+
+    #[doc = "Count the topics a profile started, across all groups"]
+    #[method(name = "count_topics_for_profile")]
+    async fn count_topics_for_profile(&self, profile_id: ProfileId) -> Result<u64, ResourceError>;
+
+    #[doc = "A topic's posts, edits and pins interleaved, oldest first"]
+    #[method(name = "topic_timeline")]
+    async fn topic_timeline(
+        &self,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<TimelineEvent>, ResourceError>;
+
+    #[doc = "Pin or unpin a topic (group admin only)"]
+    #[method(name = "set_pinned")]
+    async fn set_pinned(
+        &self,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        pinned: bool,
+    ) -> Result<GroupTopicModel, ResourceError>;
+
+    #[doc = "Reorder a group's pinned topics (group admin only, must list every pin)"]
+    #[method(name = "reorder_pins")]
+    async fn reorder_pins(
+        &self,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        ordered_topic_ids: Vec<TopicId>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError>;
+
+    #[doc = "List topics a profile started across groups, newest first, with post counts"]
+    #[method(name = "topics_started_by")]
+    async fn topics_started_by(
+        &self,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<StartedTopic>, ResourceError>;
+
+    #[doc = "Most recently active topics across a profile's groups, for a home screen"]
+    #[method(name = "recent_topics_for_profile")]
+    async fn recent_topics_for_profile(
+        &self,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+    ) -> Result<Vec<RecentTopic>, ResourceError>;
+}
+
+#[async_trait]
+impl TopicsServer for TopicsService {
+    async fn create_topic_with_post(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        author_user_id: UserId,
+        title: String,
+        body: String,
+    ) -> Result<TopicWithPost, ResourceError> {
+        let create =
+            || self._create_topic_with_post(group_id, author_user_id, title.clone(), body.clone());
+        self.breaker.call(with_retry(create)).await
+    }
+
+    async fn create_topic(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        profile_id: ProfileId,
+        title: String,
+    ) -> Result<GroupTopicModel, ResourceError> {
+        let create = || self._create_topic(group_id, profile_id, title.clone());
+        self.breaker.call(with_retry(create)).await
+    }
+
+    async fn get_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+    ) -> Result<GroupTopicModel, ResourceError> {
+        self.breaker.call(self._get_topic(topic_id)).await
+    }
+
+    async fn list_topics_for_group(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(self._list_topics_for_group(group_id, limit, offset))
+            .await
+    }
+
+    async fn delete_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<(), ResourceError> {
+        self.breaker.call(self._delete_topic(topic_id, actor_profile_id)).await
+    }
+
+    async fn delete_topic_with_stats(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+    ) -> Result<TopicDeletion, ResourceError> {
+        self.breaker
+            .call(self._delete_topic_with_stats(topic_id, actor_profile_id))
+            .await
+    }
+
+    async fn set_subscription(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+        level: SubscriptionLevel,
+    ) -> Result<TopicSubscriptionModel, ResourceError> {
+        self.breaker
+            .call(self._set_subscription(profile_id, topic_id, level))
+            .await
+    }
+
+    async fn get_subscription(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+        topic_id: TopicId,
+    ) -> Result<SubscriptionLevel, ResourceError> {
+        self.breaker.call(self._get_subscription(profile_id, topic_id)).await
+    }
+
+    async fn rename_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        new_title: String,
+    ) -> Result<GroupTopicModel, ResourceError> {
+        self.breaker
+            .call(self._rename_topic(topic_id, actor_profile_id, new_title))
+            .await
+    }
+
+    async fn tag_topic(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        tag: String,
+    ) -> Result<String, ResourceError> {
+        self.breaker
+            .call(self._tag_topic(topic_id, actor_profile_id, tag))
+            .await
+    }
+
+    async fn list_group_tags(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<Vec<(String, u64)>, ResourceError> {
+        self.breaker.call(self._list_group_tags(group_id)).await
+    }
+
+    async fn list_topics_by_tags(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        tags: Vec<String>,
+        match_all: bool,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let offset = offset.unwrap_or(0);
+        self.breaker
+            .call(self._list_topics_by_tags(group_id, tags, match_all, limit, offset))
+            .await
+    }
+
+    async fn list_topics(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        sort: Option<TopicSort>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        let sort = sort.unwrap_or_default();
+        self.breaker
+            .call(self._list_topics(group_id, sort, limit, offset.unwrap_or(0)))
+            .await
+    }
+
+    async fn count_topics_for_group(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+    ) -> Result<u64, ResourceError> {
+        self.breaker.call(self._count_topics_for_group(group_id)).await
+    }
+
+    async fn count_topics_for_profile(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+    ) -> Result<u64, ResourceError> {
+        self.breaker.call(self._count_topics_for_profile(profile_id)).await
+    }
+
+    async fn topic_timeline(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<TimelineEvent>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._topic_timeline(topic_id, limit, offset.unwrap_or(0)))
+            .await
+    }
+
+    async fn set_pinned(
+        &self,
+        _ctx: RequestContext,
+        topic_id: TopicId,
+        actor_profile_id: ProfileId,
+        pinned: bool,
+    ) -> Result<GroupTopicModel, ResourceError> {
+        self.breaker
+            .call(self._set_pinned(topic_id, actor_profile_id, pinned))
+            .await
+    }
+
+    async fn reorder_pins(
+        &self,
+        _ctx: RequestContext,
+        group_id: GroupId,
+        actor_profile_id: ProfileId,
+        ordered_topic_ids: Vec<TopicId>,
+    ) -> Result<Vec<GroupTopicModel>, ResourceError> {
+        self.breaker
+            .call(self._reorder_pins(group_id, actor_profile_id, ordered_topic_ids))
+            .await
+    }
+
+    async fn topics_started_by(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<StartedTopic>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._topics_started_by(profile_id, limit, offset.unwrap_or(0)))
+            .await
+    }
+
+    async fn recent_topics_for_profile(
+        &self,
+        _ctx: RequestContext,
+        profile_id: ProfileId,
+        limit: Option<u64>,
+    ) -> Result<Vec<RecentTopic>, ResourceError> {
+        let limit = config::runtime(&self.runtime).limit_or_default(limit);
+        self.breaker
+            .call(self._recent_topics_for_profile(profile_id, limit))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::migrator::Migrator;
+    use sea_orm::{ConnectionTrait, Database};
+    use sea_orm_migration::MigratorTrait;
+
+    async fn setup_test_service() -> TopicsService {
+        let db = Database::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to run migrations");
+
+        TopicsService::new(db)
+    }
+
+    async fn create_test_profile(service: &TopicsService, name: &str) -> ProfileId {
+        let profile_id = ProfileId::new();
+        let profile = ProfileActiveModel {
+            id: Set(profile_id),
+            name: Set(name.to_string()),
+            desc: Set("Test".to_string()),
+            picture: Set(None),
+            created_at: Set(None),
+            owner_node_id: Set(None),
+        };
+        Profile::insert(profile).exec(&service.db).await.unwrap();
+        profile_id
+    }
+
+    async fn create_test_group(service: &TopicsService, profile_id: ProfileId) -> GroupId {
+        let group_id = GroupId::new();
+        let group = GroupActiveModel {
+            id: Set(group_id),
+            profile_id: Set(profile_id),
+            archived: Set(false),
+            archived_at: Set(None),
+            created_at: Set(None),
+        };
+        Group::insert(group).exec(&service.db).await.unwrap();
+        group_id
+    }
+
+    async fn create_test_user(
+        service: &TopicsService,
+        group_id: GroupId,
+        profile_id: ProfileId,
+    ) -> UserId {
+        let user_id = UserId::new();
+        let user = GroupUserActiveModel {
+            id: Set(user_id),
+            group_id: Set(group_id),
+            profile_id: Set(profile_id),
+            nickname: Set(None),
+            created_at: Set(None),
+        };
+        GroupUser::insert(user).exec(&service.db).await.unwrap();
+        user_id
+    }
+
+    #[tokio::test]
+    async fn test_create_get_list_and_delete_topic() {
+        let service = setup_test_service().await;
+
+        let admin = create_test_profile(&service, "Admin").await;
+        let member = create_test_profile(&service, "Member").await;
+        let outsider = create_test_profile(&service, "Outsider").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, member).await;
+
+        let topic = service._create_topic(group_id, member, "  First  ".to_string()).await.unwrap();
+        assert_eq!((topic.group_id, topic.profile_id), (group_id, member));
+        assert_eq!(topic.title, "First");
+        assert_eq!(service._get_topic(topic.id).await.unwrap(), topic);
+
+        // Only members may start topics, and only with a real title
+        assert!(matches!(
+            service._create_topic(group_id, outsider, "Nope".to_string()).await,
+            Err(TopicsServiceError::NotAMember)
+        ));
+        assert!(matches!(
+            service._create_topic(group_id, member, " ".to_string()).await,
+            Err(TopicsServiceError::InvalidTitle)
+        ));
+        assert!(matches!(
+            service._create_topic(GroupId::new(), member, "Nope".to_string()).await,
+            Err(TopicsServiceError::GroupNotFound)
+        ));
+
+        let second = service
+            ._create_topic_with_post(group_id, user_id, "Second".to_string(), "Body".to_string())
+            .await
+            .unwrap()
+            .topic;
+        let listed = service._list_topics_for_group(group_id, 10, 0).await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(service._list_topics_for_group(group_id, 1, 1).await.unwrap(), listed[1..]);
+
+        // Not even the creator may delete without being an admin
+        assert!(matches!(
+            service._delete_topic(second.id, member).await,
+            Err(TopicsServiceError::Unauthorized)
+        ));
+        service._delete_topic(second.id, admin).await.unwrap();
+        assert!(matches!(
+            service._get_topic(second.id).await,
+            Err(TopicsServiceError::TopicNotFound)
+        ));
+        assert_eq!(GroupPost::find().count(&service.db).await.unwrap(), 0);
+        assert_eq!(service._list_topics_for_group(group_id, 10, 0).await.unwrap(), vec![topic]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_topic_with_stats_counts_what_existed() {
+        use crate::service::posts::PostsService;
+
+        let service = setup_test_service().await;
+        let posts = PostsService::new(service.db.clone());
+        let creator = create_test_profile(&service, "Creator").await;
+        let other = create_test_profile(&service, "Other").await;
+        let group_id = create_test_group(&service, creator).await;
+        let user_id = create_test_user(&service, group_id, creator).await;
+        create_test_user(&service, group_id, other).await;
+
+        let started = service
+            ._create_topic_with_post(group_id, user_id, "Doomed".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let topic_id = started.topic.id;
+        let (title, body) = ("Title".to_string(), "Body".to_string());
+        posts._create_post(user_id, topic_id, title.clone(), body.clone()).await.unwrap();
+        let parent_id = started.post.id;
+        for _ in 0..3 {
+            posts._create_reply(parent_id, user_id, title.clone(), body.clone()).await.unwrap();
+        }
+        let gone = posts._create_reply(parent_id, user_id, title, body).await.unwrap();
+        posts._soft_delete_post(gone.id, user_id).await.unwrap();
+
+        let kept = service
+            ._create_topic_with_post(group_id, user_id, "Kept".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service._delete_topic_with_stats(topic_id, other).await,
+            Err(TopicsServiceError::Unauthorized)
+        ));
+        let stats = service._delete_topic_with_stats(topic_id, creator).await.unwrap();
+        assert_eq!(
+            stats,
+            TopicDeletion {
+                posts_deleted: 6,
+                replies_deleted: 4,
+            }
+        );
+
+        // Only the other topic's post is left
+        let remaining = GroupPost::find().all(&service.db).await.unwrap();
+        assert_eq!(remaining, vec![kept.post]);
+        assert!(matches!(
+            service._delete_topic_with_stats(topic_id, creator).await,
+            Err(TopicsServiceError::TopicNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        let created = service
+            ._create_topic_with_post(
+                group_id,
+                user_id,
+                "First Post".to_string(),
+                "Kicking things off".to_string(),
+            )
+            .await
+            .expect("Failed to create topic with post");
+
+        assert_eq!(created.topic.group_id, group_id);
+        assert_eq!(created.topic.profile_id, profile_id);
+        assert_eq!(created.post.topic_id, created.topic.id);
+        assert_eq!(created.post.user_id, user_id);
+        assert_eq!(created.post.parent_post_id, None);
+        assert_eq!(created.post.title, "First Post");
+
+        let posts = GroupPost::find()
+            .filter(GroupPostColumn::TopicId.eq(created.topic.id))
+            .all(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_rejects_non_member() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let other_group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, other_group_id, profile_id).await;
+
+        let result = service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await;
+
+        assert!(matches!(result, Err(TopicsServiceError::NotAMember)));
+    }
+
+    #[tokio::test]
+    async fn test_list_group_tags() {
+        let service = setup_test_service().await;
+
+        let creator = create_test_profile(&service, "Creator").await;
+        let outsider = create_test_profile(&service, "Outsider").await;
+        let group_id = create_test_group(&service, creator).await;
+        let other_group_id = create_test_group(&service, creator).await;
+        let user_id = create_test_user(&service, group_id, creator).await;
+        let other_user_id = create_test_user(&service, other_group_id, creator).await;
+
+        let mut topics = Vec::new();
+        for i in 0..3 {
+            let created = service
+                ._create_topic_with_post(group_id, user_id, format!("Topic {i}"), "Body".into())
+                .await
+                .unwrap();
+            topics.push(created.topic.id);
+        }
+        let elsewhere = service
+            ._create_topic_with_post(other_group_id, other_user_id, "Other".into(), "Body".into())
+            .await
+            .unwrap()
+            .topic
+            .id;
+
+        for (topic_id, tag) in [
+            (topics[0], "rust"),
+            (topics[1], " Rust "),
+            (topics[2], "rust"),
+            (topics[0], "help"),
+            (topics[1], "help"),
+            (topics[2], "meta"),
+            (topics[2], "meta"), // Re-tagging counts once
+            (elsewhere, "meta"),
+        ] {
+            service._tag_topic(topic_id, creator, tag.to_string()).await.unwrap();
+        }
+
+        let tags = service._list_group_tags(group_id).await.unwrap();
+        assert_eq!(
+            tags,
+            vec![("rust".to_string(), 3), ("help".to_string(), 2), ("meta".to_string(), 1)]
+        );
+
+        // Each new tag is logged as a topic update; re-tagging isn't
+        let logged = ChangeLog::find()
+            .filter(ChangeLogColumn::EntityId.eq(uuid::Uuid::from(topics[2])))
+            .filter(ChangeLogColumn::Op.eq(ChangeOp::Update))
+            .count(&service.db)
+            .await
+            .unwrap();
+        assert_eq!(logged, 2);
+
+        let result = service._tag_topic(topics[0], outsider, "spam".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::Unauthorized)));
+        let result = service._tag_topic(topics[0], creator, "two words".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::InvalidTag)));
+    }
+
+    /// Four topics in one group tagged {rust, help}, {rust}, {help, meta} and {},
+    /// plus one tagged {rust, help} in another group
+    async fn setup_tagged_topics() -> (TopicsService, GroupId, Vec<TopicId>) {
+        let service = setup_test_service().await;
+        let creator = create_test_profile(&service, "Creator").await;
+        let group_id = create_test_group(&service, creator).await;
+        let other_group_id = create_test_group(&service, creator).await;
+        let user_id = create_test_user(&service, group_id, creator).await;
+        let other_user_id = create_test_user(&service, other_group_id, creator).await;
+
+        let mut topics = Vec::new();
+        for i in 0..4 {
+            let created = service
+                ._create_topic_with_post(group_id, user_id, format!("Topic {i}"), "Body".into())
+                .await
+                .unwrap();
+            topics.push(created.topic.id);
+        }
+        let elsewhere = service
+            ._create_topic_with_post(other_group_id, other_user_id, "Other".into(), "Body".into())
+            .await
+            .unwrap()
+            .topic
+            .id;
+
+        for (topic_id, tag) in [
+            (topics[0], "rust"),
+            (topics[0], "help"),
+            (topics[1], "rust"),
+            (topics[2], "help"),
+            (topics[2], "meta"),
+            (elsewhere, "rust"),
+            (elsewhere, "help"),
+        ] {
+            service._tag_topic(topic_id, creator, tag.to_string()).await.unwrap();
+        }
+        (service, group_id, topics)
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    fn topic_ids(topics: &[GroupTopicModel]) -> HashSet<TopicId> {
+        topics.iter().map(|topic| topic.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_topics_by_tags_match_all() {
+        let (service, group_id, topics) = setup_tagged_topics().await;
+
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&["rust", "help"]), true, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(topic_ids(&found), HashSet::from([topics[0]]));
+
+        // Input is normalized and deduplicated before counting
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&[" RUST", "rust", "Help "]), true, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(topic_ids(&found), HashSet::from([topics[0]]));
+
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&["rust"]), true, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(topic_ids(&found), HashSet::from([topics[0], topics[1]]));
+
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&["rust", "meta"]), true, 10, 0)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_topics_by_tags_match_any() {
+        let (service, group_id, topics) = setup_tagged_topics().await;
+
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&["rust", "meta"]), false, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(topic_ids(&found), HashSet::from([topics[0], topics[1], topics[2]]));
+        assert!(found.windows(2).all(|pair| pair[0].created_at >= pair[1].created_at));
+
+        let page = service
+            ._list_topics_by_tags(group_id, tags(&["rust", "meta"]), false, 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(page, found[1..]);
+
+        let found = service
+            ._list_topics_by_tags(group_id, tags(&["help", "HELP"]), false, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(topic_ids(&found), HashSet::from([topics[0], topics[2]]));
+
+        let found = service._list_topics_by_tags(group_id, vec![], false, 10, 0).await.unwrap();
+        assert!(found.is_empty());
+        let result = service._list_topics_by_tags(group_id, tags(&["a b"]), false, 10, 0).await;
+        assert!(matches!(result, Err(TopicsServiceError::InvalidTag)));
+    }
+
+    #[tokio::test]
+    async fn test_reorder_pins() {
+        let service = setup_test_service().await;
+
+        let admin = create_test_profile(&service, "Admin").await;
+        let member = create_test_profile(&service, "Member").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, member).await;
+
+        let mut topics = Vec::new();
+        for i in 0..4 {
+            let created = service
+                ._create_topic_with_post(group_id, user_id, format!("Topic {i}"), "Body".into())
+                .await
+                .unwrap();
+            topics.push(created.topic.id);
+        }
+        let (a, b, c, unpinned) = (topics[0], topics[1], topics[2], topics[3]);
+
+        for topic_id in [a, b, c] {
+            service._set_pinned(topic_id, admin, true).await.unwrap();
+        }
+        let result = service._set_pinned(unpinned, member, true).await;
+        assert!(matches!(result, Err(TopicsServiceError::Unauthorized)));
+
+        service._reorder_pins(group_id, admin, vec![c, a, b]).await.unwrap();
+
+        let listed: Vec<TopicId> = service
+            ._list_topics(group_id, TopicSort::Newest, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|topic| topic.id)
+            .collect();
+        assert_eq!(listed, vec![c, a, b, unpinned]);
+
+        // Every pin, each once, and only by admins
+        for ordered in [vec![c, a], vec![c, a, b, unpinned], vec![c, a, a]] {
+            let result = service._reorder_pins(group_id, admin, ordered).await;
+            assert!(matches!(result, Err(TopicsServiceError::PinSetMismatch)));
+        }
+        let result = service._reorder_pins(group_id, member, vec![a, b, c]).await;
+        assert!(matches!(result, Err(TopicsServiceError::Unauthorized)));
+
+        // Unpinning drops a topic back among the unpinned ones
+        service._set_pinned(a, admin, false).await.unwrap();
+        let listed = service._list_topics(group_id, TopicSort::Newest, 10, 0).await.unwrap();
+        assert_eq!(listed[0].id, c);
+        assert_eq!(listed[1].id, b);
+        assert_eq!(listed[2].pin_order, None);
+    }
+
+    #[tokio::test]
+    async fn test_topic_timeline_merges_posts_and_pins() {
+        use crate::service::{
+            clock::{Clock, MockClock},
+            posts::PostsService,
+        };
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let posts = PostsService::new(service.db.clone()).with_clock(Arc::new(clock.clone()));
+
+        let admin = create_test_profile(&service, "Admin").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, admin).await;
+
+        clock.advance(chrono::Duration::seconds(1));
+        let started = service
+            ._create_topic_with_post(group_id, user_id, "Title".into(), "Body".into())
+            .await
+            .unwrap();
+        let (topic_id, opening) = (started.topic.id, started.post);
+        clock.advance(chrono::Duration::seconds(1));
+        let reply = posts
+            ._create_reply(opening.id, user_id, "Re".into(), "Body".into())
+            .await
+            .unwrap();
+        clock.advance(chrono::Duration::seconds(1));
+        service._set_pinned(topic_id, admin, true).await.unwrap();
+        let pinned_at = clock.now_rfc3339();
+        clock.advance(chrono::Duration::seconds(1));
+        let edited = posts._update_post(opening.id, user_id, None, Some("Edited".into())).await;
+        let edited_at = edited.unwrap().edited_at.unwrap();
+        clock.advance(chrono::Duration::seconds(1));
+        service._set_pinned(topic_id, admin, false).await.unwrap();
+        let unpinned_at = clock.now_rfc3339();
+
+        // Deleted posts drop out, and so does renaming
+        clock.advance(chrono::Duration::seconds(1));
+        let doomed = posts._create_reply(opening.id, user_id, "Re".into(), "Gone".into()).await;
+        posts._soft_delete_post(doomed.unwrap().id, user_id).await.unwrap();
+        service._rename_topic(topic_id, admin, "Renamed".into()).await.unwrap();
+
+        let timeline = service._topic_timeline(topic_id, 10, 0).await.unwrap();
+        let expected = vec![
+            TimelineEvent::PostCreated {
+                post_id: opening.id,
+                user_id,
+                at: opening.created_at,
+            },
+            TimelineEvent::PostCreated {
+                post_id: reply.id,
+                user_id,
+                at: reply.created_at,
+            },
+            TimelineEvent::Pinned { at: pinned_at },
+            TimelineEvent::PostEdited {
+                post_id: opening.id,
+                user_id,
+                at: edited_at,
+            },
+            TimelineEvent::Unpinned { at: unpinned_at },
+        ];
+        assert_eq!(timeline, expected);
+
+        let page = service._topic_timeline(topic_id, 2, 2).await.unwrap();
+        assert_eq!(page, expected[2..4]);
+        let result = service._topic_timeline(TopicId::new(), 10, 0).await;
+        assert!(matches!(result, Err(TopicsServiceError::TopicNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_count_topics_for_group_and_profile() {
+        let service = setup_test_service().await;
+
+        let alice = create_test_profile(&service, "Alice").await;
+        let bob = create_test_profile(&service, "Bob").await;
+        let first = create_test_group(&service, alice).await;
+        let second = create_test_group(&service, alice).await;
+        let empty = create_test_group(&service, alice).await;
+
+        let seeded = [(first, alice, 2), (first, bob, 1), (second, alice, 3)];
+        for (group_id, profile_id, count) in seeded {
+            let user_id = create_test_user(&service, group_id, profile_id).await;
+            for i in 0..count {
+                service
+                    ._create_topic_with_post(group_id, user_id, format!("Topic {i}"), "Body".into())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(service._count_topics_for_group(first).await.unwrap(), 3);
+        assert_eq!(service._count_topics_for_group(second).await.unwrap(), 3);
+        assert_eq!(service._count_topics_for_group(empty).await.unwrap(), 0);
+        assert_eq!(service._count_topics_for_group(GroupId::new()).await.unwrap(), 0);
+
+        assert_eq!(service._count_topics_for_profile(alice).await.unwrap(), 5);
+        assert_eq!(service._count_topics_for_profile(bob).await.unwrap(), 1);
+        assert_eq!(service._count_topics_for_profile(ProfileId::new()).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_topics_sorts() {
+        use crate::service::{clock::MockClock, posts::PostsService};
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let posts = PostsService::new(service.db.clone()).with_clock(Arc::new(clock.clone()));
+
+        let profile_id = create_test_profile(&service, "Author").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        let mut topics = Vec::new();
+        for title in ["A", "B", "C"] {
+            clock.advance(chrono::Duration::seconds(1));
+            let created = service
+                ._create_topic_with_post(group_id, user_id, title.into(), "Body".into())
+                .await
+                .unwrap();
+            topics.push(created.topic.id);
+        }
+        let (a, b, c) = (topics[0], topics[1], topics[2]);
+
+        // A ends up with the most posts, B with the latest one
+        for topic_id in [a, a, b] {
+            clock.advance(chrono::Duration::seconds(1));
+            posts._create_post(user_id, topic_id, "Re".into(), "Body".into()).await.unwrap();
+        }
+
+        for (sort, expected) in [
+            (TopicSort::Newest, [c, b, a]),
+            (TopicSort::Oldest, [a, b, c]),
+            (TopicSort::RecentlyActive, [b, a, c]),
+            (TopicSort::MostPosts, [a, b, c]),
+        ] {
+            let listed: Vec<TopicId> = service
+                ._list_topics(group_id, sort, 10, 0)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|topic| topic.id)
+                .collect();
+            assert_eq!(listed, expected, "{sort:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recent_topics_for_profile() {
+        use crate::service::{clock::MockClock, posts::PostsService};
+
+        let clock = MockClock::new(chrono::Utc::now());
+        let service = setup_test_service().await.with_clock(Arc::new(clock.clone()));
+        let posts = PostsService::new(service.db.clone()).with_clock(Arc::new(clock.clone()));
+
+        let reader = create_test_profile(&service, "Reader").await;
+        let mut groups = Vec::new();
+        for _ in 0..4 {
+            let group_id = create_test_group(&service, reader).await;
+            let user_id = create_test_user(&service, group_id, reader).await;
+            groups.push((group_id, user_id));
+        }
+        let [(first, first_user), (second, second_user), archived_pair, banned_pair] = groups[..]
+        else {
+            unreachable!()
+        };
+        let (archived, archived_user) = archived_pair;
+        let (banned, banned_user) = banned_pair;
+        let outsider = create_test_profile(&service, "Outsider").await;
+        let elsewhere = create_test_group(&service, outsider).await;
+        let outsider_user = create_test_user(&service, elsewhere, outsider).await;
+
+        let create = async |group_id, user_id, title: &str| {
+            clock.advance(chrono::Duration::seconds(1));
+            service
+                ._create_topic_with_post(group_id, user_id, title.into(), "Body".into())
+                .await
+                .unwrap()
+                .topic
+                .id
+        };
+        let old = create(first, first_user, "Old").await;
+        let middle = create(second, second_user, "Middle").await;
+        let newest = create(first, first_user, "Newest").await;
+        create(archived, archived_user, "Archived").await;
+        create(banned, banned_user, "Banned").await;
+        create(elsewhere, outsider_user, "Not a member").await;
+
+        // A reply makes the oldest topic the most recently active one
+        clock.advance(chrono::Duration::seconds(1));
+        posts._create_post(first_user, old, "Re".into(), "Body".into()).await.unwrap();
+
+        Group::update_many()
+            .col_expr(GroupColumn::Archived, Expr::value(true))
+            .filter(GroupColumn::Id.eq(archived))
+            .exec(&service.db)
+            .await
+            .unwrap();
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(banned),
+            identity_id: Set(reader),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let recent = service._recent_topics_for_profile(reader, 10).await.unwrap();
+        let ids: Vec<TopicId> = recent.iter().map(|topic| topic.id).collect();
+        assert_eq!(ids, vec![old, newest, middle]);
+        assert_eq!(recent[0].title, "Old");
+        assert_eq!(recent[0].group_id, first);
+        assert!(recent[0].last_activity_at > recent[0].created_at);
+
+        let limited = service._recent_topics_for_profile(reader, 2).await.unwrap();
+        assert_eq!(limited.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rename_topic() {
+        let service = setup_test_service().await;
+
+        let creator = create_test_profile(&service, "Creator").await;
+        let admin = create_test_profile(&service, "Admin").await;
+        let outsider = create_test_profile(&service, "Outsider").await;
+        let group_id = create_test_group(&service, admin).await;
+        GroupAdmin::insert(GroupAdminActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(admin),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+        let user_id = create_test_user(&service, group_id, creator).await;
+
+        let created = service
+            ._create_topic_with_post(group_id, user_id, "Original".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        assert_eq!(created.topic.title, "Original");
+        assert_eq!(created.topic.updated_at, None);
+        let topic_id = created.topic.id;
+
+        // Creator
+        let renamed = service
+            ._rename_topic(topic_id, creator, "  By creator  ".to_string())
+            .await
+            .unwrap();
+        assert_eq!(renamed.title, "By creator");
+        assert!(renamed.updated_at.is_some());
+
+        // Group admin
+        let renamed = service
+            ._rename_topic(topic_id, admin, "By admin".to_string())
+            .await
+            .unwrap();
+        assert_eq!(renamed.title, "By admin");
+
+        // Anyone else
+        let result = service._rename_topic(topic_id, outsider, "Hijacked".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::Unauthorized)));
+
+        let too_long = "x".repeat(MAX_TOPIC_TITLE_CHARS + 1);
+        for title in ["   ".to_string(), "a\nb".to_string(), too_long] {
+            let result = service._rename_topic(topic_id, creator, title).await;
+            assert!(matches!(result, Err(TopicsServiceError::InvalidTitle)));
+        }
+        // The limit counts characters, not bytes
+        let title = "é".repeat(MAX_TOPIC_TITLE_CHARS);
+        assert!(service._rename_topic(topic_id, creator, title).await.is_ok());
+
+        let result = service._rename_topic(TopicId::new(), creator, "Missing".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::TopicNotFound)));
+
+        let stored = GroupTopic::find_by_id(topic_id).one(&service.db).await.unwrap().unwrap();
+        assert_eq!(stored.title, "é".repeat(MAX_TOPIC_TITLE_CHARS));
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_validates_title() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        let too_long = "x".repeat(MAX_TOPIC_TITLE_CHARS + 1);
+        for title in ["   ".to_string(), "a\nb".to_string(), too_long] {
+            let result = service
+                ._create_topic_with_post(group_id, user_id, title, "Body".to_string())
+                .await;
+            assert!(matches!(result, Err(TopicsServiceError::InvalidTitle)));
+        }
+        assert_eq!(GroupTopic::find().count(&service.db).await.unwrap(), 0);
+
+        let created = service
+            ._create_topic_with_post(group_id, user_id, "  Title  ".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        assert_eq!(created.topic.title, "Title");
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_validates_body() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        let too_long = "x".repeat(MAX_POST_BODY_CHARS + 1);
+        for body in ["  ".to_string(), too_long] {
+            let result = service
+                ._create_topic_with_post(group_id, user_id, "Title".to_string(), body)
+                .await;
+            assert!(matches!(result, Err(TopicsServiceError::Invalid(e)) if e.field == "body"));
+        }
+        assert_eq!(GroupPost::find().count(&service.db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_rejects_banned_member() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        // Banned after joining; the membership row is still there
+        GroupBanned::insert(GroupBannedActiveModel {
+            group_id: Set(group_id),
+            identity_id: Set(profile_id),
+        })
+        .exec(&service.db)
+        .await
+        .unwrap();
+
+        let result = service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(TopicsServiceError::Banned)));
+        let result = service._create_topic(group_id, profile_id, "Title".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::Banned)));
+        assert_eq!(GroupTopic::find().count(&service.db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_rejects_while_frozen() {
+        let runtime = SharedRuntimeConfig::default();
+        let service = setup_test_service().await.with_runtime_config(runtime.clone());
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+        let existing = service
+            ._create_topic_with_post(group_id, user_id, "Before".to_string(), "Body".to_string())
+            .await
+            .unwrap()
+            .topic;
+
+        runtime.write().unwrap().posting_frozen = true;
+        let result = service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(TopicsServiceError::Frozen)));
+
+        // Existing topics can't be renamed or deleted either
+        let result = service._rename_topic(existing.id, profile_id, "Renamed".to_string()).await;
+        assert!(matches!(result, Err(TopicsServiceError::Frozen)));
+        let result = service._delete_topic(existing.id, profile_id).await;
+        assert!(matches!(result, Err(TopicsServiceError::Frozen)));
+        let result = service._delete_topic_with_stats(existing.id, profile_id).await;
+        assert!(matches!(result, Err(TopicsServiceError::Frozen)));
+        assert_eq!(service._get_topic(existing.id).await.unwrap(), existing);
+
+        runtime.write().unwrap().posting_frozen = false;
+        assert!(service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await
+            .is_ok());
+        let renamed = service._rename_topic(existing.id, profile_id, "Renamed".to_string()).await;
+        assert!(renamed.is_ok());
+        assert!(service._delete_topic_with_stats(existing.id, profile_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_rejects_archived_group() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        let mut group: GroupActiveModel = Group::find_by_id(group_id)
+            .one(&service.db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        group.archived = Set(true);
+        group.update(&service.db).await.unwrap();
+
+        let result = service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(TopicsServiceError::GroupArchived)));
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_post_rolls_back_on_post_failure() {
+        let service = setup_test_service().await;
+
+        let profile_id = create_test_profile(&service, "Test User").await;
+        let group_id = create_test_group(&service, profile_id).await;
+        let user_id = create_test_user(&service, group_id, profile_id).await;
+
+        // Make the post insert fail after the topic insert has succeeded
+        service
+            .db
+            .execute_unprepared("DROP TABLE group_post")
+            .await
+            .unwrap();
+
+        let result = service
+            ._create_topic_with_post(group_id, user_id, "Title".to_string(), "Body".to_string())
+            .await;
+        assert!(matches!(result, Err(TopicsServiceError::DbError(_))));
+
+        // The topic must not be left behind
+        let topics = GroupTopic::find()
+            .filter(GroupTopicColumn::GroupId.eq(group_id))
+            .all(&service.db)
+            .await
+            .unwrap();
+        assert!(topics.is_empty(), "Topic insert should be rolled back");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_levels_filter_notifications() {
+        use crate::service::posts::PostsService;
+
+        let service = setup_test_service().await;
+        let posts = PostsService::new(service.db.clone());
+
+        let author = create_test_profile(&service, "author").await;
+        let replier = create_test_profile(&service, "replier").await;
+        let group_id = create_test_group(&service, author).await;
+        let author_user = create_test_user(&service, group_id, author).await;
+        let replier_user = create_test_user(&service, group_id, replier).await;
+
+        let created = service
+            ._create_topic_with_post(group_id, author_user, "Title".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let topic_id = created.topic.id;
+        assert_eq!(
+            service._get_subscription(author, topic_id).await.unwrap(),
+            SubscriptionLevel::All
+        );
+
+        let reply = |body: &str| {
+            posts._create_reply(created.post.id, replier_user, "Re".to_string(), body.to_string())
+        };
+        let inbox = || async {
+            Notification::find()
+                .filter(NotificationColumn::RecipientProfileId.eq(author))
+                .count(&service.db)
+                .await
+                .unwrap()
+        };
+
+        reply("first").await.unwrap();
+        assert_eq!(inbox().await, 1, "`all` delivers replies");
+
+        service._set_subscription(author, topic_id, SubscriptionLevel::Muted).await.unwrap();
+        reply("muted").await.unwrap();
+        reply("@author muted").await.unwrap();
+        assert_eq!(inbox().await, 1, "`muted` suppresses everything");
+
+        service._set_subscription(author, topic_id, SubscriptionLevel::Mentions).await.unwrap();
+        reply("plain").await.unwrap();
+        assert_eq!(inbox().await, 1, "`mentions` drops plain replies");
+        reply("@author look").await.unwrap();
+        assert_eq!(inbox().await, 2, "`mentions` keeps the mention");
+
+        let result = service
+            ._set_subscription(author, TopicId::new(), SubscriptionLevel::Muted)
+            .await;
+        assert!(matches!(result, Err(TopicsServiceError::TopicNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_topics_started_by() {
+        let service = setup_test_service().await;
+
+        let author = create_test_profile(&service, "Author").await;
+        let other = create_test_profile(&service, "Other").await;
+        let group_a = create_test_group(&service, author).await;
+        let group_b = create_test_group(&service, other).await;
+        let author_in_a = create_test_user(&service, group_a, author).await;
+        let author_in_b = create_test_user(&service, group_b, author).await;
+        let other_in_b = create_test_user(&service, group_b, other).await;
+
+        let first = service
+            ._create_topic_with_post(group_a, author_in_a, "A".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        let second = service
+            ._create_topic_with_post(group_b, author_in_b, "B".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+        // Someone else's topic shouldn't show up
+        service
+            ._create_topic_with_post(group_b, other_in_b, "C".to_string(), "Body".to_string())
+            .await
+            .unwrap();
+
+        // Two replies on the second topic
+        for seq in 2..4 {
+            let reply = GroupPostActiveModel {
+                id: Set(PostId::new()),
+                user_id: Set(other_in_b),
+                topic_id: Set(second.topic.id),
+                parent_post_id: Set(Some(second.post.id)),
+                title: Set("Re".to_string()),
+                body: Set("Reply".to_string()),
+                created_at: Set(chrono::Utc::now().to_rfc3339()),
+                deleted_at: Set(None),
+                edited_at: Set(None),
+                version: Set(1),
+                format: Set(PostFormat::Plain),
+                seq: Set(seq),
+            };
+            GroupPost::insert(reply).exec(&service.db).await.unwrap();
+        }
+
+        let started = service._topics_started_by(author, 10, 0).await.unwrap();
+
+        let ids: Vec<TopicId> = started.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![second.topic.id, first.topic.id], "Newest first");
+        assert_eq!(started[0].group_id, group_b);
+        assert_eq!(started[0].post_count, 3);
+        assert_eq!(started[1].group_id, group_a);
+        assert_eq!(started[1].post_count, 1);
+
+        let paged = service._topics_started_by(author, 1, 1).await.unwrap();
+        assert_eq!(paged.len(), 1);
+        assert_eq!(paged[0].id, first.topic.id);
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let db_error = TopicsServiceError::DbError(DbErr::Custom("boom".to_string()));
+        let db_error = ResourceError::from(db_error);
+        assert_eq!(db_error.severity(), ErrorSeverity::Infrastructure);
+
+        for error in [
+            TopicsServiceError::GroupNotFound,
+            TopicsServiceError::UserNotFound,
+            TopicsServiceError::NotAMember,
+            TopicsServiceError::GroupArchived,
+            TopicsServiceError::TopicNotFound,
+            TopicsServiceError::ProfileNotFound,
+            TopicsServiceError::Frozen,
+            TopicsServiceError::Unauthorized,
+            TopicsServiceError::InvalidTitle,
+            TopicsServiceError::InvalidTag,
+            TopicsServiceError::PinSetMismatch,
+            TopicsServiceError::Invalid(FieldError::new("body", "must not be empty")),
+            TopicsServiceError::Banned,
+        ] {
+            let message = error.to_string();
+            let mapped = ResourceError::from(error);
+            assert_eq!(mapped.severity(), ErrorSeverity::Application, "{message}");
+        }
+    }
+}
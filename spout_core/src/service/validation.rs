@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zel_core::prelude::*;
+
+/// A rejected input, naming the field at fault.
+///
+/// Travels as the `context` of an application `ResourceError`, so clients can
+/// point at the right form field instead of parsing the message.
+#[derive(Clone, Debug, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[error("invalid {field}: {reason}")]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// The field error carried by an RPC error, if it has one
+    pub fn from_resource_error(error: &ResourceError) -> Option<Self> {
+        match error {
+            ResourceError::CallbackError {
+                context: Some(context),
+                ..
+            } => serde_json::from_value(context.clone()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<FieldError> for ResourceError {
+    fn from(error: FieldError) -> Self {
+        let context = serde_json::to_value(&error).ok();
+        ResourceError::CallbackError {
+            message: error.to_string(),
+            severity: ErrorSeverity::Application,
+            context,
+        }
+    }
+}
+
+/// Reject blank text and text longer than `max_chars` characters
+pub(crate) fn check_text(field: &str, value: &str, max_chars: usize) -> Result<(), FieldError> {
+    if value.trim().is_empty() {
+        return Err(FieldError::new(field, "must not be empty"));
+    }
+    if value.chars().count() > max_chars {
+        return Err(FieldError::new(field, format!("must be at most {max_chars} characters")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_error_roundtrips_through_resource_error() {
+        let error = ResourceError::from(FieldError::new("title", "must not be empty"));
+        assert_eq!(error.severity(), ErrorSeverity::Application);
+        assert_eq!(
+            error.to_string(),
+            "Callback execution failed: invalid title: must not be empty"
+        );
+
+        // Survives the wire
+        let json = serde_json::to_string(&error).unwrap();
+        let error: ResourceError = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            FieldError::from_resource_error(&error),
+            Some(FieldError::new("title", "must not be empty"))
+        );
+
+        assert_eq!(FieldError::from_resource_error(&ResourceError::app("plain")), None);
+    }
+
+    #[test]
+    fn test_check_text() {
+        assert!(check_text("name", "ok", 2).is_ok());
+        assert_eq!(check_text("name", "  ", 2).unwrap_err().reason, "must not be empty");
+        assert_eq!(
+            check_text("name", "abc", 2).unwrap_err().reason,
+            "must be at most 2 characters"
+        );
+        // Characters, not bytes
+        assert!(check_text("name", "éé", 2).is_ok());
+    }
+}
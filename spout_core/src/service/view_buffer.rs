@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use sea_orm::{DatabaseConnection, DbErr};
+use tokio::{sync::Notify, task::JoinHandle};
+
+use crate::{
+    ids::{PostId, UserId},
+    service::posts::write_views,
+};
+
+/// When a [`ViewBuffer`] writes its pending views out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ViewBufferConfig {
+    /// How often the background flusher runs
+    pub flush_interval: Duration,
+    /// Pending views that trigger an immediate flush from `record_view`
+    pub max_pending: usize,
+}
+
+impl Default for ViewBufferConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_secs(5),
+            max_pending: 1000,
+        }
+    }
+}
+
+/// Write-behind buffer for `record_view`.
+///
+/// Views are low value and high frequency, so instead of a transaction per
+/// call they collect here (latest view per user and post) and go out in one
+/// batch per flush. A clean `SpoutCore::shutdown` flushes everything; a crash
+/// loses at most the views of one `flush_interval`.
+pub struct ViewBuffer {
+    db: DatabaseConnection,
+    config: ViewBufferConfig,
+    pending: Mutex<HashMap<(UserId, PostId), String>>,
+    closing: Notify,
+}
+
+/// [`ViewBuffer`] shared between `SpoutCore` and the posts service
+pub type SharedViewBuffer = Arc<ViewBuffer>;
+
+impl ViewBuffer {
+    pub fn new(db: DatabaseConnection, config: ViewBufferConfig) -> Self {
+        Self {
+            db,
+            config,
+            pending: Mutex::new(HashMap::new()),
+            closing: Notify::new(),
+        }
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, HashMap<(UserId, PostId), String>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Buffer a view; `true` once enough are pending that the caller should flush
+    pub(crate) fn record(&self, user_id: UserId, post_id: PostId, viewed_at: String) -> bool {
+        let mut pending = self.lock_pending();
+        pending.insert((user_id, post_id), viewed_at);
+        pending.len() >= self.config.max_pending
+    }
+
+    /// A user's views that haven't reached the DB yet, as `(post, viewed_at)`
+    pub(crate) fn pending_for(&self, user_id: UserId) -> Vec<(PostId, String)> {
+        self.lock_pending()
+            .iter()
+            .filter(|((user, _), _)| *user == user_id)
+            .map(|((_, post_id), viewed_at)| (*post_id, viewed_at.clone()))
+            .collect()
+    }
+
+    /// Views waiting for the next flush
+    pub fn pending_len(&self) -> usize {
+        self.lock_pending().len()
+    }
+
+    /// Write every pending view to the DB; returns how many were written.
+    ///
+    /// On failure the views go back into the buffer for the next attempt.
+    pub async fn flush(&self) -> Result<usize, DbErr> {
+        let views = std::mem::take(&mut *self.lock_pending());
+        if views.is_empty() {
+            return Ok(0);
+        }
+
+        let batch: Vec<_> = views
+            .iter()
+            .map(|((user_id, post_id), viewed_at)| (*user_id, *post_id, viewed_at.clone()))
+            .collect();
+
+        match write_views(&self.db, batch).await {
+            Ok(()) => Ok(views.len()),
+            Err(error) => {
+                // Views recorded since the take are newer; keep those
+                let mut pending = self.lock_pending();
+                for (key, viewed_at) in views {
+                    pending.entry(key).or_insert(viewed_at);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Flush every `flush_interval` until [`ViewBuffer::shutdown`]
+    pub fn spawn_flusher(self: &Arc<Self>) -> JoinHandle<()> {
+        let buffer = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(buffer.config.flush_interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = buffer.closing.notified() => break,
+                }
+                if let Err(error) = buffer.flush().await {
+                    eprintln!("view buffer: flush failed, retrying next tick: {error}");
+                }
+            }
+        })
+    }
+
+    /// Stop `flusher` and write out whatever it hadn't got to yet
+    pub async fn shutdown(&self, flusher: JoinHandle<()>) -> Result<usize, DbErr> {
+        // A stored permit, so a flusher that's mid-flush still sees it
+        self.closing.notify_one();
+        // Let an in-flight flush finish rather than cancelling it halfway
+        let _ = flusher.await;
+        self.flush().await
+    }
+}
@@ -0,0 +1,131 @@
+/// Appended to a preview that was cut short
+pub const ELLIPSIS: char = '…';
+
+/// A snippet of `body` at most `max_chars` characters long, ellipsis included.
+///
+/// Cuts on character boundaries, so multibyte UTF-8 is never split, and trims
+/// trailing whitespace before adding the ellipsis. A body that already fits is
+/// returned whole, minus trailing whitespace.
+pub fn preview(body: &str, max_chars: usize) -> String {
+    let body = body.trim_end();
+    if body.chars().count() <= max_chars {
+        return body.to_string();
+    }
+
+    // Keep one char fewer than the budget to leave room for the ellipsis
+    let Some(keep) = max_chars.checked_sub(1) else {
+        return String::new();
+    };
+    let cut = body.char_indices().nth(keep).map_or(body.len(), |(cut, _)| cut);
+
+    let mut snippet = body[..cut].trim_end().to_string();
+    snippet.push(ELLIPSIS);
+    snippet
+}
+
+/// Clean-up applied to post titles and bodies before they're stored; the
+/// default keeps text verbatim
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Strip leading and trailing whitespace, newlines included
+    pub trim_ends: bool,
+    /// Squeeze each run of blank (or whitespace-only) lines down to one
+    pub collapse_blank_lines: bool,
+}
+
+impl Normalization {
+    pub fn apply(&self, text: String) -> String {
+        let text = if self.collapse_blank_lines {
+            collapse_blank_lines(&text)
+        } else {
+            text
+        };
+        if self.trim_ends {
+            text.trim().to_string()
+        } else {
+            text
+        }
+    }
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut previous_blank = false;
+    for line in text.split('\n') {
+        let blank = line.trim().is_empty();
+        if !(blank && previous_blank) {
+            lines.push(line);
+        }
+        previous_blank = blank;
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization_off_keeps_text_verbatim() {
+        let text = "\n  Title\n\n\n\nBody \n".to_string();
+        assert_eq!(Normalization::default().apply(text.clone()), text);
+    }
+
+    #[test]
+    fn test_normalization_steps() {
+        let text = || "\n\nFirst\n\n \n\t\nSecond\r\n\r\n\r\nThird\n\n".to_string();
+
+        let trim = Normalization {
+            trim_ends: true,
+            collapse_blank_lines: false,
+        };
+        assert_eq!(trim.apply(text()), "First\n\n \n\t\nSecond\r\n\r\n\r\nThird");
+
+        let collapse = Normalization {
+            trim_ends: false,
+            collapse_blank_lines: true,
+        };
+        assert_eq!(collapse.apply(text()), "\nFirst\n\nSecond\r\n\r\nThird\n");
+
+        let both = Normalization {
+            trim_ends: true,
+            collapse_blank_lines: true,
+        };
+        assert_eq!(both.apply(text()), "First\n\nSecond\r\n\r\nThird");
+        assert_eq!(both.apply("plain".to_string()), "plain");
+    }
+
+    #[test]
+    fn test_preview_short_body_is_untouched() {
+        assert_eq!(preview("hello", 10), "hello");
+        assert_eq!(preview("hello  \n", 10), "hello");
+        assert_eq!(preview("", 10), "");
+    }
+
+    #[test]
+    fn test_preview_exact_boundary() {
+        // Exactly max_chars fits without an ellipsis
+        assert_eq!(preview("hello", 5), "hello");
+        // One more and the last char makes room for the ellipsis
+        assert_eq!(preview("hello!", 5), "hell…");
+        assert_eq!(preview("h", 1), "h");
+        assert_eq!(preview("hi", 1), "…");
+        assert_eq!(preview("hello", 0), "");
+    }
+
+    #[test]
+    fn test_preview_multibyte() {
+        let body = "héllo wörld 🎉🎉";
+        assert_eq!(preview(body, 14), body);
+        assert_eq!(preview(body, 13), "héllo wörld…");
+        assert_eq!(preview(body, 3), "hé…");
+        assert_eq!(preview("🎉🎉🎉", 2), "🎉…");
+        assert_eq!(preview("日本語のテキスト", 4), "日本語…");
+    }
+
+    #[test]
+    fn test_preview_trims_before_ellipsis() {
+        assert_eq!(preview("hello   world", 8), "hello…");
+        assert_eq!(preview("a\n\nb", 3), "a…");
+    }
+}